@@ -0,0 +1,83 @@
+use crate::{FramedReadError, Framing};
+use huntsman::runtime::{io::Read, net::TCPStream, time::timeout};
+use std::time::Duration;
+
+// rustdoc imports
+#[allow(unused_imports)]
+use super::FramedClient;
+
+/// A buffer which frames from a [`FramedClient`] are read into
+pub struct FramedBuffer {
+    /// The buffer itself
+    buffer: Box<[u8]>,
+
+    /// The number of bytes read into the buffer
+    length: usize,
+
+    /// The length of the frame at the start of the buffer which was last returned
+    consumed: usize,
+}
+
+impl FramedBuffer {
+    /// Creates a new [`FramedBuffer`] with `capacity` bytes of space
+    pub(super) fn new(capacity: usize) -> Self {
+        FramedBuffer {
+            buffer: vec![0; capacity].into_boxed_slice(),
+            length: 0,
+            consumed: 0,
+        }
+    }
+
+    /// Gets the contents of a frame returned by [`FramedBuffer::read_frame`]
+    pub(super) fn frame(&self, start: usize, end: usize) -> &[u8] {
+        &self.buffer[start..end]
+    }
+
+    /// Reads from `socket` until the buffer contains a complete frame
+    ///
+    /// Returns the start and end of the contents of the frame, or [`None`] if the client
+    /// disconnected between frames
+    pub(super) async fn read_frame<E>(
+        &mut self,
+        socket: &mut TCPStream,
+        framing: &Framing,
+        max_frame_size: usize,
+        read_timeout: Duration,
+    ) -> Result<Option<(usize, usize)>, FramedReadError<E>> {
+        self.discard();
+
+        loop {
+            if let Some((start, end, length)) =
+                framing.find(&self.buffer[..self.length], max_frame_size)?
+            {
+                self.consumed = length;
+                return Ok(Some((start, end)));
+            }
+
+            let count = timeout(socket.read(&mut self.buffer[self.length..]), read_timeout)
+                .await?
+                .map(|result| result.map_err(Into::into))
+                .unwrap_or(Err(FramedReadError::ReadTimeout))?;
+
+            if count == 0 {
+                return match self.length {
+                    0 => Ok(None),
+                    _ => Err(FramedReadError::IncompleteFrame),
+                };
+            }
+
+            self.length += count;
+        }
+    }
+
+    /// Removes the last returned frame from the start of the buffer
+    fn discard(&mut self) {
+        if self.consumed == 0 {
+            return;
+        }
+
+        self.buffer.copy_within(self.consumed..self.length, 0);
+        self.length -= self.consumed;
+        self.consumed = 0;
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{Codec, FramedOptions, FramedReadError, FramedSendError, Framing};
+use huntsman::{
+    runtime::{io::Write, net::TCPStream, time::timeout},
+    ProtocolClient,
+};
+use std::{cell::RefCell, sync::Arc, time::Duration};
+
+mod buffer;
+
+pub use buffer::FramedBuffer;
+
+/// A client connected to the server
+pub struct FramedClient<C: Codec> {
+    /// The socket representing the underlying connection
+    ///
+    /// Frames are answered one at a time, so the socket is never borrowed twice
+    socket: RefCell<TCPStream>,
+
+    /// The codec used to decode requests and encode responses
+    codec: Arc<C>,
+
+    /// How the stream is split into frames
+    framing: Framing,
+
+    /// The maximum size of the contents of a frame in a request
+    max_frame_size: usize,
+
+    /// The maximum amount of time allowed between reads
+    read_timeout: Duration,
+
+    /// The maximum amount of time allowed between writes
+    write_timeout: Duration,
+}
+
+impl<C: Codec> FramedClient<C> {
+    /// Creates a new [`FramedClient`] on `socket`
+    pub(crate) fn new(socket: TCPStream, options: &FramedOptions<C>) -> Self {
+        FramedClient {
+            socket: RefCell::new(socket),
+            codec: options.codec.clone(),
+            framing: options.framing.clone(),
+            max_frame_size: options.max_frame_size,
+            read_timeout: options.read_timeout,
+            write_timeout: options.write_timeout,
+        }
+    }
+}
+
+impl<C: Codec> ProtocolClient for FramedClient<C> {
+    type ReadError = FramedReadError<C::DecodeError>;
+
+    type SendError = FramedSendError;
+
+    type Request<'a> = C::Request<'a>;
+
+    type Response<'a> = C::Response<'a>;
+
+    type Buffer = FramedBuffer;
+
+    fn new_buffer(&self) -> Self::Buffer {
+        FramedBuffer::new(self.max_frame_size + self.framing.overhead())
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn read<'a>(
+        &self,
+        _: usize,
+        buffer: &'a mut Self::Buffer,
+    ) -> Result<Option<Self::Request<'a>>, Self::ReadError> {
+        let (start, end) = match buffer
+            .read_frame(
+                &mut self.socket.borrow_mut(),
+                &self.framing,
+                self.max_frame_size,
+                self.read_timeout,
+            )
+            .await?
+        {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+
+        let buffer: &'a FramedBuffer = buffer;
+        self.codec
+            .decode(buffer.frame(start, end))
+            .map(Some)
+            .map_err(FramedReadError::Decode)
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn send<'a>(
+        &self,
+        _: usize,
+        response: Self::Response<'a>,
+    ) -> Result<(), Self::SendError> {
+        let mut frame = vec![0; self.framing.prefix_size()];
+        self.codec.encode(response, &mut frame);
+        self.framing
+            .wrap(&mut frame)
+            .ok_or(FramedSendError::FrameTooLarge)?;
+
+        match timeout(
+            self.socket.borrow_mut().write_all(&frame),
+            self.write_timeout,
+        )
+        .await?
+        {
+            Some(result) => Ok(result?),
+            None => Err(FramedSendError::WriteTimeout),
+        }
+    }
+}
+
+unsafe impl<C: Codec> Send for FramedClient<C> {}
+unsafe impl<C: Codec> Sync for FramedClient<C> {}
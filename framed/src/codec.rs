@@ -0,0 +1,22 @@
+/// Converts between the contents of frames and the requests and responses of a protocol
+pub trait Codec: 'static + Send + Sync {
+    /// A request decoded from a frame
+    type Request<'a>;
+
+    /// A response which can be encoded into a frame
+    type Response<'a>;
+
+    /// The error when a frame can't be decoded
+    type DecodeError: 'static + std::error::Error;
+
+    /// Decodes a request from the contents of `frame`
+    ///
+    /// The request can borrow from `frame`, which stays valid until the next request is read
+    fn decode<'a>(&self, frame: &'a [u8]) -> Result<Self::Request<'a>, Self::DecodeError>;
+
+    /// Encodes `response` by appending the contents of its frame to `output`
+    ///
+    /// The length prefix or delimiter is added around `output` by the framing. With delimited
+    /// framing, the contents must not contain the delimiter.
+    fn encode(&self, response: Self::Response<'_>, output: &mut Vec<u8>);
+}
@@ -0,0 +1,96 @@
+/// An error while reading a frame and decoding a request from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FramedReadError<E> {
+    /// The frame is larger than the maximum frame size
+    FrameTooLarge,
+
+    /// The client sent an incomplete frame before disconnecting
+    IncompleteFrame,
+
+    /// The client took too long to send the frame
+    ReadTimeout,
+
+    /// The codec could not decode the frame
+    Decode(E),
+
+    /// An I/O error occurred while reading a frame
+    IO(crate::Error),
+}
+
+/// An error while encoding and sending a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FramedSendError {
+    /// The encoded response is too long for the length prefix
+    FrameTooLarge,
+
+    /// The client took too long to receive the response
+    WriteTimeout,
+
+    /// An I/O error occurred while sending the response
+    IO(crate::Error),
+}
+
+impl<E: 'static + std::error::Error> std::error::Error for FramedReadError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FramedReadError::Decode(error) => Some(error),
+            FramedReadError::IO(error) => Some(error),
+
+            FramedReadError::FrameTooLarge
+            | FramedReadError::IncompleteFrame
+            | FramedReadError::ReadTimeout => None,
+        }
+    }
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for FramedReadError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FramedReadError::FrameTooLarge => write!(f, "frame too large"),
+            FramedReadError::IncompleteFrame => write!(f, "frame is incomplete"),
+            FramedReadError::ReadTimeout => write!(f, "reading frame timed out"),
+            FramedReadError::Decode(error) => write!(f, "unable to decode frame - {}", error),
+
+            FramedReadError::IO(error) => {
+                write!(f, "an I/O error occurred while reading a frame - {}", error)
+            }
+        }
+    }
+}
+
+impl<E> From<crate::Error> for FramedReadError<E> {
+    fn from(error: crate::Error) -> Self {
+        FramedReadError::IO(error)
+    }
+}
+
+impl std::error::Error for FramedSendError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FramedSendError::IO(error) => Some(error),
+
+            FramedSendError::FrameTooLarge | FramedSendError::WriteTimeout => None,
+        }
+    }
+}
+
+impl std::fmt::Display for FramedSendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FramedSendError::FrameTooLarge => write!(f, "response too large for its frame"),
+            FramedSendError::WriteTimeout => write!(f, "sending response timed out"),
+
+            FramedSendError::IO(error) => write!(
+                f,
+                "an I/O error occurred while sending a response - {}",
+                error
+            ),
+        }
+    }
+}
+
+impl From<crate::Error> for FramedSendError {
+    fn from(error: crate::Error) -> Self {
+        FramedSendError::IO(error)
+    }
+}
@@ -0,0 +1,210 @@
+use crate::FramedReadError;
+
+#[cfg(test)]
+mod tests;
+
+/// How a stream of bytes is split into frames
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Framing {
+    /// Each frame is preceded by its length
+    LengthPrefixed(LengthPrefix),
+
+    /// Each frame is followed by a delimiter, which is not part of the frame
+    Delimited(Delimiter),
+}
+
+/// The bytes which follow each frame of a [`Framing::Delimited`] stream
+///
+/// A delimiter can't be empty, as the end of a frame couldn't be found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delimiter(Vec<u8>);
+
+/// The unsigned integer the length of a frame is encoded as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthPrefix {
+    /// A single byte
+    U8,
+
+    /// Two bytes
+    U16(Endian),
+
+    /// Four bytes
+    U32(Endian),
+
+    /// Eight bytes
+    U64(Endian),
+}
+
+/// The order of the bytes in a length prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    /// The most significant byte comes first
+    Big,
+
+    /// The least significant byte comes first
+    Little,
+}
+
+impl Framing {
+    /// Creates a [`Framing`] where frames are separated by newlines
+    pub fn lines() -> Self {
+        Framing::Delimited(Delimiter(b"\n".to_vec()))
+    }
+
+    /// Creates a [`Framing`] where frames are separated by `delimiter`
+    ///
+    /// Returns [`None`] if `delimiter` is empty
+    pub fn delimited<T: Into<Vec<u8>>>(delimiter: T) -> Option<Self> {
+        Delimiter::new(delimiter).map(Framing::Delimited)
+    }
+
+    /// Gets the number of bytes added to a frame by this framing
+    pub(crate) fn overhead(&self) -> usize {
+        match self {
+            Framing::LengthPrefixed(prefix) => prefix.size(),
+            Framing::Delimited(delimiter) => delimiter.0.len(),
+        }
+    }
+
+    /// Gets the number of bytes which come before the contents of a frame
+    pub(crate) fn prefix_size(&self) -> usize {
+        match self {
+            Framing::LengthPrefixed(prefix) => prefix.size(),
+            Framing::Delimited(_) => 0,
+        }
+    }
+
+    /// Searches `buffer` for a complete frame
+    ///
+    /// Returns the start and end of the frame's contents and the total length of the frame, or
+    /// [`None`] if `buffer` doesn't contain a complete frame yet
+    pub(crate) fn find<E>(
+        &self,
+        buffer: &[u8],
+        max_size: usize,
+    ) -> Result<Option<(usize, usize, usize)>, FramedReadError<E>> {
+        match self {
+            Framing::LengthPrefixed(prefix) => {
+                let size = prefix.size();
+                if buffer.len() < size {
+                    return Ok(None);
+                }
+
+                let length = prefix.decode(&buffer[..size]);
+                if length > max_size as u64 {
+                    return Err(FramedReadError::FrameTooLarge);
+                }
+
+                let end = size + length as usize;
+                if buffer.len() < end {
+                    return Ok(None);
+                }
+
+                Ok(Some((size, end, end)))
+            }
+            Framing::Delimited(Delimiter(delimiter)) => {
+                match buffer
+                    .windows(delimiter.len())
+                    .position(|window| window == delimiter.as_slice())
+                {
+                    Some(end) if end > max_size => Err(FramedReadError::FrameTooLarge),
+                    Some(end) => Ok(Some((0, end, end + delimiter.len()))),
+                    None if buffer.len() >= max_size + delimiter.len() => {
+                        Err(FramedReadError::FrameTooLarge)
+                    }
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+
+    /// Adds the framing around the contents of a frame in `frame`, which start after
+    /// [`Framing::prefix_size`] reserved bytes
+    ///
+    /// Returns [`None`] if the contents are too long for the length prefix
+    pub(crate) fn wrap(&self, frame: &mut Vec<u8>) -> Option<()> {
+        match self {
+            Framing::LengthPrefixed(prefix) => {
+                let size = prefix.size();
+                prefix.encode((frame.len() - size) as u64, &mut frame[..size])
+            }
+            Framing::Delimited(delimiter) => {
+                frame.extend_from_slice(&delimiter.0);
+                Some(())
+            }
+        }
+    }
+}
+
+impl Delimiter {
+    /// Creates a new [`Delimiter`]
+    ///
+    /// Returns [`None`] if `delimiter` is empty
+    pub fn new<T: Into<Vec<u8>>>(delimiter: T) -> Option<Self> {
+        let delimiter = delimiter.into();
+        if delimiter.is_empty() {
+            return None;
+        }
+
+        Some(Delimiter(delimiter))
+    }
+
+    /// Gets the bytes of this delimiter
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl LengthPrefix {
+    /// Gets the number of bytes in this prefix
+    pub fn size(&self) -> usize {
+        match self {
+            LengthPrefix::U8 => 1,
+            LengthPrefix::U16(_) => 2,
+            LengthPrefix::U32(_) => 4,
+            LengthPrefix::U64(_) => 8,
+        }
+    }
+
+    /// Gets the endianness of this prefix
+    fn endian(&self) -> Endian {
+        match self {
+            LengthPrefix::U8 => Endian::Big,
+            LengthPrefix::U16(endian) | LengthPrefix::U32(endian) | LengthPrefix::U64(endian) => {
+                *endian
+            }
+        }
+    }
+
+    /// Decodes a length from `bytes`, which must be [`LengthPrefix::size`] bytes long
+    fn decode(&self, bytes: &[u8]) -> u64 {
+        let mut value = [0; 8];
+        match self.endian() {
+            Endian::Big => {
+                value[8 - bytes.len()..].copy_from_slice(bytes);
+                u64::from_be_bytes(value)
+            }
+            Endian::Little => {
+                value[..bytes.len()].copy_from_slice(bytes);
+                u64::from_le_bytes(value)
+            }
+        }
+    }
+
+    /// Encodes `length` into `bytes`, which must be [`LengthPrefix::size`] bytes long
+    ///
+    /// Returns [`None`] if `length` doesn't fit in this prefix
+    fn encode(&self, length: u64, bytes: &mut [u8]) -> Option<()> {
+        let size = bytes.len();
+        if size < 8 && length >> (size * 8) != 0 {
+            return None;
+        }
+
+        match self.endian() {
+            Endian::Big => bytes.copy_from_slice(&length.to_be_bytes()[8 - size..]),
+            Endian::Little => bytes.copy_from_slice(&length.to_le_bytes()[..size]),
+        }
+
+        Some(())
+    }
+}
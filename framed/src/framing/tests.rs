@@ -0,0 +1,33 @@
+use super::{Delimiter, Framing};
+use crate::FramedReadError;
+
+#[test]
+fn empty_delimiters_are_rejected() {
+    assert_eq!(Delimiter::new(Vec::new()), None);
+    assert_eq!(Delimiter::new(""), None);
+    assert_eq!(Framing::delimited(b"".as_slice()), None);
+}
+
+#[test]
+fn delimited_frames_are_found() {
+    let framing = Framing::delimited("\r\n").unwrap();
+
+    assert_eq!(framing.find::<()>(b"PING\r", 16), Ok(None));
+    assert_eq!(framing.find::<()>(b"PING\r\nPONG", 16), Ok(Some((0, 4, 6))));
+    assert_eq!(framing.find::<()>(b"\r\n", 16), Ok(Some((0, 0, 2))));
+}
+
+#[test]
+fn delimited_frames_are_limited() {
+    let framing = Framing::lines();
+
+    assert_eq!(framing.find::<()>(b"1234\n", 4), Ok(Some((0, 4, 5))));
+    assert_eq!(
+        framing.find::<()>(b"12345\n", 4),
+        Err(FramedReadError::FrameTooLarge)
+    );
+    assert_eq!(
+        framing.find::<()>(b"12345", 4),
+        Err(FramedReadError::FrameTooLarge)
+    );
+}
@@ -0,0 +1,87 @@
+//! Framed TCP protocol implementation for huntsman
+//!
+//! This crate implements [`huntsman::Protocol`] for any TCP protocol whose messages are split into
+//! frames, either by a length prefix or by a delimiter. The contents of each frame are decoded into
+//! requests and responses are encoded into frames by a user supplied [`Codec`].
+
+#![deny(missing_docs)]
+#![deny(rustdoc::private_intra_doc_links)]
+#![deny(rustdoc::unescaped_backticks)]
+#![deny(rustdoc::redundant_explicit_links)]
+#![warn(rustdoc::broken_intra_doc_links)]
+
+use huntsman::Protocol;
+use std::net::SocketAddr;
+
+mod client;
+mod codec;
+mod error;
+mod framing;
+mod listener;
+mod options;
+
+pub use client::{FramedBuffer, FramedClient};
+pub use codec::Codec;
+pub use error::{FramedReadError, FramedSendError};
+pub use framing::{Delimiter, Endian, Framing, LengthPrefix};
+pub use huntsman::runtime::{Error, Result};
+pub use listener::FramedListener;
+pub use options::FramedOptions;
+
+/// A TCP protocol split into frames which are decoded and encoded by `C`
+pub struct Framed<C: Codec> {
+    /// The sockets for accepting clients
+    listeners: Vec<FramedListener<C>>,
+
+    /// The addresses the server is listening on
+    listen_addresses: Vec<SocketAddr>,
+
+    /// The options to define how this server should run
+    options: FramedOptions<C>,
+}
+
+impl<C: Codec> Protocol for Framed<C> {
+    type Options = FramedOptions<C>;
+
+    type ClientAddress = SocketAddr;
+    type Request<'a> = C::Request<'a>;
+    type Response<'a> = C::Response<'a>;
+    type ReadError = FramedReadError<C::DecodeError>;
+    type SendError = FramedSendError;
+    type Client = FramedClient<C>;
+
+    type ListenAddress = SocketAddr;
+    type ListenError = Error;
+    type Listener = FramedListener<C>;
+
+    async fn start(addresses: &[Self::ListenAddress], options: Self::Options) -> Result<Self> {
+        let mut listeners = Vec::with_capacity(addresses.len());
+        let mut listen_addresses = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let (listener, listen_address) = FramedListener::new(*address)?;
+            listeners.push(listener);
+            listen_addresses.push(listen_address);
+        }
+
+        Ok(Framed {
+            listeners,
+            listen_addresses,
+            options,
+        })
+    }
+
+    fn addresses(&self) -> &[Self::ListenAddress] {
+        &self.listen_addresses
+    }
+
+    fn listeners(&self) -> &[Self::Listener] {
+        &self.listeners
+    }
+
+    fn options(&self) -> &Self::Options {
+        &self.options
+    }
+}
+
+unsafe impl<C: Codec> Send for Framed<C> {}
+unsafe impl<C: Codec> Sync for Framed<C> {}
@@ -0,0 +1,45 @@
+use crate::{Codec, FramedClient, FramedOptions, Result};
+use huntsman::{runtime::net::TCPListener, ProtocolListener};
+use std::{marker::PhantomData, net::SocketAddr};
+
+/// A socket to listen for connections on
+pub struct FramedListener<C: Codec> {
+    /// The socket itself
+    socket: TCPListener,
+
+    /// The codec clients will use
+    _codec: PhantomData<C>,
+}
+
+impl<C: Codec> FramedListener<C> {
+    /// Creates a new [`FramedListener`] bound to `address`
+    pub(crate) fn new(address: SocketAddr) -> Result<(Self, SocketAddr)> {
+        let socket = TCPListener::bind(address)?;
+        let listen_address = socket.local_addr()?;
+        Ok((
+            FramedListener {
+                socket,
+                _codec: PhantomData,
+            },
+            listen_address,
+        ))
+    }
+}
+
+impl<C: Codec> ProtocolListener for FramedListener<C> {
+    type Address = SocketAddr;
+    type Client = FramedClient<C>;
+    type ClientAddress = SocketAddr;
+    type Error = crate::Error;
+    type Options = FramedOptions<C>;
+
+    async fn accept(
+        &self,
+        options: &Self::Options,
+    ) -> std::result::Result<(Self::Client, Self::ClientAddress), Self::Error> {
+        let (mut socket, address) = self.socket.accept().await?;
+        socket.set_nodelay(true)?;
+
+        Ok((FramedClient::new(socket, options), address))
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{Codec, Framing};
+use std::{sync::Arc, time::Duration};
+
+/// The options to determine how a framed protocol will operate
+#[derive(Debug)]
+pub struct FramedOptions<C: Codec> {
+    /// The codec used to decode requests and encode responses
+    pub codec: Arc<C>,
+
+    /// How the stream is split into frames
+    pub framing: Framing,
+
+    /// The maximum size of the contents of a frame in a request
+    pub max_frame_size: usize,
+
+    /// The maximum amount of time allowed between reads
+    pub read_timeout: Duration,
+
+    /// The maximum amount of time allowed between writes
+    pub write_timeout: Duration,
+}
+
+impl<C: Codec> FramedOptions<C> {
+    /// Creates new [`FramedOptions`] for `codec` using `framing` with the default limits
+    pub fn new(codec: C, framing: Framing) -> Self {
+        FramedOptions {
+            codec: Arc::new(codec),
+            framing,
+            max_frame_size: 64 * 1024, // 64 Kb
+            read_timeout: Duration::from_secs(60),
+            write_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl<C: Codec> Clone for FramedOptions<C> {
+    fn clone(&self) -> Self {
+        FramedOptions {
+            codec: self.codec.clone(),
+            framing: self.framing.clone(),
+            max_frame_size: self.max_frame_size,
+            read_timeout: self.read_timeout,
+            write_timeout: self.write_timeout,
+        }
+    }
+}
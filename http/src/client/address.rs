@@ -1,5 +1,8 @@
 use std::{fmt::Display, net::SocketAddr};
 
+#[cfg(feature = "tls")]
+use crate::HTTPTLSInfo;
+
 /// The protocol being used by the client
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,13 +21,17 @@ pub enum HTTPProtocol {
 }
 
 /// The address a client connected with
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HTTPClientAddress {
     /// The protocol the client is using
     protocol: HTTPProtocol,
 
     /// The address the client connected with
     socket_address: SocketAddr,
+
+    /// The parameters negotiated if the client connected over TLS
+    #[cfg(feature = "tls")]
+    tls: Option<HTTPTLSInfo>,
 }
 
 impl HTTPClientAddress {
@@ -33,9 +40,22 @@ impl HTTPClientAddress {
         HTTPClientAddress {
             protocol,
             socket_address,
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
+    /// Sets the protocol the client is using
+    pub(crate) fn set_protocol(&mut self, protocol: HTTPProtocol) {
+        self.protocol = protocol;
+    }
+
+    /// Sets the parameters negotiated with the client over TLS
+    #[cfg(feature = "tls")]
+    pub(crate) fn set_tls(&mut self, tls: HTTPTLSInfo) {
+        self.tls = Some(tls);
+    }
+
     /// Gets the protocol the client connected with
     pub fn protocol(&self) -> HTTPProtocol {
         self.protocol
@@ -45,6 +65,12 @@ impl HTTPClientAddress {
     pub fn socket_address(&self) -> SocketAddr {
         self.socket_address
     }
+
+    /// Gets the parameters negotiated with the client if it connected over TLS
+    #[cfg(feature = "tls")]
+    pub fn tls(&self) -> Option<&HTTPTLSInfo> {
+        self.tls.as_ref()
+    }
 }
 
 impl Display for HTTPClientAddress {
@@ -1,10 +1,18 @@
 use super::HTTPSocket;
-use crate::HTTPParseError;
-use lasync::time::Timeout;
-use std::time::Duration;
 
-/// A buffer for more efficient reading from a socket
-pub(super) struct HeaderBuffer {
+// rustdoc imports
+#[allow(unused_imports)]
+use super::HTTPClient;
+use crate::{
+    http2::HTTP2Request,
+    request::{BodyFraming, HeaderLimits},
+    HTTPParseError,
+};
+use huntsman::runtime::time::timeout;
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+/// A buffer which requests from an [`HTTPClient`] are read into
+pub struct HTTPRequestBuffer {
     /// The buffer itself
     buffer: Box<[u8]>,
 
@@ -16,26 +24,94 @@ pub(super) struct HeaderBuffer {
 
     /// The maximum time between reads
     read_timeout: Duration,
+
+    /// The maximum time to wait for the first byte of a request after the first
+    keep_alive_timeout: Duration,
+
+    /// Has a request already been read into this buffer?
+    keep_alive: bool,
+
+    /// The limits on the headers parsed from this buffer
+    limits: HeaderLimits,
+
+    /// The framing of the body of the current request
+    body_framing: BodyFraming,
+
+    /// Is the client waiting for a "100 Continue" before sending the body of the current request?
+    ///
+    /// This is shared with the [`HTTPClient`] so it knows when a body was never asked for.
+    continue_pending: Rc<Cell<bool>>,
+
+    /// The current request if the client is using HTTP/2
+    http2_request: Option<HTTP2Request>,
 }
 
-impl HeaderBuffer {
+impl HTTPRequestBuffer {
     /// Creates a new [`Buffer`] with `capacity` bytes of space
-    pub(super) fn new(capacity: usize, read_timeout: Duration) -> Self {
+    pub(super) fn new(
+        capacity: usize,
+        read_timeout: Duration,
+        keep_alive_timeout: Duration,
+        limits: HeaderLimits,
+        continue_pending: Rc<Cell<bool>>,
+    ) -> Self {
         let buffer = vec![0; capacity].into_boxed_slice();
 
-        HeaderBuffer {
+        HTTPRequestBuffer {
             buffer,
             length: 0,
             index: 0,
             read_timeout,
+            keep_alive_timeout,
+            keep_alive: false,
+            limits,
+            body_framing: BodyFraming::Done,
+            continue_pending,
+            http2_request: None,
         }
     }
 
+    /// Fills the start of the buffer with `bytes` which were read before the buffer was created
+    pub(super) fn prefill(&mut self, bytes: &[u8]) {
+        self.buffer[..bytes.len()].copy_from_slice(bytes);
+        self.length = bytes.len();
+    }
+
+    /// Stores `request` as the current request so it can be borrowed for as long as this buffer
+    pub(super) fn set_http2_request(&mut self, request: HTTP2Request) -> &mut HTTP2Request {
+        self.http2_request.insert(request)
+    }
+
     /// Gets the current index into the buffer
     pub(super) fn index(&self) -> usize {
         self.index
     }
 
+    /// Gets the limits on the headers parsed from this buffer
+    pub(super) fn limits(&self) -> HeaderLimits {
+        self.limits
+    }
+
+    /// Gets the framing of the body of the current request
+    pub(crate) fn body_framing(&self) -> BodyFraming {
+        self.body_framing
+    }
+
+    /// Sets the framing of the body of the current request
+    pub(super) fn set_body_framing(&mut self, body_framing: BodyFraming) {
+        self.body_framing = body_framing;
+    }
+
+    /// Is the client waiting for a "100 Continue" before sending the body of the current request?
+    pub(super) fn continue_pending(&self) -> bool {
+        self.continue_pending.get()
+    }
+
+    /// Sets if the client is waiting for a "100 Continue" before sending the current body
+    pub(super) fn set_continue_pending(&mut self, continue_pending: bool) {
+        self.continue_pending.set(continue_pending);
+    }
+
     /// Gets a subslice from the buffer
     ///
     /// # SAFETY
@@ -74,6 +150,7 @@ impl HeaderBuffer {
             return;
         }
 
+        self.keep_alive = true;
         self.length -= self.index;
         for i in 0..self.length {
             self.buffer[i] = self.buffer[self.index + i];
@@ -87,24 +164,65 @@ impl HeaderBuffer {
         let remaining = self.length - self.index;
 
         let copy_length = remaining.min(buffer.len());
-        (&mut buffer[..copy_length])
-            .copy_from_slice(&mut self.buffer[self.index..self.index + copy_length]);
+        buffer[..copy_length].copy_from_slice(&self.buffer[self.index..self.index + copy_length]);
         self.index += copy_length;
 
         copy_length
     }
 
+    /// Moves bytes following the current index into `body`, reading more from `stream` if none are
+    /// buffered, and returns the number of bytes moved
+    ///
+    /// The moved bytes are removed from the buffer so bodies larger than the buffer can pass
+    /// through it.
+    pub(super) async fn take(
+        &mut self,
+        stream: &mut HTTPSocket,
+        body: &mut [u8],
+    ) -> Result<usize, HTTPParseError> {
+        if self.index == self.buffer.len() {
+            return Err(HTTPParseError::HeadersTooLong);
+        }
+
+        if self.index == self.length {
+            self.read(stream).await?;
+        }
+
+        let count = body.len().min(self.length - self.index);
+        body[..count].copy_from_slice(&self.buffer[self.index..self.index + count]);
+        self.buffer
+            .copy_within(self.index + count..self.length, self.index);
+        self.length -= count;
+
+        Ok(count)
+    }
+
+    /// Removes the bytes between `start` and the current index from the buffer
+    ///
+    /// Any slices into the buffer past `start` are invalidated by this.
+    pub(super) fn discard(&mut self, start: usize) {
+        assert!(start <= self.index);
+
+        self.buffer.copy_within(self.index..self.length, start);
+        self.length -= self.index - start;
+        self.index = start;
+    }
+
     /// Extend the buffer by reading from `stream`
     async fn read(&mut self, stream: &mut HTTPSocket) -> Result<(), HTTPParseError> {
         assert_ne!(self.length, self.buffer.len());
 
-        let count = Timeout::new(
-            stream.read(&mut self.buffer[self.length..]),
-            self.read_timeout,
-        )?
-        .await
-        .map(|result| result.map_err(Into::into))
-        .unwrap_or(Err(HTTPParseError::HeaderReadTimeout))?;
+        // Idle connections waiting for their next request use the keep-alive timeout
+        let read_timeout = if self.keep_alive && self.length == 0 {
+            self.keep_alive_timeout
+        } else {
+            self.read_timeout
+        };
+
+        let count = timeout(stream.read(&mut self.buffer[self.length..]), read_timeout)
+            .await?
+            .map(|result| result.map_err(Into::into))
+            .unwrap_or(Err(HTTPParseError::HeaderReadTimeout))?;
 
         if count == 0 {
             return Err(HTTPParseError::IncompleteHeader);
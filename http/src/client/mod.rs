@@ -1,10 +1,35 @@
+// The socket is shared with streamed request bodies, which only read from it while the request is
+// handled, so borrows of it held across reads and writes never overlap
+#![allow(clippy::await_holding_refcell_ref)]
+
 use crate::{
-    HTTPChunkedResponseBody, HTTPParseError, HTTPRequest, HTTPResponse, HTTPResponseBodyContent,
+    coding::EncodedHTTPChunkedResponseBody,
+    http2::{self, HTTP2Connection, PREFACE},
+    request::{skip_body, HeaderLimits},
+    Error, HTTPChunkedResponseBody, HTTPCompressionOptions, HTTPContentEncoder, HTTPMethod,
+    HTTPOptions, HTTPParseError, HTTPRequest, HTTPResponse, HTTPResponseBodyContent, HTTPVersion,
+    HTTPWebSocket,
+};
+use huntsman::{
+    runtime::time::{timeout, timeout_error},
+    ProtocolClient, Shutdown,
+};
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    num::NonZeroUsize,
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
 };
-use buffer::HeaderBuffer;
-use huntsman::ProtocolClient;
-use lasync::{time::Timeout, Error};
-use std::{future::Future, marker::PhantomData, time::Duration};
+
+#[cfg(feature = "http3")]
+use crate::{
+    http3::{self, HTTP3Connection},
+    HTTPContentEncoderStream,
+};
+#[cfg(feature = "http3")]
+use quinn_proto::StreamId;
 
 mod address;
 mod buffer;
@@ -12,6 +37,7 @@ mod socket;
 mod stream;
 
 pub use address::{HTTPClientAddress, HTTPProtocol};
+pub use buffer::HTTPRequestBuffer;
 
 pub(crate) use socket::HTTPSocket;
 pub(crate) use stream::Stream;
@@ -19,10 +45,19 @@ pub(crate) use stream::Stream;
 /// A client connected to the server
 pub struct HTTPClient<B: HTTPChunkedResponseBody> {
     /// The socket representing the underlying connection
-    socket: HTTPSocket,
+    ///
+    /// This is shared with the streamed bodies of requests, which read from it while the request
+    /// is handled.
+    socket: Rc<RefCell<HTTPSocket>>,
+
+    /// The maximum size for request headers
+    max_header_size: usize,
 
-    /// The buffer for more efficient header reading and parsing
-    buffer: HeaderBuffer,
+    /// The limits on the parts of request headers
+    header_limits: HeaderLimits,
+
+    /// The maximum amount of time allowed between header reads
+    header_read_timeout: Duration,
 
     /// The maximum size for request bodies
     max_body_size: usize,
@@ -33,31 +68,187 @@ pub struct HTTPClient<B: HTTPChunkedResponseBody> {
     /// The maximum amount of time allowed between writes
     write_timeout: Duration,
 
+    /// The maximum amount of time to wait for the next request
+    keep_alive_timeout: Duration,
+
+    /// Should request bodies be streamed to the handler?
+    stream_request_bodies: bool,
+
+    /// The options for compressing response bodies
+    compression: Option<HTTPCompressionOptions>,
+
+    /// The maximum number of requests to answer before closing the connection
+    max_requests: Option<NonZeroUsize>,
+
+    /// The number of requests read from this client
+    requests: Cell<usize>,
+
+    /// Should the connection be closed after the current response?
+    close: Cell<bool>,
+
+    /// What the responses to the requests last read in each slot depend on
+    slots: RefCell<Vec<Slot>>,
+
+    /// Is the client waiting for a "100 Continue" before sending the body of the last request?
+    continue_pending: Rc<Cell<bool>>,
+
+    /// The state of the connection if the client is using HTTP/2
+    http2: Option<Box<HTTP2Connection>>,
+
+    /// The state of the connection if the client is using HTTP/3, which is shared with the socket
+    #[cfg(feature = "http3")]
+    http3: Option<Rc<HTTP3Connection>>,
+
+    /// The bytes read while checking if the client is using HTTP/2, which start its first request
+    preread: Cell<Vec<u8>>,
+
+    /// The value of the "Alt-Svc" field advertising HTTP/3, if the server is listening for it
+    alt_svc: Option<Arc<[u8]>>,
+
+    /// The maximum size of a message received after the connection is upgraded to a WebSocket
+    max_websocket_message_size: usize,
+
+    /// The handle the server is shut down with, which WebSockets close on
+    shutdown: Shutdown,
+
     /// The type used for chunked response bodies
     _chunked_response_body: PhantomData<B>,
 }
 
+/// What the response to a request depends on, kept from when the request is read until it is
+/// answered
+#[derive(Clone)]
+struct Slot {
+    /// The version of HTTP the request was sent with
+    version: HTTPVersion,
+
+    /// Was the request a "HEAD" request?
+    head: bool,
+
+    /// Did the request ask to upgrade the connection to a WebSocket?
+    websocket_upgrade: bool,
+
+    /// The encoder the client accepted for the response
+    encoder: Option<Arc<dyn HTTPContentEncoder>>,
+
+    /// The ID of the HTTP/2 stream the request was sent on
+    stream: Option<u32>,
+
+    /// The ID of the HTTP/3 stream the request was sent on
+    #[cfg(feature = "http3")]
+    quic_stream: Option<StreamId>,
+}
+
 impl<B: HTTPChunkedResponseBody> HTTPClient<B> {
     /// Creates a new [`HTTPClient`]
+    ///
+    /// Responses sent over HTTP/1.1 and HTTP/2 include `alt_svc` in an "Alt-Svc" field.
     pub(crate) fn new(
         socket: HTTPSocket,
-        max_header_size: usize,
-        max_body_size: usize,
-        header_read_timeout: Duration,
-        body_read_timeout: Duration,
-        write_timeout: Duration,
+        options: &HTTPOptions,
+        alt_svc: Option<Arc<[u8]>>,
     ) -> crate::Result<Self> {
-        let buffer = HeaderBuffer::new(max_header_size, header_read_timeout);
-
         Ok(HTTPClient {
-            socket,
-            buffer,
-            max_body_size,
-            body_read_timeout,
-            write_timeout,
+            #[cfg(feature = "http3")]
+            http3: socket.http3(),
+            socket: Rc::new(RefCell::new(socket)),
+            max_header_size: options.max_header_size,
+            header_limits: HeaderLimits::new(options),
+            header_read_timeout: options.header_read_timeout,
+            max_body_size: options.max_body_size,
+            body_read_timeout: options.body_read_timeout,
+            write_timeout: options.write_timeout,
+            keep_alive_timeout: options.keep_alive_timeout,
+            max_requests: options.max_requests_per_connection,
+            stream_request_bodies: options.stream_request_bodies,
+            compression: options.compression.clone(),
+            requests: Cell::new(0),
+            close: Cell::new(false),
+            slots: RefCell::new(Vec::new()),
+            continue_pending: Rc::new(Cell::new(false)),
+            http2: None,
+            preread: Cell::new(Vec::new()),
+            alt_svc,
+            max_websocket_message_size: options.max_websocket_message_size,
+            shutdown: Shutdown::new(),
             _chunked_response_body: PhantomData,
         })
     }
+
+    /// Checks if the client starts the connection with the HTTP/2 connection preface, starting
+    /// HTTP/2 if it does
+    ///
+    /// Anything else read is kept as the start of the first HTTP/1.1 request.
+    pub(crate) async fn detect_http2(&mut self, options: &HTTPOptions) -> crate::Result<bool> {
+        let mut preface = vec![0; PREFACE.len()];
+        let mut length = 0;
+        while length < PREFACE.len() && PREFACE.starts_with(&preface[..length]) {
+            let count = timeout(
+                self.socket.borrow_mut().read(&mut preface[length..]),
+                options.header_read_timeout,
+            )
+            .await?
+            .unwrap_or(Err(timeout_error()))?;
+
+            if count == 0 {
+                break;
+            }
+            length += count;
+        }
+        preface.truncate(length);
+
+        if preface == PREFACE {
+            self.start_http2(preface, options);
+            Ok(true)
+        } else {
+            self.preread.set(preface);
+            Ok(false)
+        }
+    }
+
+    /// Starts using HTTP/2 with the client, which has already sent `input`
+    pub(crate) fn start_http2(&mut self, input: Vec<u8>, options: &HTTPOptions) {
+        self.http2 = Some(Box::new(HTTP2Connection::new(input, options)));
+    }
+
+    /// Remembers what the response to `request`, read in `slot`, depends on
+    ///
+    /// Returns true if this is the last request allowed on the connection.
+    fn start_request(&self, slot: usize, request: &HTTPRequest, stream: Option<u32>) -> bool {
+        *self.slot_mut(slot) = Slot {
+            version: request.version(),
+            head: request.method() == HTTPMethod::HEAD,
+            websocket_upgrade: request.is_websocket_upgrade(),
+            encoder: self
+                .compression
+                .as_ref()
+                .and_then(|compression| compression.negotiate(request.fields().accept_encoding())),
+            stream,
+            #[cfg(feature = "http3")]
+            quic_stream: None,
+        };
+
+        self.requests.set(self.requests.get() + 1);
+        self.max_requests
+            .map(|max_requests| self.requests.get() >= max_requests.get())
+            .unwrap_or(false)
+    }
+
+    /// Gets what the response to the request read in `slot` depends on
+    fn slot_mut(&self, slot: usize) -> std::cell::RefMut<'_, Slot> {
+        std::cell::RefMut::map(self.slots.borrow_mut(), |slots| {
+            if slots.len() <= slot {
+                slots.resize_with(slot + 1, Slot::new);
+            }
+            &mut slots[slot]
+        })
+    }
+
+    /// Gets the socket connected to the client
+    #[cfg(feature = "tls")]
+    pub(crate) fn socket_mut(&mut self) -> std::cell::RefMut<'_, HTTPSocket> {
+        self.socket.borrow_mut()
+    }
 }
 
 const HEX_STR_LEN: usize = std::mem::size_of::<usize>() * 2;
@@ -99,7 +290,7 @@ async fn send_chunked<B: HTTPChunkedResponseBody>(
     loop {
         let socket = &mut *socket;
         let body = &mut body;
-        if !Timeout::new(
+        if !timeout(
             async move {
                 match body.next().await? {
                     Some(chunk) => {
@@ -114,17 +305,97 @@ async fn send_chunked<B: HTTPChunkedResponseBody>(
                 }
             },
             write_timeout,
-        )?
-        .await
-        .unwrap_or(Err(Error::ETIMEDOUT))?
+        )
+        .await?
+        .unwrap_or(Err(timeout_error()))?
         {
             break;
         }
     }
 
-    Timeout::new(send_chunk(socket, &[]), write_timeout)?
-        .await
-        .unwrap_or(Err(Error::ETIMEDOUT))
+    timeout(send_chunk(socket, &[]), write_timeout)
+        .await?
+        .unwrap_or(Err(timeout_error()))
+}
+
+async fn send_chunked_body<B: HTTPChunkedResponseBody>(
+    socket: &mut HTTPSocket,
+    version: HTTPVersion,
+    body: B,
+    write_timeout: Duration,
+) -> Result<(), Error> {
+    match version {
+        HTTPVersion::HTTP10 => send_unframed(socket, body, write_timeout).await,
+        HTTPVersion::HTTP11 | HTTPVersion::HTTP2 | HTTPVersion::HTTP3 => {
+            send_chunked(socket, body, write_timeout).await
+        }
+    }
+}
+
+async fn send_http2_chunked<B: HTTPChunkedResponseBody>(
+    connection: &HTTP2Connection,
+    socket: &RefCell<HTTPSocket>,
+    id: u32,
+    mut body: B,
+    write_timeout: Duration,
+) -> Result<(), Error> {
+    loop {
+        match timeout(body.next(), write_timeout)
+            .await?
+            .unwrap_or(Err(timeout_error()))?
+        {
+            Some(chunk) if !chunk.is_empty() => {
+                connection.send_data(socket, id, chunk, false).await?
+            }
+            _ => return connection.send_data(socket, id, &[], true).await,
+        }
+    }
+}
+
+#[cfg(feature = "http3")]
+async fn send_http3_chunked<B: HTTPChunkedResponseBody>(
+    connection: &HTTP3Connection,
+    id: StreamId,
+    mut body: B,
+    write_timeout: Duration,
+) -> Result<(), Error> {
+    loop {
+        match timeout(body.next(), write_timeout)
+            .await?
+            .unwrap_or(Err(timeout_error()))?
+        {
+            Some(chunk) if !chunk.is_empty() => connection.send_data(id, chunk, false).await?,
+            _ => return connection.send_data(id, &[], true).await,
+        }
+    }
+}
+
+async fn send_unframed<B: HTTPChunkedResponseBody>(
+    socket: &mut HTTPSocket,
+    mut body: B,
+    write_timeout: Duration,
+) -> Result<(), Error> {
+    loop {
+        let socket = &mut *socket;
+        let body = &mut body;
+        if !timeout(
+            async move {
+                match body.next().await? {
+                    Some(chunk) if !chunk.is_empty() => {
+                        socket.write(chunk).await?;
+                        Ok(true)
+                    }
+                    _ => Ok(false),
+                }
+            },
+            write_timeout,
+        )
+        .await?
+        .unwrap_or(Err(timeout_error()))?
+        {
+            return Ok(());
+        }
+    }
 }
 
 impl<B: HTTPChunkedResponseBody> ProtocolClient for HTTPClient<B> {
@@ -136,37 +407,424 @@ impl<B: HTTPChunkedResponseBody> ProtocolClient for HTTPClient<B> {
 
     type Response<'a> = HTTPResponse<'a, B>;
 
-    fn read<'a>(
-        &'a mut self,
-    ) -> impl Future<Output = Result<Option<Self::Request<'a>>, Self::ReadError>> {
-        let stream = Stream::new(&mut self.buffer, &mut self.socket);
+    type Buffer = HTTPRequestBuffer;
 
-        HTTPRequest::parse(stream, self.max_body_size, self.body_read_timeout)
+    fn new_buffer(&self) -> Self::Buffer {
+        let mut buffer = HTTPRequestBuffer::new(
+            self.max_header_size,
+            self.header_read_timeout,
+            self.keep_alive_timeout,
+            self.header_limits,
+            self.continue_pending.clone(),
+        );
+        buffer.prefill(&self.preread.take());
+        buffer
     }
 
-    async fn send<'a>(&mut self, response: Self::Response<'a>) -> Result<(), Self::SendError> {
-        let (header, body) = response.generate_header();
+    fn max_concurrent_requests(&self) -> usize {
+        #[cfg(feature = "http3")]
+        if self.http3.is_some() {
+            return http3::MAX_CONCURRENT_STREAMS;
+        }
+
+        match self.http2 {
+            Some(_) => http2::MAX_CONCURRENT_STREAMS,
+            None => 1,
+        }
+    }
+
+    async fn read<'a>(
+        &self,
+        slot: usize,
+        buffer: &'a mut Self::Buffer,
+    ) -> Result<Option<Self::Request<'a>>, Self::ReadError> {
+        if self.close.get() {
+            return Ok(None);
+        }
+
+        // Only HTTP/1.1 requests can ask for a WebSocket
+        {
+            let mut slot = self.slot_mut(slot);
+            slot.websocket_upgrade = false;
+            slot.head = false;
+            slot.stream = None;
+            #[cfg(feature = "http3")]
+            {
+                slot.quic_stream = None;
+            }
+        }
+
+        if let Some(connection) = &self.http2 {
+            return self.read_http2(connection, slot, buffer).await;
+        }
+
+        #[cfg(feature = "http3")]
+        if let Some(connection) = &self.http3 {
+            return self.read_http3(connection, slot, buffer).await;
+        }
+
+        let mut stream = Stream::new(buffer, self.socket.clone());
+
+        // Whatever the handler didn't read of the last streamed body is still in the way
+        if !stream.body_framing().is_done() {
+            if skip_body(&mut stream, self.body_read_timeout, self.max_body_size)
+                .await
+                .is_err()
+            {
+                self.close.set(true);
+                return Ok(None);
+            }
+
+            stream.reset();
+        }
+
+        let request = match HTTPRequest::parse(
+            stream,
+            self.max_body_size,
+            self.body_read_timeout,
+            self.write_timeout,
+            self.stream_request_bodies,
+        )
+        .await
+        {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(None),
+            Err(error) => {
+                // The end of a request that failed to parse can't be found, so the response
+                // to it has to be the last
+                self.close.set(true);
+                return Err(error);
+            }
+        };
+
+        if self.start_request(slot, &request, None) || !request.keep_alive() {
+            self.close.set(true);
+        }
+
+        Ok(Some(request))
+    }
+
+    fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.shutdown = shutdown;
+    }
+
+    async fn send<'a>(
+        &self,
+        slot: usize,
+        mut response: Self::Response<'a>,
+    ) -> Result<(), Self::SendError> {
+        let request = self.slot_mut(slot).clone();
+
+        // A response to anything but an HTTP/1.1 request asking for a WebSocket can't hand the
+        // connection over to one
+        let websocket = response.take_websocket(request.websocket_upgrade);
+
+        #[cfg(feature = "http3")]
+        if let Some(connection) = &self.http3 {
+            return self.send_http3(connection, &request, response).await;
+        }
+
+        if let Some(alt_svc) = &self.alt_svc {
+            response.push_field(b"Alt-Svc", alt_svc);
+        }
+
+        if let Some(connection) = &self.http2 {
+            return self.send_http2(connection, &request, response).await;
+        }
+
+        // A client which never got a "100 Continue" may or may not send the body it was holding
+        // back, so where the next request starts is unknown
+        if self.continue_pending.get() {
+            self.close.set(true);
+        }
+
+        // HTTP/1.0 clients find the end of chunked bodies by the connection closing
+        if request.version == HTTPVersion::HTTP10 && response.is_chunked() {
+            self.close.set(true);
+        }
+
+        // An upgraded connection belongs to the WebSocket until it closes
+        if websocket.is_some() {
+            self.close.set(true);
+        } else if self.close.get() {
+            response.close_connection();
+        } else if response.closes_connection() {
+            self.close.set(true);
+        } else if request.version == HTTPVersion::HTTP10 {
+            response.push_field(b"Connection", b"keep-alive");
+        }
+
+        let stream = match &self.compression {
+            Some(compression) => response.encode(compression, request.encoder.as_deref()),
+            None => None,
+        };
+
+        let (header, body) = response.generate_header(request.version);
+
+        // The response to a HEAD request has the header the body would have, but no body
+        let body = if request.head { None } else { body };
 
         let write_timeout = self.write_timeout;
-        let socket = &mut self.socket;
-        Timeout::new(async move { socket.write(&header).await }, write_timeout)?
-            .await
-            .unwrap_or(Err(Error::ETIMEDOUT))?;
+        let mut socket = self.socket.borrow_mut();
+        let socket = &mut *socket;
+        timeout(socket.write(&header), write_timeout)
+            .await?
+            .unwrap_or(Err(timeout_error()))?;
+
+        if let Some(handler) = websocket {
+            let mut websocket = HTTPWebSocket::new(
+                socket,
+                self.shutdown.clone(),
+                self.max_websocket_message_size,
+                self.body_read_timeout,
+                write_timeout,
+            );
+            handler.run(&mut websocket).await;
+            return websocket.finish().await;
+        }
+
+        match body {
+            Some(HTTPResponseBodyContent::Slice(body)) => {
+                timeout(socket.write(&body), write_timeout)
+                    .await?
+                    .unwrap_or(Err(timeout_error()))
+            }
+            Some(HTTPResponseBodyContent::Chunked(body)) => match stream {
+                Some(stream) => {
+                    let body = EncodedHTTPChunkedResponseBody::new(body, stream);
+                    send_chunked_body(socket, request.version, body, write_timeout).await
+                }
+                None => send_chunked_body(socket, request.version, body, write_timeout).await,
+            },
+
+            None => Ok(()),
+        }
+    }
+}
+
+impl<B: HTTPChunkedResponseBody> HTTPClient<B> {
+    /// Reads the next request from a client using HTTP/2 into `slot`
+    ///
+    /// Requests are read while the responses to earlier ones are being sent from other slots.
+    async fn read_http2<'a>(
+        &self,
+        connection: &HTTP2Connection,
+        slot: usize,
+        buffer: &'a mut HTTPRequestBuffer,
+    ) -> Result<Option<HTTPRequest<'a>>, HTTPParseError> {
+        let (id, request) = match connection.next_request(&self.socket).await {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(None),
+            Err((id, error)) => {
+                self.slot_mut(slot).stream = id;
+                self.close.set(true);
+                return Err(error);
+            }
+        };
+
+        let request = match buffer
+            .set_http2_request(request)
+            .parse(self.header_limits, self.max_body_size)
+        {
+            Ok(request) => request,
+            Err(error) => {
+                self.slot_mut(slot).stream = Some(id);
+                self.close.set(true);
+                return Err(error);
+            }
+        };
+
+        if self.start_request(slot, &request, Some(id)) {
+            connection.shut_down();
+        }
+
+        Ok(Some(request))
+    }
+
+    /// Sends a response to a client using HTTP/2 on the stream its request was sent on
+    ///
+    /// Closing the connection tells the client no more requests will be accepted, but the requests
+    /// it already sent are still answered.
+    async fn send_http2(
+        &self,
+        connection: &HTTP2Connection,
+        request: &Slot,
+        mut response: HTTPResponse<'_, B>,
+    ) -> Result<(), Error> {
+        if self.close.get() || response.closes_connection() {
+            connection.shut_down();
+        }
+
+        // Errors affecting the whole connection don't have a stream to answer them on
+        let id = match request.stream {
+            Some(id) => id,
+            None => return connection.flush(&self.socket).await,
+        };
+
+        let stream = match &self.compression {
+            Some(compression) => response.encode(compression, request.encoder.as_deref()),
+            None => None,
+        };
+
+        let status = response.status();
+        let (header, body) = response.generate_header(HTTPVersion::HTTP2);
+        let block = http2::encode_header(status, &header);
+
+        // Responses to "HEAD" requests describe the body without sending it
+        let body = if request.head { None } else { body };
+
+        let socket = &*self.socket;
+        connection
+            .send_headers(socket, id, &block, body.is_none())
+            .await?;
 
         match body {
             Some(HTTPResponseBodyContent::Slice(body)) => {
-                Timeout::new(async move { self.socket.write(&body).await }, write_timeout)?
-                    .await
-                    .unwrap_or(Err(Error::ETIMEDOUT))
+                connection.send_data(socket, id, &body, true).await
             }
-            Some(HTTPResponseBodyContent::Chunked(body)) => {
-                send_chunked(&mut self.socket, body, write_timeout).await
+            Some(HTTPResponseBodyContent::Chunked(body)) => match stream {
+                Some(stream) => {
+                    let body = EncodedHTTPChunkedResponseBody::new(body, stream);
+                    send_http2_chunked(connection, socket, id, body, self.write_timeout).await
+                }
+                None => send_http2_chunked(connection, socket, id, body, self.write_timeout).await,
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "http3")]
+impl<B: HTTPChunkedResponseBody> HTTPClient<B> {
+    /// Reads the next request from a client using HTTP/3 into `slot`
+    ///
+    /// Requests are read while the responses to earlier ones are being sent from other slots.
+    async fn read_http3<'a>(
+        &self,
+        connection: &HTTP3Connection,
+        slot: usize,
+        buffer: &'a mut HTTPRequestBuffer,
+    ) -> Result<Option<HTTPRequest<'a>>, HTTPParseError> {
+        let (id, request) = match connection.next_request().await {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(None),
+            Err((id, error)) => {
+                self.slot_mut(slot).quic_stream = id;
+                self.close.set(true);
+                return Err(error);
+            }
+        };
+
+        let request = match buffer
+            .set_http2_request(request)
+            .parse(self.header_limits, self.max_body_size)
+        {
+            Ok(request) => request,
+            Err(error) => {
+                self.slot_mut(slot).quic_stream = Some(id);
+                self.close.set(true);
+                return Err(error);
             }
+        };
+
+        if self.start_request(slot, &request, None) {
+            connection.shut_down();
+        }
+        self.slot_mut(slot).quic_stream = Some(id);
+
+        Ok(Some(request))
+    }
+
+    /// Sends a response to a client using HTTP/3 on the stream its request was sent on
+    ///
+    /// Closing the connection tells the client no more requests will be accepted, but the requests
+    /// it already sent are still answered. After a request which couldn't be read, the connection
+    /// is closed as soon as every response being sent is finished.
+    async fn send_http3(
+        &self,
+        connection: &HTTP3Connection,
+        request: &Slot,
+        mut response: HTTPResponse<'_, B>,
+    ) -> Result<(), Error> {
+        if response.closes_connection() {
+            connection.shut_down();
+        }
+
+        // Errors affecting the whole connection don't have a stream to answer them on
+        let id = match request.quic_stream {
+            Some(id) => id,
+            None => return connection.close_when_answered().await,
+        };
+
+        let stream = match &self.compression {
+            Some(compression) => response.encode(compression, request.encoder.as_deref()),
+            None => None,
+        };
+
+        let status = response.status();
+        let (header, body) = response.generate_header(HTTPVersion::HTTP3);
+        let block = http3::encode_header(status, &header);
+
+        // Responses to "HEAD" requests describe the body without sending it
+        let body = if request.head { None } else { body };
+
+        let result = self
+            .send_http3_response(connection, id, &block, body, stream)
+            .await;
+
+        // A response which failed part way through is still finished, so the connection isn't left
+        // waiting for it
+        connection.finish(id).await?;
+        result?;
 
+        if self.close.get() {
+            connection.close_when_answered().await?;
+        }
+        Ok(())
+    }
+
+    /// Sends the field section `block` and `body` of a response on the HTTP/3 stream `id`,
+    /// compressing the body with `stream` if it is chunked and compression was negotiated
+    async fn send_http3_response(
+        &self,
+        connection: &HTTP3Connection,
+        id: StreamId,
+        block: &[u8],
+        body: Option<HTTPResponseBodyContent<'_, B>>,
+        stream: Option<Box<dyn HTTPContentEncoderStream>>,
+    ) -> Result<(), Error> {
+        connection.send_headers(id, block, body.is_none()).await?;
+
+        match body {
+            Some(HTTPResponseBodyContent::Slice(body)) => {
+                connection.send_data(id, &body, true).await
+            }
+            Some(HTTPResponseBodyContent::Chunked(body)) => match stream {
+                Some(stream) => {
+                    let body = EncodedHTTPChunkedResponseBody::new(body, stream);
+                    send_http3_chunked(connection, id, body, self.write_timeout).await
+                }
+                None => send_http3_chunked(connection, id, body, self.write_timeout).await,
+            },
             None => Ok(()),
         }
     }
 }
 
+impl Slot {
+    /// Creates a [`Slot`] for before any request has been read into it
+    fn new() -> Self {
+        Slot {
+            version: HTTPVersion::HTTP11,
+            head: false,
+            websocket_upgrade: false,
+            encoder: None,
+            stream: None,
+            #[cfg(feature = "http3")]
+            quic_stream: None,
+        }
+    }
+}
+
 unsafe impl<B: HTTPChunkedResponseBody> Send for HTTPClient<B> {}
 unsafe impl<B: HTTPChunkedResponseBody> Sync for HTTPClient<B> {}
@@ -1,32 +1,96 @@
-use lasync::{
+use crate::Result;
+use huntsman::runtime::{
     io::{Read, Write},
     net::TCPStream,
 };
 
+#[cfg(feature = "http3")]
+use crate::http3::{self, HTTP3Connection};
+#[cfg(feature = "tls")]
+use crate::{tls::TLSStream, HTTPTLSInfo};
+#[cfg(feature = "http3")]
+use huntsman::runtime::protocol_error;
+#[cfg(feature = "http3")]
+use std::rc::Rc;
+
 /// A socket which is connected a client
 pub(crate) enum HTTPSocket {
     /// The connection is an insecure HTTP/1.1 connection
     HTTP(TCPStream),
+
+    /// The connection is an HTTP/1.1 connection secured with TLS
+    #[cfg(feature = "tls")]
+    HTTPS(Box<TLSStream>),
+
+    /// The connection is an HTTP/3 connection over QUIC
+    ///
+    /// QUIC connections aren't byte streams, so reading and writing them directly fails. Requests
+    /// and responses go through the [`HTTP3Connection`] instead.
+    #[cfg(feature = "http3")]
+    HTTP3(Rc<HTTP3Connection>),
 }
 
 impl HTTPSocket {
+    /// Completes the TLS handshake if this is a secure socket, returning the negotiated parameters
+    #[cfg(feature = "tls")]
+    pub(crate) async fn handshake(&mut self) -> Result<Option<HTTPTLSInfo>> {
+        match self {
+            HTTPSocket::HTTP(_) => Ok(None),
+            HTTPSocket::HTTPS(stream) => stream.handshake().await.map(Some),
+            #[cfg(feature = "http3")]
+            HTTPSocket::HTTP3(connection) => connection.handshake().await.map(Some),
+        }
+    }
+
+    /// Gets the protocol the client negotiated through ALPN if this is a secure socket
+    #[cfg(feature = "tls")]
+    pub(crate) fn alpn_protocol(&self) -> Option<&[u8]> {
+        match self {
+            HTTPSocket::HTTP(_) => None,
+            HTTPSocket::HTTPS(stream) => stream.alpn_protocol(),
+            #[cfg(feature = "http3")]
+            HTTPSocket::HTTP3(_) => Some(http3::ALPN),
+        }
+    }
+
+    /// Gets the HTTP/3 connection if this is a QUIC connection
+    #[cfg(feature = "http3")]
+    pub(crate) fn http3(&self) -> Option<Rc<HTTP3Connection>> {
+        match self {
+            HTTPSocket::HTTP3(connection) => Some(connection.clone()),
+            _ => None,
+        }
+    }
+
     /// Attempts to read bytes into `buffer` from the socket
-    pub(crate) async fn read(&mut self, buffer: &mut [u8]) -> Result<usize, lasync::Error> {
+    pub(crate) async fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
         match self {
             HTTPSocket::HTTP(stream) => stream.read(buffer).await,
+            #[cfg(feature = "tls")]
+            HTTPSocket::HTTPS(stream) => stream.read(buffer).await,
+            #[cfg(feature = "http3")]
+            HTTPSocket::HTTP3(_) => Err(protocol_error()),
         }
     }
     /// Attempts to fill `buffer` by reading bytes from the socket
-    pub(crate) async fn read_exact(&mut self, buffer: &mut [u8]) -> Result<(), lasync::Error> {
+    pub(crate) async fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
         match self {
             HTTPSocket::HTTP(stream) => stream.read_exact(buffer).await,
+            #[cfg(feature = "tls")]
+            HTTPSocket::HTTPS(stream) => stream.read_exact(buffer).await,
+            #[cfg(feature = "http3")]
+            HTTPSocket::HTTP3(_) => Err(protocol_error()),
         }
     }
 
     /// Attempts to write all bytes from `buffer` into the socket
-    pub(crate) async fn write(&mut self, buffer: &[u8]) -> Result<(), lasync::Error> {
+    pub(crate) async fn write(&mut self, buffer: &[u8]) -> Result<()> {
         match self {
             HTTPSocket::HTTP(stream) => stream.write_all(buffer).await,
+            #[cfg(feature = "tls")]
+            HTTPSocket::HTTPS(stream) => stream.write(buffer).await,
+            #[cfg(feature = "http3")]
+            HTTPSocket::HTTP3(_) => Err(protocol_error()),
         }
     }
 }
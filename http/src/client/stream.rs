@@ -1,23 +1,67 @@
-use super::{HTTPSocket, HeaderBuffer};
-use crate::HTTPParseError;
+//! The socket is only borrowed for each read or write, so a [`Stream`] can be kept by a streamed
+//! request body while the client uses the socket to send the response.
+
+#![allow(clippy::await_holding_refcell_ref)]
+
+use super::{HTTPRequestBuffer, HTTPSocket};
+use crate::{
+    request::{BodyFraming, HeaderLimits},
+    HTTPParseError,
+};
+use std::{cell::RefCell, rc::Rc};
 
 /// A stream of bytes from a [`TcpStream`]
-pub(crate) struct Stream<'a, 'b> {
+pub(crate) struct Stream<'a> {
     /// The buffer to improve read efficiency
-    buffer: &'a mut HeaderBuffer,
+    buffer: &'a mut HTTPRequestBuffer,
 
-    /// The stream to read from
-    socket: &'b mut HTTPSocket,
+    /// The stream to read from, shared with the client
+    socket: Rc<RefCell<HTTPSocket>>,
 }
 
-impl<'a, 'b> Stream<'a, 'b> {
+impl<'a> Stream<'a> {
     /// Creates a new [`Stream`]
-    pub(super) fn new(buffer: &'a mut HeaderBuffer, socket: &'b mut HTTPSocket) -> Self {
+    pub(super) fn new(buffer: &'a mut HTTPRequestBuffer, socket: Rc<RefCell<HTTPSocket>>) -> Self {
         buffer.reset();
 
         Stream { buffer, socket }
     }
 
+    /// Gets the limits on the headers parsed from this stream
+    pub(crate) fn limits(&self) -> HeaderLimits {
+        self.buffer.limits()
+    }
+
+    /// Gets the framing of the body of the current request
+    pub(crate) fn body_framing(&self) -> BodyFraming {
+        self.buffer.body_framing()
+    }
+
+    /// Sets the framing of the body of the current request
+    pub(crate) fn set_body_framing(&mut self, body_framing: BodyFraming) {
+        self.buffer.set_body_framing(body_framing);
+    }
+
+    /// Is the client waiting for a "100 Continue" before sending the body of the current request?
+    pub(crate) fn continue_pending(&self) -> bool {
+        self.buffer.continue_pending()
+    }
+
+    /// Sets if the client is waiting for a "100 Continue" before sending the current body
+    pub(crate) fn set_continue_pending(&mut self, continue_pending: bool) {
+        self.buffer.set_continue_pending(continue_pending);
+    }
+
+    /// Writes all of `buffer` to the client
+    pub(crate) async fn write(&mut self, buffer: &[u8]) -> crate::Result<()> {
+        self.socket.borrow_mut().write(buffer).await
+    }
+
+    /// Resets the buffer so that parsing starts after the bytes parsed so far
+    pub(crate) fn reset(&mut self) {
+        self.buffer.reset();
+    }
+
     /// Gets the number of bytes parsed so far
     pub(crate) fn len(&self) -> usize {
         self.buffer.index()
@@ -25,11 +69,11 @@ impl<'a, 'b> Stream<'a, 'b> {
 
     /// Reads the next value from the stream without consuming it
     pub(crate) async fn peek(&mut self) -> Result<u8, HTTPParseError> {
-        self.buffer.peek(self.socket).await
+        self.buffer.peek(&mut self.socket.borrow_mut()).await
     }
 
     pub(crate) async fn next(&mut self) -> Result<u8, HTTPParseError> {
-        self.buffer.next(self.socket).await
+        self.buffer.next(&mut self.socket.borrow_mut()).await
     }
 
     /// Skips any whitespace (space or tab) characters in the stream until reaching a
@@ -45,35 +89,6 @@ impl<'a, 'b> Stream<'a, 'b> {
         Ok(())
     }
 
-    /// Attempts to collect bytes from the stream until a `predicate` returns true
-    ///
-    /// This function will provide the `predicate` with the next two bytes from the stream. The
-    /// predicate will still be called for every byte, meaning the second parameter passed to the
-    /// predicate in one call will be the first parameter in the next call.
-    ///
-    /// This function returns a slice with the bytes up to and including the second byte the
-    /// `predicate` returns true on. The next character returned by this stream will be the one
-    /// following the character the predicate returned true on.
-    ///
-    /// If the end of the buffer is reached before the `predicate` returns true, the function will
-    /// return an [`HTTPParseError::HeadersTooLong`]
-    pub(crate) async fn collect_until_predicate_double<F: Fn(u8, u8) -> bool>(
-        &mut self,
-        predicate: F,
-    ) -> Result<&'a [u8], HTTPParseError> {
-        let start = self.buffer.index();
-
-        let mut prev = self.next().await?;
-        let mut next = self.next().await?;
-
-        while !predicate(prev, next) {
-            prev = next;
-            next = self.next().await?;
-        }
-
-        Ok(unsafe { self.buffer.subslice(start, self.buffer.index()) })
-    }
-
     /// Attempts to collect bytes from the stream until a `predicate` returns true
     ///
     /// This function returns a slice with the bytes up to and including the byte the `predicate`
@@ -93,68 +108,59 @@ impl<'a, 'b> Stream<'a, 'b> {
         Ok(unsafe { self.buffer.subslice(start, self.buffer.index()) })
     }
 
-    /// Attempts to collect bytes from the stream until a `predicate` returns true
+    /// Attempts to collect a line ending with a carriage return and line feed from the stream
     ///
-    /// This function returns a slice with the bytes up to and including the byte the `predicate`
-    /// returns true on. The next character returned by this stream will be the one following the
-    /// character the predicate returned true on.
+    /// This function returns a slice with the bytes before the line ending. The next character
+    /// returned by this stream will be the one following the line ending.
     ///
-    /// If the end of the buffer is reached before the `predicate` returns true, the function will
-    /// return an [`HTTPParseError::HeadersTooLong`]
-    pub(crate) async fn collect_until_predicate<F: Fn(u8) -> bool>(
-        &mut self,
-        predicate: F,
-    ) -> Result<&'a [u8], HTTPParseError> {
-        self.collect_until_predicate_error(|value| Ok(predicate(value)))
-            .await
-    }
+    /// Returns an [`HTTPParseError::BareLineFeed`] or an [`HTTPParseError::BareCarriageReturn`] if
+    /// the line ends with only one of the two.
+    pub(crate) async fn collect_line(&mut self) -> Result<&'a [u8], HTTPParseError> {
+        let line = self
+            .collect_until_predicate_error(|c| match c {
+                b'\r' => Ok(true),
+                b'\n' => Err(HTTPParseError::BareLineFeed),
+                _ => Ok(false),
+            })
+            .await?;
+
+        if self.next().await? != b'\n' {
+            return Err(HTTPParseError::BareCarriageReturn);
+        }
 
-    /// Attempts to collect bytes from the stream until a `c` byte is encountered
-    ///
-    /// This function returns a slice with the bytes up to and including `c`. The next character
-    /// returned by this stream will be the one following `c`.
-    ///
-    /// If the end of the buffer is reached before a `c` byte is encountered, the function will
-    /// return an [`HTTPParseError::HeadersTooLong`]
-    pub(crate) async fn collect_until(&mut self, c: u8) -> Result<&'a [u8], HTTPParseError> {
-        self.collect_until_predicate(|value| c == value).await
+        Ok(&line[..line.len() - 1])
     }
 
-    /// Attempts to collect bytes from the stream until `a` followed by `b` is encountered.
+    /// Moves bytes from the stream into `body`, returning the number of bytes moved
     ///
-    /// This function returns a slice with the bytes up to and including the `b` encountered. The
-    /// next character returned by this stream will be the one following that `b`.
-    ///
-    /// If the end of the buffer is reached before the pattern is matched, the function will return
-    /// an [`HTTPParseError::HeadersTooLong`]
-    pub(crate) async fn collect_until_double(
-        &mut self,
-        a: u8,
-        b: u8,
-    ) -> Result<&'a [u8], HTTPParseError> {
-        self.collect_until_predicate_double(|na, nb| na == a && nb == b)
-            .await
+    /// Unlike the `collect` functions, the moved bytes don't take up space in the buffer.
+    pub(crate) async fn take(&mut self, body: &mut [u8]) -> Result<usize, HTTPParseError> {
+        self.buffer.take(&mut self.socket.borrow_mut(), body).await
     }
 
-    /// Attempts to collect bytes from the stream until a newline ("\r\n") is encountered.
+    /// Removes the bytes parsed since `start` from the buffer
     ///
-    /// This function returns a slice with the bytes up to and including the newline that was
-    /// encountered. The next character returned by this stream will be the one following the
-    /// newline.
-    ///
-    /// If the end of the buffer is reached before a newline is found, the function will return an
-    /// [`HTTPParseError::HeadersTooLong`]
-    pub(crate) async fn collect_until_newline(&mut self) -> Result<&'a [u8], HTTPParseError> {
-        self.collect_until_double(b'\r', b'\n').await
+    /// # SAFETY
+    /// Any slices previously collected from past `start` must no longer be used.
+    pub(crate) unsafe fn discard(&mut self, start: usize) {
+        self.buffer.discard(start);
     }
 
-    /// Creates a buffer for the body, copies any data currently in the header buffer for the body,
-    /// and returns the body buffer and the number of bytes copied.
-    pub(crate) fn body(self, content_length: usize) -> (&'b mut HTTPSocket, Box<[u8]>, usize) {
+    /// Reads a body of `content_length` bytes, starting with any of it already in the buffer
+    pub(crate) async fn read_exact_body(
+        &mut self,
+        content_length: usize,
+    ) -> Result<Box<[u8]>, HTTPParseError> {
         let mut buffer = vec![0; content_length].into_boxed_slice();
 
         let length = self.buffer.copy_body(&mut buffer);
+        if length < buffer.len() {
+            self.socket
+                .borrow_mut()
+                .read_exact(&mut buffer[length..])
+                .await?;
+        }
 
-        (self.socket, buffer, length)
+        Ok(buffer)
     }
 }
@@ -0,0 +1,51 @@
+use crate::{Error, HTTPChunkedResponseBody, HTTPContentEncoderStream};
+
+/// A chunked response body which is encoded as it is sent
+pub(crate) struct EncodedHTTPChunkedResponseBody<B: HTTPChunkedResponseBody> {
+    /// The body being encoded
+    body: B,
+
+    /// The encoding of the body
+    stream: Box<dyn HTTPContentEncoderStream>,
+
+    /// The encoded chunk to be sent next
+    output: Vec<u8>,
+
+    /// Has the encoding been finished?
+    finished: bool,
+}
+
+impl<B: HTTPChunkedResponseBody> EncodedHTTPChunkedResponseBody<B> {
+    /// Creates a new [`EncodedHTTPChunkedResponseBody`] which encodes `body` with `stream`
+    pub(crate) fn new(body: B, stream: Box<dyn HTTPContentEncoderStream>) -> Self {
+        EncodedHTTPChunkedResponseBody {
+            body,
+            stream,
+            output: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<B: HTTPChunkedResponseBody> HTTPChunkedResponseBody for EncodedHTTPChunkedResponseBody<B> {
+    async fn next(&mut self) -> Result<Option<&[u8]>, Error> {
+        self.output.clear();
+
+        // Encoders can hold back their output, so keep going until there is something to send
+        while self.output.is_empty() {
+            if self.finished {
+                return Ok(None);
+            }
+
+            match self.body.next().await? {
+                Some(chunk) if !chunk.is_empty() => self.stream.write(chunk, &mut self.output),
+                _ => {
+                    self.stream.finish(&mut self.output);
+                    self.finished = true;
+                }
+            }
+        }
+
+        Ok(Some(&self.output))
+    }
+}
@@ -0,0 +1,668 @@
+use super::inflate::{CODE_LENGTH_ORDER, DISTANCE_BASE, DISTANCE_EXTRA, LENGTH_BASE, LENGTH_EXTRA};
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// The size of the window matches can be found in
+const WINDOW_SIZE: usize = 32768;
+
+/// The number of bits in a hash of three bytes
+const HASH_BITS: u32 = 15;
+
+/// The shortest match which can be encoded
+const MIN_MATCH: usize = 3;
+
+/// The longest match which can be encoded
+const MAX_MATCH: usize = 258;
+
+/// The most earlier positions checked when looking for a match
+const MAX_CHAIN: usize = 64;
+
+/// Matches shorter than this are given up if a longer one starts at the next byte
+const LAZY_LENGTH: usize = 32;
+
+/// The most symbols put into one block before starting another
+const MAX_BLOCK_SYMBOLS: usize = 16384;
+
+/// The longest stored block
+const MAX_STORED: usize = 65535;
+
+/// The end of block symbol
+const END_OF_BLOCK: usize = 256;
+
+/// The number of literal and length symbols which can be used
+const LITERAL_SYMBOLS: usize = 286;
+
+/// The number of distance symbols which can be used
+const DISTANCE_SYMBOLS: usize = 30;
+
+/// The longest code allowed for literals, lengths and distances
+const MAX_CODE_LENGTH: usize = 15;
+
+/// The longest code allowed for code lengths
+const MAX_CODE_LENGTH_LENGTH: usize = 7;
+
+/// Compresses data into a raw deflate stream as it is written
+///
+/// Each write is compressed into its own blocks, with matches reaching back into earlier writes.
+/// Every block is sent stored, with the fixed Huffman codes or with its own Huffman codes,
+/// whichever is shortest.
+pub(super) struct Deflater {
+    /// The most recent bytes written, which matches can be found in
+    window: Vec<u8>,
+
+    /// The position in the stream of the first byte in `window`
+    base: usize,
+
+    /// The position after the most recent position for each hash, or 0 if there is none
+    head: Box<[usize]>,
+
+    /// The position after the previous position with the same hash for each position in the
+    /// window, or 0 if there is none
+    prev: Box<[usize]>,
+
+    /// The bits written but not yet output
+    writer: BitWriter,
+}
+
+/// A literal byte or a match in a block
+#[derive(Debug, Clone, Copy)]
+enum Symbol {
+    /// A byte which didn't match anything earlier
+    Literal(u8),
+
+    /// A copy of `length` earlier bytes starting `distance` back
+    Match { length: u16, distance: u16 },
+}
+
+/// Writes bits to a deflate stream, least significant bit first
+struct BitWriter {
+    /// The bits written but not yet output
+    bits: u32,
+
+    /// The number of bits in `bits`
+    count: u32,
+}
+
+impl Deflater {
+    /// Creates a new [`Deflater`]
+    pub(super) fn new() -> Self {
+        Deflater {
+            window: Vec::new(),
+            base: 0,
+            head: vec![0; 1 << HASH_BITS].into_boxed_slice(),
+            prev: vec![0; WINDOW_SIZE].into_boxed_slice(),
+            writer: BitWriter { bits: 0, count: 0 },
+        }
+    }
+
+    /// Compresses `input` into one or more blocks, appending them to `output`
+    ///
+    /// The final bits of the last block are held back until the next block or the end of the
+    /// stream.
+    pub(super) fn write(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        if input.is_empty() {
+            return;
+        }
+
+        let mut index = self.window.len();
+        self.window.extend_from_slice(input);
+
+        let mut symbols = Vec::new();
+        let mut start = index;
+        while index < self.window.len() {
+            let (length, distance) = self.longest_match(index);
+            self.insert(index);
+
+            // Lazy matching: a literal followed by a longer match is better than a short match
+            if length < MIN_MATCH
+                || (length < LAZY_LENGTH && self.longest_match(index + 1).0 > length)
+            {
+                symbols.push(Symbol::Literal(self.window[index]));
+                index += 1;
+            } else {
+                symbols.push(Symbol::Match {
+                    length: length as u16,
+                    distance: distance as u16,
+                });
+                for i in index + 1..index + length {
+                    self.insert(i);
+                }
+                index += length;
+            }
+
+            if symbols.len() == MAX_BLOCK_SYMBOLS {
+                self.writer
+                    .write_block(&self.window[start..index], &symbols, output);
+                symbols.clear();
+                start = index;
+            }
+        }
+
+        if !symbols.is_empty() {
+            self.writer
+                .write_block(&self.window[start..index], &symbols, output);
+        }
+
+        if self.window.len() > WINDOW_SIZE * 2 {
+            let remove = self.window.len() - WINDOW_SIZE;
+            self.window.drain(..remove);
+            self.base += remove;
+        }
+    }
+
+    /// Ends the stream with an empty final block, appending the rest of it to `output`
+    pub(super) fn finish(&mut self, output: &mut Vec<u8>) {
+        // A final block with the fixed codes, where the end of block code is seven zero bits
+        self.writer.write_bits(0b011, 3, output);
+        self.writer.write_bits(0, 7, output);
+        self.writer.align(output);
+    }
+
+    /// Gets the hash of the three bytes at `index` in the window
+    fn hash(&self, index: usize) -> usize {
+        let value = (self.window[index] as u32) << 16
+            | (self.window[index + 1] as u32) << 8
+            | self.window[index + 2] as u32;
+
+        (value.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+    }
+
+    /// Records that the bytes at `index` in the window can be matched
+    fn insert(&mut self, index: usize) {
+        if index + MIN_MATCH > self.window.len() {
+            return;
+        }
+
+        let hash = self.hash(index);
+        let position = self.base + index;
+        self.prev[position % WINDOW_SIZE] = self.head[hash];
+        self.head[hash] = position + 1;
+    }
+
+    /// Finds the longest earlier match for the bytes at `index` in the window
+    ///
+    /// Returns the tuple `(length, distance)`, with a length of 0 if there is no match.
+    fn longest_match(&self, index: usize) -> (usize, usize) {
+        if index + MIN_MATCH > self.window.len() {
+            return (0, 0);
+        }
+
+        let position = self.base + index;
+        let max_length = MAX_MATCH.min(self.window.len() - index);
+
+        let mut best = (0, 0);
+        let mut candidate = self.head[self.hash(index)];
+        for _ in 0..MAX_CHAIN {
+            if candidate == 0
+                || candidate - 1 < self.base
+                || position - (candidate - 1) > WINDOW_SIZE
+            {
+                break;
+            }
+
+            let start = candidate - 1 - self.base;
+            let length = self.window[start..start + max_length]
+                .iter()
+                .zip(&self.window[index..index + max_length])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if length > best.0 {
+                best = (length, index - start);
+                if length == max_length {
+                    break;
+                }
+            }
+
+            let next = self.prev[(candidate - 1) % WINDOW_SIZE];
+            if next >= candidate {
+                break;
+            }
+            candidate = next;
+        }
+
+        best
+    }
+}
+
+impl Symbol {
+    /// Gets the literal or length symbol and the distance symbol, if there is one
+    fn codes(&self) -> (usize, Option<usize>) {
+        match *self {
+            Symbol::Literal(byte) => (byte as usize, None),
+            Symbol::Match { length, distance } => {
+                (257 + length_symbol(length), Some(distance_symbol(distance)))
+            }
+        }
+    }
+
+    /// Gets the number of extra bits sent after the codes for this symbol
+    fn extra_bits(&self) -> usize {
+        match *self {
+            Symbol::Literal(_) => 0,
+            Symbol::Match { length, distance } => {
+                LENGTH_EXTRA[length_symbol(length)] as usize
+                    + DISTANCE_EXTRA[distance_symbol(distance)] as usize
+            }
+        }
+    }
+}
+
+impl BitWriter {
+    /// Writes `input`, which is encoded as `symbols`, as blocks in whichever way is shortest
+    fn write_block(&mut self, input: &[u8], symbols: &[Symbol], output: &mut Vec<u8>) {
+        let mut literal_frequencies = [0; LITERAL_SYMBOLS];
+        let mut distance_frequencies = [0; DISTANCE_SYMBOLS];
+        literal_frequencies[END_OF_BLOCK] = 1;
+        let mut extra_bits = 0;
+        for symbol in symbols {
+            let (literal, distance) = symbol.codes();
+            literal_frequencies[literal] += 1;
+            if let Some(distance) = distance {
+                distance_frequencies[distance] += 1;
+            }
+            extra_bits += symbol.extra_bits();
+        }
+
+        let fixed_literal_lengths = fixed_literal_lengths();
+        let fixed_distance_lengths = [5; DISTANCE_SYMBOLS];
+        let fixed_size = 3
+            + extra_bits
+            + cost(&literal_frequencies, &fixed_literal_lengths)
+            + cost(&distance_frequencies, &fixed_distance_lengths);
+
+        let literal_lengths = code_lengths(&literal_frequencies, MAX_CODE_LENGTH);
+        let mut distance_lengths = code_lengths(&distance_frequencies, MAX_CODE_LENGTH);
+        if distance_lengths.iter().all(|length| *length == 0) {
+            // At least one distance code has to be sent, even if it is never used
+            distance_lengths[0] = 1;
+        }
+        let header = DynamicHeader::new(&literal_lengths, &distance_lengths);
+        let dynamic_size = 3
+            + header.size()
+            + extra_bits
+            + cost(&literal_frequencies, &literal_lengths)
+            + cost(&distance_frequencies, &distance_lengths);
+
+        let stored_size = input.len().div_ceil(MAX_STORED) * (3 + 7 + 32) + input.len() * 8;
+
+        if stored_size < fixed_size.min(dynamic_size) {
+            self.write_stored(input, output);
+        } else if dynamic_size < fixed_size {
+            self.write_bits(0b100, 3, output);
+            header.write(self, output);
+            self.write_symbols(symbols, &literal_lengths, &distance_lengths, output);
+        } else {
+            self.write_bits(0b010, 3, output);
+            self.write_symbols(
+                symbols,
+                &fixed_literal_lengths,
+                &fixed_distance_lengths,
+                output,
+            );
+        }
+    }
+
+    /// Writes `input` as stored blocks
+    fn write_stored(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        for chunk in input.chunks(MAX_STORED) {
+            self.write_bits(0b000, 3, output);
+            self.align(output);
+
+            let length = chunk.len() as u16;
+            output.extend_from_slice(&length.to_le_bytes());
+            output.extend_from_slice(&(!length).to_le_bytes());
+            output.extend_from_slice(chunk);
+        }
+    }
+
+    /// Writes `symbols` and the end of block symbol using the codes with the given lengths
+    fn write_symbols(
+        &mut self,
+        symbols: &[Symbol],
+        literal_lengths: &[u8],
+        distance_lengths: &[u8],
+        output: &mut Vec<u8>,
+    ) {
+        let literal_codes = canonical_codes(literal_lengths);
+        let distance_codes = canonical_codes(distance_lengths);
+
+        for symbol in symbols {
+            match *symbol {
+                Symbol::Literal(byte) => self.write_code(
+                    literal_codes[byte as usize],
+                    literal_lengths[byte as usize],
+                    output,
+                ),
+                Symbol::Match { length, distance } => {
+                    let symbol = length_symbol(length);
+                    self.write_code(
+                        literal_codes[257 + symbol],
+                        literal_lengths[257 + symbol],
+                        output,
+                    );
+                    self.write_bits(
+                        (length - LENGTH_BASE[symbol]) as u32,
+                        LENGTH_EXTRA[symbol] as u32,
+                        output,
+                    );
+
+                    let symbol = distance_symbol(distance);
+                    self.write_code(distance_codes[symbol], distance_lengths[symbol], output);
+                    self.write_bits(
+                        (distance - DISTANCE_BASE[symbol]) as u32,
+                        DISTANCE_EXTRA[symbol] as u32,
+                        output,
+                    );
+                }
+            }
+        }
+
+        self.write_code(
+            literal_codes[END_OF_BLOCK],
+            literal_lengths[END_OF_BLOCK],
+            output,
+        );
+    }
+
+    /// Outputs any bits written so far, padding them to a whole byte with zeros
+    fn align(&mut self, output: &mut Vec<u8>) {
+        if self.count > 0 {
+            output.push(self.bits as u8);
+            self.bits = 0;
+            self.count = 0;
+        }
+    }
+
+    /// Writes a Huffman code, which is sent starting from its most significant bit
+    fn write_code(&mut self, code: u16, length: u8, output: &mut Vec<u8>) {
+        let length = length as u32;
+        self.write_bits(
+            (code as u32).reverse_bits() >> (32 - length),
+            length,
+            output,
+        );
+    }
+
+    /// Writes the lowest `count` bits of `value`, starting from the least significant bit
+    fn write_bits(&mut self, value: u32, count: u32, output: &mut Vec<u8>) {
+        self.bits |= value << self.count;
+        self.count += count;
+
+        while self.count >= 8 {
+            output.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+}
+
+/// The description of the codes used by a dynamic block, sent at its start
+struct DynamicHeader {
+    /// The number of literal and length codes sent
+    literal_count: usize,
+
+    /// The number of distance codes sent
+    distance_count: usize,
+
+    /// The code lengths of the literal and distance codes, as `(symbol, extra)` pairs of the
+    /// code length alphabet
+    lengths: Vec<(u8, u8)>,
+
+    /// The lengths of the codes for each code length symbol
+    code_length_lengths: Vec<u8>,
+
+    /// The number of code length code lengths sent
+    code_length_count: usize,
+}
+
+impl DynamicHeader {
+    /// Creates the header describing the codes with `literal_lengths` and `distance_lengths`
+    fn new(literal_lengths: &[u8], distance_lengths: &[u8]) -> Self {
+        let literal_count = used(literal_lengths).max(257);
+        let distance_count = used(distance_lengths).max(1);
+
+        let mut all_lengths = literal_lengths[..literal_count].to_vec();
+        all_lengths.extend_from_slice(&distance_lengths[..distance_count]);
+        let lengths = run_lengths(&all_lengths);
+
+        let mut frequencies = [0; 19];
+        for (symbol, _) in &lengths {
+            frequencies[*symbol as usize] += 1;
+        }
+        let code_length_lengths = code_lengths(&frequencies, MAX_CODE_LENGTH_LENGTH);
+
+        let code_length_count = CODE_LENGTH_ORDER
+            .iter()
+            .rposition(|symbol| code_length_lengths[*symbol] > 0)
+            .map(|index| index + 1)
+            .unwrap_or(0)
+            .max(4);
+
+        DynamicHeader {
+            literal_count,
+            distance_count,
+            lengths,
+            code_length_lengths,
+            code_length_count,
+        }
+    }
+
+    /// Gets the number of bits in this header
+    fn size(&self) -> usize {
+        5 + 5
+            + 4
+            + self.code_length_count * 3
+            + self
+                .lengths
+                .iter()
+                .map(|(symbol, _)| {
+                    self.code_length_lengths[*symbol as usize] as usize
+                        + code_length_extra(*symbol) as usize
+                })
+                .sum::<usize>()
+    }
+
+    /// Writes this header to `writer`
+    fn write(&self, writer: &mut BitWriter, output: &mut Vec<u8>) {
+        writer.write_bits((self.literal_count - 257) as u32, 5, output);
+        writer.write_bits((self.distance_count - 1) as u32, 5, output);
+        writer.write_bits((self.code_length_count - 4) as u32, 4, output);
+        for symbol in &CODE_LENGTH_ORDER[..self.code_length_count] {
+            writer.write_bits(self.code_length_lengths[*symbol] as u32, 3, output);
+        }
+
+        let codes = canonical_codes(&self.code_length_lengths);
+        for (symbol, extra) in &self.lengths {
+            let symbol = *symbol as usize;
+            writer.write_code(codes[symbol], self.code_length_lengths[symbol], output);
+            writer.write_bits(*extra as u32, code_length_extra(symbol as u8), output);
+        }
+    }
+}
+
+/// Gets the index of the length symbol for a match of `length` bytes
+fn length_symbol(length: u16) -> usize {
+    LENGTH_BASE
+        .iter()
+        .rposition(|base| *base <= length)
+        .unwrap()
+}
+
+/// Gets the distance symbol for a match `distance` bytes back
+fn distance_symbol(distance: u16) -> usize {
+    DISTANCE_BASE
+        .iter()
+        .rposition(|base| *base <= distance)
+        .unwrap()
+}
+
+/// Gets the number of extra bits sent after a code length `symbol`
+fn code_length_extra(symbol: u8) -> u32 {
+    match symbol {
+        16 => 2,
+        17 => 3,
+        18 => 7,
+        _ => 0,
+    }
+}
+
+/// Gets the lengths of the fixed literal and length codes
+fn fixed_literal_lengths() -> [u8; 288] {
+    let mut lengths = [0; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    lengths
+}
+
+/// Gets the number of bits needed to send symbols with `frequencies` using codes of `lengths`
+fn cost(frequencies: &[usize], lengths: &[u8]) -> usize {
+    frequencies
+        .iter()
+        .zip(lengths)
+        .map(|(frequency, length)| frequency * *length as usize)
+        .sum()
+}
+
+/// Gets the number of symbols up to and including the last one with a code
+fn used(lengths: &[u8]) -> usize {
+    lengths
+        .iter()
+        .rposition(|length| *length > 0)
+        .map(|index| index + 1)
+        .unwrap_or(0)
+}
+
+/// Builds the lengths of a Huffman code for symbols with `frequencies`
+///
+/// No code is longer than `max_length`. Symbols which aren't used get no code.
+fn code_lengths(frequencies: &[usize], max_length: usize) -> Vec<u8> {
+    let mut frequencies = frequencies.to_vec();
+    let mut lengths = vec![0; frequencies.len()];
+
+    loop {
+        let symbols: Vec<usize> = (0..frequencies.len())
+            .filter(|symbol| frequencies[*symbol] > 0)
+            .collect();
+
+        match symbols.len() {
+            0 => return lengths,
+            1 => {
+                lengths[symbols[0]] = 1;
+                return lengths;
+            }
+            _ => {}
+        }
+
+        // The leaves are the first nodes and every other node is made after its children
+        let mut parents = vec![0; symbols.len()];
+        let mut nodes: BinaryHeap<_> = symbols
+            .iter()
+            .enumerate()
+            .map(|(node, symbol)| Reverse((frequencies[*symbol], node)))
+            .collect();
+
+        while nodes.len() > 1 {
+            let Reverse((a_frequency, a)) = nodes.pop().unwrap();
+            let Reverse((b_frequency, b)) = nodes.pop().unwrap();
+
+            let node = parents.len();
+            parents.push(node);
+            parents[a] = node;
+            parents[b] = node;
+            nodes.push(Reverse((a_frequency + b_frequency, node)));
+        }
+
+        let root = parents.len() - 1;
+        let mut depths = vec![0; parents.len()];
+        for node in (0..root).rev() {
+            depths[node] = depths[parents[node]] + 1;
+        }
+
+        if depths[..symbols.len()]
+            .iter()
+            .all(|depth| *depth <= max_length)
+        {
+            for (symbol, depth) in symbols.iter().zip(&depths) {
+                lengths[*symbol] = *depth as u8;
+            }
+            return lengths;
+        }
+
+        // Flatten the frequencies until the tree is shallow enough
+        for frequency in &mut frequencies {
+            if *frequency > 0 {
+                *frequency = frequency.div_ceil(2);
+            }
+        }
+    }
+}
+
+/// Assigns the canonical Huffman codes for symbols with code `lengths`
+fn canonical_codes(lengths: &[u8]) -> Vec<u16> {
+    let mut counts = [0; MAX_CODE_LENGTH + 1];
+    for length in lengths {
+        counts[*length as usize] += 1;
+    }
+    counts[0] = 0;
+
+    let mut next = [0; MAX_CODE_LENGTH + 1];
+    let mut code = 0;
+    for length in 1..=MAX_CODE_LENGTH {
+        code = (code + counts[length - 1]) << 1;
+        next[length] = code;
+    }
+
+    lengths
+        .iter()
+        .map(|length| {
+            let code = next[*length as usize];
+            next[*length as usize] += 1;
+            code
+        })
+        .collect()
+}
+
+/// Encodes code `lengths` using the run lengths of the code length alphabet
+///
+/// Returns `(symbol, extra)` pairs, where `extra` is the value of the extra bits of `symbol`.
+fn run_lengths(lengths: &[u8]) -> Vec<(u8, u8)> {
+    let mut runs = Vec::new();
+
+    let mut index = 0;
+    while index < lengths.len() {
+        let length = lengths[index];
+        let mut run = lengths[index..]
+            .iter()
+            .take_while(|other| **other == length)
+            .count();
+        index += run;
+
+        if length == 0 {
+            while run >= 11 {
+                let repeat = run.min(138);
+                runs.push((18, (repeat - 11) as u8));
+                run -= repeat;
+            }
+
+            if run >= 3 {
+                runs.push((17, (run - 3) as u8));
+                run = 0;
+            }
+        } else {
+            runs.push((length, 0));
+            run -= 1;
+
+            while run >= 3 {
+                let repeat = run.min(6);
+                runs.push((16, (repeat - 3) as u8));
+                run -= repeat;
+            }
+        }
+
+        runs.extend(std::iter::repeat_n((length, 0), run));
+    }
+
+    runs
+}
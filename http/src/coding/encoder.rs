@@ -0,0 +1,143 @@
+use super::{deflate::Deflater, gzip::update_crc32, zlib::update_adler32};
+
+/// A content coding which can be applied to response bodies
+///
+/// Encoders are chosen for a response based on the "Accept-Encoding" field of the request.
+pub trait HTTPContentEncoder: 'static + Send + Sync {
+    /// Gets the name of the coding, as it is written in "Accept-Encoding" and "Content-Encoding"
+    fn coding(&self) -> &'static [u8];
+
+    /// Starts encoding a new body
+    fn start(&self) -> Box<dyn HTTPContentEncoderStream>;
+}
+
+/// The encoding of a single response body, which is written as it is produced
+pub trait HTTPContentEncoderStream {
+    /// Encodes `input`, appending the result to `output`
+    ///
+    /// Encoders are free to hold back some of the result until later writes.
+    fn write(&mut self, input: &[u8], output: &mut Vec<u8>);
+
+    /// Ends the body, appending the rest of the result to `output`
+    fn finish(&mut self, output: &mut Vec<u8>);
+}
+
+/// The "gzip" content coding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HTTPGzipEncoder;
+
+/// The "deflate" content coding, which is a zlib stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HTTPDeflateEncoder;
+
+/// A body being encoded with [`HTTPGzipEncoder`]
+struct GzipStream {
+    /// The compressor for the body
+    deflater: Deflater,
+
+    /// Has the header been written?
+    started: bool,
+
+    /// The CRC-32 of the body so far
+    crc: u32,
+
+    /// The length of the body so far
+    size: u32,
+}
+
+/// A body being encoded with [`HTTPDeflateEncoder`]
+struct DeflateStream {
+    /// The compressor for the body
+    deflater: Deflater,
+
+    /// Has the header been written?
+    started: bool,
+
+    /// The Adler-32 checksum of the body so far
+    adler: u32,
+}
+
+/// The header written at the start of gzip bodies
+const GZIP_HEADER: [u8; 10] = [0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 0xFF];
+
+/// The header written at the start of zlib bodies
+const ZLIB_HEADER: [u8; 2] = [0x78, 0x9C];
+
+impl HTTPContentEncoder for HTTPGzipEncoder {
+    fn coding(&self) -> &'static [u8] {
+        b"gzip"
+    }
+
+    fn start(&self) -> Box<dyn HTTPContentEncoderStream> {
+        Box::new(GzipStream {
+            deflater: Deflater::new(),
+            started: false,
+            crc: 0,
+            size: 0,
+        })
+    }
+}
+
+impl HTTPContentEncoder for HTTPDeflateEncoder {
+    fn coding(&self) -> &'static [u8] {
+        b"deflate"
+    }
+
+    fn start(&self) -> Box<dyn HTTPContentEncoderStream> {
+        Box::new(DeflateStream {
+            deflater: Deflater::new(),
+            started: false,
+            adler: 1,
+        })
+    }
+}
+
+impl GzipStream {
+    /// Writes the header if it hasn't been written yet
+    fn start(&mut self, output: &mut Vec<u8>) {
+        if !self.started {
+            output.extend_from_slice(&GZIP_HEADER);
+            self.started = true;
+        }
+    }
+}
+
+impl HTTPContentEncoderStream for GzipStream {
+    fn write(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        self.start(output);
+        self.crc = update_crc32(self.crc, input);
+        self.size = self.size.wrapping_add(input.len() as u32);
+        self.deflater.write(input, output);
+    }
+
+    fn finish(&mut self, output: &mut Vec<u8>) {
+        self.start(output);
+        self.deflater.finish(output);
+        output.extend_from_slice(&self.crc.to_le_bytes());
+        output.extend_from_slice(&self.size.to_le_bytes());
+    }
+}
+
+impl DeflateStream {
+    /// Writes the header if it hasn't been written yet
+    fn start(&mut self, output: &mut Vec<u8>) {
+        if !self.started {
+            output.extend_from_slice(&ZLIB_HEADER);
+            self.started = true;
+        }
+    }
+}
+
+impl HTTPContentEncoderStream for DeflateStream {
+    fn write(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        self.start(output);
+        self.adler = update_adler32(self.adler, input);
+        self.deflater.write(input, output);
+    }
+
+    fn finish(&mut self, output: &mut Vec<u8>) {
+        self.start(output);
+        self.deflater.finish(output);
+        output.extend_from_slice(&self.adler.to_be_bytes());
+    }
+}
@@ -0,0 +1,115 @@
+use super::{inflate::inflate, DecodeError};
+
+/// The bytes every gzip member starts with
+const MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// The compression method for deflate
+const DEFLATE: u8 = 8;
+
+/// The flag for a header checksum
+const FLAG_HCRC: u8 = 1 << 1;
+
+/// The flag for extra fields
+const FLAG_EXTRA: u8 = 1 << 2;
+
+/// The flag for an original file name
+const FLAG_NAME: u8 = 1 << 3;
+
+/// The flag for a comment
+const FLAG_COMMENT: u8 = 1 << 4;
+
+/// Decompresses the gzip members in `input`
+///
+/// The result is not allowed to be longer than `max_size`.
+pub(super) fn decode(input: &[u8], max_size: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut output = Vec::new();
+
+    let mut index = 0;
+    while index < input.len() {
+        index += decode_member(&input[index..], &mut output, max_size)?;
+    }
+
+    Ok(output)
+}
+
+/// Decompresses the gzip member at the start of `input` into `output`
+///
+/// Returns the number of bytes in the member.
+fn decode_member(
+    input: &[u8],
+    output: &mut Vec<u8>,
+    max_size: usize,
+) -> Result<usize, DecodeError> {
+    if input.len() < 10 || input[..2] != MAGIC || input[2] != DEFLATE {
+        return Err(DecodeError::Invalid);
+    }
+
+    let flags = input[3];
+    let mut index = 10;
+
+    if flags & FLAG_EXTRA != 0 {
+        let length = u16::from_le_bytes(read_array(input, index)?) as usize;
+        index += 2 + length;
+    }
+
+    if flags & FLAG_NAME != 0 {
+        index += skip_string(input, index)?;
+    }
+
+    if flags & FLAG_COMMENT != 0 {
+        index += skip_string(input, index)?;
+    }
+
+    if flags & FLAG_HCRC != 0 {
+        index += 2;
+    }
+
+    if index > input.len() {
+        return Err(DecodeError::Invalid);
+    }
+
+    let start = output.len();
+    index += inflate(&input[index..], output, max_size)?;
+
+    let crc = u32::from_le_bytes(read_array(input, index)?);
+    let size = u32::from_le_bytes(read_array(input, index + 4)?);
+    if crc != crc32(&output[start..]) || size != (output.len() - start) as u32 {
+        return Err(DecodeError::Invalid);
+    }
+
+    Ok(index + 8)
+}
+
+/// Reads `N` bytes from `input` starting at `index`
+fn read_array<const N: usize>(input: &[u8], index: usize) -> Result<[u8; N], DecodeError> {
+    input
+        .get(index..index + N)
+        .map(|bytes| bytes.try_into().unwrap())
+        .ok_or(DecodeError::Invalid)
+}
+
+/// Gets the length of the zero-terminated string at `index`, including the terminator
+fn skip_string(input: &[u8], index: usize) -> Result<usize, DecodeError> {
+    input
+        .get(index..)
+        .and_then(|string| string.iter().position(|c| *c == 0))
+        .map(|length| length + 1)
+        .ok_or(DecodeError::Invalid)
+}
+
+/// Calculates the CRC-32 of `bytes`
+fn crc32(bytes: &[u8]) -> u32 {
+    update_crc32(0, bytes)
+}
+
+/// Continues calculating the CRC-32 `crc` with `bytes`
+pub(super) fn update_crc32(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
@@ -0,0 +1,337 @@
+use super::DecodeError;
+
+/// The order the lengths of the code length codes are sent in
+pub(super) const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// The base lengths for length symbols 257 to 285
+pub(super) const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+/// The number of extra bits for length symbols 257 to 285
+pub(super) const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// The base distances for distance symbols 0 to 29
+pub(super) const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// The number of extra bits for distance symbols 0 to 29
+pub(super) const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The longest code allowed in a deflate stream
+const MAX_CODE_LENGTH: usize = 15;
+
+/// Reads bits from a deflate stream, least significant bit first
+struct BitReader<'a> {
+    /// The bytes being read
+    input: &'a [u8],
+
+    /// The index of the next byte to read into `bits`
+    index: usize,
+
+    /// The bits read but not yet used
+    bits: u32,
+
+    /// The number of bits in `bits`
+    count: u32,
+}
+
+/// A canonical Huffman code
+struct Huffman {
+    /// The number of codes of each length
+    counts: [u16; MAX_CODE_LENGTH + 1],
+
+    /// The symbols ordered by their codes
+    symbols: Vec<u16>,
+}
+
+/// Decompresses the raw deflate stream at the start of `input` into `output`
+///
+/// Returns the number of bytes of `input` used by the stream. `output` is not allowed to grow
+/// longer than `max_size`.
+pub(super) fn inflate(
+    input: &[u8],
+    output: &mut Vec<u8>,
+    max_size: usize,
+) -> Result<usize, DecodeError> {
+    let mut reader = BitReader::new(input);
+
+    loop {
+        let last = reader.bits(1)? == 1;
+
+        match reader.bits(2)? {
+            0 => stored(&mut reader, output, max_size)?,
+            1 => {
+                let (literals, distances) = Huffman::fixed();
+                codes(&mut reader, output, max_size, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = Huffman::dynamic(&mut reader)?;
+                codes(&mut reader, output, max_size, &literals, &distances)?;
+            }
+            _ => return Err(DecodeError::Invalid),
+        }
+
+        if last {
+            return Ok(reader.finish());
+        }
+    }
+}
+
+/// Copies a stored block from `reader` into `output`
+fn stored(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    max_size: usize,
+) -> Result<(), DecodeError> {
+    reader.align();
+
+    let length = reader.bits(16)?;
+    if length ^ 0xFFFF != reader.bits(16)? {
+        return Err(DecodeError::Invalid);
+    }
+
+    let bytes = reader.bytes(length as usize)?;
+    if bytes.len() > max_size - output.len() {
+        return Err(DecodeError::TooLarge);
+    }
+
+    output.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Decodes a block compressed with `literals` and `distances` from `reader` into `output`
+fn codes(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    max_size: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), DecodeError> {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        if symbol < 256 {
+            if output.len() == max_size {
+                return Err(DecodeError::TooLarge);
+            }
+
+            output.push(symbol as u8);
+            continue;
+        }
+
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let symbol = symbol - 257;
+        if symbol >= LENGTH_BASE.len() {
+            return Err(DecodeError::Invalid);
+        }
+        let length =
+            LENGTH_BASE[symbol] as usize + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+        let symbol = distances.decode(reader)? as usize;
+        if symbol >= DISTANCE_BASE.len() {
+            return Err(DecodeError::Invalid);
+        }
+        let distance =
+            DISTANCE_BASE[symbol] as usize + reader.bits(DISTANCE_EXTRA[symbol] as u32)? as usize;
+
+        if distance > output.len() {
+            return Err(DecodeError::Invalid);
+        }
+
+        if length > max_size - output.len() {
+            return Err(DecodeError::TooLarge);
+        }
+
+        let start = output.len() - distance;
+        for i in 0..length {
+            output.push(output[start + i]);
+        }
+    }
+}
+
+impl<'a> BitReader<'a> {
+    /// Creates a new [`BitReader`] over `input`
+    fn new(input: &'a [u8]) -> Self {
+        BitReader {
+            input,
+            index: 0,
+            bits: 0,
+            count: 0,
+        }
+    }
+
+    /// Reads the next `count` bits, up to 16
+    fn bits(&mut self, count: u32) -> Result<u32, DecodeError> {
+        while self.count < count {
+            if self.index == self.input.len() {
+                return Err(DecodeError::Invalid);
+            }
+
+            self.bits |= (self.input[self.index] as u32) << self.count;
+            self.index += 1;
+            self.count += 8;
+        }
+
+        let value = self.bits & ((1 << count) - 1);
+        self.bits >>= count;
+        self.count -= count;
+        Ok(value)
+    }
+
+    /// Discards the remaining bits of the current byte
+    fn align(&mut self) {
+        self.bits = 0;
+        self.count = 0;
+    }
+
+    /// Reads the next `count` bytes after aligning to a byte
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        if count > self.input.len() - self.index {
+            return Err(DecodeError::Invalid);
+        }
+
+        let bytes = &self.input[self.index..self.index + count];
+        self.index += count;
+        Ok(bytes)
+    }
+
+    /// Gets the number of bytes used, including the partially used last byte
+    fn finish(self) -> usize {
+        self.index - (self.count / 8) as usize
+    }
+}
+
+impl Huffman {
+    /// Creates a [`Huffman`] code from the lengths of the codes for each symbol
+    fn new(lengths: &[u8]) -> Result<Self, DecodeError> {
+        let mut counts = [0; MAX_CODE_LENGTH + 1];
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+
+        let mut left: i32 = 1;
+        for count in &counts[1..] {
+            left = (left << 1) - *count as i32;
+            if left < 0 {
+                return Err(DecodeError::Invalid);
+            }
+        }
+
+        let mut offsets = [0; MAX_CODE_LENGTH + 1];
+        for length in 1..MAX_CODE_LENGTH {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, length) in lengths.iter().enumerate() {
+            if *length > 0 {
+                symbols[offsets[*length as usize] as usize] = symbol as u16;
+                offsets[*length as usize] += 1;
+            }
+        }
+
+        counts[0] = 0;
+        Ok(Huffman { counts, symbols })
+    }
+
+    /// Creates the fixed literal and distance codes
+    fn fixed() -> (Self, Self) {
+        let mut lengths = [0; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+
+        (
+            Huffman::new(&lengths).unwrap(),
+            Huffman::new(&[5; 30]).unwrap(),
+        )
+    }
+
+    /// Reads the literal and distance codes at the start of a dynamic block from `reader`
+    fn dynamic(reader: &mut BitReader) -> Result<(Self, Self), DecodeError> {
+        let literal_count = reader.bits(5)? as usize + 257;
+        let distance_count = reader.bits(5)? as usize + 1;
+        let code_length_count = reader.bits(4)? as usize + 4;
+
+        if literal_count > 286 || distance_count > 30 {
+            return Err(DecodeError::Invalid);
+        }
+
+        let mut code_lengths = [0; 19];
+        for index in &CODE_LENGTH_ORDER[..code_length_count] {
+            code_lengths[*index] = reader.bits(3)? as u8;
+        }
+        let code_lengths = Huffman::new(&code_lengths)?;
+
+        let mut lengths = vec![0; literal_count + distance_count];
+        let mut index = 0;
+        while index < lengths.len() {
+            let symbol = code_lengths.decode(reader)?;
+            let (length, repeat) = match symbol {
+                0..16 => {
+                    lengths[index] = symbol as u8;
+                    index += 1;
+                    continue;
+                }
+                16 => match index {
+                    0 => return Err(DecodeError::Invalid),
+                    _ => (lengths[index - 1], 3 + reader.bits(2)? as usize),
+                },
+                17 => (0, 3 + reader.bits(3)? as usize),
+                _ => (0, 11 + reader.bits(7)? as usize),
+            };
+
+            if repeat > lengths.len() - index {
+                return Err(DecodeError::Invalid);
+            }
+
+            lengths[index..index + repeat].fill(length);
+            index += repeat;
+        }
+
+        if lengths[256] == 0 {
+            return Err(DecodeError::Invalid);
+        }
+
+        Ok((
+            Huffman::new(&lengths[..literal_count])?,
+            Huffman::new(&lengths[literal_count..])?,
+        ))
+    }
+
+    /// Reads the next symbol from `reader`
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, DecodeError> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+
+        for count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+
+            let count = *count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(DecodeError::Invalid)
+    }
+}
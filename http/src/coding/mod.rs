@@ -0,0 +1,93 @@
+use crate::{HTTPParseError, HTTPRequestFields};
+
+mod body;
+mod deflate;
+mod encoder;
+mod gzip;
+mod inflate;
+mod options;
+mod zlib;
+
+#[cfg(test)]
+mod tests;
+
+pub use encoder::{
+    HTTPContentEncoder, HTTPContentEncoderStream, HTTPDeflateEncoder, HTTPGzipEncoder,
+};
+pub use options::HTTPCompressionOptions;
+
+pub(crate) use body::EncodedHTTPChunkedResponseBody;
+
+/// A content coding which can be removed from a request body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentCoding {
+    /// The "gzip" coding
+    Gzip,
+
+    /// The "deflate" coding
+    Deflate,
+}
+
+/// An error while decoding a body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DecodeError {
+    /// The body is not validly encoded
+    Invalid,
+
+    /// The decoded body is longer than allowed
+    TooLarge,
+}
+
+/// Removes the content codings listed in the "Content-Encoding" fields of `fields` from `body`
+///
+/// The decoded body is not allowed to be longer than `max_size`.
+pub(crate) fn decode(
+    fields: &HTTPRequestFields,
+    body: Box<[u8]>,
+    max_size: usize,
+) -> Result<Box<[u8]>, HTTPParseError> {
+    let mut codings = Vec::new();
+    for coding in fields.get_list(b"Content-Encoding") {
+        if coding.eq_ignore_ascii_case(b"identity") {
+            continue;
+        }
+
+        codings.push(ContentCoding::parse(coding)?);
+    }
+
+    let mut body = body;
+    for coding in codings.into_iter().rev() {
+        body = coding.decode(&body, max_size)?.into_boxed_slice();
+    }
+    Ok(body)
+}
+
+impl ContentCoding {
+    /// Parses the name of a content coding
+    fn parse(name: &[u8]) -> Result<Self, HTTPParseError> {
+        if name.eq_ignore_ascii_case(b"gzip") || name.eq_ignore_ascii_case(b"x-gzip") {
+            Ok(ContentCoding::Gzip)
+        } else if name.eq_ignore_ascii_case(b"deflate") {
+            Ok(ContentCoding::Deflate)
+        } else {
+            Err(HTTPParseError::UnsupportedContentEncoding)
+        }
+    }
+
+    /// Removes this coding from `body`
+    fn decode(&self, body: &[u8], max_size: usize) -> Result<Vec<u8>, DecodeError> {
+        match self {
+            ContentCoding::Gzip => gzip::decode(body, max_size),
+            ContentCoding::Deflate => zlib::decode(body, max_size),
+        }
+    }
+}
+
+impl From<DecodeError> for HTTPParseError {
+    fn from(error: DecodeError) -> Self {
+        match error {
+            DecodeError::Invalid => HTTPParseError::InvalidContentEncoding,
+            DecodeError::TooLarge => HTTPParseError::BodyTooLarge,
+        }
+    }
+}
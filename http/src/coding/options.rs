@@ -0,0 +1,133 @@
+use crate::{HTTPContentEncoder, HTTPDeflateEncoder, HTTPGzipEncoder, HTTPQualityValue};
+use std::{borrow::Cow, sync::Arc};
+
+/// The options for compressing response bodies
+#[derive(Clone)]
+pub struct HTTPCompressionOptions {
+    /// The codings which can be applied to responses
+    ///
+    /// When a client accepts several of these equally, the earliest one is used.
+    pub encoders: Vec<Arc<dyn HTTPContentEncoder>>,
+
+    /// The smallest body which will be compressed
+    ///
+    /// The size of chunked bodies isn't known ahead of time, so this doesn't apply to them.
+    pub min_size: usize,
+
+    /// The content types which will be compressed
+    ///
+    /// A type ending with "/*" matches every subtype of it.
+    pub compressible_types: Vec<Cow<'static, [u8]>>,
+}
+
+impl HTTPCompressionOptions {
+    /// Chooses the encoder to use for a client which sent `accept_encoding`
+    ///
+    /// Returns [`None`] if the client accepts none of the encoders or didn't send any accepted
+    /// codings.
+    pub(crate) fn negotiate<'a>(
+        &self,
+        accept_encoding: impl Iterator<Item = HTTPQualityValue<'a>>,
+    ) -> Option<Arc<dyn HTTPContentEncoder>> {
+        let accept_encoding: Vec<_> = accept_encoding.collect();
+
+        let wildcard = accept_encoding
+            .iter()
+            .find(|coding| coding.value() == b"*")
+            .map(HTTPQualityValue::quality)
+            .unwrap_or(0);
+
+        let mut best: Option<(&Arc<dyn HTTPContentEncoder>, u16)> = None;
+        for encoder in &self.encoders {
+            let quality = accept_encoding
+                .iter()
+                .find(|coding| coding.value().eq_ignore_ascii_case(encoder.coding()))
+                .map(HTTPQualityValue::quality)
+                .unwrap_or(wildcard);
+
+            if quality > best.map(|(_, quality)| quality).unwrap_or(0) {
+                best = Some((encoder, quality));
+            }
+        }
+
+        best.map(|(encoder, _)| encoder.clone())
+    }
+
+    /// Should bodies of `content_type` be compressed?
+    pub(crate) fn is_compressible(&self, content_type: &[u8]) -> bool {
+        let content_type = match content_type.iter().position(|c| *c == b';') {
+            Some(end) => &content_type[..end],
+            None => content_type,
+        }
+        .trim_ascii();
+
+        self.compressible_types.iter().any(|compressible_type| {
+            match compressible_type.strip_suffix(b"*") {
+                Some(prefix) if prefix.ends_with(b"/") => {
+                    content_type.len() > prefix.len()
+                        && content_type[..prefix.len()].eq_ignore_ascii_case(prefix)
+                }
+                _ => content_type.eq_ignore_ascii_case(compressible_type),
+            }
+        })
+    }
+}
+
+impl Default for HTTPCompressionOptions {
+    fn default() -> Self {
+        HTTPCompressionOptions {
+            encoders: vec![Arc::new(HTTPGzipEncoder), Arc::new(HTTPDeflateEncoder)],
+            min_size: 1024, // 1 Kb
+            compressible_types: [
+                b"text/*" as &[u8],
+                b"application/json",
+                b"application/javascript",
+                b"application/xml",
+                b"application/xhtml+xml",
+                b"image/svg+xml",
+            ]
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect(),
+        }
+    }
+}
+
+impl std::fmt::Debug for HTTPCompressionOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HTTPCompressionOptions")
+            .field(
+                "encoders",
+                &self
+                    .encoders
+                    .iter()
+                    .map(|encoder| String::from_utf8_lossy(encoder.coding()))
+                    .collect::<Vec<_>>(),
+            )
+            .field("min_size", &self.min_size)
+            .field(
+                "compressible_types",
+                &self
+                    .compressible_types
+                    .iter()
+                    .map(|compressible_type| String::from_utf8_lossy(compressible_type))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl PartialEq for HTTPCompressionOptions {
+    fn eq(&self, other: &Self) -> bool {
+        self.encoders.len() == other.encoders.len()
+            && self
+                .encoders
+                .iter()
+                .zip(&other.encoders)
+                .all(|(a, b)| a.coding() == b.coding())
+            && self.min_size == other.min_size
+            && self.compressible_types == other.compressible_types
+    }
+}
+
+impl Eq for HTTPCompressionOptions {}
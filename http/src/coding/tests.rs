@@ -0,0 +1,213 @@
+use super::*;
+
+/// Parses a string of hexadecimal digits into bytes
+fn hex(digits: &str) -> Vec<u8> {
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Creates `length` bytes which don't compress
+fn random(length: usize) -> Vec<u8> {
+    let mut state: u32 = 0x12345678;
+    (0..length)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect()
+}
+
+/// Creates `lines` lines of text which compress well
+fn text(lines: usize) -> Vec<u8> {
+    (0..lines)
+        .flat_map(|i| format!("line {} of a dynamic block, line {}\n", i, i * 7 % 13).into_bytes())
+        .collect()
+}
+
+/// Encodes `chunks` as one body with `encoder`
+fn encode(encoder: &dyn HTTPContentEncoder, chunks: &[&[u8]]) -> Vec<u8> {
+    let mut stream = encoder.start();
+    let mut output = Vec::new();
+    for chunk in chunks {
+        stream.write(chunk, &mut output);
+    }
+    stream.finish(&mut output);
+    output
+}
+
+/// Checks that `input` written as `chunks` decodes back to itself with both encoders
+fn assert_round_trip(chunks: &[&[u8]]) {
+    let input = chunks.concat();
+
+    let gzip = encode(&HTTPGzipEncoder, chunks);
+    assert_eq!(
+        ContentCoding::Gzip.decode(&gzip, usize::MAX),
+        Ok(input.clone())
+    );
+
+    let deflate = encode(&HTTPDeflateEncoder, chunks);
+    assert_eq!(
+        ContentCoding::Deflate.decode(&deflate, usize::MAX),
+        Ok(input)
+    );
+}
+
+#[test]
+fn round_trips() {
+    assert_round_trip(&[]);
+    assert_round_trip(&[b"a"]);
+    assert_round_trip(&[b"hello world"]);
+    assert_round_trip(&[&[0; 100000]]);
+    assert_round_trip(&[&text(40)]);
+    assert_round_trip(&[&random(100000)]);
+}
+
+#[test]
+fn round_trips_across_writes() {
+    let input = text(5000);
+    let chunks: Vec<&[u8]> = input.chunks(4096).collect();
+    assert_round_trip(&chunks);
+
+    let chunks: Vec<&[u8]> = input.chunks(7).collect();
+    assert_round_trip(&chunks);
+
+    let random = random(1000);
+    assert_round_trip(&[&random, b"", &text(10), &random]);
+}
+
+#[test]
+fn round_trips_past_the_window() {
+    // Matches far enough back to have slid out of the window can't be used
+    let block = random(20000);
+    let input = [block.as_slice(); 10].concat();
+    assert_round_trip(&[&input]);
+
+    let chunks: Vec<&[u8]> = input.chunks(30000).collect();
+    assert_round_trip(&chunks);
+}
+
+#[test]
+fn compresses_text() {
+    let input = text(5000);
+
+    let deflate = encode(&HTTPDeflateEncoder, &[&input]);
+    assert!(deflate.len() < input.len() / 5);
+
+    // Text gets its own Huffman codes
+    assert_eq!((deflate[2] >> 1) & 0b11, 0b10);
+
+    let chunks: Vec<&[u8]> = input.chunks(1024).collect();
+    assert!(encode(&HTTPDeflateEncoder, &chunks).len() < input.len() / 5);
+}
+
+#[test]
+fn stores_random_data() {
+    let input = random(100000);
+
+    let deflate = encode(&HTTPDeflateEncoder, &[&input]);
+    assert!(deflate.len() < input.len() + 64);
+
+    // Random data is stored
+    assert_eq!((deflate[2] >> 1) & 0b11, 0b00);
+}
+
+#[test]
+fn decodes_stored_blocks() {
+    assert_eq!(
+        ContentCoding::Deflate.decode(&hex("7801010500faff68656c6c6f062c0215"), usize::MAX),
+        Ok(b"hello".to_vec())
+    );
+}
+
+#[test]
+fn decodes_fixed_blocks() {
+    assert_eq!(
+        ContentCoding::Deflate.decode(&hex("789ccb48cdc9c90700062c0215"), usize::MAX),
+        Ok(b"hello".to_vec())
+    );
+
+    assert_eq!(
+        ContentCoding::Gzip.decode(
+            &hex("1f8b0800000000000203cb48cdc9c95728cf2fca49010085114a0d0b000000"),
+            usize::MAX
+        ),
+        Ok(b"hello world".to_vec())
+    );
+}
+
+#[test]
+fn decodes_dynamic_blocks() {
+    // zlib.compress(text(40), 9)
+    let input = hex(concat!(
+        "78da85d33d0ec2300c86e19d53e4000cb1bffc1ea714902a4a3b737ba43aabf34d19fa2aaafd28fb76bc42",
+        "0ce73b2ce1f93b96efb686c77eae9f7bd8af4fb7eb10bfa856a85f8815f08b6645f20bb522fb45b7a2f805",
+        "aca8933f1de3363f4956f4c925635e99ac358f64b2571923cb64b36524e080890a4aa68452a8a1548a288d",
+        "2a4aa78c1ab9a30a8554e5920a2aa9894b6aa6925aa8a456fe161b95d44e2511a924844a42a924c02591a8",
+        "24329744a192a85c128d4aa2cf24ffb090bdc9"
+    ));
+    assert_eq!((input[2] >> 1) & 0b11, 0b10);
+
+    assert_eq!(
+        ContentCoding::Deflate.decode(&input, usize::MAX),
+        Ok(text(40))
+    );
+}
+
+#[test]
+fn rejects_bad_checksums() {
+    let mut deflate = hex("789ccb48cdc9c90700062c0215");
+    *deflate.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        ContentCoding::Deflate.decode(&deflate, usize::MAX),
+        Err(DecodeError::Invalid)
+    );
+
+    let gzip = hex("1f8b0800000000000203cb48cdc9c95728cf2fca49010085114a0d0b000000");
+    let crc = gzip.len() - 8;
+    for index in [crc, crc + 4] {
+        let mut gzip = gzip.clone();
+        gzip[index] ^= 1;
+        assert_eq!(
+            ContentCoding::Gzip.decode(&gzip, usize::MAX),
+            Err(DecodeError::Invalid)
+        );
+    }
+}
+
+#[test]
+fn rejects_truncated_input() {
+    let input = text(100);
+    for (coding, encoded) in [
+        (ContentCoding::Gzip, encode(&HTTPGzipEncoder, &[&input])),
+        (
+            ContentCoding::Deflate,
+            encode(&HTTPDeflateEncoder, &[&input]),
+        ),
+    ] {
+        for length in 1..encoded.len() {
+            assert_eq!(
+                coding.decode(&encoded[..length], usize::MAX),
+                Err(DecodeError::Invalid)
+            );
+        }
+    }
+}
+
+#[test]
+fn limits_the_decoded_size() {
+    let input = [0; 10000];
+    let deflate = encode(&HTTPDeflateEncoder, &[&input]);
+
+    assert_eq!(
+        ContentCoding::Deflate.decode(&deflate, input.len()),
+        Ok(input.to_vec())
+    );
+    assert_eq!(
+        ContentCoding::Deflate.decode(&deflate, input.len() - 1),
+        Err(DecodeError::TooLarge)
+    );
+}
@@ -0,0 +1,58 @@
+use super::{inflate::inflate, DecodeError};
+
+/// The compression method for deflate
+const DEFLATE: u8 = 8;
+
+/// The flag for a preset dictionary
+const FLAG_DICTIONARY: u8 = 1 << 5;
+
+/// Decompresses the "deflate" coding in `input`
+///
+/// This is a zlib stream, but some clients send a raw deflate stream instead so those are accepted
+/// too. The result is not allowed to be longer than `max_size`.
+pub(super) fn decode(input: &[u8], max_size: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut output = Vec::new();
+
+    if !is_zlib_header(input) {
+        inflate(input, &mut output, max_size)?;
+        return Ok(output);
+    }
+
+    if input[1] & FLAG_DICTIONARY != 0 {
+        return Err(DecodeError::Invalid);
+    }
+
+    let index = 2 + inflate(&input[2..], &mut output, max_size)?;
+    match input.get(index..index + 4) {
+        Some(checksum) if checksum == adler32(&output).to_be_bytes() => Ok(output),
+        _ => Err(DecodeError::Invalid),
+    }
+}
+
+/// Does `input` start with a zlib header?
+fn is_zlib_header(input: &[u8]) -> bool {
+    input.len() >= 2
+        && input[0] & 0x0F == DEFLATE
+        && input[0] >> 4 <= 7
+        && u16::from_be_bytes([input[0], input[1]]).is_multiple_of(31)
+}
+
+/// Calculates the Adler-32 checksum of `bytes`
+fn adler32(bytes: &[u8]) -> u32 {
+    update_adler32(1, bytes)
+}
+
+/// Continues calculating the Adler-32 checksum `adler` with `bytes`
+pub(super) fn update_adler32(adler: u32, bytes: &[u8]) -> u32 {
+    let mut a = adler & 0xFFFF;
+    let mut b = adler >> 16;
+    for chunk in bytes.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
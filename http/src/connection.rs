@@ -0,0 +1,8 @@
+/// Does the value of a "Connection" field contain `option`?
+///
+/// The value is a comma separated list of case-insensitive options
+pub(crate) fn has_connection_option(value: &[u8], option: &[u8]) -> bool {
+    value
+        .split(|byte| *byte == b',')
+        .any(|token| token.trim_ascii().eq_ignore_ascii_case(option))
+}
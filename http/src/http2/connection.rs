@@ -0,0 +1,1109 @@
+use super::{
+    error::{ErrorCode, HTTP2Error},
+    frame::{
+        read_u32, write_frame, FrameHeader, FrameType, ACK, DEFAULT_MAX_FRAME_SIZE, END_HEADERS,
+        END_STREAM, HEADER_LENGTH, PADDED, PRIORITY,
+    },
+    hpack::{self, Decoder},
+    request::validate,
+    settings::{self, Settings, DEFAULT_WINDOW_SIZE, MAX_WINDOW_SIZE},
+    stream::Stream,
+    HTTP2Request, PREFACE,
+};
+use crate::{client::HTTPSocket, HTTPOptions, HTTPParseError, Result};
+use huntsman::runtime::{
+    closed_error, protocol_error,
+    sync::LocalNotify,
+    time::{timeout, timeout_error},
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+    time::Duration,
+};
+
+/// The most streams a client is allowed to have open at once
+///
+/// A stream stays open until the response to its request has been sent, so this is also the most
+/// requests from a client which are handled at the same time.
+pub(crate) const MAX_CONCURRENT_STREAMS: usize = 100;
+
+/// The number of bytes requested from the socket at a time
+const READ_SIZE: usize = 16 * 1024;
+
+/// The number of bytes of frames which are allowed to build up before they are written
+const WRITE_SIZE: usize = 64 * 1024;
+
+/// An HTTP/2 connection with a client
+///
+/// Requests are handed out as soon as they are complete, so the responses to requests on different
+/// streams can be sent at the same time. Only one task uses the socket at a time: whichever needs
+/// something from the client first writes the frames waiting to be sent and processes the frames
+/// the client sends, while the others wait for it to do so. Streams opened past
+/// [`MAX_CONCURRENT_STREAMS`] are refused, which lets the client retry them once an open stream is
+/// answered.
+pub(crate) struct HTTP2Connection {
+    /// The state of the streams, which is never borrowed across an await
+    state: RefCell<State>,
+
+    /// Is a task currently using the socket?
+    driving: Cell<bool>,
+
+    /// Notified when frames are queued to be written and when the socket is released
+    notify: LocalNotify,
+
+    /// The buffer the socket is read into, kept between reads to avoid allocating it every time
+    buffer: Cell<Vec<u8>>,
+
+    /// The maximum amount of time allowed between header reads
+    header_read_timeout: Duration,
+
+    /// The maximum amount of time allowed between body reads
+    body_read_timeout: Duration,
+
+    /// The maximum amount of time allowed between writes
+    write_timeout: Duration,
+
+    /// The maximum amount of time to wait for the next request
+    keep_alive_timeout: Duration,
+}
+
+/// The state of the streams of an [`HTTP2Connection`] and the frames going to and from the client
+struct State {
+    /// Bytes read from the client which haven't been processed
+    input: Vec<u8>,
+
+    /// Frames waiting to be written to the client
+    output: Vec<u8>,
+
+    /// The number of bytes of frames which have been written to the client
+    written: u64,
+
+    /// Has the connection preface been received?
+    preface: bool,
+
+    /// Decodes the header blocks sent by the client
+    decoder: Decoder,
+
+    /// The settings the client has sent
+    settings: Settings,
+
+    /// The maximum size of the names and values of the fields in a header block
+    max_header_size: usize,
+
+    /// The maximum size for request bodies
+    max_body_size: usize,
+
+    /// The streams the client has open
+    streams: Vec<Stream>,
+
+    /// The IDs of the streams with complete requests, in the order they were completed
+    ready: VecDeque<u32>,
+
+    /// The highest stream ID the client has opened
+    last_stream: u32,
+
+    /// The number of bytes of responses which can be sent before the client makes room
+    send_window: i64,
+
+    /// The header block being received, if it continues into "CONTINUATION" frames
+    header_block: Option<HeaderBlock>,
+
+    /// An error in a request on a stream, which is handed out instead of the next request
+    error: Option<(u32, HTTPParseError)>,
+
+    /// Has a "GOAWAY" been sent to the client?
+    going_away: bool,
+
+    /// Has the client sent a "GOAWAY"?
+    client_going_away: bool,
+
+    /// Was the connection closed because of an error?
+    failed: bool,
+
+    /// Has the client closed the connection, or did reading from or writing to it fail?
+    closed: bool,
+}
+
+/// A header block split over multiple frames
+struct HeaderBlock {
+    /// The stream the header block is for
+    stream: u32,
+
+    /// Was the "END_STREAM" flag set on the "HEADERS" frame?
+    end_stream: bool,
+
+    /// The parts of the block received so far
+    block: Vec<u8>,
+}
+
+/// Marks the socket as being used by a task until dropped
+struct Driving<'a>(&'a HTTP2Connection);
+
+/// What happened while waiting on the connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    /// Frames were written or received, or another task finished using the socket
+    Processed,
+
+    /// The client closed the connection
+    Closed,
+
+    /// Nothing happened in time
+    TimedOut,
+
+    /// The client broke the protocol and the connection was closed
+    Failed,
+}
+
+/// Removes the padding from the payload of a "DATA" or "HEADERS" frame
+fn strip_padding(header: FrameHeader, payload: &[u8]) -> std::result::Result<&[u8], HTTP2Error> {
+    if !header.has_flag(PADDED) {
+        return Ok(payload);
+    }
+
+    let (&padding, payload) = payload
+        .split_first()
+        .ok_or(HTTP2Error::Connection(ErrorCode::FrameSizeError))?;
+    if padding as usize > payload.len() {
+        return Err(HTTP2Error::Connection(ErrorCode::ProtocolError));
+    }
+
+    Ok(&payload[..payload.len() - padding as usize])
+}
+
+impl HTTP2Connection {
+    /// Creates a new [`HTTP2Connection`] which has already read `input` from the client
+    pub(crate) fn new(input: Vec<u8>, options: &HTTPOptions) -> Self {
+        let mut output = Vec::new();
+        write_frame(
+            &mut output,
+            FrameType::Settings,
+            0,
+            0,
+            &settings::encode(
+                MAX_CONCURRENT_STREAMS as u32,
+                options.max_header_size.min(u32::MAX as usize) as u32,
+            ),
+        );
+
+        HTTP2Connection {
+            state: RefCell::new(State {
+                input,
+                output,
+                written: 0,
+                preface: false,
+                decoder: Decoder::new(),
+                settings: Settings::new(),
+                max_header_size: options.max_header_size,
+                max_body_size: options.max_body_size,
+                streams: Vec::new(),
+                ready: VecDeque::new(),
+                last_stream: 0,
+                send_window: DEFAULT_WINDOW_SIZE as i64,
+                header_block: None,
+                error: None,
+                going_away: false,
+                client_going_away: false,
+                failed: false,
+                closed: false,
+            }),
+            driving: Cell::new(false),
+            notify: LocalNotify::new(),
+            buffer: Cell::new(Vec::new()),
+            header_read_timeout: options.header_read_timeout,
+            body_read_timeout: options.body_read_timeout,
+            write_timeout: options.write_timeout,
+            keep_alive_timeout: options.keep_alive_timeout,
+        }
+    }
+
+    /// Waits for the next complete request from the client, returning it with the ID of the stream
+    /// it was sent on
+    ///
+    /// Returns [`None`] once the connection is finished, either because the client closed it, it
+    /// was idle for too long, or a "GOAWAY" was sent or received and every request before it has
+    /// been answered. Errors come with the ID of the stream their response is sent on, if they
+    /// only affect one request.
+    pub(crate) async fn next_request(
+        &self,
+        socket: &RefCell<HTTPSocket>,
+    ) -> std::result::Result<Option<(u32, HTTP2Request)>, (Option<u32>, HTTPParseError)> {
+        loop {
+            let timeouts = {
+                let mut state = self.state.borrow_mut();
+                if state.failed || state.closed {
+                    return Ok(None);
+                }
+
+                // The connection closes after an error is answered, so the requests received
+                // before it are handed out first
+                if let Some(id) = state.ready.pop_front() {
+                    let request = state.stream_mut(id).unwrap().take_request();
+                    return Ok(Some((id, request)));
+                }
+
+                if let Some((id, error)) = state.error.take() {
+                    return Err((Some(id), error));
+                }
+
+                // The first request gets as long as any other header, while later ones are waited
+                // for with the keep-alive timeout. Once every open stream is waiting for its
+                // response, the client has nothing left to send.
+                let idle = state.streams.is_empty() && state.header_block.is_none();
+                if idle && (state.going_away || state.client_going_away) {
+                    None
+                } else if idle && state.last_stream > 0 {
+                    Some((Some(self.keep_alive_timeout), None))
+                } else if idle {
+                    Some((Some(self.header_read_timeout), None))
+                } else if state.header_block.is_some() {
+                    Some((
+                        Some(self.header_read_timeout),
+                        Some(HTTPParseError::HeaderReadTimeout),
+                    ))
+                } else if state.streams.iter().any(|stream| !stream.complete) {
+                    Some((
+                        Some(self.body_read_timeout),
+                        Some(HTTPParseError::BodyReadTimeout),
+                    ))
+                } else {
+                    Some((None, None))
+                }
+            };
+
+            let (read_timeout, timeout_error) = match timeouts {
+                Some(timeouts) => timeouts,
+                None => {
+                    self.flush(socket)
+                        .await
+                        .map_err(|error| (None, error.into()))?;
+                    return Ok(None);
+                }
+            };
+
+            let event = self
+                .drive(socket, read_timeout)
+                .await
+                .map_err(|error| (None, error.into()))?;
+            match event {
+                Event::Processed => {}
+                Event::Closed => return Ok(None),
+                Event::TimedOut => match timeout_error {
+                    Some(error) => {
+                        self.state.borrow_mut().failed = true;
+                        self.notify.notify_all();
+                        return Err((None, error));
+                    }
+                    None => {
+                        self.state.borrow_mut().go_away(ErrorCode::NoError);
+                        self.flush(socket)
+                            .await
+                            .map_err(|error| (None, error.into()))?;
+                        return Ok(None);
+                    }
+                },
+                Event::Failed => return Err((None, HTTPParseError::HTTP2ConnectionError)),
+            }
+        }
+    }
+
+    /// Tells the client no more requests will be accepted
+    ///
+    /// The requests the client already started are still handed out and answered.
+    pub(crate) fn shut_down(&self) {
+        let mut state = self.state.borrow_mut();
+        if !state.going_away {
+            state.go_away(ErrorCode::NoError);
+            drop(state);
+            self.notify.notify_all();
+        }
+    }
+
+    /// Sends the header block of the response on the stream `id`
+    ///
+    /// If `end_stream` is true, the response has no body and the stream is finished.
+    pub(crate) async fn send_headers(
+        &self,
+        socket: &RefCell<HTTPSocket>,
+        id: u32,
+        block: &[u8],
+        end_stream: bool,
+    ) -> Result<()> {
+        let open = {
+            let mut state = self.state.borrow_mut();
+            let open = !state.failed && state.stream_mut(id).is_some();
+            if open {
+                state.write_header_block(id, block, end_stream);
+            }
+            open
+        };
+
+        if !open {
+            return self.finish(socket, id).await;
+        }
+        self.notify.notify_all();
+
+        if end_stream {
+            self.finish(socket, id).await
+        } else {
+            self.make_room(socket).await
+        }
+    }
+
+    /// Sends `data` as part of the body of the response on the stream `id`, waiting for the
+    /// client to make room for it if needed
+    ///
+    /// If `end_stream` is true, this is the end of the body and the stream is finished.
+    pub(crate) async fn send_data(
+        &self,
+        socket: &RefCell<HTTPSocket>,
+        id: u32,
+        mut data: &[u8],
+        end_stream: bool,
+    ) -> Result<()> {
+        loop {
+            // The client may reset the stream while the response is being sent
+            let queued = self.state.borrow_mut().queue_data(id, data, end_stream);
+            let length = match queued {
+                Some(length) => length,
+                None => return self.finish(socket, id).await,
+            };
+
+            if length > 0 || data.is_empty() {
+                self.notify.notify_all();
+
+                data = &data[length..];
+                if data.is_empty() {
+                    return match end_stream {
+                        true => self.finish(socket, id).await,
+                        false => self.make_room(socket).await,
+                    };
+                }
+
+                self.make_room(socket).await?;
+                continue;
+            }
+
+            // Wait for the client to make room in the flow control windows
+            match self.drive(socket, Some(self.write_timeout)).await? {
+                Event::Processed => {}
+                Event::Closed | Event::Failed => return Err(protocol_error()),
+                Event::TimedOut => return Err(timeout_error()),
+            }
+        }
+    }
+
+    /// Finishes the response on the stream `id` and waits for every frame queued so far to be
+    /// written
+    pub(crate) async fn finish(&self, socket: &RefCell<HTTPSocket>, id: u32) -> Result<()> {
+        self.state.borrow_mut().remove(id);
+        self.notify.notify_all();
+
+        self.flush(socket).await
+    }
+
+    /// Waits for every frame queued so far to be written
+    pub(crate) async fn flush(&self, socket: &RefCell<HTTPSocket>) -> Result<()> {
+        let target = self.state.borrow().queued();
+        self.write_until(socket, target).await
+    }
+
+    /// Waits for the frames waiting to be written to be below [`WRITE_SIZE`]
+    async fn make_room(&self, socket: &RefCell<HTTPSocket>) -> Result<()> {
+        if self.state.borrow().output.len() < WRITE_SIZE {
+            return Ok(());
+        }
+
+        let target = self.state.borrow().queued();
+        self.write_until(socket, target).await
+    }
+
+    /// Waits until `target` bytes of frames have been written to the client
+    async fn write_until(&self, socket: &RefCell<HTTPSocket>, target: u64) -> Result<()> {
+        loop {
+            let (written, closed) = {
+                let state = self.state.borrow();
+                (state.written, state.closed)
+            };
+
+            if written >= target {
+                return Ok(());
+            }
+
+            if closed {
+                return Err(closed_error());
+            }
+
+            if self.drive(socket, Some(self.write_timeout)).await? == Event::TimedOut {
+                return Err(timeout_error());
+            }
+        }
+    }
+
+    /// Waits for something to happen on the connection, for at most `read_timeout` if it is given
+    ///
+    /// If no other task is using the socket, this writes the frames waiting to be sent. If there
+    /// were none, it reads and processes frames from the client until some arrive or another task
+    /// queues frames to be sent. Otherwise, it waits for the other task to finish with the socket.
+    // Only the task marked as driving borrows the socket
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn drive(
+        &self,
+        socket: &RefCell<HTTPSocket>,
+        read_timeout: Option<Duration>,
+    ) -> Result<Event> {
+        if self.driving.get() {
+            let notified = self.notify.notified();
+            return Ok(match read_timeout {
+                Some(read_timeout) => match timeout(notified, read_timeout).await? {
+                    Some(()) => Event::Processed,
+                    None => Event::TimedOut,
+                },
+                None => {
+                    notified.await;
+                    Event::Processed
+                }
+            });
+        }
+
+        let _driving = Driving::new(self);
+        let mut socket = socket.borrow_mut();
+        if self.write(&mut socket).await? {
+            return Ok(Event::Processed);
+        }
+
+        let mut buffer = self.buffer.take();
+        buffer.resize(READ_SIZE, 0);
+
+        // Reads are cancelled by frames being queued, so whatever is read has to be kept
+        let result = {
+            let mut read = pin!(socket.read(&mut buffer));
+            let mut notified = pin!(self.notify.notified());
+            let select = poll_fn(|cx| {
+                if let Poll::Ready(result) = read.as_mut().poll(cx) {
+                    return Poll::Ready(Some(result));
+                }
+
+                notified.as_mut().poll(cx).map(|()| None)
+            });
+
+            match read_timeout {
+                Some(read_timeout) => timeout(select, read_timeout).await?,
+                None => Some(select.await),
+            }
+        };
+
+        let count = match result {
+            Some(Some(Ok(count))) => count,
+            Some(Some(Err(error))) => {
+                self.state.borrow_mut().closed = true;
+                return Err(error);
+            }
+            Some(None) => {
+                self.buffer.set(buffer);
+                return Ok(Event::Processed);
+            }
+            None => {
+                self.buffer.set(buffer);
+                return Ok(Event::TimedOut);
+            }
+        };
+
+        if count == 0 {
+            self.state.borrow_mut().closed = true;
+            return Ok(Event::Closed);
+        }
+
+        let event = {
+            let mut state = self.state.borrow_mut();
+            state.input.extend_from_slice(&buffer[..count]);
+            state.process_input()
+        };
+        self.buffer.set(buffer);
+
+        // The client is told why the connection is closing before anything else happens
+        if event == Event::Failed {
+            let _ = self.write(&mut socket).await;
+        }
+        Ok(event)
+    }
+
+    /// Writes the frames waiting to be sent, including any queued while writing
+    ///
+    /// Returns true if any frames were written
+    async fn write(&self, socket: &mut HTTPSocket) -> Result<bool> {
+        let mut written = false;
+        loop {
+            let output = std::mem::take(&mut self.state.borrow_mut().output);
+            if output.is_empty() {
+                return Ok(written);
+            }
+
+            let result = timeout(socket.write(&output), self.write_timeout).await;
+            let mut state = self.state.borrow_mut();
+            match result {
+                Ok(Some(Ok(()))) => state.written += output.len() as u64,
+                Ok(Some(Err(error))) | Err(error) => {
+                    state.closed = true;
+                    return Err(error);
+                }
+                Ok(None) => {
+                    state.closed = true;
+                    return Err(timeout_error());
+                }
+            }
+            written = true;
+        }
+    }
+}
+
+impl<'a> Driving<'a> {
+    /// Marks the socket of `connection` as being used until the returned value is dropped
+    fn new(connection: &'a HTTP2Connection) -> Self {
+        connection.driving.set(true);
+        Driving(connection)
+    }
+}
+
+impl<'a> Drop for Driving<'a> {
+    fn drop(&mut self) {
+        self.0.driving.set(false);
+        self.0.notify.notify_all();
+    }
+}
+
+impl State {
+    /// Gets the number of bytes of frames which have been written or are waiting to be
+    fn queued(&self) -> u64 {
+        self.written + self.output.len() as u64
+    }
+
+    /// Processes every complete frame received from the client
+    ///
+    /// Errors which only affect a single stream reset it, while errors affecting the whole
+    /// connection send a "GOAWAY" and close it.
+    fn process_input(&mut self) -> Event {
+        loop {
+            let result = match self.next_frame() {
+                Ok(Some((header, payload))) => self.process(header, &payload),
+                Ok(None) => return Event::Processed,
+                Err(code) => Err(HTTP2Error::Connection(code)),
+            };
+
+            match result {
+                Ok(()) => {}
+                Err(HTTP2Error::Stream(id, code)) => self.reset(id, code),
+                Err(HTTP2Error::Request(id, error)) => {
+                    if self.error.is_none() {
+                        self.error = Some((id, error));
+                    }
+                }
+                Err(HTTP2Error::Connection(code)) => {
+                    self.go_away(code);
+                    self.failed = true;
+                    return Event::Failed;
+                }
+            }
+        }
+    }
+
+    /// Takes the next complete frame out of the input, checking the connection preface first
+    fn next_frame(&mut self) -> std::result::Result<Option<(FrameHeader, Vec<u8>)>, ErrorCode> {
+        if !self.preface {
+            if self.input.len() < PREFACE.len() {
+                return Ok(None);
+            }
+
+            if !self.input.starts_with(PREFACE) {
+                return Err(ErrorCode::ProtocolError);
+            }
+
+            self.input.drain(..PREFACE.len());
+            self.preface = true;
+        }
+
+        if self.input.len() < HEADER_LENGTH {
+            return Ok(None);
+        }
+
+        let header = FrameHeader::parse(&self.input);
+        if header.length > DEFAULT_MAX_FRAME_SIZE {
+            return Err(ErrorCode::FrameSizeError);
+        }
+
+        if self.input.len() < HEADER_LENGTH + header.length {
+            return Ok(None);
+        }
+
+        let payload = self.input[HEADER_LENGTH..HEADER_LENGTH + header.length].to_vec();
+        self.input.drain(..HEADER_LENGTH + header.length);
+        Ok(Some((header, payload)))
+    }
+
+    /// Queues as much of `data` as the flow control windows allow as a "DATA" frame on the stream
+    /// `id`, setting "END_STREAM" if all of it fits and `end_stream` is true
+    ///
+    /// Returns how many bytes were queued, or [`None`] if the stream or connection is closed.
+    fn queue_data(&mut self, id: u32, data: &[u8], end_stream: bool) -> Option<usize> {
+        if self.failed || self.closed {
+            return None;
+        }
+
+        let max_frame_size = self.settings.max_frame_size() as i64;
+        let connection_window = self.send_window;
+        let stream = self.stream_mut(id)?;
+
+        let available = connection_window
+            .min(stream.send_window)
+            .min(max_frame_size);
+        if !data.is_empty() && available <= 0 {
+            return Some(0);
+        }
+
+        let length = data.len().min(available.max(0) as usize);
+        stream.send_window -= length as i64;
+        self.send_window -= length as i64;
+
+        let last = end_stream && length == data.len();
+        write_frame(
+            &mut self.output,
+            FrameType::Data,
+            if last { END_STREAM } else { 0 },
+            id,
+            &data[..length],
+        );
+        Some(length)
+    }
+
+    /// Processes a frame received from the client
+    fn process(
+        &mut self,
+        header: FrameHeader,
+        payload: &[u8],
+    ) -> std::result::Result<(), HTTP2Error> {
+        // Nothing is allowed between the frames of a header block
+        if let Some(header_block) = &self.header_block {
+            if header.kind != Some(FrameType::Continuation) || header.stream != header_block.stream
+            {
+                return Err(HTTP2Error::Connection(ErrorCode::ProtocolError));
+            }
+        }
+
+        let kind = match header.kind {
+            Some(kind) => kind,
+            None => return Ok(()),
+        };
+
+        match kind {
+            FrameType::Data => self.process_data(header, payload),
+            FrameType::Headers => self.process_headers(header, payload),
+            FrameType::Continuation => self.process_continuation(header, payload),
+            FrameType::Priority => {
+                self.check_stream_frame(header)?;
+                if payload.len() != 5 {
+                    return Err(HTTP2Error::Stream(header.stream, ErrorCode::FrameSizeError));
+                }
+                Ok(())
+            }
+            FrameType::RstStream => {
+                self.check_stream_frame(header)?;
+                if payload.len() != 4 {
+                    return Err(HTTP2Error::Connection(ErrorCode::FrameSizeError));
+                }
+
+                self.remove(header.stream);
+                Ok(())
+            }
+            FrameType::Settings => self.process_settings(header, payload),
+            FrameType::PushPromise => Err(HTTP2Error::Connection(ErrorCode::ProtocolError)),
+            FrameType::Ping => {
+                self.check_connection_frame(header)?;
+                if payload.len() != 8 {
+                    return Err(HTTP2Error::Connection(ErrorCode::FrameSizeError));
+                }
+
+                if !header.has_flag(ACK) {
+                    write_frame(&mut self.output, FrameType::Ping, ACK, 0, payload);
+                }
+                Ok(())
+            }
+            FrameType::GoAway => {
+                self.check_connection_frame(header)?;
+                if payload.len() < 8 {
+                    return Err(HTTP2Error::Connection(ErrorCode::FrameSizeError));
+                }
+
+                self.client_going_away = true;
+                Ok(())
+            }
+            FrameType::WindowUpdate => self.process_window_update(header, payload),
+        }
+    }
+
+    /// Processes a "DATA" frame, adding it to the body of its request
+    fn process_data(
+        &mut self,
+        header: FrameHeader,
+        payload: &[u8],
+    ) -> std::result::Result<(), HTTP2Error> {
+        self.check_stream_frame(header)?;
+        let data = strip_padding(header, payload)?;
+
+        // Room is made for everything received straight away, as request bodies are limited by
+        // size instead of by flow control
+        if header.length > 0 {
+            self.window_update(0, header.length);
+        }
+
+        let id = header.stream;
+        let max_body_size = self.max_body_size;
+        let stream = match self.stream_mut(id) {
+            Some(stream) if !stream.complete => stream,
+            _ => return Err(HTTP2Error::Stream(id, ErrorCode::StreamClosed)),
+        };
+
+        stream.body.extend_from_slice(data);
+        if stream.body.len() > max_body_size {
+            stream.complete = true;
+            return Err(HTTP2Error::Request(id, HTTPParseError::BodyTooLarge));
+        }
+
+        if stream
+            .content_length
+            .map(|content_length| stream.body.len() > content_length)
+            .unwrap_or(false)
+        {
+            return Err(HTTP2Error::Stream(id, ErrorCode::ProtocolError));
+        }
+
+        if header.has_flag(END_STREAM) {
+            return self.complete(id);
+        }
+
+        if header.length > 0 {
+            self.window_update(id, header.length);
+        }
+        Ok(())
+    }
+
+    /// Processes a "HEADERS" frame, which starts a header block
+    fn process_headers(
+        &mut self,
+        header: FrameHeader,
+        payload: &[u8],
+    ) -> std::result::Result<(), HTTP2Error> {
+        if header.stream == 0 || header.stream.is_multiple_of(2) {
+            return Err(HTTP2Error::Connection(ErrorCode::ProtocolError));
+        }
+
+        let mut fragment = strip_padding(header, payload)?;
+
+        // Priorities are ignored
+        if header.has_flag(PRIORITY) {
+            if fragment.len() < 5 {
+                return Err(HTTP2Error::Connection(ErrorCode::FrameSizeError));
+            }
+            fragment = &fragment[5..];
+        }
+
+        self.header_block = Some(HeaderBlock {
+            stream: header.stream,
+            end_stream: header.has_flag(END_STREAM),
+            block: fragment.to_vec(),
+        });
+
+        if header.has_flag(END_HEADERS) {
+            self.finish_header_block()
+        } else {
+            self.check_header_block_size()
+        }
+    }
+
+    /// Processes a "CONTINUATION" frame, which continues a header block
+    fn process_continuation(
+        &mut self,
+        header: FrameHeader,
+        payload: &[u8],
+    ) -> std::result::Result<(), HTTP2Error> {
+        let header_block = self
+            .header_block
+            .as_mut()
+            .ok_or(HTTP2Error::Connection(ErrorCode::ProtocolError))?;
+        header_block.block.extend_from_slice(payload);
+
+        if header.has_flag(END_HEADERS) {
+            self.finish_header_block()
+        } else {
+            self.check_header_block_size()
+        }
+    }
+
+    /// Checks that the header block being received hasn't grown too large to decode
+    ///
+    /// Header blocks can't be skipped without breaking the decoder, so this closes the connection.
+    fn check_header_block_size(&self) -> std::result::Result<(), HTTP2Error> {
+        match &self.header_block {
+            Some(header_block) if header_block.block.len() > self.max_header_size * 2 => {
+                Err(HTTP2Error::Connection(ErrorCode::EnhanceYourCalm))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes a complete header block, opening a stream or adding trailers to one
+    fn finish_header_block(&mut self) -> std::result::Result<(), HTTP2Error> {
+        let HeaderBlock {
+            stream: id,
+            end_stream,
+            block,
+        } = self.header_block.take().unwrap();
+
+        let fields = self
+            .decoder
+            .decode(&block, self.max_header_size)
+            .map_err(|_| HTTP2Error::Connection(ErrorCode::CompressionError))?;
+
+        // A second header block holds the trailers, which have to end the stream
+        if let Some(stream) = self.stream_mut(id) {
+            if stream.complete {
+                return Err(HTTP2Error::Stream(id, ErrorCode::StreamClosed));
+            }
+
+            if !end_stream {
+                return Err(HTTP2Error::Stream(id, ErrorCode::ProtocolError));
+            }
+
+            let fields = match fields {
+                Some(fields) => fields,
+                None => {
+                    stream.complete = true;
+                    return Err(HTTP2Error::Request(id, HTTPParseError::HeadersTooLong));
+                }
+            };
+
+            validate(&fields, true).map_err(|code| HTTP2Error::Stream(id, code))?;
+            stream.trailers = fields;
+            return self.complete(id);
+        }
+
+        if id <= self.last_stream {
+            return Err(HTTP2Error::Connection(ErrorCode::StreamClosed));
+        }
+        self.last_stream = id;
+
+        // The client will retry requests sent after a "GOAWAY" on a new connection
+        if self.going_away {
+            return Ok(());
+        }
+
+        if self.streams.len() >= MAX_CONCURRENT_STREAMS {
+            return Err(HTTP2Error::Stream(id, ErrorCode::RefusedStream));
+        }
+
+        let initial_window_size = self.settings.initial_window_size();
+        let fields = match fields {
+            Some(fields) => fields,
+            None => {
+                let mut stream = Stream::new(id, Vec::new(), None, initial_window_size);
+                stream.complete = true;
+                self.streams.push(stream);
+                return Err(HTTP2Error::Request(id, HTTPParseError::HeadersTooLong));
+            }
+        };
+
+        let content_length =
+            validate(&fields, false).map_err(|code| HTTP2Error::Stream(id, code))?;
+
+        // Bodies are always accepted, so a client waiting for a "100 Continue" gets one straight
+        // away
+        let expects_continue = !end_stream
+            && fields.iter().any(|(name, value)| {
+                name.as_ref() == b"expect" && value.eq_ignore_ascii_case(b"100-continue")
+            });
+
+        self.streams
+            .push(Stream::new(id, fields, content_length, initial_window_size));
+
+        if expects_continue {
+            let mut block = Vec::new();
+            hpack::encode(b":status", b"100", &mut block);
+            self.write_header_block(id, &block, false);
+        }
+
+        if end_stream {
+            self.complete(id)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Processes a "SETTINGS" frame, acknowledging it
+    fn process_settings(
+        &mut self,
+        header: FrameHeader,
+        payload: &[u8],
+    ) -> std::result::Result<(), HTTP2Error> {
+        self.check_connection_frame(header)?;
+
+        if header.has_flag(ACK) {
+            if !payload.is_empty() {
+                return Err(HTTP2Error::Connection(ErrorCode::FrameSizeError));
+            }
+            return Ok(());
+        }
+
+        let delta = self
+            .settings
+            .apply(payload)
+            .map_err(HTTP2Error::Connection)?;
+        for stream in &mut self.streams {
+            stream.send_window += delta;
+            if stream.send_window > MAX_WINDOW_SIZE {
+                return Err(HTTP2Error::Connection(ErrorCode::FlowControlError));
+            }
+        }
+
+        write_frame(&mut self.output, FrameType::Settings, ACK, 0, &[]);
+        Ok(())
+    }
+
+    /// Processes a "WINDOW_UPDATE" frame, making room to send more
+    fn process_window_update(
+        &mut self,
+        header: FrameHeader,
+        payload: &[u8],
+    ) -> std::result::Result<(), HTTP2Error> {
+        if payload.len() != 4 {
+            return Err(HTTP2Error::Connection(ErrorCode::FrameSizeError));
+        }
+        let increment = (read_u32(payload) & 0x7FFF_FFFF) as i64;
+
+        if header.stream == 0 {
+            if increment == 0 {
+                return Err(HTTP2Error::Connection(ErrorCode::ProtocolError));
+            }
+
+            self.send_window += increment;
+            if self.send_window > MAX_WINDOW_SIZE {
+                return Err(HTTP2Error::Connection(ErrorCode::FlowControlError));
+            }
+            return Ok(());
+        }
+
+        self.check_stream_frame(header)?;
+        if increment == 0 {
+            return Err(HTTP2Error::Stream(header.stream, ErrorCode::ProtocolError));
+        }
+
+        if let Some(stream) = self.stream_mut(header.stream) {
+            stream.send_window += increment;
+            if stream.send_window > MAX_WINDOW_SIZE {
+                return Err(HTTP2Error::Stream(
+                    header.stream,
+                    ErrorCode::FlowControlError,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a frame which applies to the whole connection isn't sent on a stream
+    fn check_connection_frame(&self, header: FrameHeader) -> std::result::Result<(), HTTP2Error> {
+        if header.stream != 0 {
+            return Err(HTTP2Error::Connection(ErrorCode::ProtocolError));
+        }
+        Ok(())
+    }
+
+    /// Checks that a frame which applies to a stream is sent on one the client has opened
+    fn check_stream_frame(&self, header: FrameHeader) -> std::result::Result<(), HTTP2Error> {
+        if header.stream == 0 || header.stream > self.last_stream {
+            return Err(HTTP2Error::Connection(ErrorCode::ProtocolError));
+        }
+        Ok(())
+    }
+
+    /// Marks the request on the stream `id` as complete so it can be handed out
+    fn complete(&mut self, id: u32) -> std::result::Result<(), HTTP2Error> {
+        let stream = self.stream_mut(id).unwrap();
+        stream.complete = true;
+
+        if let Some(content_length) = stream.content_length {
+            if content_length != stream.body.len() {
+                return Err(HTTP2Error::Stream(id, ErrorCode::ProtocolError));
+            }
+        }
+
+        self.ready.push_back(id);
+        Ok(())
+    }
+
+    /// Gets the stream with the ID `id` if it is open
+    fn stream_mut(&mut self, id: u32) -> Option<&mut Stream> {
+        self.streams.iter_mut().find(|stream| stream.id == id)
+    }
+
+    /// Forgets the stream with the ID `id`
+    fn remove(&mut self, id: u32) {
+        self.streams.retain(|stream| stream.id != id);
+        self.ready.retain(|ready| *ready != id);
+    }
+
+    /// Closes the stream with the ID `id` because of `code`
+    fn reset(&mut self, id: u32, code: ErrorCode) {
+        self.remove(id);
+        write_frame(
+            &mut self.output,
+            FrameType::RstStream,
+            0,
+            id,
+            &code.code().to_be_bytes(),
+        );
+    }
+
+    /// Tells the client the connection is closing because of `code`
+    fn go_away(&mut self, code: ErrorCode) {
+        let mut payload = [0; 8];
+        payload[..4].copy_from_slice(&self.last_stream.to_be_bytes());
+        payload[4..].copy_from_slice(&code.code().to_be_bytes());
+        write_frame(&mut self.output, FrameType::GoAway, 0, 0, &payload);
+        self.going_away = true;
+    }
+
+    /// Gives the client room to send `increment` more bytes on the stream `id`, or on the whole
+    /// connection if `id` is 0
+    fn window_update(&mut self, id: u32, increment: usize) {
+        write_frame(
+            &mut self.output,
+            FrameType::WindowUpdate,
+            0,
+            id,
+            &(increment as u32).to_be_bytes(),
+        );
+    }
+
+    /// Writes a header block on the stream `id`, splitting it into as many frames as needed
+    fn write_header_block(&mut self, id: u32, block: &[u8], end_stream: bool) {
+        let mut chunks = block.chunks(self.settings.max_frame_size()).peekable();
+        let mut kind = FrameType::Headers;
+        let mut flags = if end_stream { END_STREAM } else { 0 };
+        loop {
+            let chunk = chunks.next().unwrap_or(&[]);
+            if chunks.peek().is_none() {
+                flags |= END_HEADERS;
+            }
+
+            write_frame(&mut self.output, kind, flags, id, chunk);
+            if flags & END_HEADERS != 0 {
+                return;
+            }
+
+            kind = FrameType::Continuation;
+            flags = 0;
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::HTTPParseError;
+
+/// The reason a stream or connection is closed, sent in "RST_STREAM" and "GOAWAY" frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorCode {
+    /// The stream or connection is closed without an error
+    NoError = 0x0,
+
+    /// The client broke the protocol
+    ProtocolError = 0x1,
+
+    /// The client broke flow control
+    FlowControlError = 0x3,
+
+    /// A frame was received for a stream which was already closed
+    StreamClosed = 0x5,
+
+    /// A frame had an invalid size
+    FrameSizeError = 0x6,
+
+    /// The stream was refused before any of it was processed
+    RefusedStream = 0x7,
+
+    /// A header block couldn't be decoded
+    CompressionError = 0x9,
+
+    /// The client is sending more than the server is willing to handle
+    EnhanceYourCalm = 0xB,
+}
+
+/// An error caused by a frame from the client
+#[derive(Debug)]
+pub(super) enum HTTP2Error {
+    /// The whole connection has to be closed
+    Connection(ErrorCode),
+
+    /// Only the stream with the contained ID has to be reset
+    Stream(u32, ErrorCode),
+
+    /// The request on the stream with the contained ID can't be handled, but can be answered
+    Request(u32, HTTPParseError),
+}
+
+impl ErrorCode {
+    /// Gets the code as it is sent in frames
+    pub(super) fn code(&self) -> u32 {
+        *self as u32
+    }
+}
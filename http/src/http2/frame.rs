@@ -0,0 +1,126 @@
+/// The length of the header at the start of every frame
+pub(super) const HEADER_LENGTH: usize = 9;
+
+/// The largest frame payload allowed before the client is told otherwise
+pub(super) const DEFAULT_MAX_FRAME_SIZE: usize = 16384;
+
+/// The flag on "DATA" and "HEADERS" frames marking the last frame the sender will send on a stream
+pub(super) const END_STREAM: u8 = 0x1;
+
+/// The flag on "SETTINGS" and "PING" frames marking them as acknowledgements
+pub(super) const ACK: u8 = 0x1;
+
+/// The flag on "HEADERS" and "CONTINUATION" frames marking the end of a header block
+pub(super) const END_HEADERS: u8 = 0x4;
+
+/// The flag on "DATA" and "HEADERS" frames marking that they contain padding
+pub(super) const PADDED: u8 = 0x8;
+
+/// The flag on "HEADERS" frames marking that they contain a priority
+pub(super) const PRIORITY: u8 = 0x20;
+
+/// The type of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FrameType {
+    /// Part of the body of a request or response
+    Data = 0x0,
+
+    /// The start of a header block, opening a stream or carrying trailers
+    Headers = 0x1,
+
+    /// A change to the priority of a stream, which is ignored
+    Priority = 0x2,
+
+    /// The immediate end of a stream
+    RstStream = 0x3,
+
+    /// Parameters for how the sender wants to be communicated with
+    Settings = 0x4,
+
+    /// A stream the server intends to open, which clients can't send
+    PushPromise = 0x5,
+
+    /// A request for an acknowledgement, used to measure round trips and check the connection
+    Ping = 0x6,
+
+    /// The end of the connection
+    GoAway = 0x7,
+
+    /// More room in a flow control window
+    WindowUpdate = 0x8,
+
+    /// The continuation of a header block
+    Continuation = 0x9,
+}
+
+/// The header at the start of every frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FrameHeader {
+    /// The length of the payload following the header
+    pub(super) length: usize,
+
+    /// The type of the frame, or [`None`] for unknown types which are ignored
+    pub(super) kind: Option<FrameType>,
+
+    /// The flags set on the frame
+    pub(super) flags: u8,
+
+    /// The ID of the stream the frame belongs to, or 0 for the whole connection
+    pub(super) stream: u32,
+}
+
+impl FrameType {
+    /// Gets the frame type with the code `kind`
+    fn parse(kind: u8) -> Option<Self> {
+        Some(match kind {
+            0x0 => FrameType::Data,
+            0x1 => FrameType::Headers,
+            0x2 => FrameType::Priority,
+            0x3 => FrameType::RstStream,
+            0x4 => FrameType::Settings,
+            0x5 => FrameType::PushPromise,
+            0x6 => FrameType::Ping,
+            0x7 => FrameType::GoAway,
+            0x8 => FrameType::WindowUpdate,
+            0x9 => FrameType::Continuation,
+            _ => return None,
+        })
+    }
+}
+
+impl FrameHeader {
+    /// Parses a [`FrameHeader`] from the first [`HEADER_LENGTH`] bytes of `bytes`
+    pub(super) fn parse(bytes: &[u8]) -> Self {
+        FrameHeader {
+            length: u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]) as usize,
+            kind: FrameType::parse(bytes[3]),
+            flags: bytes[4],
+            stream: read_u32(&bytes[5..]) & 0x7FFF_FFFF,
+        }
+    }
+
+    /// Is `flag` set on this frame?
+    pub(super) fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+}
+
+/// Reads a big-endian 32-bit integer from the start of `bytes`
+pub(super) fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Writes a frame onto the end of `output`
+pub(super) fn write_frame(
+    output: &mut Vec<u8>,
+    kind: FrameType,
+    flags: u8,
+    stream: u32,
+    payload: &[u8],
+) {
+    output.extend_from_slice(&(payload.len() as u32).to_be_bytes()[1..]);
+    output.push(kind as u8);
+    output.push(flags);
+    output.extend_from_slice(&stream.to_be_bytes());
+    output.extend_from_slice(payload);
+}
@@ -0,0 +1,110 @@
+use super::{decode_integer, huffman, table::Table, Field, HPACKError};
+
+/// The size of the dynamic table before the client changes it, which is the most it may use
+const MAX_TABLE_SIZE: usize = 4096;
+
+/// The fields decoded from a header block, as pairs of names and values
+pub(crate) type DecodedFields = Vec<Field>;
+
+/// Decodes the header blocks sent by a client
+///
+/// Every header block on a connection has to be decoded in order, even the ones for requests
+/// which are refused, as they may change the dynamic table.
+pub(in crate::http2) struct Decoder {
+    /// The fields header blocks can refer to
+    table: Table,
+}
+
+/// Decodes a string literal from the start of `input`
+fn decode_string(input: &mut &[u8]) -> Result<Box<[u8]>, HPACKError> {
+    let huffman = input.first().ok_or(HPACKError)? & 0x80 != 0;
+    let length = decode_integer(input, 7)?;
+    if length > input.len() {
+        return Err(HPACKError);
+    }
+
+    let (string, rest) = input.split_at(length);
+    *input = rest;
+
+    if !huffman {
+        return Ok(string.into());
+    }
+
+    let mut output = Vec::with_capacity(length * 2);
+    huffman::decode(string, &mut output)?;
+    Ok(output.into_boxed_slice())
+}
+
+impl Decoder {
+    /// Creates a new [`Decoder`]
+    pub(in crate::http2) fn new() -> Self {
+        Decoder {
+            table: Table::new(MAX_TABLE_SIZE),
+        }
+    }
+
+    /// Decodes the fields in `block`
+    ///
+    /// Returns [`None`] if the names and values of the fields add up to more than `max_size`
+    /// bytes, in which case the block is still decoded to keep the dynamic table up to date.
+    pub(in crate::http2) fn decode(
+        &mut self,
+        mut block: &[u8],
+        max_size: usize,
+    ) -> Result<Option<DecodedFields>, HPACKError> {
+        let mut fields = Some(Vec::new());
+        let mut size = 0;
+        let mut first = true;
+
+        while !block.is_empty() {
+            let representation = block[0];
+
+            let (name, value) = if representation & 0x80 != 0 {
+                // Indexed field
+                let (name, value) = self
+                    .table
+                    .get(decode_integer(&mut block, 7)?)
+                    .ok_or(HPACKError)?;
+                (Box::from(name), Box::from(value))
+            } else if representation & 0xE0 == 0x20 {
+                // Dynamic table size update, which must come before any fields
+                let max_size = decode_integer(&mut block, 5)?;
+                if !first || max_size > MAX_TABLE_SIZE {
+                    return Err(HPACKError);
+                }
+
+                self.table.set_max_size(max_size);
+                continue;
+            } else {
+                // Literal field, which is added to the dynamic table if the 0x40 bit is set
+                let indexed = representation & 0x40 != 0;
+                let index = decode_integer(&mut block, if indexed { 6 } else { 4 })?;
+
+                let name = if index == 0 {
+                    decode_string(&mut block)?
+                } else {
+                    Box::from(self.table.get(index).ok_or(HPACKError)?.0)
+                };
+                let value = decode_string(&mut block)?;
+
+                if indexed {
+                    self.table.insert(name.clone(), value.clone());
+                }
+
+                (name, value)
+            };
+            first = false;
+
+            size += name.len() + value.len();
+            if size > max_size {
+                fields = None;
+            }
+
+            if let Some(fields) = &mut fields {
+                fields.push((name, value));
+            }
+        }
+
+        Ok(fields)
+    }
+}
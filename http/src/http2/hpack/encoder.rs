@@ -0,0 +1,26 @@
+use super::{encode_integer, table::find_static};
+
+/// Encodes a string literal without Huffman coding
+fn encode_string(string: &[u8], output: &mut Vec<u8>) {
+    encode_integer(string.len(), 7, 0, output);
+    output.extend_from_slice(string);
+}
+
+/// Encodes the field `name` with `value` onto the end of `output`
+///
+/// Fields are never added to the dynamic table, so blocks encoded by this can be decoded in any
+/// order. Fields in the static table are sent by index, and the names of other fields in it are.
+pub(in crate::http2) fn encode(name: &[u8], value: &[u8], output: &mut Vec<u8>) {
+    match find_static(name, value) {
+        (Some(index), _) => encode_integer(index, 7, 0x80, output),
+        (None, Some(index)) => {
+            encode_integer(index, 4, 0, output);
+            encode_string(value, output);
+        }
+        (None, None) => {
+            output.push(0);
+            encode_string(name, output);
+            encode_string(value, output);
+        }
+    }
+}
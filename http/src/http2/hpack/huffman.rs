@@ -0,0 +1,116 @@
+use super::HPACKError;
+
+/// The number of bits in the code for each symbol, where symbol 256 marks the end of a string
+///
+/// The code is canonical, so the codes themselves follow from their lengths: shorter codes come
+/// first and codes of the same length are ordered by their symbols.
+const LENGTHS: [u8; 257] = [
+    13, 23, 28, 28, 28, 28, 28, 28, 28, 24, 30, 28, 28, 30, 28, 28, // 0x00
+    28, 28, 28, 28, 28, 28, 30, 28, 28, 28, 28, 28, 28, 28, 28, 28, // 0x10
+    6, 10, 10, 12, 13, 6, 8, 11, 10, 10, 8, 11, 8, 6, 6, 6, // 0x20
+    5, 5, 5, 6, 6, 6, 6, 6, 6, 6, 7, 8, 15, 6, 12, 10, // 0x30
+    13, 6, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, // 0x40
+    7, 7, 7, 7, 7, 7, 7, 7, 8, 7, 8, 13, 19, 13, 14, 6, // 0x50
+    15, 5, 6, 5, 6, 5, 6, 6, 6, 5, 7, 7, 6, 6, 6, 5, // 0x60
+    6, 7, 6, 5, 5, 6, 7, 7, 7, 7, 7, 15, 11, 14, 13, 28, // 0x70
+    20, 22, 20, 20, 22, 22, 22, 23, 22, 23, 23, 23, 23, 23, 24, 23, // 0x80
+    24, 24, 22, 23, 24, 23, 23, 23, 23, 21, 22, 23, 22, 23, 23, 24, // 0x90
+    22, 21, 20, 22, 22, 23, 23, 21, 23, 22, 22, 24, 21, 22, 23, 23, // 0xA0
+    21, 21, 22, 21, 23, 22, 23, 23, 20, 22, 22, 22, 23, 22, 22, 23, // 0xB0
+    26, 26, 20, 19, 22, 23, 22, 25, 26, 26, 26, 27, 27, 26, 24, 25, // 0xC0
+    19, 21, 26, 27, 27, 26, 27, 24, 21, 21, 26, 26, 28, 27, 27, 27, // 0xD0
+    20, 24, 20, 21, 22, 21, 21, 23, 22, 22, 25, 25, 24, 24, 26, 23, // 0xE0
+    26, 27, 26, 26, 27, 27, 27, 27, 27, 28, 27, 27, 27, 27, 27, 26, // 0xF0
+    30, // End of string
+];
+
+/// The length of the longest code
+const MAX_LENGTH: usize = 30;
+
+/// The symbol marking the end of a string, which is never allowed inside one
+const END_OF_STRING: u16 = 256;
+
+/// The code in the form it is decoded with
+struct Code {
+    /// The number of codes of each length
+    counts: [u16; MAX_LENGTH + 1],
+
+    /// The symbols ordered by their codes
+    symbols: [u16; 257],
+}
+
+/// The code decoded strings are decoded with
+const CODE: Code = Code::new();
+
+impl Code {
+    /// Orders the symbols by their codes
+    const fn new() -> Self {
+        let mut counts = [0; MAX_LENGTH + 1];
+        let mut symbol = 0;
+        while symbol < LENGTHS.len() {
+            counts[LENGTHS[symbol] as usize] += 1;
+            symbol += 1;
+        }
+
+        let mut symbols = [0; 257];
+        let mut next = 0;
+        let mut length = 1;
+        while length <= MAX_LENGTH {
+            let mut symbol = 0;
+            while symbol < LENGTHS.len() {
+                if LENGTHS[symbol] as usize == length {
+                    symbols[next] = symbol as u16;
+                    next += 1;
+                }
+                symbol += 1;
+            }
+            length += 1;
+        }
+
+        Code { counts, symbols }
+    }
+}
+
+/// Decodes the Huffman encoded string in `input` onto the end of `output`
+pub(crate) fn decode(input: &[u8], output: &mut Vec<u8>) -> Result<(), HPACKError> {
+    // The bits of the code being decoded
+    let mut code = 0;
+    let mut length = 0;
+
+    // The first code with the current length and the index of its symbol
+    let mut first = 0;
+    let mut index = 0;
+
+    for byte in input {
+        for bit in (0..8).rev() {
+            code = (code << 1) | ((byte >> bit) & 1) as u32;
+            length += 1;
+
+            let count = CODE.counts[length] as u32;
+            if code - first < count {
+                let symbol = CODE.symbols[index + (code - first) as usize];
+                if symbol == END_OF_STRING {
+                    return Err(HPACKError);
+                }
+
+                output.push(symbol as u8);
+                code = 0;
+                length = 0;
+                first = 0;
+                index = 0;
+            } else if length == MAX_LENGTH {
+                return Err(HPACKError);
+            } else {
+                first = (first + count) << 1;
+                index += count as usize;
+            }
+        }
+    }
+
+    // Strings are padded with fewer than 8 bits from the start of the end of string code
+    if length > 7 || code != (1 << length) - 1 {
+        return Err(HPACKError);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! HPACK header compression for HTTP/2 (RFC 7541)
+
+mod decoder;
+mod encoder;
+mod table;
+
+pub(crate) mod huffman;
+
+#[cfg(test)]
+mod tests;
+
+pub(super) use decoder::Decoder;
+pub(super) use encoder::encode;
+
+pub(crate) use decoder::DecodedFields;
+
+/// The name and value of a field
+pub(crate) type Field = (Box<[u8]>, Box<[u8]>);
+
+/// A header block which can't be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HPACKError;
+
+/// Decodes an integer with a `prefix` bit prefix from the start of `input`
+pub(crate) fn decode_integer(input: &mut &[u8], prefix: u32) -> Result<usize, HPACKError> {
+    let (&first, rest) = input.split_first().ok_or(HPACKError)?;
+    *input = rest;
+
+    let max = (1 << prefix) - 1;
+    let mut value = first as usize & max;
+    if value < max {
+        return Ok(value);
+    }
+
+    let mut shift = 0;
+    loop {
+        let (&byte, rest) = input.split_first().ok_or(HPACKError)?;
+        *input = rest;
+
+        // No integer this decoder cares about needs more than four continuation bytes
+        if shift > 21 {
+            return Err(HPACKError);
+        }
+
+        value += ((byte & 0x7F) as usize) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+}
+
+/// Encodes `value` with a `prefix` bit prefix, with `flags` in the bits before the prefix
+pub(crate) fn encode_integer(value: usize, prefix: u32, flags: u8, output: &mut Vec<u8>) {
+    let max = (1 << prefix) - 1;
+    if value < max {
+        output.push(flags | value as u8);
+        return;
+    }
+
+    output.push(flags | max as u8);
+    let mut value = value - max;
+    while value >= 0x80 {
+        output.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
@@ -0,0 +1,165 @@
+use super::Field;
+use std::collections::VecDeque;
+
+/// The number of entries in the static table
+pub(super) const STATIC_TABLE_LENGTH: usize = STATIC_TABLE.len();
+
+/// The fields every header block can refer to by index
+const STATIC_TABLE: [(&[u8], &[u8]); 61] = [
+    (b":authority", b""),
+    (b":method", b"GET"),
+    (b":method", b"POST"),
+    (b":path", b"/"),
+    (b":path", b"/index.html"),
+    (b":scheme", b"http"),
+    (b":scheme", b"https"),
+    (b":status", b"200"),
+    (b":status", b"204"),
+    (b":status", b"206"),
+    (b":status", b"304"),
+    (b":status", b"400"),
+    (b":status", b"404"),
+    (b":status", b"500"),
+    (b"accept-charset", b""),
+    (b"accept-encoding", b"gzip, deflate"),
+    (b"accept-language", b""),
+    (b"accept-ranges", b""),
+    (b"accept", b""),
+    (b"access-control-allow-origin", b""),
+    (b"age", b""),
+    (b"allow", b""),
+    (b"authorization", b""),
+    (b"cache-control", b""),
+    (b"content-disposition", b""),
+    (b"content-encoding", b""),
+    (b"content-language", b""),
+    (b"content-length", b""),
+    (b"content-location", b""),
+    (b"content-range", b""),
+    (b"content-type", b""),
+    (b"cookie", b""),
+    (b"date", b""),
+    (b"etag", b""),
+    (b"expect", b""),
+    (b"expires", b""),
+    (b"from", b""),
+    (b"host", b""),
+    (b"if-match", b""),
+    (b"if-modified-since", b""),
+    (b"if-none-match", b""),
+    (b"if-range", b""),
+    (b"if-unmodified-since", b""),
+    (b"last-modified", b""),
+    (b"link", b""),
+    (b"location", b""),
+    (b"max-forwards", b""),
+    (b"proxy-authenticate", b""),
+    (b"proxy-authorization", b""),
+    (b"range", b""),
+    (b"referer", b""),
+    (b"refresh", b""),
+    (b"retry-after", b""),
+    (b"server", b""),
+    (b"set-cookie", b""),
+    (b"strict-transport-security", b""),
+    (b"transfer-encoding", b""),
+    (b"user-agent", b""),
+    (b"vary", b""),
+    (b"via", b""),
+    (b"www-authenticate", b""),
+];
+
+/// The size every entry in the dynamic table takes on top of its name and value
+const ENTRY_OVERHEAD: usize = 32;
+
+/// The fields a header block can refer to by index, made up of the static table followed by the
+/// fields previous header blocks added
+pub(super) struct Table {
+    /// The fields added by previous header blocks, newest first
+    entries: VecDeque<Field>,
+
+    /// The size of the entries in the dynamic table
+    size: usize,
+
+    /// The maximum size of the entries in the dynamic table
+    max_size: usize,
+}
+
+/// Finds the index of the static table entry for `name` and `value`
+///
+/// Returns the tuple `(exact, name)` where `exact` is the index of an entry matching both and
+/// `name` is the index of the first entry matching only the name.
+pub(super) fn find_static(name: &[u8], value: &[u8]) -> (Option<usize>, Option<usize>) {
+    let mut name_index = None;
+    for (index, (entry_name, entry_value)) in STATIC_TABLE.iter().enumerate() {
+        if *entry_name != name {
+            continue;
+        }
+
+        if *entry_value == value {
+            return (Some(index + 1), Some(index + 1));
+        }
+
+        if name_index.is_none() {
+            name_index = Some(index + 1);
+        }
+    }
+
+    (None, name_index)
+}
+
+impl Table {
+    /// Creates a new [`Table`] whose dynamic table can hold `max_size` bytes
+    pub(super) fn new(max_size: usize) -> Self {
+        Table {
+            entries: VecDeque::new(),
+            size: 0,
+            max_size,
+        }
+    }
+
+    /// Gets the name and value of the field at `index`, which starts at 1
+    pub(super) fn get(&self, index: usize) -> Option<(&[u8], &[u8])> {
+        if index == 0 {
+            return None;
+        }
+
+        if index <= STATIC_TABLE_LENGTH {
+            return Some(STATIC_TABLE[index - 1]);
+        }
+
+        self.entries
+            .get(index - STATIC_TABLE_LENGTH - 1)
+            .map(|(name, value)| (name.as_ref(), value.as_ref()))
+    }
+
+    /// Adds a field to the dynamic table, evicting the oldest fields to make room for it
+    ///
+    /// A field larger than the whole table empties it without being added.
+    pub(super) fn insert(&mut self, name: Box<[u8]>, value: Box<[u8]>) {
+        let size = name.len() + value.len() + ENTRY_OVERHEAD;
+        if size > self.max_size {
+            self.entries.clear();
+            self.size = 0;
+            return;
+        }
+
+        self.evict(self.max_size - size);
+        self.size += size;
+        self.entries.push_front((name, value));
+    }
+
+    /// Changes the maximum size of the dynamic table, evicting fields until they fit
+    pub(super) fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        self.evict(max_size);
+    }
+
+    /// Evicts the oldest fields until the dynamic table is no larger than `size`
+    fn evict(&mut self, size: usize) {
+        while self.size > size {
+            let (name, value) = self.entries.pop_back().unwrap();
+            self.size -= name.len() + value.len() + ENTRY_OVERHEAD;
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Tests against the examples in RFC 7541 Appendix C
+
+use super::*;
+
+/// Parses a string of hexadecimal digits into bytes, ignoring whitespace
+fn hex(digits: &str) -> Vec<u8> {
+    let digits: Vec<u8> = digits.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+/// Decodes `block` with `decoder`, checking it decodes to `expected`
+fn assert_decodes(decoder: &mut Decoder, block: &[u8], expected: &[(&str, &str)]) {
+    let fields = decoder.decode(block, usize::MAX).unwrap().unwrap();
+    let fields: Vec<(&str, &str)> = fields
+        .iter()
+        .map(|(name, value)| {
+            (
+                std::str::from_utf8(name).unwrap(),
+                std::str::from_utf8(value).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(fields, expected);
+}
+
+/// The requests of C.3 and C.4, which are the same with and without Huffman coding
+const REQUESTS: [&[(&str, &str)]; 3] = [
+    &[
+        (":method", "GET"),
+        (":scheme", "http"),
+        (":path", "/"),
+        (":authority", "www.example.com"),
+    ],
+    &[
+        (":method", "GET"),
+        (":scheme", "http"),
+        (":path", "/"),
+        (":authority", "www.example.com"),
+        ("cache-control", "no-cache"),
+    ],
+    &[
+        (":method", "GET"),
+        (":scheme", "https"),
+        (":path", "/index.html"),
+        (":authority", "www.example.com"),
+        ("custom-key", "custom-value"),
+    ],
+];
+
+/// The responses of C.5 and C.6, which are the same with and without Huffman coding
+const RESPONSES: [&[(&str, &str)]; 3] = [
+    &[
+        (":status", "302"),
+        ("cache-control", "private"),
+        ("date", "Mon, 21 Oct 2013 20:13:21 GMT"),
+        ("location", "https://www.example.com"),
+    ],
+    &[
+        (":status", "307"),
+        ("cache-control", "private"),
+        ("date", "Mon, 21 Oct 2013 20:13:21 GMT"),
+        ("location", "https://www.example.com"),
+    ],
+    &[
+        (":status", "200"),
+        ("cache-control", "private"),
+        ("date", "Mon, 21 Oct 2013 20:13:22 GMT"),
+        ("location", "https://www.example.com"),
+        ("content-encoding", "gzip"),
+        (
+            "set-cookie",
+            "foo=ASDJKHQKBZXOQWEOPIUAXQWEOIU; max-age=3600; version=1",
+        ),
+    ],
+];
+
+/// Decodes the responses of C.5 or C.6 from `blocks`
+///
+/// The examples use a dynamic table of 256 bytes, which is set by a size update at the start of
+/// the first block.
+fn assert_decodes_responses(blocks: [&str; 3]) {
+    let mut decoder = Decoder::new();
+    for (index, (block, expected)) in blocks.iter().zip(RESPONSES).enumerate() {
+        let mut block = hex(block);
+        if index == 0 {
+            let mut update = Vec::new();
+            encode_integer(256, 5, 0x20, &mut update);
+            block.splice(..0, update);
+        }
+
+        assert_decodes(&mut decoder, &block, expected);
+    }
+}
+
+#[test]
+fn integers() {
+    // C.1.1 to C.1.3
+    for (value, prefix, encoded) in [(10, 5, "0a"), (1337, 5, "1f9a0a"), (42, 8, "2a")] {
+        let mut output = Vec::new();
+        encode_integer(value, prefix, 0, &mut output);
+        assert_eq!(output, hex(encoded));
+
+        let mut input = output.as_slice();
+        assert_eq!(decode_integer(&mut input, prefix), Ok(value));
+        assert!(input.is_empty());
+    }
+
+    // The flags before the prefix aren't part of the value
+    assert_eq!(decode_integer(&mut hex("ea").as_slice(), 5), Ok(10));
+
+    assert_eq!(decode_integer(&mut hex("1f9a").as_slice(), 5), Err(HPACKError));
+    assert_eq!(
+        decode_integer(&mut hex("1fffffffff7f").as_slice(), 5),
+        Err(HPACKError)
+    );
+}
+
+#[test]
+fn literal_fields() {
+    let mut decoder = Decoder::new();
+
+    // C.2.1, which adds the field to the dynamic table as index 62
+    let block = hex("400a637573746f6d2d6b65790d637573746f6d2d686561646572");
+    assert_decodes(&mut decoder, &block, &[("custom-key", "custom-header")]);
+    assert_decodes(&mut decoder, &[0xBE], &[("custom-key", "custom-header")]);
+
+    // C.2.2 and C.2.3, which leave the dynamic table alone
+    let block = hex("040c2f73616d706c652f70617468");
+    assert_decodes(&mut decoder, &block, &[(":path", "/sample/path")]);
+    let block = hex("100870617373776f726406736563726574");
+    assert_decodes(&mut decoder, &block, &[("password", "secret")]);
+    assert_eq!(decoder.decode(&[0xBF], usize::MAX), Err(HPACKError));
+}
+
+#[test]
+fn indexed_fields() {
+    // C.2.4
+    assert_decodes(&mut Decoder::new(), &[0x82], &[(":method", "GET")]);
+
+    assert_eq!(Decoder::new().decode(&[0x80], usize::MAX), Err(HPACKError));
+    assert_eq!(Decoder::new().decode(&[0xBE], usize::MAX), Err(HPACKError));
+}
+
+#[test]
+fn requests_without_huffman_coding() {
+    // C.3
+    let mut decoder = Decoder::new();
+    for (block, expected) in [
+        "828684410f7777772e6578616d706c652e636f6d",
+        "828684be58086e6f2d6361636865",
+        "828785bf400a637573746f6d2d6b65790c637573746f6d2d76616c7565",
+    ]
+    .iter()
+    .zip(REQUESTS)
+    {
+        assert_decodes(&mut decoder, &hex(block), expected);
+    }
+}
+
+#[test]
+fn requests_with_huffman_coding() {
+    // C.4
+    let mut decoder = Decoder::new();
+    for (block, expected) in [
+        "828684418cf1e3c2e5f23a6ba0ab90f4ff",
+        "828684be5886a8eb10649cbf",
+        "828785bf408825a849e95ba97d7f8925a849e95bb8e8b4bf",
+    ]
+    .iter()
+    .zip(REQUESTS)
+    {
+        assert_decodes(&mut decoder, &hex(block), expected);
+    }
+}
+
+#[test]
+fn responses_without_huffman_coding() {
+    // C.5, where adding each response evicts fields from the last
+    assert_decodes_responses([
+        "4803333032580770726976617465611d4d6f6e2c203231204f637420323031332032303a31333a323120474d
+         546e1768747470733a2f2f7777772e6578616d706c652e636f6d",
+        "4803333037c1c0bf",
+        "88c1611d4d6f6e2c203231204f637420323031332032303a31333a323220474d54c05a04677a697077386
+         66f6f3d4153444a4b48514b425a584f5157454f50495541585157454f49553b206d61782d6167653d33363
+         0303b2076657273696f6e3d31",
+    ]);
+}
+
+#[test]
+fn responses_with_huffman_coding() {
+    // C.6
+    assert_decodes_responses([
+        "488264025885aec3771a4b6196d07abe941054d444a8200595040b8166e082a62d1bff6e919d29ad171863c7
+         8f0b97c8e9ae82ae43d3",
+        "4883640effc1c0bf",
+        "88c16196d07abe941054d444a8200595040b8166e084a62d1bffc05a839bd9ab77ad94e7821dd7f2e6c7b335
+         dfdfcd5b3960d5af27087f3672c1ab270fb5291f9587316065c003ed4ee5b1063d5007",
+    ]);
+}
+
+#[test]
+fn table_size_updates() {
+    // An update has to come before any fields and can't grow the table past what was allowed
+    let mut update = Vec::new();
+    encode_integer(4097, 5, 0x20, &mut update);
+    assert_eq!(Decoder::new().decode(&update, usize::MAX), Err(HPACKError));
+    assert_eq!(
+        Decoder::new().decode(&[0x82, 0x20], usize::MAX),
+        Err(HPACKError)
+    );
+
+    // Shrinking the table to nothing evicts everything in it
+    let mut decoder = Decoder::new();
+    let block = hex("400a637573746f6d2d6b65790d637573746f6d2d686561646572");
+    assert_decodes(&mut decoder, &block, &[("custom-key", "custom-header")]);
+    assert_decodes(&mut decoder, &[0x20, 0x82], &[(":method", "GET")]);
+    assert_eq!(decoder.decode(&[0xBE], usize::MAX), Err(HPACKError));
+}
+
+#[test]
+fn oversized_blocks_still_update_the_table() {
+    let mut decoder = Decoder::new();
+    let block = hex("400a637573746f6d2d6b65790d637573746f6d2d686561646572");
+    assert_eq!(decoder.decode(&block, 10), Ok(None));
+    assert_decodes(&mut decoder, &[0xBE], &[("custom-key", "custom-header")]);
+}
+
+#[test]
+fn invalid_huffman_strings() {
+    // The end of string code can't be decoded
+    let mut output = Vec::new();
+    assert_eq!(
+        huffman::decode(&hex("fffffffc"), &mut output),
+        Err(HPACKError)
+    );
+
+    // Padding has to be the start of the end of string code and shorter than a byte
+    assert_eq!(huffman::decode(&hex("00"), &mut output), Err(HPACKError));
+    assert_eq!(huffman::decode(&hex("1fff"), &mut output), Err(HPACKError));
+    assert_eq!(huffman::decode(&hex("1f"), &mut output), Ok(()));
+}
+
+#[test]
+fn encoded_fields_decode() {
+    let fields: [(&[u8], &[u8]); 4] = [
+        (b":status", b"200"),
+        (b":status", b"418"),
+        (b"content-type", b"text/plain"),
+        (b"x-custom", b"value"),
+    ];
+
+    let mut block = Vec::new();
+    for (name, value) in fields {
+        encode(name, value, &mut block);
+    }
+
+    // Fields in the static table are sent by index
+    assert_eq!(block[0], 0x88);
+
+    let decoded = Decoder::new().decode(&block, usize::MAX).unwrap().unwrap();
+    let decoded: Vec<(&[u8], &[u8])> = decoded
+        .iter()
+        .map(|(name, value)| (name.as_ref(), value.as_ref()))
+        .collect();
+    assert_eq!(decoded, fields);
+}
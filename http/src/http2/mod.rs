@@ -0,0 +1,34 @@
+//! HTTP/2 (RFC 9113), used for clients which send the connection preface in the clear or
+//! negotiate "h2" through TLS ALPN
+
+mod connection;
+mod error;
+mod frame;
+mod hpack;
+mod request;
+mod response;
+mod settings;
+mod stream;
+
+pub(crate) use connection::{HTTP2Connection, MAX_CONCURRENT_STREAMS};
+pub(crate) use request::HTTP2Request;
+pub(crate) use response::encode_header;
+
+#[cfg(feature = "http3")]
+pub(crate) use hpack::{decode_integer, encode_integer, huffman, DecodedFields, HPACKError};
+#[cfg(feature = "http3")]
+pub(crate) use request::validate;
+#[cfg(feature = "http3")]
+pub(crate) use response::header_fields;
+
+/// The bytes every HTTP/2 connection from a client starts with
+pub(crate) const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// The fields which only apply to HTTP/1.1 connections, which aren't allowed in HTTP/2 or HTTP/3
+pub(crate) const CONNECTION_FIELDS: [&[u8]; 5] = [
+    b"connection",
+    b"keep-alive",
+    b"proxy-connection",
+    b"transfer-encoding",
+    b"upgrade",
+];
@@ -0,0 +1,247 @@
+use super::{error::ErrorCode, hpack::DecodedFields, CONNECTION_FIELDS};
+use crate::{
+    coding,
+    request::{is_token_char, HeaderLimits},
+    HTTPMethod, HTTPParseError, HTTPParseLimit, HTTPRequest, HTTPRequestField, HTTPRequestFields,
+    HTTPRequestHeader, HTTPTarget, HTTPVersion,
+};
+
+// rustdoc imports
+#[allow(unused_imports)]
+use crate::HTTPRequestBuffer;
+
+/// A request received on an HTTP/2 stream
+///
+/// This is kept in the [`HTTPRequestBuffer`] so the [`HTTPRequest`] parsed from it can borrow it.
+pub(crate) struct HTTP2Request {
+    /// The fields of the request header, including the pseudo-header fields
+    fields: DecodedFields,
+
+    /// The trailer fields sent after the body
+    trailers: DecodedFields,
+
+    /// The body of the request
+    body: Vec<u8>,
+
+    /// The "cookie" fields joined into one, as HTTP/2 clients may split them up
+    cookie: Vec<u8>,
+
+    /// The version the request was sent with, either HTTP/2 or HTTP/3
+    version: HTTPVersion,
+}
+
+/// Is `value` allowed as the value of a field?
+fn is_valid_value(value: &[u8]) -> bool {
+    !value.starts_with(b" ")
+        && !value.starts_with(b"\t")
+        && !value.ends_with(b" ")
+        && !value.ends_with(b"\t")
+        && value
+            .iter()
+            .all(|c| *c >= 0x21 || *c == b' ' || *c == b'\t')
+}
+
+/// Is `name` allowed as the name of a regular field?
+fn is_valid_name(name: &[u8]) -> bool {
+    !name.is_empty()
+        && name
+            .iter()
+            .all(|c| is_token_char(*c) && !c.is_ascii_uppercase())
+}
+
+/// Checks that a header block from the client is well-formed, returning the length the
+/// "content-length" field gives the body
+///
+/// Trailers can't contain pseudo-header fields. A malformed block resets the stream.
+pub(crate) fn validate(fields: &DecodedFields, trailers: bool) -> Result<Option<usize>, ErrorCode> {
+    let mut method = None;
+    let mut scheme = None;
+    let mut path = None;
+    let mut authority = None;
+    let mut content_length = None;
+    let mut regular = false;
+
+    for (name, value) in fields {
+        if !is_valid_value(value) {
+            return Err(ErrorCode::ProtocolError);
+        }
+
+        // Pseudo-header fields have to come before any regular fields
+        if name.starts_with(b":") {
+            let pseudo_header = match name.as_ref() {
+                b":method" => &mut method,
+                b":scheme" => &mut scheme,
+                b":path" => &mut path,
+                b":authority" => &mut authority,
+                _ => return Err(ErrorCode::ProtocolError),
+            };
+
+            if regular || trailers || pseudo_header.replace(value).is_some() {
+                return Err(ErrorCode::ProtocolError);
+            }
+            continue;
+        }
+        regular = true;
+
+        if !is_valid_name(name)
+            || CONNECTION_FIELDS.contains(&name.as_ref())
+            || (name.as_ref() == b"te" && value.as_ref() != b"trailers")
+        {
+            return Err(ErrorCode::ProtocolError);
+        }
+
+        if name.as_ref() == b"content-length" {
+            if value.is_empty() || !value.iter().all(u8::is_ascii_digit) {
+                return Err(ErrorCode::ProtocolError);
+            }
+
+            let length = std::str::from_utf8(value)
+                .unwrap()
+                .parse()
+                .map_err(|_| ErrorCode::ProtocolError)?;
+            if content_length.replace(length).unwrap_or(length) != length {
+                return Err(ErrorCode::ProtocolError);
+            }
+        }
+    }
+
+    if trailers {
+        return Ok(content_length);
+    }
+
+    let valid = match method {
+        Some(method) if method.as_ref() == b"CONNECT" => {
+            scheme.is_none() && path.is_none() && authority.is_some()
+        }
+        Some(method) => {
+            !method.is_empty()
+                && method.iter().all(|c| is_token_char(*c))
+                && scheme.is_some()
+                && path.map(|path| !path.is_empty()).unwrap_or(false)
+        }
+        None => false,
+    };
+
+    if valid {
+        Ok(content_length)
+    } else {
+        Err(ErrorCode::ProtocolError)
+    }
+}
+
+/// Converts decoded fields into [`HTTPRequestField`]s, applying `limits` to them
+///
+/// Pseudo-header fields and "cookie" fields are skipped.
+fn convert_fields<'a>(
+    fields: &'a DecodedFields,
+    limits: HeaderLimits,
+) -> Result<Vec<HTTPRequestField<'a>>, HTTPParseError> {
+    let mut converted = Vec::new();
+    for (name, value) in fields {
+        if name.starts_with(b":") || name.as_ref() == b"cookie" {
+            continue;
+        }
+
+        if converted.len() == limits.max_fields {
+            return Err(HTTPParseError::LimitExceeded(HTTPParseLimit::FieldCount));
+        }
+
+        if name.len() + value.len() > limits.max_field_size {
+            return Err(HTTPParseError::LimitExceeded(HTTPParseLimit::FieldSize));
+        }
+
+        converted.push(HTTPRequestField::new(name, value));
+    }
+    Ok(converted)
+}
+
+/// Gets the value of the pseudo-header field called `name`
+fn pseudo_header<'a>(fields: &'a DecodedFields, name: &[u8]) -> Option<&'a [u8]> {
+    fields
+        .iter()
+        .find(|(field, _)| field.as_ref() == name)
+        .map(|(_, value)| value.as_ref())
+}
+
+impl HTTP2Request {
+    /// Creates a new [`HTTP2Request`] from a validated header and trailers
+    ///
+    /// HTTP/3 requests have the same form as HTTP/2 requests, so they are kept in this too.
+    pub(crate) fn new(
+        fields: DecodedFields,
+        trailers: DecodedFields,
+        body: Vec<u8>,
+        version: HTTPVersion,
+    ) -> Self {
+        let mut cookie = Vec::new();
+        for (_, value) in fields.iter().filter(|(name, _)| name.as_ref() == b"cookie") {
+            if !cookie.is_empty() {
+                cookie.extend_from_slice(b"; ");
+            }
+            cookie.extend_from_slice(value);
+        }
+
+        HTTP2Request {
+            fields,
+            trailers,
+            body,
+            cookie,
+            version,
+        }
+    }
+
+    /// Parses an [`HTTPRequest`] from this request
+    ///
+    /// The ":authority" pseudo-header field is turned into a "host" field if the request doesn't
+    /// have one, so requests look the same regardless of the version they were sent with.
+    pub(crate) fn parse(
+        &mut self,
+        limits: HeaderLimits,
+        max_body_size: usize,
+    ) -> Result<HTTPRequest<'_>, HTTPParseError> {
+        let body = std::mem::take(&mut self.body);
+        let request: &HTTP2Request = self;
+
+        let method = HTTPMethod::from_slice(pseudo_header(&request.fields, b":method").unwrap());
+        let authority = pseudo_header(&request.fields, b":authority");
+        let target = match method {
+            HTTPMethod::CONNECT => authority.unwrap(),
+            _ => pseudo_header(&request.fields, b":path").unwrap(),
+        };
+        let target = HTTPTarget::new(target, limits.max_target_length)?;
+
+        let mut fields = convert_fields(&request.fields, limits)?;
+        if !request.cookie.is_empty() {
+            fields.push(HTTPRequestField::new(b"cookie", &request.cookie));
+        }
+        if let Some(authority) = authority {
+            if !fields.iter().any(|field| field.name() == b"host") {
+                fields.insert(0, HTTPRequestField::new(b"host", authority));
+            }
+        }
+
+        let header = HTTPRequestHeader::new(
+            method,
+            target,
+            request.version,
+            HTTPRequestFields::new(fields),
+        )?;
+
+        // The client was already sent a "100 Continue", but other expectations can't be met
+        header.expects_continue()?;
+
+        let trailers = HTTPRequestFields::new(convert_fields(&request.trailers, limits)?);
+
+        let body = if body.is_empty() {
+            None
+        } else {
+            Some(coding::decode(
+                header.fields(),
+                body.into_boxed_slice(),
+                max_body_size,
+            )?)
+        };
+
+        Ok(HTTPRequest::new(header, body, max_body_size, trailers))
+    }
+}
@@ -0,0 +1,34 @@
+use super::{hpack, CONNECTION_FIELDS};
+use crate::HTTPStatus;
+
+/// Encodes the header generated for an HTTP/1.1 response as an HTTP/2 header block
+///
+/// The status line is replaced by the ":status" pseudo-header field, field names are lowercased,
+/// and fields which only apply to HTTP/1.1 connections are dropped.
+pub(crate) fn encode_header(status: HTTPStatus, header: &[u8]) -> Vec<u8> {
+    let mut block = Vec::new();
+    hpack::encode(b":status", status.code().to_string().as_bytes(), &mut block);
+
+    for (name, value) in header_fields(header) {
+        hpack::encode(&name, value, &mut block);
+    }
+
+    block
+}
+
+/// Gets the fields of the header generated for an HTTP/1.1 response, with their names lowercased
+///
+/// The status line and fields which only apply to HTTP/1.1 connections are skipped.
+pub(crate) fn header_fields(header: &[u8]) -> impl Iterator<Item = (Vec<u8>, &[u8])> {
+    header.split(|c| *c == b'\n').skip(1).filter_map(|line| {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let colon = line.iter().position(|c| *c == b':')?;
+
+        let name = line[..colon].to_ascii_lowercase();
+        if CONNECTION_FIELDS.contains(&name.as_slice()) {
+            return None;
+        }
+
+        Some((name, line[colon + 1..].trim_ascii()))
+    })
+}
@@ -0,0 +1,101 @@
+use super::{error::ErrorCode, frame::DEFAULT_MAX_FRAME_SIZE};
+
+/// The identifier of the setting allowing the server to push streams
+const ENABLE_PUSH: u16 = 0x2;
+
+/// The identifier of the setting limiting how many streams the sender allows to be open at once
+const MAX_CONCURRENT_STREAMS: u16 = 0x3;
+
+/// The identifier of the setting for the size of the flow control window of new streams
+const INITIAL_WINDOW_SIZE: u16 = 0x4;
+
+/// The identifier of the setting for the largest frame payload the sender accepts
+const MAX_FRAME_SIZE: u16 = 0x5;
+
+/// The identifier of the setting for the largest header block the sender accepts
+const MAX_HEADER_LIST_SIZE: u16 = 0x6;
+
+/// The size of flow control windows before any settings change them
+pub(super) const DEFAULT_WINDOW_SIZE: u32 = 65535;
+
+/// The largest allowed flow control window
+pub(super) const MAX_WINDOW_SIZE: i64 = 0x7FFF_FFFF;
+
+/// The largest frame payload which can be allowed
+const MAX_MAX_FRAME_SIZE: u32 = 0xFF_FFFF;
+
+/// The settings sent by a client which affect what the server sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Settings {
+    /// The flow control window new streams start with
+    initial_window_size: u32,
+
+    /// The largest frame payload the client accepts
+    max_frame_size: u32,
+}
+
+/// Encodes the settings the server sends at the start of a connection
+pub(super) fn encode(max_concurrent_streams: u32, max_header_list_size: u32) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(12);
+    for (identifier, value) in [
+        (MAX_CONCURRENT_STREAMS, max_concurrent_streams),
+        (MAX_HEADER_LIST_SIZE, max_header_list_size),
+    ] {
+        payload.extend_from_slice(&identifier.to_be_bytes());
+        payload.extend_from_slice(&value.to_be_bytes());
+    }
+    payload
+}
+
+impl Settings {
+    /// Creates the [`Settings`] in effect before the client sends any
+    pub(super) fn new() -> Self {
+        Settings {
+            initial_window_size: DEFAULT_WINDOW_SIZE,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE as u32,
+        }
+    }
+
+    /// Gets the flow control window new streams start with
+    pub(super) fn initial_window_size(&self) -> u32 {
+        self.initial_window_size
+    }
+
+    /// Gets the largest frame payload the client accepts
+    pub(super) fn max_frame_size(&self) -> usize {
+        self.max_frame_size as usize
+    }
+
+    /// Applies the settings in the payload of a "SETTINGS" frame
+    ///
+    /// Returns how much the initial window size changed by, which applies to every open stream.
+    /// Settings which don't affect the server are ignored.
+    pub(super) fn apply(&mut self, payload: &[u8]) -> Result<i64, ErrorCode> {
+        if !payload.len().is_multiple_of(6) {
+            return Err(ErrorCode::FrameSizeError);
+        }
+
+        let old_window_size = self.initial_window_size;
+        for setting in payload.chunks_exact(6) {
+            let identifier = u16::from_be_bytes([setting[0], setting[1]]);
+            let value = u32::from_be_bytes([setting[2], setting[3], setting[4], setting[5]]);
+
+            match identifier {
+                ENABLE_PUSH if value > 1 => return Err(ErrorCode::ProtocolError),
+                INITIAL_WINDOW_SIZE if value as i64 > MAX_WINDOW_SIZE => {
+                    return Err(ErrorCode::FlowControlError)
+                }
+                INITIAL_WINDOW_SIZE => self.initial_window_size = value,
+                MAX_FRAME_SIZE
+                    if value < DEFAULT_MAX_FRAME_SIZE as u32 || value > MAX_MAX_FRAME_SIZE =>
+                {
+                    return Err(ErrorCode::ProtocolError)
+                }
+                MAX_FRAME_SIZE => self.max_frame_size = value,
+                _ => {}
+            }
+        }
+
+        Ok(self.initial_window_size as i64 - old_window_size as i64)
+    }
+}
@@ -0,0 +1,56 @@
+use super::{hpack::DecodedFields, HTTP2Request};
+use crate::HTTPVersion;
+
+/// A stream opened by a request from the client
+pub(super) struct Stream {
+    /// The ID the client opened the stream with
+    pub(super) id: u32,
+
+    /// Has the client finished sending the request?
+    pub(super) complete: bool,
+
+    /// The fields of the request header, including the pseudo-header fields
+    pub(super) fields: DecodedFields,
+
+    /// The trailer fields sent after the body
+    pub(super) trailers: DecodedFields,
+
+    /// The body received so far
+    pub(super) body: Vec<u8>,
+
+    /// The length the "content-length" field says the body has
+    pub(super) content_length: Option<usize>,
+
+    /// The number of bytes of the response which can be sent before the client makes room
+    pub(super) send_window: i64,
+}
+
+impl Stream {
+    /// Creates a new [`Stream`] for a request starting with `fields`
+    pub(super) fn new(
+        id: u32,
+        fields: DecodedFields,
+        content_length: Option<usize>,
+        send_window: u32,
+    ) -> Self {
+        Stream {
+            id,
+            complete: false,
+            fields,
+            trailers: Vec::new(),
+            body: Vec::new(),
+            content_length,
+            send_window: send_window as i64,
+        }
+    }
+
+    /// Takes the request received on this stream
+    pub(super) fn take_request(&mut self) -> HTTP2Request {
+        HTTP2Request::new(
+            std::mem::take(&mut self.fields),
+            std::mem::take(&mut self.trailers),
+            std::mem::take(&mut self.body),
+            HTTPVersion::HTTP2,
+        )
+    }
+}
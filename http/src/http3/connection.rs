@@ -0,0 +1,1095 @@
+use super::{
+    endpoint::QUICEndpoint,
+    error::{ErrorCode, H3Error},
+    frame::{read_varint, write_frame, write_frame_header, write_varint, FrameHeader, FrameType},
+    qpack, settings,
+    stream::{RequestStream, UniStream, UniStreamKind},
+};
+use crate::{
+    http2::{validate, HTTP2Request},
+    HTTPOptions, HTTPParseError, HTTPTLSInfo, Result,
+};
+use bytes::Bytes;
+use huntsman::runtime::{
+    protocol_error,
+    sync::LocalNotify,
+    time::{timeout, timeout_error},
+};
+use quinn_proto::{
+    Connection, ConnectionHandle, Dir, Event as QUICEvent, ReadError, StreamEvent, StreamId,
+    VarInt, WriteError,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    future::{poll_fn, Future},
+    pin::pin,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+/// The type of the stream carrying the settings and "GOAWAY" frames of each side
+const CONTROL_STREAM: u64 = 0x0;
+
+/// The type of the streams carrying pushed responses, which clients can't open
+const PUSH_STREAM: u64 = 0x1;
+
+/// The type of the stream carrying instructions for the QPACK dynamic table
+const ENCODER_STREAM: u64 = 0x2;
+
+/// The type of the stream acknowledging field sections which used the QPACK dynamic table
+const DECODER_STREAM: u64 = 0x3;
+
+/// The instruction setting the capacity of the QPACK dynamic table to zero, which is the only one
+/// the client can send on its encoder stream
+const ZERO_CAPACITY: u8 = 0x20;
+
+/// The largest frame allowed on the control stream
+const MAX_CONTROL_FRAME_SIZE: u64 = 16 * 1024;
+
+/// The most request streams a client is allowed to have open at once
+///
+/// A stream stays open until the response to its request has been sent, so this is also the most
+/// requests from a client which are handled at the same time.
+pub(crate) const MAX_CONCURRENT_STREAMS: usize = 100;
+
+/// An HTTP/3 connection with a client
+///
+/// Requests are handed out as soon as they are complete, so the responses to requests on different
+/// streams can be sent at the same time. Only one task waits on the endpoint at a time: whichever
+/// needs something from the client first receives and processes what the client sends, while the
+/// others wait for it to do so. QUIC itself stops the client from opening more request streams
+/// than [`MAX_CONCURRENT_STREAMS`] at once.
+pub(crate) struct HTTP3Connection {
+    /// The endpoint the connection was accepted through
+    endpoint: Arc<QUICEndpoint>,
+
+    /// The handle the endpoint knows the connection by
+    handle: ConnectionHandle,
+
+    /// The state of the connection and its streams, which is never borrowed across an await
+    state: RefCell<State>,
+
+    /// Is a task currently waiting on the endpoint?
+    driving: Cell<bool>,
+
+    /// Notified when a response is finished and when the endpoint is released
+    notify: LocalNotify,
+
+    /// The maximum amount of time allowed between header reads
+    header_read_timeout: Duration,
+
+    /// The maximum amount of time allowed between body reads
+    body_read_timeout: Duration,
+
+    /// The maximum amount of time allowed between writes
+    write_timeout: Duration,
+
+    /// The maximum amount of time to wait for the next request
+    keep_alive_timeout: Duration,
+}
+
+/// The state of an [`HTTP3Connection`] and its streams
+struct State {
+    /// The state of the QUIC connection
+    connection: Connection,
+
+    /// The maximum size of the names and values of the fields in a field section
+    max_header_size: usize,
+
+    /// The maximum size for request bodies
+    max_body_size: usize,
+
+    /// The control stream opened by the server, once the handshake is complete
+    control: Option<StreamId>,
+
+    /// Frames waiting to be written on the control stream
+    control_output: Vec<u8>,
+
+    /// The unidirectional streams the client has open
+    uni_streams: Vec<UniStream>,
+
+    /// The request streams the client has open
+    streams: Vec<RequestStream>,
+
+    /// The IDs of the streams with complete requests, in the order they were completed
+    ready: VecDeque<StreamId>,
+
+    /// The IDs of the streams whose requests have been handed out but not answered
+    answering: Vec<StreamId>,
+
+    /// The ID after the highest request stream the client has opened
+    next_stream: u64,
+
+    /// The streams with finished responses the client hasn't acknowledged yet
+    unacknowledged: Vec<StreamId>,
+
+    /// An error in a request on a stream, which is handed out instead of the next request
+    error: Option<(StreamId, HTTPParseError)>,
+
+    /// Has the handshake completed?
+    connected: bool,
+
+    /// Has the client sent its settings?
+    settings_received: bool,
+
+    /// Has a "GOAWAY" been sent to the client?
+    going_away: bool,
+
+    /// Has the client sent a "GOAWAY"?
+    client_going_away: bool,
+
+    /// Has the QUIC connection been closed by either side?
+    closed: bool,
+
+    /// Was the connection closed because of an error?
+    failed: bool,
+}
+
+/// Marks the endpoint as being waited on by a task until dropped
+struct Driving<'a>(&'a HTTP3Connection);
+
+/// The data read from a stream
+enum Read {
+    /// Data was read, along with whether the stream is finished
+    Data(Vec<u8>, bool),
+
+    /// The client reset the stream
+    Reset,
+}
+
+/// The result of receiving from the client
+enum Event {
+    /// Something was received and processed, or another task finished a response or stopped
+    /// waiting on the endpoint
+    Processed,
+
+    /// The connection was closed
+    Closed,
+
+    /// The client didn't send anything in time
+    TimedOut,
+
+    /// The client broke the protocol and the connection was closed
+    Failed,
+}
+
+/// Is the field `name` with `value` asking for a "100 Continue"?
+fn expects_continue(name: &[u8], value: &[u8]) -> bool {
+    name == b"expect" && value.eq_ignore_ascii_case(b"100-continue")
+}
+
+impl HTTP3Connection {
+    /// Creates a new [`HTTP3Connection`] for the QUIC connection `handle` just accepted through
+    /// `endpoint`
+    pub(crate) fn new(
+        endpoint: Arc<QUICEndpoint>,
+        handle: ConnectionHandle,
+        connection: Connection,
+        options: &HTTPOptions,
+    ) -> Self {
+        HTTP3Connection {
+            endpoint,
+            handle,
+            state: RefCell::new(State {
+                connection,
+                max_header_size: options.max_header_size,
+                max_body_size: options.max_body_size,
+                control: None,
+                control_output: Vec::new(),
+                uni_streams: Vec::new(),
+                streams: Vec::new(),
+                ready: VecDeque::new(),
+                answering: Vec::new(),
+                next_stream: 0,
+                unacknowledged: Vec::new(),
+                error: None,
+                connected: false,
+                settings_received: false,
+                going_away: false,
+                client_going_away: false,
+                closed: false,
+                failed: false,
+            }),
+            driving: Cell::new(false),
+            notify: LocalNotify::new(),
+            header_read_timeout: options.header_read_timeout,
+            body_read_timeout: options.body_read_timeout,
+            write_timeout: options.write_timeout,
+            keep_alive_timeout: options.keep_alive_timeout,
+        }
+    }
+
+    /// Completes the QUIC handshake, returning the negotiated TLS parameters, and sends the
+    /// server's settings
+    pub(crate) async fn handshake(&self) -> Result<HTTPTLSInfo> {
+        while !self.state.borrow().connected {
+            match self.receive(Some(self.header_read_timeout)).await? {
+                Event::Processed => {}
+                Event::Closed | Event::Failed => return Err(protocol_error()),
+                Event::TimedOut => return Err(timeout_error()),
+            }
+        }
+
+        let tls = {
+            let mut state = self.state.borrow_mut();
+            let tls = HTTPTLSInfo::quic(&state.connection).ok_or_else(protocol_error)?;
+
+            state.control = state.connection.streams().open(Dir::Uni);
+            state.control_output.push(CONTROL_STREAM as u8);
+            let max_header_size = state.max_header_size;
+            write_frame(
+                &mut state.control_output,
+                FrameType::Settings,
+                &settings::encode(max_header_size),
+            );
+            state.write_control();
+            tls
+        };
+        self.transmit().await?;
+
+        Ok(tls)
+    }
+
+    /// Waits for the next complete request from the client, returning it with the ID of the stream
+    /// it was sent on
+    ///
+    /// Returns [`None`] once the connection is finished, either because the client closed it, it
+    /// was idle for too long, or a "GOAWAY" was sent or received and every request before it has
+    /// been answered. Errors come with the ID of the stream their response is sent on, if they
+    /// only affect one request.
+    pub(crate) async fn next_request(
+        &self,
+    ) -> std::result::Result<Option<(StreamId, HTTP2Request)>, (Option<StreamId>, HTTPParseError)>
+    {
+        loop {
+            let timeouts = {
+                let mut state = self.state.borrow_mut();
+                if state.closed {
+                    return Ok(None);
+                }
+
+                // The connection closes after an error is answered, so the requests received
+                // before it are handed out first
+                if let Some(id) = state.ready.pop_front() {
+                    state.answering.push(id);
+                    let request = state.stream_mut(id).unwrap().take_request();
+                    return Ok(Some((id, request)));
+                }
+
+                if let Some((id, error)) = state.error.take() {
+                    return Err((Some(id), error));
+                }
+
+                // The first request gets as long as any other header, while later ones are waited
+                // for with the keep-alive timeout. Once every open stream is waiting for its
+                // response, the client has nothing left to send.
+                let idle = state.streams.is_empty();
+                if idle && (state.going_away || state.client_going_away) {
+                    None
+                } else if idle && state.next_stream > 0 {
+                    Some((Some(self.keep_alive_timeout), None))
+                } else if idle {
+                    Some((Some(self.header_read_timeout), None))
+                } else if state
+                    .streams
+                    .iter()
+                    .any(|stream| !stream.complete && stream.fields.is_none())
+                {
+                    Some((
+                        Some(self.header_read_timeout),
+                        Some(HTTPParseError::HeaderReadTimeout),
+                    ))
+                } else if state.streams.iter().any(|stream| !stream.complete) {
+                    Some((
+                        Some(self.body_read_timeout),
+                        Some(HTTPParseError::BodyReadTimeout),
+                    ))
+                } else {
+                    Some((None, None))
+                }
+            };
+
+            let (read_timeout, timeout_error) = match timeouts {
+                Some(timeouts) => timeouts,
+                None => {
+                    self.close().await.map_err(|error| (None, error.into()))?;
+                    return Ok(None);
+                }
+            };
+
+            let event = self
+                .receive(read_timeout)
+                .await
+                .map_err(|error| (None, error.into()))?;
+            match event {
+                Event::Processed => {}
+                Event::Closed => return Ok(None),
+                Event::TimedOut => match timeout_error {
+                    Some(error) => {
+                        self.state.borrow_mut().fail(ErrorCode::NoError);
+                        self.notify.notify_all();
+                        self.transmit()
+                            .await
+                            .map_err(|error| (None, error.into()))?;
+                        return Err((None, error));
+                    }
+                    None => {
+                        self.close().await.map_err(|error| (None, error.into()))?;
+                        return Ok(None);
+                    }
+                },
+                Event::Failed => return Err((None, HTTPParseError::HTTP3ConnectionError)),
+            }
+        }
+    }
+
+    /// Tells the client no more requests will be accepted
+    ///
+    /// The requests the client already started are still handed out and answered.
+    pub(crate) fn shut_down(&self) {
+        self.state.borrow_mut().shut_down();
+    }
+
+    /// Sends the field section of the response on the stream `id`
+    ///
+    /// If `end_stream` is true, the response has no body and the stream is finished.
+    pub(crate) async fn send_headers(
+        &self,
+        id: StreamId,
+        block: &[u8],
+        end_stream: bool,
+    ) -> Result<()> {
+        let mut frame = Vec::with_capacity(block.len() + 16);
+        write_frame(&mut frame, FrameType::Headers, block);
+        self.write(id, &frame, end_stream).await
+    }
+
+    /// Sends `data` as part of the body of the response on the stream `id`, waiting for the
+    /// client to make room for it if needed
+    ///
+    /// If `end_stream` is true, this is the end of the body and the stream is finished.
+    pub(crate) async fn send_data(
+        &self,
+        id: StreamId,
+        data: &[u8],
+        end_stream: bool,
+    ) -> Result<()> {
+        let mut frame = Vec::with_capacity(data.len() + 16);
+        if !data.is_empty() {
+            write_frame_header(&mut frame, FrameType::Data, data.len());
+            frame.extend_from_slice(data);
+        }
+        self.write(id, &frame, end_stream).await
+    }
+
+    /// Finishes the response on the stream `id` and sends anything waiting to be sent
+    ///
+    /// Responses which were already finished, or which the client no longer wants, are left alone.
+    pub(crate) async fn finish(&self, id: StreamId) -> Result<()> {
+        self.state.borrow_mut().finish(id);
+        self.notify.notify_all();
+
+        self.transmit().await
+    }
+
+    /// Closes the connection once every request handed out has been answered
+    ///
+    /// Until then, the client is told no more requests will be accepted and the connection is
+    /// closed when the last response is finished.
+    pub(crate) async fn close_when_answered(&self) -> Result<()> {
+        self.shut_down();
+        if self.state.borrow().answering.is_empty() {
+            self.close().await
+        } else {
+            self.transmit().await
+        }
+    }
+
+    /// Closes the connection once the client has acknowledged every finished response
+    ///
+    /// The client is given the write timeout to acknowledge them, after which the connection is
+    /// closed regardless.
+    pub(crate) async fn close(&self) -> Result<()> {
+        self.shut_down();
+
+        let deadline = Instant::now() + self.write_timeout;
+        loop {
+            {
+                let state = self.state.borrow();
+                if state.unacknowledged.is_empty() || state.closed {
+                    break;
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            match self.receive(Some(deadline - now)).await? {
+                Event::Processed => {}
+                Event::Closed | Event::TimedOut | Event::Failed => break,
+            }
+        }
+
+        {
+            let mut state = self.state.borrow_mut();
+            if !state.closed {
+                state
+                    .connection
+                    .close(Instant::now(), ErrorCode::NoError.code(), Bytes::new());
+                state.closed = true;
+            }
+        }
+        self.notify.notify_all();
+
+        self.transmit().await
+    }
+
+    /// Writes `data` on the stream `id`, waiting for the client to make room for it if needed
+    ///
+    /// If `end_stream` is true, the stream is finished after the data. Responses the client no
+    /// longer wants are dropped without an error.
+    async fn write(&self, id: StreamId, mut data: &[u8], end_stream: bool) -> Result<()> {
+        loop {
+            let written = {
+                let mut state = self.state.borrow_mut();
+                if state.closed || state.stream_mut(id).is_none() {
+                    None
+                } else {
+                    match state.connection.send_stream(id).write(data) {
+                        Ok(length) => Some(length),
+                        Err(WriteError::Blocked) => Some(0),
+                        Err(_) => {
+                            state.remove(id);
+                            None
+                        }
+                    }
+                }
+            };
+
+            match written {
+                Some(length) => data = &data[length..],
+                None => return self.finish(id).await,
+            }
+
+            if data.is_empty() {
+                return if end_stream {
+                    self.finish(id).await
+                } else {
+                    self.transmit().await
+                };
+            }
+
+            // Wait for the client to make room in the flow control windows
+            self.transmit().await?;
+            match self.receive(Some(self.write_timeout)).await? {
+                Event::Processed => {}
+                Event::Closed | Event::Failed => return Err(protocol_error()),
+                Event::TimedOut => return Err(timeout_error()),
+            }
+        }
+    }
+
+    /// Sends the datagrams the connection has waiting to the client
+    async fn transmit(&self) -> Result<()> {
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            let transmit =
+                self.state
+                    .borrow_mut()
+                    .connection
+                    .poll_transmit(Instant::now(), 1, &mut buffer);
+
+            match transmit {
+                Some(transmit) => self.endpoint.send(&transmit, &buffer).await?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Waits for something to happen on the connection, for at most `read_timeout` if it is given
+    ///
+    /// If no other task is waiting on the endpoint, this receives and processes what the client
+    /// sends until something arrives or another task finishes a response. Datagrams which only
+    /// matter to QUIC, like acknowledgements, are handled without returning. Errors which only
+    /// affect a single stream reset it, while errors affecting the whole connection close it.
+    /// Otherwise, this waits for the other task to finish with the endpoint.
+    async fn receive(&self, read_timeout: Option<Duration>) -> Result<Event> {
+        if self.driving.get() {
+            let notified = self.notify.notified();
+            return Ok(match read_timeout {
+                Some(read_timeout) => match timeout(notified, read_timeout).await? {
+                    Some(()) => Event::Processed,
+                    None => Event::TimedOut,
+                },
+                None => {
+                    notified.await;
+                    Event::Processed
+                }
+            });
+        }
+
+        let _driving = Driving::new(self);
+        let deadline = read_timeout.map(|read_timeout| Instant::now() + read_timeout);
+        loop {
+            let (processed, closed, failed) = {
+                let mut state = self.state.borrow_mut();
+                let mut processed = false;
+                while let Some(event) = state.poll(&self.endpoint, self.handle) {
+                    processed = true;
+                    state.process(event);
+                }
+                state.write_control();
+
+                (processed, state.closed, state.failed)
+            };
+            self.transmit().await?;
+
+            if closed {
+                return Ok(if failed { Event::Failed } else { Event::Closed });
+            }
+
+            if processed {
+                return Ok(Event::Processed);
+            }
+
+            let now = Instant::now();
+            if deadline.map(|deadline| now >= deadline).unwrap_or(false) {
+                return Ok(Event::TimedOut);
+            }
+
+            // QUIC has its own timers for retransmissions and acknowledgements
+            let timer = self.state.borrow_mut().connection.poll_timeout();
+            let wake = match (timer, deadline) {
+                (Some(timer), Some(deadline)) => Some(timer.min(deadline)),
+                (timer, deadline) => timer.or(deadline),
+            };
+
+            let notified = {
+                let mut wait = pin!(self.endpoint.wait(self.handle));
+                let mut notified = pin!(self.notify.notified());
+                let select = poll_fn(|cx| {
+                    if let Poll::Ready(result) = wait.as_mut().poll(cx) {
+                        return Poll::Ready(result.map(|()| false));
+                    }
+
+                    notified.as_mut().poll(cx).map(|()| Ok(true))
+                });
+
+                match wake {
+                    Some(wake) => timeout(select, wake.saturating_duration_since(now))
+                        .await?
+                        .transpose()?
+                        .unwrap_or(false),
+                    None => select.await?,
+                }
+            };
+
+            if notified {
+                return Ok(Event::Processed);
+            }
+        }
+    }
+}
+
+impl<'a> Driving<'a> {
+    /// Marks the endpoint as being waited on for `connection` until the returned value is dropped
+    fn new(connection: &'a HTTP3Connection) -> Self {
+        connection.driving.set(true);
+        Driving(connection)
+    }
+}
+
+impl<'a> Drop for Driving<'a> {
+    fn drop(&mut self) {
+        self.0.driving.set(false);
+        self.0.notify.notify_all();
+    }
+}
+
+impl State {
+    /// Hands the connection everything the endpoint received for it, then gets the next event
+    fn poll(&mut self, endpoint: &QUICEndpoint, handle: ConnectionHandle) -> Option<QUICEvent> {
+        for event in endpoint.take_events(handle) {
+            self.connection.handle_event(event);
+        }
+
+        let now = Instant::now();
+        if self
+            .connection
+            .poll_timeout()
+            .map(|timer| timer <= now)
+            .unwrap_or(false)
+        {
+            self.connection.handle_timeout(now);
+        }
+
+        while let Some(event) = self.connection.poll_endpoint_events() {
+            if let Some(event) = endpoint.handle_event(handle, event) {
+                self.connection.handle_event(event);
+            }
+        }
+
+        self.connection.poll()
+    }
+
+    /// Processes an event from the QUIC connection
+    fn process(&mut self, event: QUICEvent) {
+        match event {
+            QUICEvent::Connected => self.connected = true,
+            QUICEvent::ConnectionLost { .. } => self.closed = true,
+            QUICEvent::Stream(StreamEvent::Opened { dir }) => {
+                while let Some(id) = self.connection.streams().accept(dir) {
+                    let result = self.open(id);
+                    self.handle(result);
+                }
+            }
+            QUICEvent::Stream(StreamEvent::Readable { id }) => {
+                let result = match id.dir() {
+                    Dir::Bi => self.read_request(id),
+                    Dir::Uni => self.read_uni(id),
+                };
+                self.handle(result);
+            }
+            QUICEvent::Stream(StreamEvent::Finished { id })
+            | QUICEvent::Stream(StreamEvent::Stopped { id, .. }) => {
+                self.unacknowledged.retain(|other| *other != id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Tells the client no more requests will be accepted, if it hasn't been told already
+    fn shut_down(&mut self) {
+        if self.going_away || self.control.is_none() {
+            return;
+        }
+
+        let mut payload = Vec::new();
+        write_varint(self.next_stream, &mut payload);
+        write_frame(&mut self.control_output, FrameType::GoAway, &payload);
+        self.write_control();
+        self.going_away = true;
+    }
+
+    /// Finishes the response on the stream `id`, if it is still open
+    fn finish(&mut self, id: StreamId) {
+        self.answering.retain(|other| *other != id);
+        if self.stream_mut(id).is_some() && self.connection.send_stream(id).finish().is_ok() {
+            self.unacknowledged.push(id);
+        }
+
+        self.remove(id);
+    }
+
+    /// Handles an error caused by something the client sent
+    fn handle(&mut self, result: std::result::Result<(), H3Error>) {
+        match result {
+            Ok(()) => {}
+            Err(H3Error::Stream(id, code)) => self.reset(id, code),
+            Err(H3Error::Request(id, error)) => {
+                if self.error.is_some() {
+                    return self.reset(id, ErrorCode::RequestRejected);
+                }
+
+                // The rest of the request isn't needed to answer it
+                let _ = self
+                    .connection
+                    .recv_stream(id)
+                    .stop(ErrorCode::NoError.code());
+                if let Some(stream) = self.stream_mut(id) {
+                    stream.complete = true;
+                }
+                self.error = Some((id, error));
+            }
+            Err(H3Error::Connection(code)) => self.fail(code),
+        }
+    }
+
+    /// Starts tracking the stream `id` the client just opened, processing anything already
+    /// received on it
+    fn open(&mut self, id: StreamId) -> std::result::Result<(), H3Error> {
+        if id.dir() == Dir::Uni {
+            self.uni_streams.push(UniStream::new(id));
+            return self.read_uni(id);
+        }
+
+        self.next_stream = VarInt::from(id).into_inner() + 4;
+
+        // The client will retry requests sent after a "GOAWAY" on a new connection
+        if self.going_away {
+            return Err(H3Error::Stream(id, ErrorCode::RequestRejected));
+        }
+
+        self.streams.push(RequestStream::new(id));
+        self.read_request(id)
+    }
+
+    /// Reads everything available on the stream `id`
+    fn read(&mut self, id: StreamId) -> Read {
+        let mut stream = self.connection.recv_stream(id);
+        let mut chunks = match stream.read(true) {
+            Ok(chunks) => chunks,
+            Err(_) => return Read::Data(Vec::new(), false),
+        };
+
+        let mut data = Vec::new();
+        let result = loop {
+            match chunks.next(usize::MAX) {
+                Ok(Some(chunk)) => data.extend_from_slice(&chunk.bytes),
+                Ok(None) => break Read::Data(data, true),
+                Err(ReadError::Blocked) => break Read::Data(data, false),
+                Err(ReadError::Reset(_)) => break Read::Reset,
+            }
+        };
+        let _ = chunks.finalize();
+
+        result
+    }
+
+    /// Reads and processes the frames received on the request stream `id`
+    fn read_request(&mut self, id: StreamId) -> std::result::Result<(), H3Error> {
+        let (data, finished) = match self.read(id) {
+            Read::Data(data, finished) => (data, finished),
+            Read::Reset => {
+                self.remove(id);
+                return Ok(());
+            }
+        };
+
+        // Streams which were already answered or rejected are drained
+        match self.stream_mut(id) {
+            Some(stream) if !stream.complete => stream.input.extend_from_slice(&data),
+            _ => return Ok(()),
+        }
+
+        self.process_request(id)?;
+        if finished {
+            self.complete(id)?;
+        }
+        Ok(())
+    }
+
+    /// Processes the complete frames received on the request stream `id`
+    fn process_request(&mut self, id: StreamId) -> std::result::Result<(), H3Error> {
+        let max_header_size = self.max_header_size;
+        let max_body_size = self.max_body_size;
+        loop {
+            let stream = self.stream_mut(id).unwrap();
+            if stream.complete {
+                return Ok(());
+            }
+
+            if stream.data_remaining > 0 || stream.skip_remaining > 0 {
+                if stream.input.is_empty() {
+                    return Ok(());
+                }
+
+                let remaining = stream.data_remaining.max(stream.skip_remaining);
+                let length = (stream.input.len() as u64).min(remaining) as usize;
+                if stream.data_remaining > 0 {
+                    stream.body.extend(stream.input.drain(..length));
+                    stream.data_remaining -= length as u64;
+                } else {
+                    stream.input.drain(..length);
+                    stream.skip_remaining -= length as u64;
+                }
+
+                if stream.body.len() > max_body_size {
+                    return Err(H3Error::Request(id, HTTPParseError::BodyTooLarge));
+                }
+
+                if stream
+                    .content_length
+                    .map(|content_length| stream.body.len() > content_length)
+                    .unwrap_or(false)
+                {
+                    return Err(H3Error::Stream(id, ErrorCode::MessageError));
+                }
+                continue;
+            }
+
+            let header = match FrameHeader::parse(&stream.input) {
+                Some(header) => header,
+                None => return Ok(()),
+            };
+
+            match header.kind {
+                Some(FrameType::Data) => {
+                    if stream.fields.is_none() || stream.trailers.is_some() {
+                        return Err(H3Error::Connection(ErrorCode::FrameUnexpected));
+                    }
+
+                    stream.input.drain(..header.header_length);
+                    stream.data_remaining = header.length;
+                }
+                Some(FrameType::Headers) => {
+                    if stream.trailers.is_some() {
+                        return Err(H3Error::Connection(ErrorCode::FrameUnexpected));
+                    }
+
+                    // Field sections are limited before decoding, as the whole frame is buffered
+                    if header.length > max_header_size as u64 * 2 {
+                        return Err(H3Error::Request(id, HTTPParseError::HeadersTooLong));
+                    }
+
+                    let end = header.header_length + header.length as usize;
+                    if stream.input.len() < end {
+                        return Ok(());
+                    }
+
+                    let block: Vec<u8> = stream
+                        .input
+                        .drain(..end)
+                        .skip(header.header_length)
+                        .collect();
+                    self.process_headers(id, &block)?;
+                }
+                Some(_) => return Err(H3Error::Connection(ErrorCode::FrameUnexpected)),
+                None => {
+                    stream.input.drain(..header.header_length);
+                    stream.skip_remaining = header.length;
+                }
+            }
+        }
+    }
+
+    /// Decodes a field section received on the request stream `id`, which is either the header
+    /// of the request or its trailers
+    fn process_headers(&mut self, id: StreamId, block: &[u8]) -> std::result::Result<(), H3Error> {
+        let fields = qpack::decode(block, self.max_header_size)
+            .map_err(|_| H3Error::Connection(ErrorCode::DecompressionFailed))?
+            .ok_or(H3Error::Request(id, HTTPParseError::HeadersTooLong))?;
+
+        let stream = self.stream_mut(id).unwrap();
+        if stream.fields.is_some() {
+            validate(&fields, true).map_err(|_| H3Error::Stream(id, ErrorCode::MessageError))?;
+            stream.trailers = Some(fields);
+            return Ok(());
+        }
+
+        stream.content_length =
+            validate(&fields, false).map_err(|_| H3Error::Stream(id, ErrorCode::MessageError))?;
+
+        // Bodies are always accepted, so a client waiting for a "100 Continue" gets one straight
+        // away
+        let expects_continue = fields
+            .iter()
+            .any(|(name, value)| expects_continue(name, value));
+        stream.fields = Some(fields);
+
+        if expects_continue {
+            let mut block = qpack::PREFIX.to_vec();
+            qpack::encode(b":status", b"100", &mut block);
+
+            let mut frame = Vec::new();
+            write_frame(&mut frame, FrameType::Headers, &block);
+            let _ = self.connection.send_stream(id).write(&frame);
+        }
+
+        Ok(())
+    }
+
+    /// Marks the request on the stream `id`, which the client finished, as complete so it can be
+    /// handed out
+    fn complete(&mut self, id: StreamId) -> std::result::Result<(), H3Error> {
+        let stream = match self.stream_mut(id) {
+            Some(stream) if !stream.complete => stream,
+            _ => return Ok(()),
+        };
+
+        if !stream.input.is_empty() || stream.data_remaining > 0 || stream.skip_remaining > 0 {
+            return Err(H3Error::Connection(ErrorCode::FrameError));
+        }
+
+        if stream.fields.is_none() {
+            return Err(H3Error::Stream(id, ErrorCode::RequestIncomplete));
+        }
+
+        if let Some(content_length) = stream.content_length {
+            if content_length != stream.body.len() {
+                return Err(H3Error::Stream(id, ErrorCode::MessageError));
+            }
+        }
+
+        stream.complete = true;
+        self.ready.push_back(id);
+        Ok(())
+    }
+
+    /// Reads and processes the data received on the unidirectional stream `id`
+    fn read_uni(&mut self, id: StreamId) -> std::result::Result<(), H3Error> {
+        let (data, finished) = match self.read(id) {
+            Read::Data(data, finished) => (data, finished),
+            Read::Reset => (Vec::new(), true),
+        };
+
+        let index = match self.uni_streams.iter().position(|stream| stream.id == id) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        self.uni_streams[index].input.extend_from_slice(&data);
+
+        let kind = match self.uni_streams[index].kind {
+            Some(kind) => kind,
+            None => {
+                let stream = &mut self.uni_streams[index];
+                let (kind, length) = match read_varint(&stream.input) {
+                    Some(kind) => kind,
+                    None => {
+                        if finished {
+                            self.uni_streams.remove(index);
+                        }
+                        return Ok(());
+                    }
+                };
+                stream.input.drain(..length);
+
+                let kind = match kind {
+                    CONTROL_STREAM => UniStreamKind::Control,
+                    ENCODER_STREAM => UniStreamKind::Encoder,
+                    DECODER_STREAM => UniStreamKind::Decoder,
+                    PUSH_STREAM => return Err(H3Error::Connection(ErrorCode::StreamCreationError)),
+                    _ => {
+                        // Streams of unknown types are ignored
+                        let _ = self
+                            .connection
+                            .recv_stream(id)
+                            .stop(ErrorCode::StreamCreationError.code());
+                        self.uni_streams.remove(index);
+                        return Ok(());
+                    }
+                };
+
+                if self
+                    .uni_streams
+                    .iter()
+                    .any(|stream| stream.kind == Some(kind))
+                {
+                    return Err(H3Error::Connection(ErrorCode::StreamCreationError));
+                }
+                self.uni_streams[index].kind = Some(kind);
+                kind
+            }
+        };
+
+        // The control and QPACK streams have to stay open as long as the connection
+        if finished {
+            return Err(H3Error::Connection(ErrorCode::ClosedCriticalStream));
+        }
+
+        match kind {
+            UniStreamKind::Control => self.process_control(index),
+            UniStreamKind::Encoder => {
+                let input = std::mem::take(&mut self.uni_streams[index].input);
+                if input
+                    .iter()
+                    .any(|instruction| *instruction != ZERO_CAPACITY)
+                {
+                    return Err(H3Error::Connection(ErrorCode::EncoderStreamError));
+                }
+                Ok(())
+            }
+            UniStreamKind::Decoder => {
+                self.uni_streams[index].input.clear();
+                Ok(())
+            }
+        }
+    }
+
+    /// Processes the complete frames received on the client's control stream, which is
+    /// `self.uni_streams[index]`
+    fn process_control(&mut self, index: usize) -> std::result::Result<(), H3Error> {
+        loop {
+            let stream = &mut self.uni_streams[index];
+            let header = match FrameHeader::parse(&stream.input) {
+                Some(header) => header,
+                None => return Ok(()),
+            };
+
+            if header.length > MAX_CONTROL_FRAME_SIZE {
+                return Err(H3Error::Connection(ErrorCode::ExcessiveLoad));
+            }
+
+            let end = header.header_length + header.length as usize;
+            if stream.input.len() < end {
+                return Ok(());
+            }
+
+            let payload: Vec<u8> = stream
+                .input
+                .drain(..end)
+                .skip(header.header_length)
+                .collect();
+
+            // The settings have to come first, and only once
+            if !self.settings_received {
+                if header.kind != Some(FrameType::Settings) {
+                    return Err(H3Error::Connection(ErrorCode::MissingSettings));
+                }
+
+                settings::validate(&payload).map_err(H3Error::Connection)?;
+                self.settings_received = true;
+                continue;
+            }
+
+            match header.kind {
+                Some(FrameType::GoAway) => self.client_going_away = true,
+                Some(FrameType::CancelPush) | Some(FrameType::MaxPushId) | None => {}
+                Some(_) => return Err(H3Error::Connection(ErrorCode::FrameUnexpected)),
+            }
+        }
+    }
+
+    /// Writes as much of the frames waiting for the control stream as the client has room for
+    fn write_control(&mut self) {
+        let id = match self.control {
+            Some(id) if !self.control_output.is_empty() => id,
+            _ => return,
+        };
+
+        if let Ok(length) = self.connection.send_stream(id).write(&self.control_output) {
+            self.control_output.drain(..length);
+        }
+    }
+
+    /// Gets the request stream with the ID `id` if it is open
+    fn stream_mut(&mut self, id: StreamId) -> Option<&mut RequestStream> {
+        self.streams.iter_mut().find(|stream| stream.id == id)
+    }
+
+    /// Forgets the request stream with the ID `id`
+    fn remove(&mut self, id: StreamId) {
+        self.streams.retain(|stream| stream.id != id);
+        self.ready.retain(|ready| *ready != id);
+    }
+
+    /// Closes both sides of the request stream with the ID `id` because of `code`
+    fn reset(&mut self, id: StreamId, code: ErrorCode) {
+        self.remove(id);
+        let _ = self.connection.recv_stream(id).stop(code.code());
+        let _ = self.connection.send_stream(id).reset(code.code());
+    }
+
+    /// Closes the connection immediately because of `code`
+    fn fail(&mut self, code: ErrorCode) {
+        if !self.closed {
+            self.connection
+                .close(Instant::now(), code.code(), Bytes::new());
+        }
+
+        self.closed = true;
+        self.failed = true;
+    }
+}
+
+impl Drop for HTTP3Connection {
+    fn drop(&mut self) {
+        let drained = self.state.get_mut().connection.is_drained();
+        self.endpoint.remove(self.handle, !drained);
+    }
+}
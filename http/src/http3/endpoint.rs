@@ -0,0 +1,314 @@
+use super::MAX_CONCURRENT_STREAMS;
+use crate::{HTTPOptions, HTTPTLSConfig, Result};
+use bytes::BytesMut;
+use huntsman::runtime::net::UDPSocket;
+use quinn_proto::{
+    crypto::rustls::QuicServerConfig, Connection, ConnectionEvent, ConnectionHandle, DatagramEvent,
+    Endpoint, EndpointConfig, EndpointEvent, IdleTimeout, Incoming, ServerConfig, Transmit,
+    TransportConfig, VarInt,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    future::{poll_fn, Future},
+    io::ErrorKind,
+    net::SocketAddr,
+    pin::pin,
+    sync::{Arc, Mutex},
+    task::{Poll, Waker},
+    time::Instant,
+};
+
+/// The largest UDP payload the server accepts
+const MAX_DATAGRAM_SIZE: usize = 1472;
+
+/// The most connections which can wait to be accepted before new ones are refused
+const MAX_INCOMING: usize = 64;
+
+/// The most unidirectional streams a client is allowed to have open at once, which leaves room
+/// for its control and QPACK streams along with a few of unknown types
+const MAX_CONCURRENT_UNI_STREAMS: u32 = 16;
+
+/// A UDP socket shared by every QUIC connection accepted through it
+///
+/// There is no task dedicated to the socket. Instead, every task waiting on the endpoint receives
+/// datagrams while it waits and hands them to the connections they belong to, waking the tasks
+/// driving those connections.
+pub struct QUICEndpoint {
+    /// The socket datagrams are sent and received on
+    socket: UDPSocket,
+
+    /// The state shared between the tasks using the endpoint
+    state: Mutex<State>,
+}
+
+/// The state of an endpoint, which any task may change
+struct State {
+    /// Routes datagrams to connections and accepts new ones
+    endpoint: Endpoint,
+
+    /// The events received for each accepted connection
+    inboxes: HashMap<ConnectionHandle, Inbox>,
+
+    /// The connections waiting to be accepted
+    incoming: VecDeque<Incoming>,
+
+    /// The tasks waiting for a connection to accept
+    acceptors: Vec<Waker>,
+}
+
+/// The events received for a connection which haven't been handled
+#[derive(Default)]
+struct Inbox {
+    /// The events, in the order they were received
+    events: Vec<ConnectionEvent>,
+
+    /// The task driving the connection, if it is waiting for events
+    waker: Option<Waker>,
+}
+
+/// Creates the configuration for connections accepted with `config` and `options`
+fn server_config(config: &HTTPTLSConfig, options: &HTTPOptions) -> Result<ServerConfig> {
+    let crypto = QuicServerConfig::try_from(config.http3())
+        .map_err(|_| crate::Error::from(ErrorKind::InvalidInput))?;
+
+    // The connection is left open for as long as any of the timeouts, which decide when it
+    // closes instead
+    let idle_timeout = options
+        .header_read_timeout
+        .max(options.body_read_timeout)
+        .max(options.write_timeout)
+        .max(options.keep_alive_timeout);
+
+    let mut transport = TransportConfig::default();
+    transport
+        .max_concurrent_bidi_streams(VarInt::from_u32(MAX_CONCURRENT_STREAMS as u32))
+        .max_concurrent_uni_streams(VarInt::from_u32(MAX_CONCURRENT_UNI_STREAMS))
+        .max_idle_timeout(IdleTimeout::try_from(idle_timeout).ok());
+
+    let mut server_config = ServerConfig::with_crypto(Arc::new(crypto));
+    server_config.transport_config(Arc::new(transport));
+    Ok(server_config)
+}
+
+impl QUICEndpoint {
+    /// Creates a new [`QUICEndpoint`] bound to `address`, accepting connections secured with
+    /// `config`
+    ///
+    /// This must be called from inside the runtime
+    pub(crate) fn bind(
+        address: SocketAddr,
+        config: &HTTPTLSConfig,
+        options: &HTTPOptions,
+    ) -> Result<Self> {
+        let mut endpoint_config = EndpointConfig::default();
+        endpoint_config
+            .max_udp_payload_size(MAX_DATAGRAM_SIZE as u16)
+            .unwrap();
+
+        let endpoint = Endpoint::new(
+            Arc::new(endpoint_config),
+            Some(Arc::new(server_config(config, options)?)),
+            true,
+            None,
+        );
+
+        Ok(QUICEndpoint {
+            socket: UDPSocket::bind(address)?,
+            state: Mutex::new(State {
+                endpoint,
+                inboxes: HashMap::new(),
+                incoming: VecDeque::new(),
+                acceptors: Vec::new(),
+            }),
+        })
+    }
+
+    /// Gets the address this endpoint is bound to
+    pub(crate) fn local_addr(&self) -> Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Waits for a new connection, returning it before its handshake is complete
+    pub(crate) async fn accept(&self) -> Result<(ConnectionHandle, Connection)> {
+        loop {
+            self.run_until(|state, waker| {
+                if !state.incoming.is_empty() {
+                    return true;
+                }
+
+                if !state.acceptors.iter().any(|other| other.will_wake(waker)) {
+                    state.acceptors.push(waker.clone());
+                }
+                false
+            })
+            .await?;
+
+            let mut buffer = Vec::new();
+            let response = {
+                let mut state = self.state.lock().unwrap();
+                let incoming = match state.incoming.pop_front() {
+                    Some(incoming) => incoming,
+                    None => continue,
+                };
+
+                match state
+                    .endpoint
+                    .accept(incoming, Instant::now(), &mut buffer, None)
+                {
+                    Ok((handle, connection)) => {
+                        state.inboxes.insert(handle, Inbox::default());
+                        return Ok((handle, connection));
+                    }
+                    Err(error) => error.response,
+                }
+            };
+
+            if let Some(transmit) = response {
+                self.send(&transmit, &buffer).await?;
+            }
+        }
+    }
+
+    /// Waits until events are received for the connection `handle`
+    pub(crate) async fn wait(&self, handle: ConnectionHandle) -> Result<()> {
+        self.run_until(|state, waker| {
+            let inbox = match state.inboxes.get_mut(&handle) {
+                Some(inbox) => inbox,
+                None => return true,
+            };
+
+            if !inbox.events.is_empty() {
+                return true;
+            }
+
+            inbox.waker = Some(waker.clone());
+            false
+        })
+        .await
+    }
+
+    /// Takes the events received for the connection `handle`
+    pub(crate) fn take_events(&self, handle: ConnectionHandle) -> Vec<ConnectionEvent> {
+        let mut state = self.state.lock().unwrap();
+        match state.inboxes.get_mut(&handle) {
+            Some(inbox) => std::mem::take(&mut inbox.events),
+            None => Vec::new(),
+        }
+    }
+
+    /// Passes an event from the connection `handle` to the endpoint, returning the event it sends
+    /// back if any
+    pub(crate) fn handle_event(
+        &self,
+        handle: ConnectionHandle,
+        event: EndpointEvent,
+    ) -> Option<ConnectionEvent> {
+        self.state
+            .lock()
+            .unwrap()
+            .endpoint
+            .handle_event(handle, event)
+    }
+
+    /// Forgets the connection `handle`, which won't send or receive anything more
+    ///
+    /// If the connection didn't finish draining on its own, the endpoint is told it did.
+    pub(crate) fn remove(&self, handle: ConnectionHandle, drain: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.inboxes.remove(&handle);
+        if drain {
+            state
+                .endpoint
+                .handle_event(handle, EndpointEvent::drained());
+        }
+    }
+
+    /// Sends the datagram described by `transmit`, which was written into `buffer`
+    pub(crate) async fn send(&self, transmit: &Transmit, buffer: &[u8]) -> Result<()> {
+        let buffer = &buffer[..transmit.size];
+        match transmit.segment_size {
+            Some(segment_size) => {
+                for segment in buffer.chunks(segment_size) {
+                    self.socket.send_to(segment, transmit.destination).await?;
+                }
+                Ok(())
+            }
+            None => self.socket.send_to(buffer, transmit.destination).await,
+        }
+    }
+
+    /// Receives datagrams and routes them until `ready` returns true
+    ///
+    /// `ready` is called with the state of the endpoint every time the task is woken, and has to
+    /// keep the waker it is given to be woken when it may become true.
+    async fn run_until<F: FnMut(&mut State, &Waker) -> bool>(&self, mut ready: F) -> Result<()> {
+        let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+        loop {
+            let received = {
+                let mut receive = pin!(self.socket.recv_from(&mut buffer));
+                poll_fn(|context| {
+                    if ready(&mut self.state.lock().unwrap(), context.waker()) {
+                        return Poll::Ready(None);
+                    }
+
+                    receive.as_mut().poll(context).map(Some)
+                })
+                .await
+            };
+
+            match received {
+                Some(result) => {
+                    let (length, address) = result?;
+                    self.route(&buffer[..length], address).await?;
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Hands the datagram `data` received from `address` to the connection it belongs to
+    async fn route(&self, data: &[u8], address: SocketAddr) -> Result<()> {
+        let mut buffer = Vec::new();
+        let response = {
+            let mut state = self.state.lock().unwrap();
+            let event = state.endpoint.handle(
+                Instant::now(),
+                address,
+                None,
+                None,
+                BytesMut::from(data),
+                &mut buffer,
+            );
+
+            match event {
+                Some(DatagramEvent::ConnectionEvent(handle, event)) => {
+                    if let Some(inbox) = state.inboxes.get_mut(&handle) {
+                        inbox.events.push(event);
+                        if let Some(waker) = inbox.waker.take() {
+                            waker.wake();
+                        }
+                    }
+                    None
+                }
+                Some(DatagramEvent::NewConnection(incoming)) => {
+                    if state.incoming.len() >= MAX_INCOMING {
+                        Some(state.endpoint.refuse(incoming, &mut buffer))
+                    } else {
+                        state.incoming.push_back(incoming);
+                        for waker in state.acceptors.drain(..) {
+                            waker.wake();
+                        }
+                        None
+                    }
+                }
+                Some(DatagramEvent::Response(transmit)) => Some(transmit),
+                None => None,
+            }
+        };
+
+        match response {
+            Some(transmit) => self.send(&transmit, &buffer).await,
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::HTTPParseError;
+use quinn_proto::{StreamId, VarInt};
+
+/// The reason a stream or connection is closed, sent when resetting streams and closing
+/// connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ErrorCode {
+    /// The stream or connection is closed without an error
+    NoError = 0x100,
+
+    /// The client opened a stream of a type which isn't allowed
+    StreamCreationError = 0x103,
+
+    /// A stream the connection needs was closed
+    ClosedCriticalStream = 0x104,
+
+    /// A frame was received which isn't allowed on its stream or in its position
+    FrameUnexpected = 0x105,
+
+    /// A frame was malformed or truncated
+    FrameError = 0x106,
+
+    /// The client is sending more than the server is willing to handle
+    ExcessiveLoad = 0x107,
+
+    /// A "SETTINGS" frame was malformed
+    SettingsError = 0x109,
+
+    /// The control stream didn't start with a "SETTINGS" frame
+    MissingSettings = 0x10A,
+
+    /// The stream was refused before any of it was processed
+    RequestRejected = 0x10B,
+
+    /// The client finished a stream without a complete request
+    RequestIncomplete = 0x10D,
+
+    /// The request was malformed
+    MessageError = 0x10E,
+
+    /// A field section couldn't be decoded
+    DecompressionFailed = 0x200,
+
+    /// The client sent an invalid instruction on its QPACK encoder stream
+    EncoderStreamError = 0x201,
+}
+
+/// An error caused by something the client sent
+#[derive(Debug)]
+pub(super) enum H3Error {
+    /// The whole connection has to be closed
+    Connection(ErrorCode),
+
+    /// Only the stream with the contained ID has to be reset
+    Stream(StreamId, ErrorCode),
+
+    /// The request on the stream with the contained ID can't be handled, but can be answered
+    Request(StreamId, HTTPParseError),
+}
+
+impl ErrorCode {
+    /// Gets the code as it is sent to the client
+    pub(super) fn code(&self) -> VarInt {
+        VarInt::from_u32(*self as u32)
+    }
+}
@@ -0,0 +1,115 @@
+/// The type of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum FrameType {
+    /// Part of the body of a request or response
+    Data = 0x0,
+
+    /// A field section, holding the header or trailers of a request or response
+    Headers = 0x1,
+
+    /// A request to cancel a push, which the server never makes
+    CancelPush = 0x3,
+
+    /// Parameters for how the sender wants to be communicated with
+    Settings = 0x4,
+
+    /// A push the server intends to make, which clients can't send
+    PushPromise = 0x5,
+
+    /// The end of the connection
+    GoAway = 0x7,
+
+    /// The highest push ID the client allows, which is ignored as the server never pushes
+    MaxPushId = 0xD,
+
+    /// A frame type from HTTP/2 which has no meaning in HTTP/3 and isn't allowed
+    Reserved = 0x2,
+}
+
+/// The header at the start of every frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FrameHeader {
+    /// The type of the frame, or [`None`] for unknown types which are ignored
+    pub(super) kind: Option<FrameType>,
+
+    /// The length of the payload following the header
+    pub(super) length: u64,
+
+    /// The length of the header itself
+    pub(super) header_length: usize,
+}
+
+/// Decodes a variable-length integer from the start of `input`, returning it and its length
+///
+/// Returns [`None`] if `input` ends before the integer does.
+pub(super) fn read_varint(input: &[u8]) -> Option<(u64, usize)> {
+    let first = *input.first()?;
+    let length = 1 << (first >> 6);
+    if input.len() < length {
+        return None;
+    }
+
+    let mut value = (first & 0x3F) as u64;
+    for byte in &input[1..length] {
+        value = (value << 8) | *byte as u64;
+    }
+    Some((value, length))
+}
+
+/// Encodes `value` as a variable-length integer onto the end of `output`
+pub(super) fn write_varint(value: u64, output: &mut Vec<u8>) {
+    if value < 1 << 6 {
+        output.push(value as u8);
+    } else if value < 1 << 14 {
+        output.extend_from_slice(&(value as u16 | 0x4000).to_be_bytes());
+    } else if value < 1 << 30 {
+        output.extend_from_slice(&(value as u32 | 0x8000_0000).to_be_bytes());
+    } else {
+        output.extend_from_slice(&(value | 0xC000_0000_0000_0000).to_be_bytes());
+    }
+}
+
+/// Writes the header of a frame with a payload of `length` bytes onto the end of `output`
+pub(super) fn write_frame_header(output: &mut Vec<u8>, kind: FrameType, length: usize) {
+    write_varint(kind as u64, output);
+    write_varint(length as u64, output);
+}
+
+/// Writes a frame onto the end of `output`
+pub(super) fn write_frame(output: &mut Vec<u8>, kind: FrameType, payload: &[u8]) {
+    write_frame_header(output, kind, payload.len());
+    output.extend_from_slice(payload);
+}
+
+impl FrameType {
+    /// Gets the frame type with the code `kind`
+    fn parse(kind: u64) -> Option<Self> {
+        Some(match kind {
+            0x0 => FrameType::Data,
+            0x1 => FrameType::Headers,
+            0x3 => FrameType::CancelPush,
+            0x4 => FrameType::Settings,
+            0x5 => FrameType::PushPromise,
+            0x7 => FrameType::GoAway,
+            0xD => FrameType::MaxPushId,
+            0x2 | 0x6 | 0x8 | 0x9 => FrameType::Reserved,
+            _ => return None,
+        })
+    }
+}
+
+impl FrameHeader {
+    /// Parses the header at the start of `input`
+    ///
+    /// Returns [`None`] if `input` ends before the header does.
+    pub(super) fn parse(input: &[u8]) -> Option<Self> {
+        let (kind, kind_length) = read_varint(input)?;
+        let (length, length_length) = read_varint(&input[kind_length..])?;
+
+        Some(FrameHeader {
+            kind: FrameType::parse(kind),
+            length,
+            header_length: kind_length + length_length,
+        })
+    }
+}
@@ -0,0 +1,25 @@
+//! HTTP/3 (RFC 9114) over QUIC, used for clients which connect to a UDP listener
+//!
+//! QUIC itself is handled by `quinn-proto`, while the HTTP/3 framing and QPACK header compression
+//! are implemented here. Requests and responses have the same form as in HTTP/2, so they reuse
+//! its request type and header conversion.
+
+mod connection;
+mod endpoint;
+mod error;
+mod frame;
+mod qpack;
+mod response;
+mod settings;
+mod stream;
+
+#[cfg(test)]
+mod tests;
+
+pub(crate) use connection::{HTTP3Connection, MAX_CONCURRENT_STREAMS};
+pub(crate) use response::encode_header;
+
+pub use endpoint::QUICEndpoint;
+
+/// The protocol clients negotiate through TLS ALPN to use HTTP/3
+pub(crate) const ALPN: &[u8] = b"h3";
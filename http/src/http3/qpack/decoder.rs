@@ -0,0 +1,87 @@
+use super::{table, QPACKError};
+use crate::http2::{decode_integer, huffman, DecodedFields};
+
+/// Decodes a string literal with a `prefix` bit length from the start of `input`
+///
+/// The bit before the prefix marks strings which are Huffman coded.
+fn decode_string(input: &mut &[u8], prefix: u32) -> Result<Box<[u8]>, QPACKError> {
+    let huffman = input.first().ok_or(QPACKError)? & (1 << prefix) != 0;
+    let length = decode_integer(input, prefix)?;
+    if length > input.len() {
+        return Err(QPACKError);
+    }
+
+    let (string, rest) = input.split_at(length);
+    *input = rest;
+
+    if !huffman {
+        return Ok(string.into());
+    }
+
+    let mut output = Vec::with_capacity(length * 2);
+    huffman::decode(string, &mut output)?;
+    Ok(output.into_boxed_slice())
+}
+
+/// Gets the name of the static table entry at `index`
+fn static_name(index: usize) -> Result<Box<[u8]>, QPACKError> {
+    table::get(index)
+        .map(|(name, _)| Box::from(name))
+        .ok_or(QPACKError)
+}
+
+/// Decodes the fields in the field section `block`
+///
+/// Returns [`None`] if the names and values of the fields add up to more than `max_size` bytes.
+/// References to the dynamic table are errors, as the client was never allowed to use it.
+pub(in crate::http3) fn decode(
+    mut block: &[u8],
+    max_size: usize,
+) -> Result<Option<DecodedFields>, QPACKError> {
+    // The prefix holds the required insert count, which has to be zero without a dynamic table,
+    // and the base, which only matters with one
+    if decode_integer(&mut block, 8)? != 0 {
+        return Err(QPACKError);
+    }
+    decode_integer(&mut block, 7)?;
+
+    let mut fields = Vec::new();
+    let mut size = 0;
+    while !block.is_empty() {
+        let representation = block[0];
+
+        let (name, value) = if representation & 0x80 != 0 {
+            // Indexed field line, which has to refer to the static table
+            if representation & 0x40 == 0 {
+                return Err(QPACKError);
+            }
+
+            let (name, value) = table::get(decode_integer(&mut block, 6)?).ok_or(QPACKError)?;
+            (Box::from(name), Box::from(value))
+        } else if representation & 0xC0 == 0x40 {
+            // Literal field line with a name reference, which has to refer to the static table
+            if representation & 0x10 == 0 {
+                return Err(QPACKError);
+            }
+
+            let name = static_name(decode_integer(&mut block, 4)?)?;
+            (name, decode_string(&mut block, 7)?)
+        } else if representation & 0xE0 == 0x20 {
+            // Literal field line with a literal name
+            let name = decode_string(&mut block, 3)?;
+            (name, decode_string(&mut block, 7)?)
+        } else {
+            // Field lines with post-base indices refer to the dynamic table
+            return Err(QPACKError);
+        };
+
+        size += name.len() + value.len();
+        if size > max_size {
+            return Ok(None);
+        }
+
+        fields.push((name, value));
+    }
+
+    Ok(Some(fields))
+}
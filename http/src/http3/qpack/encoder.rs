@@ -0,0 +1,30 @@
+use super::table;
+use crate::http2::encode_integer;
+
+/// The prefix every field section sent by the server starts with
+///
+/// The dynamic table is never used, so the required insert count and base are always zero.
+pub(in crate::http3) const PREFIX: [u8; 2] = [0, 0];
+
+/// Encodes a string literal with a `prefix` bit length without Huffman coding
+fn encode_string(string: &[u8], prefix: u32, flags: u8, output: &mut Vec<u8>) {
+    encode_integer(string.len(), prefix, flags, output);
+    output.extend_from_slice(string);
+}
+
+/// Encodes the field `name` with `value` onto the end of `output`, which starts with [`PREFIX`]
+///
+/// Fields in the static table are sent by index, and the names of other fields in it are.
+pub(in crate::http3) fn encode(name: &[u8], value: &[u8], output: &mut Vec<u8>) {
+    match table::find(name, value) {
+        (Some(index), _) => encode_integer(index, 6, 0xC0, output),
+        (None, Some(index)) => {
+            encode_integer(index, 4, 0x50, output);
+            encode_string(value, 7, 0, output);
+        }
+        (None, None) => {
+            encode_string(name, 3, 0x20, output);
+            encode_string(value, 7, 0, output);
+        }
+    }
+}
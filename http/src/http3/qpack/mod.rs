@@ -0,0 +1,27 @@
+//! QPACK header compression for HTTP/3 (RFC 9204)
+//!
+//! The server never allows the client to use the dynamic table, so field sections only refer to
+//! the static table and can be decoded in any order. This keeps the encoder and decoder streams
+//! all but empty.
+
+mod decoder;
+mod encoder;
+mod table;
+
+#[cfg(test)]
+mod tests;
+
+pub(super) use decoder::decode;
+pub(super) use encoder::{encode, PREFIX};
+
+use crate::http2::HPACKError;
+
+/// A field section or encoder instruction which can't be decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct QPACKError;
+
+impl From<HPACKError> for QPACKError {
+    fn from(_: HPACKError) -> Self {
+        QPACKError
+    }
+}
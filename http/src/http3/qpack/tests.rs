@@ -0,0 +1,135 @@
+//! Tests against the static table examples in RFC 9204 Appendix B
+
+use super::*;
+use crate::http2::encode_integer;
+
+/// Parses a string of hexadecimal digits into bytes, ignoring whitespace
+fn hex(digits: &str) -> Vec<u8> {
+    let digits: Vec<u8> = digits
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+/// Decodes the field section `block`, checking it decodes to `expected`
+fn assert_decodes(block: &[u8], expected: &[(&str, &str)]) {
+    let fields = decode(block, usize::MAX).unwrap().unwrap();
+    let fields: Vec<(&str, &str)> = fields
+        .iter()
+        .map(|(name, value)| {
+            (
+                std::str::from_utf8(name).unwrap(),
+                std::str::from_utf8(value).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(fields, expected);
+}
+
+#[test]
+fn static_name_references() {
+    // B.1
+    assert_decodes(
+        &hex("0000510b2f696e6465782e68746d6c"),
+        &[(":path", "/index.html")],
+    );
+
+    // The value of C.4.1 in RFC 7541, Huffman coded
+    assert_decodes(
+        &hex("0000508cf1e3c2e5f23a6ba0ab90f4ff"),
+        &[(":authority", "www.example.com")],
+    );
+}
+
+#[test]
+fn indexed_fields() {
+    assert_decodes(
+        &hex("0000d1d9c1"),
+        &[(":method", "GET"), (":status", "200"), (":path", "/")],
+    );
+
+    // The static table ends at index 98
+    let mut block = PREFIX.to_vec();
+    encode_integer(98, 6, 0xC0, &mut block);
+    assert_decodes(&block, &[("x-frame-options", "sameorigin")]);
+
+    let mut block = PREFIX.to_vec();
+    encode_integer(99, 6, 0xC0, &mut block);
+    assert_eq!(decode(&block, usize::MAX), Err(QPACKError));
+
+    let mut block = PREFIX.to_vec();
+    encode_integer(99, 4, 0x50, &mut block);
+    block.extend_from_slice(b"\x01a");
+    assert_eq!(decode(&block, usize::MAX), Err(QPACKError));
+}
+
+#[test]
+fn literal_names() {
+    assert_decodes(&hex("000023782d610162"), &[("x-a", "b")]);
+
+    // Both the name and the value of C.4.3 in RFC 7541, Huffman coded
+    assert_decodes(
+        &hex("00002f0125a849e95ba97d7f8925a849e95bb8e8b4bf"),
+        &[("custom-key", "custom-value")],
+    );
+}
+
+#[test]
+fn dynamic_table_references() {
+    // B.2, which inserts two fields into the dynamic table before referring to them
+    assert_eq!(decode(&hex("03811011"), usize::MAX), Err(QPACKError));
+
+    // Indexed fields and name references with the static bit clear, and post-base indices
+    for representation in ["80", "4001", "10", "0001"] {
+        let block = hex(&format!("0000{representation}61"));
+        assert_eq!(decode(&block, usize::MAX), Err(QPACKError));
+    }
+}
+
+#[test]
+fn truncated_sections() {
+    assert_eq!(decode(&[], usize::MAX), Err(QPACKError));
+    assert_eq!(decode(&[0], usize::MAX), Err(QPACKError));
+    assert_eq!(decode(&hex("0000510b2f69"), usize::MAX), Err(QPACKError));
+    assert_eq!(decode(&hex("000023782d"), usize::MAX), Err(QPACKError));
+    assert_eq!(decode(&hex("0000d1ff"), usize::MAX), Err(QPACKError));
+
+    // An empty field section is still valid
+    assert_decodes(&PREFIX, &[]);
+}
+
+#[test]
+fn oversized_sections() {
+    let block = hex("0000510b2f696e6465782e68746d6c");
+    assert_eq!(decode(&block, 15), Ok(None));
+    assert!(decode(&block, 16).unwrap().is_some());
+}
+
+#[test]
+fn encoded_fields_decode() {
+    let fields: [(&[u8], &[u8]); 4] = [
+        (b":status", b"200"),
+        (b":status", b"418"),
+        (b"content-type", b"text/plain"),
+        (b"x-custom", b"value"),
+    ];
+
+    let mut block = PREFIX.to_vec();
+    for (name, value) in fields {
+        encode(name, value, &mut block);
+    }
+
+    // Fields in the static table are sent by index
+    assert_eq!(block[2], 0xD9);
+
+    let decoded = decode(&block, usize::MAX).unwrap().unwrap();
+    let decoded: Vec<(&[u8], &[u8])> = decoded
+        .iter()
+        .map(|(name, value)| (name.as_ref(), value.as_ref()))
+        .collect();
+    assert_eq!(decoded, fields);
+}
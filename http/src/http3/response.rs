@@ -0,0 +1,17 @@
+use super::qpack;
+use crate::{http2::header_fields, HTTPStatus};
+
+/// Encodes the header generated for an HTTP/1.1 response as an HTTP/3 field section
+///
+/// The status line is replaced by the ":status" pseudo-header field, field names are lowercased,
+/// and fields which only apply to HTTP/1.1 connections are dropped.
+pub(crate) fn encode_header(status: HTTPStatus, header: &[u8]) -> Vec<u8> {
+    let mut block = qpack::PREFIX.to_vec();
+    qpack::encode(b":status", status.code().to_string().as_bytes(), &mut block);
+
+    for (name, value) in header_fields(header) {
+        qpack::encode(&name, value, &mut block);
+    }
+
+    block
+}
@@ -0,0 +1,39 @@
+use super::{
+    error::ErrorCode,
+    frame::{read_varint, write_varint},
+};
+
+/// The identifier of the setting for the largest field section the sender accepts
+const MAX_FIELD_SECTION_SIZE: u64 = 0x6;
+
+/// Encodes the settings the server sends at the start of its control stream
+///
+/// The QPACK dynamic table is left at its default capacity of zero, so the client never adds
+/// fields to it.
+pub(super) fn encode(max_field_section_size: usize) -> Vec<u8> {
+    let mut payload = Vec::new();
+    write_varint(MAX_FIELD_SECTION_SIZE, &mut payload);
+    write_varint(max_field_section_size as u64, &mut payload);
+    payload
+}
+
+/// Checks the payload of the "SETTINGS" frame sent by the client
+///
+/// None of the settings affect the server, so they are only checked for being well-formed.
+pub(super) fn validate(mut payload: &[u8]) -> Result<(), ErrorCode> {
+    let mut identifiers = Vec::new();
+    while !payload.is_empty() {
+        let (identifier, length) = read_varint(payload).ok_or(ErrorCode::FrameError)?;
+        payload = &payload[length..];
+        let (_, length) = read_varint(payload).ok_or(ErrorCode::FrameError)?;
+        payload = &payload[length..];
+
+        // The identifiers of HTTP/2 settings which have no meaning in HTTP/3 are reserved
+        if (0x2..=0x5).contains(&identifier) || identifiers.contains(&identifier) {
+            return Err(ErrorCode::SettingsError);
+        }
+        identifiers.push(identifier);
+    }
+
+    Ok(())
+}
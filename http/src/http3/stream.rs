@@ -0,0 +1,98 @@
+use crate::{
+    http2::{DecodedFields, HTTP2Request},
+    HTTPVersion,
+};
+use quinn_proto::StreamId;
+
+/// A bidirectional stream opened by a request from the client
+pub(super) struct RequestStream {
+    /// The ID the client opened the stream with
+    pub(super) id: StreamId,
+
+    /// Has the client finished sending the request?
+    pub(super) complete: bool,
+
+    /// Bytes received on the stream which haven't been processed
+    pub(super) input: Vec<u8>,
+
+    /// The number of bytes left in the "DATA" frame being received
+    pub(super) data_remaining: u64,
+
+    /// The number of bytes left in the unknown frame being skipped
+    pub(super) skip_remaining: u64,
+
+    /// The fields of the request header, including the pseudo-header fields, once it is received
+    pub(super) fields: Option<DecodedFields>,
+
+    /// The trailer fields sent after the body, once they are received
+    pub(super) trailers: Option<DecodedFields>,
+
+    /// The body received so far
+    pub(super) body: Vec<u8>,
+
+    /// The length the "content-length" field says the body has
+    pub(super) content_length: Option<usize>,
+}
+
+/// The kind of a unidirectional stream opened by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum UniStreamKind {
+    /// The control stream, which carries settings and "GOAWAY" frames
+    Control,
+
+    /// The QPACK encoder stream, which can only set the dynamic table capacity to zero
+    Encoder,
+
+    /// The QPACK decoder stream, which is ignored as the server never uses the dynamic table
+    Decoder,
+}
+
+/// A unidirectional stream opened by the client
+pub(super) struct UniStream {
+    /// The ID the client opened the stream with
+    pub(super) id: StreamId,
+
+    /// The kind of stream, once its type has been received
+    pub(super) kind: Option<UniStreamKind>,
+
+    /// Bytes received on the stream which haven't been processed
+    pub(super) input: Vec<u8>,
+}
+
+impl RequestStream {
+    /// Creates a new [`RequestStream`] for a request which hasn't sent anything yet
+    pub(super) fn new(id: StreamId) -> Self {
+        RequestStream {
+            id,
+            complete: false,
+            input: Vec::new(),
+            data_remaining: 0,
+            skip_remaining: 0,
+            fields: None,
+            trailers: None,
+            body: Vec::new(),
+            content_length: None,
+        }
+    }
+
+    /// Takes the request received on this stream
+    pub(super) fn take_request(&mut self) -> HTTP2Request {
+        HTTP2Request::new(
+            self.fields.take().unwrap_or_default(),
+            self.trailers.take().unwrap_or_default(),
+            std::mem::take(&mut self.body),
+            HTTPVersion::HTTP3,
+        )
+    }
+}
+
+impl UniStream {
+    /// Creates a new [`UniStream`] whose type hasn't been received yet
+    pub(super) fn new(id: StreamId) -> Self {
+        UniStream {
+            id,
+            kind: None,
+            input: Vec::new(),
+        }
+    }
+}
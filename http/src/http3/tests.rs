@@ -0,0 +1,531 @@
+//! Tests which run a server on the loopback interface and talk to it with a QUIC client, checking
+//! which stream each response is sent on
+
+use super::{
+    frame::{write_frame, FrameHeader, FrameType},
+    qpack, MAX_CONCURRENT_STREAMS,
+};
+use crate::{
+    HTTPClientAddress, HTTPListenAddress, HTTPOptions, HTTPParseError, HTTPResponse, HTTPStatus,
+    HTTPTLSConfig, HTTP,
+};
+use bytes::BytesMut;
+use huntsman::{runtime::time::timeout, App, Options, Protocol};
+use quinn_proto::{
+    crypto::rustls::QuicClientConfig, ClientConfig, Connection, ConnectionHandle, DatagramEvent,
+    Dir, Endpoint, EndpointConfig, Event, ReadError, StreamId,
+};
+use rustls::{
+    crypto::ring::default_provider,
+    pki_types::{pem::PemObject, CertificateDer},
+    version::TLS13,
+    RootCertStore,
+};
+use std::{
+    future::pending,
+    net::{SocketAddr, UdpSocket},
+    num::NonZeroUsize,
+    sync::{mpsc, Arc, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// The certificate authority which signed [`CERTIFICATE`]
+const CA: &[u8] = include_bytes!("../tests/certificates/ca.pem");
+
+/// The certificate the server presents, for "localhost"
+const CERTIFICATE: &[u8] = include_bytes!("../tests/certificates/cert.pem");
+
+/// The private key of [`CERTIFICATE`]
+const KEY: &[u8] = include_bytes!("../tests/certificates/key.pem");
+
+/// How long the client waits for the server before a test fails
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An app which answers each request with its method and target
+///
+/// Requests for "/slow" are answered after half a second. Requests which can't be read are
+/// answered with "400 Bad Request".
+struct Echo(Mutex<mpsc::Sender<SocketAddr>>);
+
+/// A server running on its own thread
+struct Server {
+    /// The address the server is listening on
+    address: SocketAddr,
+
+    /// Stops the server
+    shutdown: huntsman::Shutdown,
+
+    /// The thread running the server
+    thread: JoinHandle<()>,
+}
+
+/// The client's end of an HTTP/3 connection
+struct Client {
+    /// The socket datagrams are sent and received on
+    socket: UdpSocket,
+
+    /// The address of the server
+    server: SocketAddr,
+
+    /// Routes datagrams to the connection
+    endpoint: Endpoint,
+
+    /// The handle of `connection` in `endpoint`
+    handle: ConnectionHandle,
+
+    /// The state of the QUIC connection
+    connection: Connection,
+
+    /// Has the connection been closed?
+    closed: bool,
+
+    /// The request streams opened and what has been received on each
+    streams: Vec<RequestStream>,
+
+    /// The request streams in the order their responses finished
+    finished: Vec<StreamId>,
+}
+
+/// A request stream opened by the client
+struct RequestStream {
+    /// The ID of the stream
+    id: StreamId,
+
+    /// What has been received on the stream
+    input: Vec<u8>,
+
+    /// Has the whole response been received?
+    finished: bool,
+
+    /// The error code the server reset the stream with, if it did
+    reset: Option<u64>,
+}
+
+/// A response read from a request stream
+#[derive(Debug, PartialEq, Eq)]
+struct Response {
+    /// The status code of the response
+    status: String,
+
+    /// The body of the response
+    body: String,
+}
+
+impl App for Echo {
+    type Protocol = HTTP;
+    type Client = ();
+
+    async fn handle_request<'a, 'b>(
+        self: &'a Arc<Self>,
+        _: &'a (),
+        request: &'a mut <HTTP as Protocol>::Request<'b>,
+    ) -> HTTPResponse<'a> {
+        let target = String::from_utf8_lossy(request.target().as_slice()).into_owned();
+        if target == "/slow" {
+            let _ = timeout(pending::<()>(), Duration::from_millis(500)).await;
+        }
+
+        let line = format!("{} {}", request.method(), target);
+        HTTPResponse::new(HTTPStatus::OK, line.into_bytes(), b"text/plain")
+    }
+
+    async fn on_client_connect(self: &Arc<Self>, _: HTTPClientAddress) -> Option<()> {
+        Some(())
+    }
+
+    async fn on_server_start(self: &Arc<Self>, addresses: &[HTTPListenAddress]) {
+        for address in addresses {
+            if let HTTPListenAddress::HTTP3(address, _) = address {
+                self.0.lock().unwrap().send(*address).unwrap();
+            }
+        }
+    }
+
+    async fn read_error<'a>(
+        self: &'a Arc<Self>,
+        _: &'a (),
+        _: HTTPParseError,
+    ) -> Option<HTTPResponse<'a>> {
+        Some(HTTPStatus::BadRequest.into())
+    }
+}
+
+impl Server {
+    /// Starts a server listening for HTTP/3 on a free port of the loopback interface
+    fn start() -> Self {
+        let config = HTTPTLSConfig::from_pem(CERTIFICATE, KEY).unwrap();
+
+        let mut options = Options::default();
+        options.add_address(HTTPListenAddress::http3("127.0.0.1:0", config).unwrap());
+        options.set_workers(NonZeroUsize::MIN);
+        let shutdown = options.shutdown().clone();
+
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            huntsman::run(Echo(Mutex::new(sender)), options, HTTPOptions::default()).unwrap()
+        });
+
+        Server {
+            address: receiver.recv_timeout(TEST_TIMEOUT).unwrap(),
+            shutdown,
+            thread,
+        }
+    }
+
+    /// Stops the server and waits for it to finish
+    fn stop(self) {
+        self.shutdown.trigger();
+        self.thread.join().unwrap();
+    }
+}
+
+impl Client {
+    /// Connects to `server` and completes the handshake
+    fn connect(server: SocketAddr) -> Self {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_slice(CA).unwrap())
+            .unwrap();
+
+        let mut crypto = rustls::ClientConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_protocol_versions(&[&TLS13])
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        crypto.alpn_protocols = vec![b"h3".to_vec()];
+        let crypto = QuicClientConfig::try_from(Arc::new(crypto)).unwrap();
+
+        let mut endpoint = Endpoint::new(Arc::new(EndpointConfig::default()), None, false, None);
+        let (handle, connection) = endpoint
+            .connect(
+                Instant::now(),
+                ClientConfig::new(Arc::new(crypto)),
+                server,
+                "localhost",
+            )
+            .unwrap();
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut client = Client {
+            socket,
+            server,
+            endpoint,
+            handle,
+            connection,
+            closed: false,
+            streams: Vec::new(),
+            finished: Vec::new(),
+        };
+
+        let deadline = Instant::now() + TEST_TIMEOUT;
+        while client.connection.is_handshaking() {
+            assert!(Instant::now() < deadline, "the handshake timed out");
+            assert!(!client.closed, "the server closed during the handshake");
+            client.drive();
+        }
+
+        client
+    }
+
+    /// Opens a request stream and sends a request on it with `fields` as its header
+    fn send(&mut self, fields: &[(&str, &str)]) -> StreamId {
+        let mut block = qpack::PREFIX.to_vec();
+        for (name, value) in fields {
+            qpack::encode(name.as_bytes(), value.as_bytes(), &mut block);
+        }
+
+        let mut frame = Vec::new();
+        write_frame(&mut frame, FrameType::Headers, &block);
+
+        let id = self.connection.streams().open(Dir::Bi).unwrap();
+        let mut stream = self.connection.send_stream(id);
+        assert_eq!(stream.write(&frame), Ok(frame.len()));
+        stream.finish().unwrap();
+
+        self.streams.push(RequestStream {
+            id,
+            input: Vec::new(),
+            finished: false,
+            reset: None,
+        });
+        id
+    }
+
+    /// Sends a "GET" request for `target` on a new request stream
+    fn get(&mut self, target: &str) -> StreamId {
+        self.send(&[
+            (":method", "GET"),
+            (":scheme", "https"),
+            (":authority", "localhost"),
+            (":path", target),
+        ])
+    }
+
+    /// Waits until the responses on every open request stream have been received, returning them
+    /// in the order the streams were opened
+    ///
+    /// Streams the server reset give the error code they were reset with instead.
+    fn responses(&mut self) -> Vec<Result<Response, u64>> {
+        let deadline = Instant::now() + TEST_TIMEOUT;
+        while self.streams.iter().any(|stream| !stream.finished) {
+            assert!(Instant::now() < deadline, "the responses timed out");
+            assert!(!self.closed, "the server closed before responding");
+            self.drive();
+        }
+
+        self.streams
+            .drain(..)
+            .map(|stream| match stream.reset {
+                Some(code) => Err(code),
+                None => Ok(Response::parse(&stream.input)),
+            })
+            .collect()
+    }
+
+    /// Waits for the server to close the connection
+    fn wait_for_close(&mut self) {
+        let deadline = Instant::now() + TEST_TIMEOUT;
+        while !self.closed {
+            assert!(
+                Instant::now() < deadline,
+                "the server didn't close the connection"
+            );
+            self.drive();
+        }
+    }
+
+    /// Sends whatever the connection has to send, then handles the next datagram from the server
+    /// or the connection's timer, whichever comes first
+    fn drive(&mut self) {
+        let mut buffer = Vec::new();
+        while let Some(transmit) = self
+            .connection
+            .poll_transmit(Instant::now(), 1, &mut buffer)
+        {
+            self.socket
+                .send_to(&buffer[..transmit.size], transmit.destination)
+                .unwrap();
+            buffer.clear();
+        }
+
+        let wait = self
+            .connection
+            .poll_timeout()
+            .map(|timer| timer.saturating_duration_since(Instant::now()))
+            .unwrap_or(Duration::from_millis(100))
+            .clamp(Duration::from_millis(1), Duration::from_millis(100));
+        self.socket.set_read_timeout(Some(wait)).unwrap();
+
+        let mut datagram = [0; 65536];
+        match self.socket.recv_from(&mut datagram) {
+            Ok((length, source)) => {
+                assert_eq!(source, self.server);
+                let data = BytesMut::from(&datagram[..length]);
+                let mut response = Vec::new();
+                match self
+                    .endpoint
+                    .handle(Instant::now(), source, None, None, data, &mut response)
+                {
+                    Some(DatagramEvent::ConnectionEvent(_, event)) => {
+                        self.connection.handle_event(event)
+                    }
+                    Some(DatagramEvent::Response(transmit)) => {
+                        self.socket
+                            .send_to(&response[..transmit.size], transmit.destination)
+                            .unwrap();
+                    }
+                    Some(DatagramEvent::NewConnection(_)) | None => {}
+                }
+            }
+            Err(_) => self.connection.handle_timeout(Instant::now()),
+        }
+
+        while let Some(event) = self.connection.poll_endpoint_events() {
+            if let Some(event) = self.endpoint.handle_event(self.handle, event) {
+                self.connection.handle_event(event);
+            }
+        }
+
+        while let Some(event) = self.connection.poll() {
+            if let Event::ConnectionLost { .. } = event {
+                self.closed = true;
+            }
+        }
+
+        self.read_streams();
+    }
+
+    /// Reads whatever has been received on the open request streams
+    fn read_streams(&mut self) {
+        for stream in &mut self.streams {
+            if stream.finished {
+                continue;
+            }
+
+            let mut recv = self.connection.recv_stream(stream.id);
+            let mut chunks = match recv.read(true) {
+                Ok(chunks) => chunks,
+                Err(_) => continue,
+            };
+
+            loop {
+                match chunks.next(usize::MAX) {
+                    Ok(Some(chunk)) => stream.input.extend_from_slice(&chunk.bytes),
+                    Ok(None) => {
+                        stream.finished = true;
+                        break;
+                    }
+                    Err(ReadError::Blocked) => break,
+                    Err(ReadError::Reset(code)) => {
+                        stream.reset = Some(code.into_inner());
+                        stream.finished = true;
+                        break;
+                    }
+                }
+            }
+            let _ = chunks.finalize();
+
+            if stream.finished {
+                self.finished.push(stream.id);
+            }
+        }
+    }
+}
+
+impl Response {
+    /// Parses the frames received on a request stream
+    fn parse(mut input: &[u8]) -> Self {
+        let mut status = None;
+        let mut body = Vec::new();
+        while !input.is_empty() {
+            let header = FrameHeader::parse(input).unwrap();
+            let end = header.header_length + header.length as usize;
+            let payload = &input[header.header_length..end];
+            input = &input[end..];
+
+            match header.kind {
+                Some(FrameType::Headers) => {
+                    let fields = qpack::decode(payload, usize::MAX).unwrap().unwrap();
+                    let (_, value) = fields
+                        .iter()
+                        .find(|(name, _)| &**name == b":status")
+                        .unwrap();
+                    status = Some(String::from_utf8(value.to_vec()).unwrap());
+                }
+                Some(FrameType::Data) => body.extend_from_slice(payload),
+                kind => panic!("unexpected frame {kind:?} on a request stream"),
+            }
+        }
+
+        Response {
+            status: status.expect("the response has no header"),
+            body: String::from_utf8(body).unwrap(),
+        }
+    }
+}
+
+/// Checks `response` is a "200 OK" response with `body`
+fn assert_ok(response: &Result<Response, u64>, body: &str) {
+    assert_eq!(
+        response,
+        &Ok(Response {
+            status: "200".into(),
+            body: body.into(),
+        })
+    );
+}
+
+#[test]
+fn responses_are_sent_on_the_stream_of_their_request() {
+    let server = Server::start();
+    let mut client = Client::connect(server.address);
+
+    // The slow request is still being handled while the others are read and answered
+    let slow = client.get("/slow");
+    client.get("/first");
+    client.get("/second");
+    let responses = client.responses();
+
+    assert_ok(&responses[0], "GET /slow");
+    assert_ok(&responses[1], "GET /first");
+    assert_ok(&responses[2], "GET /second");
+    assert_eq!(client.finished.last(), Some(&slow));
+
+    server.stop();
+}
+
+#[test]
+fn every_allowed_stream_is_handled_at_once() {
+    let server = Server::start();
+    let mut client = Client::connect(server.address);
+
+    // Handled one at a time, these would take close to a minute
+    let start = Instant::now();
+    for _ in 0..MAX_CONCURRENT_STREAMS {
+        client.get("/slow");
+    }
+    let responses = client.responses();
+
+    assert!(start.elapsed() < TEST_TIMEOUT);
+    assert_eq!(responses.len(), MAX_CONCURRENT_STREAMS);
+    for response in &responses {
+        assert_ok(response, "GET /slow");
+    }
+
+    server.stop();
+}
+
+#[test]
+fn malformed_requests_reset_only_their_stream() {
+    let server = Server::start();
+    let mut client = Client::connect(server.address);
+
+    // A request without a target is malformed
+    client.get("/slow");
+    client.send(&[
+        (":method", "GET"),
+        (":scheme", "https"),
+        (":authority", "localhost"),
+    ]);
+    client.get("/after");
+    let responses = client.responses();
+
+    assert_ok(&responses[0], "GET /slow");
+    assert_eq!(responses[1], Err(0x10E));
+    assert_ok(&responses[2], "GET /after");
+
+    server.stop();
+}
+
+#[test]
+fn read_errors_are_answered_on_the_stream_of_their_request() {
+    let server = Server::start();
+    let mut client = Client::connect(server.address);
+
+    // The header which is too long is answered on its own stream, and the connection is closed
+    // once the request before it has been answered too
+    let slow = client.get("/slow");
+    let long = "a".repeat(10000);
+    client.send(&[
+        (":method", "GET"),
+        (":scheme", "https"),
+        (":authority", "localhost"),
+        (":path", "/long"),
+        ("x-long", &long),
+    ]);
+    let responses = client.responses();
+
+    assert_ok(&responses[0], "GET /slow");
+    assert_eq!(
+        responses[1],
+        Ok(Response {
+            status: "400".into(),
+            body: String::new(),
+        })
+    );
+    assert_eq!(client.finished.last(), Some(&slow));
+
+    client.wait_for_close();
+    server.stop();
+}
@@ -11,27 +11,55 @@ use client::Stream;
 use huntsman::Protocol;
 use listener::HTTPListener;
 
+#[cfg(all(feature = "http3", any(not(feature = "tokio"), feature = "simulation")))]
+compile_error!("the \"http3\" feature requires the \"tokio\" runtime");
+
 mod client;
+mod coding;
+mod connection;
+mod http2;
+#[cfg(feature = "http3")]
+mod http3;
 mod listen_address;
 mod listener;
 mod options;
 mod path;
 mod request;
 mod response;
+#[cfg(feature = "tls")]
+mod tls;
+mod websocket;
+
+#[cfg(all(test, feature = "simulation"))]
+mod tests;
 
-pub use client::{HTTPClient, HTTPClientAddress, HTTPProtocol};
-pub use lasync::{Error, Result};
+pub use client::{HTTPClient, HTTPClientAddress, HTTPProtocol, HTTPRequestBuffer};
+pub use coding::{
+    HTTPCompressionOptions, HTTPContentEncoder, HTTPContentEncoderStream, HTTPDeflateEncoder,
+    HTTPGzipEncoder,
+};
+pub use huntsman::runtime::{Error, Result};
 pub use listen_address::HTTPListenAddress;
 pub use options::HTTPOptions;
 pub use path::{HTTPPath, HTTPQueryParam};
 pub use request::{
-    HTTPMethod, HTTPParseError, HTTPRequest, HTTPRequestDisplay, HTTPRequestField,
-    HTTPRequestHeader, HTTPTarget,
+    HTTPMediaType, HTTPMethod, HTTPParseError, HTTPParseLimit, HTTPQualityValue, HTTPRequest,
+    HTTPRequestBody, HTTPRequestDisplay, HTTPRequestField, HTTPRequestFields, HTTPRequestHeader,
+    HTTPTarget, HTTPTargetForm, HTTPVersion,
 };
 pub use response::{
     EmptyHTTPChunkedResponseBody, HTTPChunkedResponseBody, HTTPResponse, HTTPResponseBodyContent,
     HTTPStatus, ReadHTTPChunkedResponseBody,
 };
+pub use websocket::{
+    HTTPWebSocket, HTTPWebSocketCloseCode, HTTPWebSocketHandler, HTTPWebSocketMessage,
+};
+
+#[cfg(feature = "tls")]
+pub use tls::{
+    HTTPTLSClientAuth, HTTPTLSConfig, HTTPTLSError, HTTPTLSFingerprint, HTTPTLSInfo,
+    HTTPTLSPeerCertificate, HTTPTLSSubjectAltName, HTTPTLSVersion,
+};
 
 /// The HTTP protocol
 pub struct HTTP<B: HTTPChunkedResponseBody = EmptyHTTPChunkedResponseBody> {
@@ -52,22 +80,43 @@ impl<B: HTTPChunkedResponseBody> Protocol for HTTP<B> {
     type Request<'a> = HTTPRequest<'a>;
     type Response<'a> = HTTPResponse<'a, B>;
     type ReadError = HTTPParseError;
-    type SendError = lasync::Error;
+    type SendError = Error;
     type Client = HTTPClient<B>;
 
     type ListenAddress = HTTPListenAddress;
-    type ListenError = lasync::Error;
+    type ListenError = Error;
     type Listener = HTTPListener<B>;
 
     async fn start(addresses: &[Self::ListenAddress], options: Self::Options) -> Result<Self> {
         let mut listeners = Vec::with_capacity(addresses.len());
         let mut listen_addresses = Vec::with_capacity(addresses.len());
         for address in addresses {
-            let (listener, listen_address) = HTTPListener::new(address)?;
+            let (listener, listen_address) = HTTPListener::new(address, &options)?;
             listeners.push(listener);
             listen_addresses.push(listen_address);
         }
 
+        // Clients of the other listeners are told where they can switch to HTTP/3
+        #[cfg(feature = "http3")]
+        {
+            let alt_svc: Vec<String> = listen_addresses
+                .iter()
+                .filter_map(|address| match address {
+                    HTTPListenAddress::HTTP3(address, _) => {
+                        Some(format!("h3=\":{}\"", address.port()))
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            if !alt_svc.is_empty() {
+                let alt_svc: std::sync::Arc<[u8]> = alt_svc.join(", ").into_bytes().into();
+                for listener in &mut listeners {
+                    listener.set_alt_svc(&alt_svc);
+                }
+            }
+        }
+
         Ok(HTTP {
             listeners,
             listen_addresses,
@@ -1,10 +1,22 @@
 use std::net::{SocketAddr, ToSocketAddrs};
 
+#[cfg(feature = "tls")]
+use crate::HTTPTLSConfig;
+
 /// The addresses this server will listen on
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HTTPListenAddress {
     /// The address to listen for insecure HTTP/1.1 connections
     HTTP(SocketAddr),
+
+    /// The address to listen for HTTP/1.1 connections secured with TLS
+    #[cfg(feature = "tls")]
+    HTTPS(SocketAddr, HTTPTLSConfig),
+
+    /// The UDP address to listen for HTTP/3 connections over QUIC, which are always secured with
+    /// TLS
+    #[cfg(feature = "http3")]
+    HTTP3(SocketAddr, HTTPTLSConfig),
 }
 
 impl HTTPListenAddress {
@@ -17,12 +29,48 @@ impl HTTPListenAddress {
             ))?,
         ))
     }
+
+    /// Creates a new [`ListenAddress`] for HTTP/1.1 connections secured with TLS using `config`
+    #[cfg(feature = "tls")]
+    pub fn https<S: ToSocketAddrs>(addr: S, config: HTTPTLSConfig) -> std::io::Result<Self> {
+        Ok(HTTPListenAddress::HTTPS(
+            addr.to_socket_addrs()?.next().ok_or(std::io::Error::new(
+                std::io::ErrorKind::AddrNotAvailable,
+                "could not get address",
+            ))?,
+            config,
+        ))
+    }
+
+    /// Creates a new [`ListenAddress`] for HTTP/3 connections over QUIC secured with TLS using
+    /// `config`
+    ///
+    /// HTTP/1.1 and HTTP/2 listeners advertise the port of every HTTP/3 listener to their clients
+    /// in an "Alt-Svc" field. Each connection handles up to 100 requests at the same time, one on
+    /// each open request stream, and clients aren't allowed to open more than that.
+    ///
+    /// QUIC runs over a UDP socket, which only the "tokio" runtime provides, so the "http3"
+    /// feature can't be used with any other runtime.
+    #[cfg(feature = "http3")]
+    pub fn http3<S: ToSocketAddrs>(addr: S, config: HTTPTLSConfig) -> std::io::Result<Self> {
+        Ok(HTTPListenAddress::HTTP3(
+            addr.to_socket_addrs()?.next().ok_or(std::io::Error::new(
+                std::io::ErrorKind::AddrNotAvailable,
+                "could not get address",
+            ))?,
+            config,
+        ))
+    }
 }
 
 impl<'a> std::fmt::Display for HTTPListenAddress {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             HTTPListenAddress::HTTP(address) => write!(f, "{} (HTTP/1.1)", address),
+            #[cfg(feature = "tls")]
+            HTTPListenAddress::HTTPS(address, _) => write!(f, "{} (HTTPS/1.1)", address),
+            #[cfg(feature = "http3")]
+            HTTPListenAddress::HTTP3(address, _) => write!(f, "{} (HTTP/3)", address),
         }
     }
 }
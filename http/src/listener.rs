@@ -2,66 +2,212 @@ use crate::{
     client::HTTPSocket, HTTPChunkedResponseBody, HTTPClient, HTTPClientAddress, HTTPListenAddress,
     HTTPOptions, HTTPProtocol, Result,
 };
+use huntsman::runtime::net::TCPListener;
 use huntsman::ProtocolListener;
-use lasync::net::TCPListener;
-use std::{marker::PhantomData, net::SocketAddr};
+use std::{marker::PhantomData, net::SocketAddr, sync::Arc};
+
+#[cfg(feature = "http3")]
+use crate::http3::{HTTP3Connection, QUICEndpoint};
+#[cfg(feature = "tls")]
+use crate::{tls::TLSStream, HTTPTLSConfig};
+#[cfg(feature = "tls")]
+use huntsman::runtime::time::{timeout, timeout_error};
+#[cfg(feature = "http3")]
+use std::rc::Rc;
 
 /// The sockets to listen for connections on
+///
+/// The HTTP/1.1 and HTTP/2 listeners hold the value of the "Alt-Svc" field advertising HTTP/3
+/// to their clients, if the server is listening for it.
 pub enum HTTPListener<B: HTTPChunkedResponseBody> {
-    /// The listener for insecure HTTP/1.1 connections
-    HTTP(TCPListener, PhantomData<B>),
+    /// The listener for insecure HTTP/1.1 and HTTP/2 connections
+    HTTP(TCPListener, Option<Arc<[u8]>>, PhantomData<B>),
+
+    /// The listener for HTTP/1.1 and HTTP/2 connections secured with TLS
+    #[cfg(feature = "tls")]
+    HTTPS(
+        TCPListener,
+        HTTPTLSConfig,
+        Option<Arc<[u8]>>,
+        PhantomData<B>,
+    ),
+
+    /// The endpoint for HTTP/3 connections over QUIC
+    #[cfg(feature = "http3")]
+    HTTP3(Arc<QUICEndpoint>, PhantomData<B>),
 }
 
 impl<B: HTTPChunkedResponseBody> HTTPListener<B> {
     /// Creates a new [`Listener`] for `address`
-    pub(crate) fn new(address: &HTTPListenAddress) -> Result<(Self, HTTPListenAddress)> {
+    ///
+    /// TLS listeners only offer HTTP/2 through ALPN if [`HTTPOptions::http2`] is set.
+    #[cfg_attr(not(feature = "tls"), allow(unused_variables))]
+    pub(crate) fn new(
+        address: &HTTPListenAddress,
+        options: &HTTPOptions,
+    ) -> Result<(Self, HTTPListenAddress)> {
         match address {
             HTTPListenAddress::HTTP(address) => HTTPListener::new_http(*address),
+            #[cfg(feature = "tls")]
+            HTTPListenAddress::HTTPS(address, config) => HTTPListener::new_https(
+                *address,
+                if options.http2 {
+                    config.clone()
+                } else {
+                    config.http1_only()
+                },
+            ),
+            #[cfg(feature = "http3")]
+            HTTPListenAddress::HTTP3(address, config) => {
+                HTTPListener::new_http3(*address, config, options)
+            }
+        }
+    }
+
+    /// Sets the value of the "Alt-Svc" field sent to clients of HTTP/1.1 and HTTP/2 listeners
+    #[cfg(feature = "http3")]
+    pub(crate) fn set_alt_svc(&mut self, value: &Arc<[u8]>) {
+        match self {
+            HTTPListener::HTTP(_, alt_svc, _) | HTTPListener::HTTPS(_, _, alt_svc, _) => {
+                *alt_svc = Some(value.clone())
+            }
+            HTTPListener::HTTP3(_, _) => {}
         }
     }
 
-    /// Creates a new [`Listener`] for insecure HTTP/1.1 connectio
+    /// Creates a new [`Listener`] for insecure HTTP/1.1 and HTTP/2 connections
     fn new_http(address: SocketAddr) -> Result<(Self, HTTPListenAddress)> {
         let socket = TCPListener::bind(address)?;
         let listen_address = socket.local_addr().unwrap();
         Ok((
-            HTTPListener::HTTP(socket, PhantomData),
+            HTTPListener::HTTP(socket, None, PhantomData),
             HTTPListenAddress::HTTP(listen_address),
         ))
     }
+
+    /// Creates a new [`Listener`] for HTTP/1.1 and HTTP/2 connections secured with TLS using
+    /// `config`
+    #[cfg(feature = "tls")]
+    fn new_https(address: SocketAddr, config: HTTPTLSConfig) -> Result<(Self, HTTPListenAddress)> {
+        let socket = TCPListener::bind(address)?;
+        let listen_address = socket.local_addr().unwrap();
+        Ok((
+            HTTPListener::HTTPS(socket, config.clone(), None, PhantomData),
+            HTTPListenAddress::HTTPS(listen_address, config),
+        ))
+    }
+
+    /// Creates a new [`Listener`] for HTTP/3 connections over QUIC secured with TLS using
+    /// `config`
+    #[cfg(feature = "http3")]
+    fn new_http3(
+        address: SocketAddr,
+        config: &HTTPTLSConfig,
+        options: &HTTPOptions,
+    ) -> Result<(Self, HTTPListenAddress)> {
+        let endpoint = QUICEndpoint::bind(address, config, options)?;
+        let listen_address = endpoint.local_addr().unwrap();
+        Ok((
+            HTTPListener::HTTP3(Arc::new(endpoint), PhantomData),
+            HTTPListenAddress::HTTP3(listen_address, config.clone()),
+        ))
+    }
 }
 
 impl<B: HTTPChunkedResponseBody> ProtocolListener for HTTPListener<B> {
     type Address = HTTPListenAddress;
     type Client = HTTPClient<B>;
     type ClientAddress = HTTPClientAddress;
-    type Error = lasync::Error;
+    type Error = crate::Error;
     type Options = HTTPOptions;
 
     async fn accept(
         &self,
         options: &Self::Options,
     ) -> std::result::Result<(Self::Client, Self::ClientAddress), Self::Error> {
-        let (socket, client_address) = match self {
-            HTTPListener::HTTP(listener, _) => {
+        let (socket, client_address, alt_svc) = match self {
+            HTTPListener::HTTP(listener, alt_svc, _) => {
                 let (mut socket, socket_address) = listener.accept().await?;
                 socket.set_nodelay(true)?;
                 (
                     HTTPSocket::HTTP(socket),
                     HTTPClientAddress::new(HTTPProtocol::HTTP, socket_address),
+                    alt_svc.clone(),
+                )
+            }
+            #[cfg(feature = "tls")]
+            HTTPListener::HTTPS(listener, config, alt_svc, _) => {
+                let (mut socket, socket_address) = listener.accept().await?;
+                socket.set_nodelay(true)?;
+                (
+                    HTTPSocket::HTTPS(Box::new(TLSStream::new(socket, config)?)),
+                    HTTPClientAddress::new(HTTPProtocol::HTTPS, socket_address),
+                    alt_svc.clone(),
+                )
+            }
+            #[cfg(feature = "http3")]
+            HTTPListener::HTTP3(endpoint, _) => {
+                let (handle, connection) = endpoint.accept().await?;
+                let socket_address = connection.remote_address();
+                (
+                    HTTPSocket::HTTP3(Rc::new(HTTP3Connection::new(
+                        endpoint.clone(),
+                        handle,
+                        connection,
+                        options,
+                    ))),
+                    HTTPClientAddress::new(HTTPProtocol::HTTP3, socket_address),
+                    None,
                 )
             }
         };
 
-        let client = HTTPClient::new(
-            socket,
-            options.max_header_size,
-            options.max_body_size,
-            options.header_read_timeout,
-            options.body_read_timeout,
-            options.write_timeout,
-        )?;
+        let client = HTTPClient::new(socket, options, alt_svc)?;
 
         Ok((client, client_address))
     }
+
+    // Nothing else borrows the client's socket until it is handed to a worker
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn connect(
+        &self,
+        client: &mut Self::Client,
+        address: &mut Self::ClientAddress,
+        options: &Self::Options,
+    ) -> std::result::Result<(), Self::Error> {
+        match self {
+            HTTPListener::HTTP(_, _, _) => {
+                if options.http2 && client.detect_http2(options).await? {
+                    address.set_protocol(HTTPProtocol::HTTP2);
+                }
+            }
+            #[cfg(feature = "tls")]
+            HTTPListener::HTTPS(_, _, _, _) => {
+                let handshake =
+                    timeout(client.socket_mut().handshake(), options.header_read_timeout)
+                        .await?
+                        .unwrap_or(Err(timeout_error()))?;
+
+                if let Some(tls) = handshake {
+                    address.set_tls(tls);
+                }
+
+                if client.socket_mut().alpn_protocol() == Some(b"h2") {
+                    client.start_http2(Vec::new(), options);
+                    address.set_protocol(HTTPProtocol::HTTP2);
+                }
+            }
+            #[cfg(feature = "http3")]
+            HTTPListener::HTTP3(_, _) => {
+                let connection = client.socket_mut().http3().unwrap();
+                let tls = timeout(connection.handshake(), options.header_read_timeout)
+                    .await?
+                    .unwrap_or(Err(timeout_error()))?;
+
+                address.set_tls(tls);
+            }
+        }
+
+        Ok(())
+    }
 }
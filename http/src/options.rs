@@ -1,4 +1,5 @@
-use std::time::Duration;
+use crate::HTTPCompressionOptions;
+use std::{num::NonZeroUsize, time::Duration};
 
 /// The options to determine how HTTP will operate
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -6,7 +7,19 @@ pub struct HTTPOptions {
     /// The maximum size for HTTP headers in requests
     pub max_header_size: usize,
 
+    /// The maximum length of the target of a request
+    pub max_target_length: usize,
+
+    /// The maximum number of fields in the header of a request, or in its trailers
+    pub max_header_fields: usize,
+
+    /// The maximum size of a single field in a request, including its name
+    pub max_field_size: usize,
+
     /// The maximum size for bodies in HTTP requests
+    ///
+    /// This is applied to bodies both before and after any content codings are removed. It is not
+    /// applied to streamed request bodies.
     pub max_body_size: usize,
 
     /// The maximum amount of time allowed between header reads
@@ -17,16 +30,55 @@ pub struct HTTPOptions {
 
     /// The maximum amount of time allowed between writes
     pub write_timeout: Duration,
+
+    /// The maximum amount of time to wait for the next request on an open connection
+    pub keep_alive_timeout: Duration,
+
+    /// The maximum number of requests to answer on a single connection before closing it
+    ///
+    /// If this is [`None`], there is no limit
+    pub max_requests_per_connection: Option<NonZeroUsize>,
+
+    /// Should request bodies be read by the handler as they arrive instead of before the request
+    /// is handled?
+    ///
+    /// HTTP/2 request bodies are always read before the request is handled.
+    pub stream_request_bodies: bool,
+
+    /// The options for compressing response bodies, or [`None`] to never compress them
+    pub compression: Option<HTTPCompressionOptions>,
+
+    /// Should clients be allowed to use HTTP/2?
+    ///
+    /// Clients without TLS use HTTP/2 by starting with its connection preface, while clients
+    /// with TLS negotiate it through ALPN.
+    ///
+    /// Each HTTP/2 connection handles up to 100 requests at the same time, one on each open
+    /// stream. Streams opened past that are refused, and the client can retry them once an earlier
+    /// one is answered.
+    pub http2: bool,
+
+    /// The maximum size of a message received over a WebSocket, after its fragments are joined
+    pub max_websocket_message_size: usize,
 }
 
 impl Default for HTTPOptions {
     fn default() -> Self {
         HTTPOptions {
-            max_header_size: 8192,      // 8 Kb
+            max_header_size: 8192,   // 8 Kb
+            max_target_length: 4096, // 4 Kb
+            max_header_fields: 100,
+            max_field_size: 4096,       // 4 Kb
             max_body_size: 1024 * 1024, // 1 Mb
             header_read_timeout: Duration::from_secs(60),
             body_read_timeout: Duration::from_secs(60),
             write_timeout: Duration::from_secs(60),
+            keep_alive_timeout: Duration::from_secs(15),
+            max_requests_per_connection: NonZeroUsize::new(1000),
+            stream_request_bodies: false,
+            compression: None,
+            http2: true,
+            max_websocket_message_size: 1024 * 1024, // 1 Mb
         }
     }
 }
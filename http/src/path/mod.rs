@@ -16,15 +16,15 @@ fn from_hexdigit(x: u8) -> u8 {
     if x.is_ascii_digit() {
         x - b'0'
     } else if x.is_ascii_lowercase() {
-        x - b'f' + 10
+        x - b'a' + 10
     } else {
-        x - b'F' + 10
+        x - b'A' + 10
     }
 }
 
 fn parse_segment_until<'a, F: Fn(u8) -> bool>(
     mut i: usize,
-    target: HTTPTarget<'a>,
+    target: &'a [u8],
     predicate: F,
 ) -> (Cow<'a, [u8]>, usize) {
     let start = i;
@@ -58,14 +58,17 @@ fn parse_segment_until<'a, F: Fn(u8) -> bool>(
 
     let segment = match segment {
         Some(segment) => segment.into(),
-        None => target.as_slice()[start..i].into(),
+        None => target[start..i].into(),
     };
     (segment, i)
 }
 
 impl<'a> HTTPPath<'a> {
     /// Parses `target` into an [`HTTPPath`]
+    ///
+    /// Targets in the authority or asterisk form have an empty path.
     pub fn parse(target: HTTPTarget<'a>) -> Self {
+        let target = target.path().unwrap_or(&[]);
         if target.len() == 0 {
             return HTTPPath {
                 segments: Vec::new(),
@@ -1,5 +1,4 @@
 use super::parse_segment_until;
-use crate::HTTPTarget;
 use std::{borrow::Cow, fmt::Debug};
 
 /// A query parameter passed in an HTTP request
@@ -11,9 +10,9 @@ pub struct HTTPQueryParam<'a> {
 
 impl<'a> HTTPQueryParam<'a> {
     /// Parse an [`HTTPQueryParam`] from `stream`
-    pub(super) fn parse(i: usize, target: HTTPTarget<'a>) -> (Self, usize) {
+    pub(super) fn parse(i: usize, target: &'a [u8]) -> (Self, usize) {
         let (key, i) = parse_segment_until(i, target, |x| x == b'=' || x == b'&');
-        let (value, i) = if target[i] == b'=' {
+        let (value, i) = if target.len() > i && target[i] == b'=' {
             parse_segment_until(i + 1, target, |x| x == b'&')
         } else {
             (Cow::Borrowed(&[] as &[u8]), i)
@@ -0,0 +1,126 @@
+use super::framing::read_body;
+use crate::{HTTPParseError, HTTPRequestFields, HTTPStatus, HTTPVersion, Stream};
+use huntsman::runtime::time::{timeout, timeout_error};
+use std::time::Duration;
+
+// rustdoc imports
+#[allow(unused_imports)]
+use crate::{HTTPOptions, HTTPRequest};
+
+/// The body of an [`HTTPRequest`] which is read from the client as the handler asks for it
+///
+/// This is used instead of a buffered body when [`HTTPOptions::stream_request_bodies`] is set. The
+/// client isn't read from until the body is, so a slow consumer slows down the client. Any part of
+/// the body which isn't read before the response is sent is read and thrown away before the next
+/// request, unless more than [`HTTPOptions::max_body_size`] bytes are left, in which case the
+/// connection is closed instead.
+///
+/// If the client is waiting for a "100 Continue" before sending the body, it is sent on the first
+/// read. A handler rejects the body by responding without reading it.
+pub struct HTTPRequestBody<'a> {
+    /// The stream the request was read from, which the body is read from as well
+    stream: Stream<'a>,
+
+    /// The value of the "Content-Length" field, if the body isn't chunked
+    content_length: Option<usize>,
+
+    /// The maximum amount of time allowed between body reads
+    read_timeout: Duration,
+
+    /// The maximum amount of time allowed to send a "100 Continue"
+    write_timeout: Duration,
+
+    /// The trailer fields sent after a chunked body
+    trailers: HTTPRequestFields<'a>,
+}
+
+impl<'a> HTTPRequestBody<'a> {
+    /// Creates a new [`HTTPRequestBody`] which reads the rest of the body from `stream`
+    pub(super) fn new(
+        stream: Stream<'a>,
+        content_length: Option<usize>,
+        read_timeout: Duration,
+        write_timeout: Duration,
+    ) -> Self {
+        HTTPRequestBody {
+            stream,
+            content_length,
+            read_timeout,
+            write_timeout,
+            trailers: HTTPRequestFields::default(),
+        }
+    }
+
+    /// Reads the next part of the body into `buffer`, returning the number of bytes read
+    ///
+    /// Returns zero once the entire body has been read.
+    pub async fn read(&mut self, buffer: &mut [u8]) -> Result<usize, HTTPParseError> {
+        let stream = &mut self.stream;
+
+        if stream.continue_pending() {
+            let mut response = HTTPStatus::Continue.generate(HTTPVersion::HTTP11);
+            response.extend_from_slice(b"\r\n");
+
+            timeout(stream.write(&response), self.write_timeout)
+                .await?
+                .unwrap_or(Err(timeout_error()))?;
+            stream.set_continue_pending(false);
+        }
+
+        timeout(
+            read_body(stream, buffer, &mut self.trailers),
+            self.read_timeout,
+        )
+        .await?
+        .unwrap_or(Err(HTTPParseError::BodyReadTimeout))
+    }
+
+    /// Reads the rest of the body into a [`Vec`]
+    ///
+    /// Returns [`HTTPParseError::BodyTooLarge`] if more than `max_size` bytes are left.
+    pub async fn read_to_end(&mut self, max_size: usize) -> Result<Vec<u8>, HTTPParseError> {
+        let mut body = Vec::new();
+        let mut buffer = [0; 4096];
+        loop {
+            let count = self.read(&mut buffer).await?;
+            if count == 0 {
+                return Ok(body);
+            }
+
+            if count > max_size - body.len() {
+                return Err(HTTPParseError::BodyTooLarge);
+            }
+
+            body.extend_from_slice(&buffer[..count]);
+        }
+    }
+
+    /// Has the entire body been read?
+    pub fn is_complete(&self) -> bool {
+        self.stream.body_framing().is_done()
+    }
+
+    /// Gets the length of the body from the "Content-Length" field
+    ///
+    /// Returns [`None`] if the body is chunked.
+    pub fn content_length(&self) -> Option<usize> {
+        self.content_length
+    }
+
+    /// Gets the trailer fields sent after a chunked body
+    ///
+    /// These are only available once the entire body has been read.
+    pub fn trailers(&self) -> &HTTPRequestFields<'a> {
+        &self.trailers
+    }
+}
+
+impl<'a> std::fmt::Debug for HTTPRequestBody<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HTTPRequestBody")
+            .field("content_length", &self.content_length)
+            .field("complete", &self.is_complete())
+            .field("trailers", &self.trailers)
+            .finish()
+    }
+}
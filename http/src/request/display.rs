@@ -68,6 +68,13 @@ impl<'a, Response: Display> Display for HTTPRequestDisplay<'a, Response> {
             }
         }
 
+        if self.display_headers && !self.request.trailers().is_empty() {
+            write!(f, "\nREQUEST TRAILERS:")?;
+            for trailer in self.request.trailers() {
+                write!(f, "\n  {}", trailer)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -1,3 +1,9 @@
+use crate::HTTPParseLimit;
+
+// rustdoc imports
+#[allow(unused_imports)]
+use crate::HTTPStatus;
+
 /// An error while parsing an HTTP Request
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HTTPParseError {
@@ -7,6 +13,11 @@ pub enum HTTPParseError {
     /// The headers is too long to fit into the buffer
     HeadersTooLong,
 
+    /// The request exceeds one of the limits placed on its header
+    ///
+    /// This should be answered with the status from [`HTTPParseLimit::status`]
+    LimitExceeded(HTTPParseLimit),
+
     /// The client sent an incomplete header before disconnecting
     IncompleteHeader,
 
@@ -16,23 +27,86 @@ pub enum HTTPParseError {
     /// The HTTP header contains an invalid version
     InvalidVersion,
 
+    /// The HTTP header contains a version which is valid but not supported
+    ///
+    /// This should be answered with [`HTTPStatus::HTTPVersionNotSupported`]
+    UnsupportedVersion,
+
+    /// The request has an "Expect" field with an expectation other than "100-continue"
+    ///
+    /// This should be answered with [`HTTPStatus::ExpectationFailed`]
+    UnknownExpectation,
+
     /// The request contains an invalid field
     InvalidField,
 
     /// The "Content-Length" field of the request doesn't contain a number
     InvalidContentLength,
 
+    /// The request has the same "Content-Length" more than once
+    DuplicateContentLength,
+
+    /// The request has more than one "Content-Length" with different values
+    ConflictingContentLength,
+
+    /// The request has both a "Transfer-Encoding" and a "Content-Length"
+    TransferEncodingWithContentLength,
+
+    /// The "Transfer-Encoding" of the request doesn't end with a single "chunked"
+    InvalidTransferEncoding,
+
+    /// The "Transfer-Encoding" of the request contains codings other than "chunked"
+    ///
+    /// This should be answered with [`HTTPStatus::NotImplemented`]
+    UnsupportedTransferEncoding,
+
+    /// A field name in the request is followed by whitespace before the colon
+    WhitespaceBeforeColon,
+
+    /// A field value in the request is continued on the next line
+    ObsoleteLineFolding,
+
+    /// A line in the request ends with a line feed which isn't preceded by a carriage return
+    BareLineFeed,
+
+    /// The request contains a carriage return which isn't followed by a line feed
+    BareCarriageReturn,
+
     /// The request body is too large
     BodyTooLarge,
 
+    /// A chunk of a "chunked" request body is malformed
+    InvalidChunk,
+
+    /// The client sent an incomplete body before disconnecting
+    IncompleteBody,
+
+    /// The request body has a "Content-Encoding" which isn't supported
+    ///
+    /// This should be answered with [`HTTPStatus::UnsupportedMediaType`]
+    UnsupportedContentEncoding,
+
+    /// The request body isn't valid for its "Content-Encoding"
+    InvalidContentEncoding,
+
     /// The client took too long to send the headers
     HeaderReadTimeout,
 
     /// The client took too long to send the body
     BodyReadTimeout,
 
+    /// The client broke the HTTP/2 protocol, so the connection was closed
+    ///
+    /// No response can be sent after this error.
+    HTTP2ConnectionError,
+
+    /// The client broke the HTTP/3 protocol, so the connection was closed
+    ///
+    /// No response can be sent after this error.
+    HTTP3ConnectionError,
+
     /// An I/O error occurred while parsing a request
-    IO(lasync::Error),
+    IO(crate::Error),
 }
 
 impl std::error::Error for HTTPParseError {
@@ -42,14 +116,32 @@ impl std::error::Error for HTTPParseError {
 
             HTTPParseError::InvalidMethod
             | HTTPParseError::HeadersTooLong
+            | HTTPParseError::LimitExceeded(_)
             | HTTPParseError::IncompleteHeader
             | HTTPParseError::InvalidTarget
             | HTTPParseError::InvalidVersion
+            | HTTPParseError::UnsupportedVersion
+            | HTTPParseError::UnknownExpectation
             | HTTPParseError::InvalidField
             | HTTPParseError::InvalidContentLength
+            | HTTPParseError::DuplicateContentLength
+            | HTTPParseError::ConflictingContentLength
+            | HTTPParseError::TransferEncodingWithContentLength
+            | HTTPParseError::InvalidTransferEncoding
+            | HTTPParseError::UnsupportedTransferEncoding
+            | HTTPParseError::WhitespaceBeforeColon
+            | HTTPParseError::ObsoleteLineFolding
+            | HTTPParseError::BareLineFeed
+            | HTTPParseError::BareCarriageReturn
             | HTTPParseError::BodyTooLarge
+            | HTTPParseError::InvalidChunk
+            | HTTPParseError::IncompleteBody
+            | HTTPParseError::UnsupportedContentEncoding
+            | HTTPParseError::InvalidContentEncoding
             | HTTPParseError::HeaderReadTimeout
-            | HTTPParseError::BodyReadTimeout => None,
+            | HTTPParseError::BodyReadTimeout
+            | HTTPParseError::HTTP2ConnectionError
+            | HTTPParseError::HTTP3ConnectionError => None,
         }
     }
 }
@@ -59,14 +151,36 @@ impl std::fmt::Display for HTTPParseError {
         match self {
             HTTPParseError::InvalidMethod => write!(f, "invalid method"),
             HTTPParseError::HeadersTooLong => write!(f, "headers too long"),
+            HTTPParseError::LimitExceeded(limit) => write!(f, "{} limit exceeded", limit),
             HTTPParseError::IncompleteHeader => write!(f, "header is incomplete"),
             HTTPParseError::InvalidTarget => write!(f, "invalid target"),
             HTTPParseError::InvalidVersion => write!(f, "invalid version"),
+            HTTPParseError::UnsupportedVersion => write!(f, "unsupported version"),
+            HTTPParseError::UnknownExpectation => write!(f, "unknown expectation"),
             HTTPParseError::InvalidField => write!(f, "invalid field"),
             HTTPParseError::InvalidContentLength => write!(f, "invalid content length"),
+            HTTPParseError::DuplicateContentLength => write!(f, "duplicate content length"),
+            HTTPParseError::ConflictingContentLength => write!(f, "conflicting content lengths"),
+            HTTPParseError::TransferEncodingWithContentLength => {
+                write!(f, "transfer encoding with content length")
+            }
+            HTTPParseError::InvalidTransferEncoding => write!(f, "invalid transfer encoding"),
+            HTTPParseError::UnsupportedTransferEncoding => {
+                write!(f, "unsupported transfer encoding")
+            }
+            HTTPParseError::WhitespaceBeforeColon => write!(f, "whitespace before colon"),
+            HTTPParseError::ObsoleteLineFolding => write!(f, "obsolete line folding"),
+            HTTPParseError::BareLineFeed => write!(f, "bare line feed"),
+            HTTPParseError::BareCarriageReturn => write!(f, "bare carriage return"),
             HTTPParseError::BodyTooLarge => write!(f, "request body too large"),
+            HTTPParseError::InvalidChunk => write!(f, "invalid chunk"),
+            HTTPParseError::IncompleteBody => write!(f, "body is incomplete"),
+            HTTPParseError::UnsupportedContentEncoding => write!(f, "unsupported content encoding"),
+            HTTPParseError::InvalidContentEncoding => write!(f, "invalid content encoding"),
             HTTPParseError::HeaderReadTimeout => write!(f, "reading header timed out"),
             HTTPParseError::BodyReadTimeout => write!(f, "reading body timed out"),
+            HTTPParseError::HTTP2ConnectionError => write!(f, "HTTP/2 connection error"),
+            HTTPParseError::HTTP3ConnectionError => write!(f, "HTTP/3 connection error"),
 
             HTTPParseError::IO(error) => write!(
                 f,
@@ -77,8 +191,8 @@ impl std::fmt::Display for HTTPParseError {
     }
 }
 
-impl From<lasync::Error> for HTTPParseError {
-    fn from(error: lasync::Error) -> Self {
+impl From<crate::Error> for HTTPParseError {
+    fn from(error: crate::Error) -> Self {
         HTTPParseError::IO(error)
     }
 }
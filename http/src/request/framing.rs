@@ -0,0 +1,258 @@
+use crate::{HTTPParseError, HTTPRequestField, HTTPRequestFields, HTTPRequestHeader, Stream};
+use huntsman::runtime::time::timeout;
+use std::time::Duration;
+
+/// How the end of a request body is found and how much of it is left to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BodyFraming {
+    /// There is nothing left of the body to read
+    Done,
+
+    /// The body has the contained number of bytes left
+    Length(usize),
+
+    /// The body uses the "chunked" transfer coding and the current chunk has the contained number
+    /// of bytes left
+    ///
+    /// A chunk size line is next if there are no bytes left.
+    Chunked(usize),
+
+    /// Reading the body failed, so the end of it can't be found
+    Failed,
+}
+
+/// Parse the `content_length` into a [`usize`]
+fn parse_content_length(content_length: &[u8]) -> Result<usize, HTTPParseError> {
+    if content_length.is_empty() {
+        return Err(HTTPParseError::InvalidContentLength);
+    }
+
+    let mut value: usize = 0;
+    for byte in content_length {
+        if !byte.is_ascii_digit() {
+            return Err(HTTPParseError::InvalidContentLength);
+        }
+
+        value = value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add((*byte - b'0') as usize))
+            .ok_or(HTTPParseError::InvalidContentLength)?;
+    }
+
+    Ok(value)
+}
+
+impl BodyFraming {
+    /// Determines the framing of the body of the request with `header`
+    ///
+    /// Anything which could be framed differently by another server, such as a proxy in front of
+    /// this one, is rejected. A request without a body has a length of zero.
+    pub(crate) fn parse(header: &HTTPRequestHeader) -> Result<Self, HTTPParseError> {
+        let fields = header.fields();
+
+        if fields.contains(b"Transfer-Encoding") {
+            if fields.contains(b"Content-Length") {
+                return Err(HTTPParseError::TransferEncodingWithContentLength);
+            }
+
+            let codings: Vec<_> = fields.get_list(b"Transfer-Encoding").collect();
+            let chunked = codings
+                .iter()
+                .filter(|coding| coding.eq_ignore_ascii_case(b"chunked"))
+                .count();
+            if chunked != 1 || !header.is_chunked() {
+                return Err(HTTPParseError::InvalidTransferEncoding);
+            }
+
+            if codings.len() > 1 {
+                return Err(HTTPParseError::UnsupportedTransferEncoding);
+            }
+
+            return Ok(BodyFraming::Chunked(0));
+        }
+
+        let mut content_length = None;
+        for field in fields.get_all(b"Content-Length") {
+            for value in field.value().split(|c| *c == b',') {
+                let value = parse_content_length(value.trim_ascii())?;
+
+                content_length = match content_length {
+                    None => Some(value),
+                    Some(previous) if previous == value => {
+                        return Err(HTTPParseError::DuplicateContentLength)
+                    }
+                    Some(_) => return Err(HTTPParseError::ConflictingContentLength),
+                };
+            }
+        }
+
+        Ok(BodyFraming::Length(content_length.unwrap_or(0)))
+    }
+
+    /// Is there nothing left of the body to read?
+    pub(crate) fn is_done(&self) -> bool {
+        *self == BodyFraming::Done
+    }
+}
+
+/// Reads the next part of the body from `stream` into `buffer`, returning the number of bytes read
+///
+/// Returns zero once the body has been completely read. If the body is chunked, the trailer fields
+/// are pushed onto `trailers` when they are reached.
+pub(crate) async fn read_body<'a>(
+    stream: &mut Stream<'a>,
+    buffer: &mut [u8],
+    trailers: &mut HTTPRequestFields<'a>,
+) -> Result<usize, HTTPParseError> {
+    if buffer.is_empty() {
+        return Ok(0);
+    }
+
+    let result = read_body_framed(stream, buffer, trailers).await;
+    if result.is_err() {
+        stream.set_body_framing(BodyFraming::Failed);
+    }
+
+    result.map_err(|error| match error {
+        HTTPParseError::HeaderReadTimeout => HTTPParseError::BodyReadTimeout,
+        HTTPParseError::IncompleteHeader => HTTPParseError::IncompleteBody,
+        error => error,
+    })
+}
+
+/// Reads the next part of the body according to the framing of `stream`
+async fn read_body_framed<'a>(
+    stream: &mut Stream<'a>,
+    buffer: &mut [u8],
+    trailers: &mut HTTPRequestFields<'a>,
+) -> Result<usize, HTTPParseError> {
+    loop {
+        match stream.body_framing() {
+            BodyFraming::Done => return Ok(0),
+            BodyFraming::Failed => return Err(HTTPParseError::InvalidChunk),
+
+            BodyFraming::Length(0) => stream.set_body_framing(BodyFraming::Done),
+            BodyFraming::Length(remaining) => {
+                let length = remaining.min(buffer.len());
+                let count = stream.take(&mut buffer[..length]).await?;
+                stream.set_body_framing(BodyFraming::Length(remaining - count));
+                return Ok(count);
+            }
+
+            BodyFraming::Chunked(0) => {
+                let start = stream.len();
+                let size = parse_chunk_size(stream).await?;
+
+                // SAFETY: Nothing collected from the chunk size line is kept
+                unsafe { stream.discard(start) };
+
+                if size == 0 {
+                    trailers.append(HTTPRequestField::parse_list(stream).await?);
+                    stream.set_body_framing(BodyFraming::Done);
+                    return Ok(0);
+                }
+
+                stream.set_body_framing(BodyFraming::Chunked(size));
+            }
+            BodyFraming::Chunked(remaining) => {
+                let length = remaining.min(buffer.len());
+                let count = stream.take(&mut buffer[..length]).await?;
+
+                if count == remaining {
+                    let start = stream.len();
+                    if stream.next().await? != b'\r' || stream.next().await? != b'\n' {
+                        return Err(HTTPParseError::InvalidChunk);
+                    }
+
+                    // SAFETY: Nothing collected from the end of the chunk is kept
+                    unsafe { stream.discard(start) };
+                }
+
+                stream.set_body_framing(BodyFraming::Chunked(remaining - count));
+                return Ok(count);
+            }
+        }
+    }
+}
+
+/// Parses a chunk size line, returning the size of the following chunk
+///
+/// Chunk extensions are ignored.
+async fn parse_chunk_size(stream: &mut Stream<'_>) -> Result<usize, HTTPParseError> {
+    let mut size: usize = 0;
+    let mut digits = 0;
+    while let Some(digit) = (stream.peek().await? as char).to_digit(16) {
+        stream.next().await?;
+
+        size = size
+            .checked_mul(16)
+            .and_then(|size| size.checked_add(digit as usize))
+            .ok_or(HTTPParseError::BodyTooLarge)?;
+        digits += 1;
+    }
+
+    if digits == 0 {
+        return Err(HTTPParseError::InvalidChunk);
+    }
+
+    let extensions = stream.collect_line().await?.trim_ascii_start();
+    if !extensions.is_empty() && extensions[0] != b';' {
+        return Err(HTTPParseError::InvalidChunk);
+    }
+
+    Ok(size)
+}
+
+/// Reads the rest of a chunked body from `stream`, pushing its trailer fields onto `trailers`
+pub(crate) async fn read_chunked<'a>(
+    stream: &mut Stream<'a>,
+    max_body_size: usize,
+    trailers: &mut HTTPRequestFields<'a>,
+) -> Result<Box<[u8]>, HTTPParseError> {
+    let mut body = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+        let count = read_body(stream, &mut buffer, trailers).await?;
+        if count == 0 {
+            return Ok(body.into_boxed_slice());
+        }
+
+        if count > max_body_size - body.len() {
+            return Err(HTTPParseError::BodyTooLarge);
+        }
+
+        body.extend_from_slice(&buffer[..count]);
+    }
+}
+
+/// Reads and throws away whatever is left of the body of the last request from `stream`
+///
+/// Each read is limited by `body_read_timeout`. Returns [`HTTPParseError::BodyTooLarge`] instead
+/// of reading more than `max_body_size` bytes, after which the connection has to be closed.
+pub(crate) async fn skip_body(
+    stream: &mut Stream<'_>,
+    body_read_timeout: Duration,
+    max_body_size: usize,
+) -> Result<(), HTTPParseError> {
+    let mut buffer = [0; 4096];
+    let mut trailers = HTTPRequestFields::default();
+    let mut skipped = 0;
+    while !stream.body_framing().is_done() {
+        // Reading one byte past the limit is enough to know it was passed
+        let length = buffer
+            .len()
+            .min((max_body_size - skipped).saturating_add(1));
+        skipped += timeout(
+            read_body(stream, &mut buffer[..length], &mut trailers),
+            body_read_timeout,
+        )
+        .await?
+        .unwrap_or(Err(HTTPParseError::BodyReadTimeout))?;
+
+        if skipped > max_body_size {
+            return Err(HTTPParseError::BodyTooLarge);
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
-use super::Stream;
-use crate::HTTPParseError;
+use super::is_token_char;
+use crate::{HTTPParseError, HTTPParseLimit, Stream};
+use std::cell::Cell;
 
 /// A field containing metadata about an HTTP request
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -12,39 +13,87 @@ pub struct HTTPRequestField<'a> {
 }
 
 impl<'a> HTTPRequestField<'a> {
+    /// Creates an [`HTTPRequestField`] from a field which wasn't sent in a header
+    pub(crate) fn new(name: &'a [u8], value: &'a [u8]) -> Self {
+        HTTPRequestField { name, value }
+    }
+
     /// Attempts to parse an [`HTTPField`] from `stream`
-    pub(super) async fn parse(stream: &mut Stream<'a, '_>) -> Result<Self, HTTPParseError> {
+    pub(super) async fn parse(stream: &mut Stream<'a>) -> Result<Self, HTTPParseError> {
+        match stream.peek().await? {
+            b' ' | b'\t' => return Err(HTTPParseError::ObsoleteLineFolding),
+            b'\n' => return Err(HTTPParseError::BareLineFeed),
+            _ => {}
+        }
+
+        let max_size = stream.limits().max_field_size;
+        let size = Cell::new(0);
+        let count = |c| {
+            if c != b'\r' && size.replace(size.get() + 1) == max_size {
+                Err(HTTPParseError::LimitExceeded(HTTPParseLimit::FieldSize))
+            } else {
+                Ok(c)
+            }
+        };
+
         let name = stream
-            .collect_until_predicate_error(|c| match c {
+            .collect_until_predicate_error(|c| match count(c)? {
                 b':' => Ok(true),
-                x if x.is_ascii_alphanumeric() => Ok(false),
-                b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^'
-                | b'_' | b'`' | b'|' | b'~' => Ok(false),
+                c if is_token_char(c) => Ok(false),
+                b' ' | b'\t' => Err(HTTPParseError::WhitespaceBeforeColon),
+                b'\n' => Err(HTTPParseError::BareLineFeed),
                 _ => Err(HTTPParseError::InvalidField),
             })
             .await?;
 
+        if name.len() == 1 {
+            return Err(HTTPParseError::InvalidField);
+        }
+
         stream.skip_whitespace().await?;
 
         let value = stream
-            .collect_until_predicate_error(|c| match c {
+            .collect_until_predicate_error(|c| match count(c)? {
                 b'\r' => Ok(true),
-                x if x >= 0x21 => Ok(false),
+                // Visible characters and obs-text, but not DEL
+                0x21..=0x7E | 0x80..=0xFF => Ok(false),
                 b' ' | b'\t' => Ok(false),
+                b'\n' => Err(HTTPParseError::BareLineFeed),
                 _ => Err(HTTPParseError::InvalidField),
             })
             .await?;
 
         if stream.next().await? != b'\n' {
-            return Err(HTTPParseError::InvalidField);
+            return Err(HTTPParseError::BareCarriageReturn);
         }
 
         Ok(HTTPRequestField {
             name: &name[..name.len() - 1],
-            value: &value[..value.len() - 1],
+            value: value[..value.len() - 1].trim_ascii_end(),
         })
     }
 
+    /// Attempts to parse a list of [`HTTPRequestField`]s terminated by an empty line from `stream`
+    pub(crate) async fn parse_list(stream: &mut Stream<'a>) -> Result<Vec<Self>, HTTPParseError> {
+        let mut fields = Vec::new();
+        loop {
+            if stream.peek().await? == b'\r' {
+                stream.next().await?;
+                if stream.next().await? != b'\n' {
+                    return Err(HTTPParseError::BareCarriageReturn);
+                }
+
+                return Ok(fields);
+            }
+
+            if fields.len() == stream.limits().max_fields {
+                return Err(HTTPParseError::LimitExceeded(HTTPParseLimit::FieldCount));
+            }
+
+            fields.push(HTTPRequestField::parse(stream).await?);
+        }
+    }
+
     /// Gets the name of this field
     pub fn name(&self) -> &'a [u8] {
         self.name
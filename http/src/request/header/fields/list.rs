@@ -0,0 +1,52 @@
+/// An iterator over the elements of a comma-separated list in a field value
+///
+/// Commas inside of quoted strings don't separate elements. Empty elements are skipped and
+/// whitespace around each element is removed.
+#[derive(Debug, Clone)]
+pub(crate) struct ListElements<'a> {
+    /// The remaining part of the value
+    value: &'a [u8],
+
+    /// The byte which separates elements
+    separator: u8,
+}
+
+impl<'a> ListElements<'a> {
+    /// Creates a new [`ListElements`] over the elements of `value` separated by `separator`
+    pub(crate) fn new(value: &'a [u8], separator: u8) -> Self {
+        ListElements { value, separator }
+    }
+}
+
+impl<'a> Iterator for ListElements<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.value.is_empty() {
+            let mut quoted = false;
+            let mut escaped = false;
+            let mut end = self.value.len();
+            for (i, c) in self.value.iter().enumerate() {
+                if escaped {
+                    escaped = false;
+                } else if quoted && *c == b'\\' {
+                    escaped = true;
+                } else if *c == b'"' {
+                    quoted = !quoted;
+                } else if !quoted && *c == self.separator {
+                    end = i;
+                    break;
+                }
+            }
+
+            let element = self.value[..end].trim_ascii();
+            self.value = &self.value[(end + 1).min(self.value.len())..];
+
+            if !element.is_empty() {
+                return Some(element);
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,81 @@
+use super::list::ListElements;
+
+/// A media type, such as the value of a "Content-Type" field (e.g. "text/html; charset=utf-8")
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct HTTPMediaType<'a> {
+    /// The top-level type (e.g. "text")
+    type_: &'a [u8],
+
+    /// The subtype (e.g. "html")
+    subtype: &'a [u8],
+
+    /// The parameters following the type, without the leading ';'
+    parameters: &'a [u8],
+}
+
+impl<'a> HTTPMediaType<'a> {
+    /// Attempts to parse `value` as an [`HTTPMediaType`]
+    pub(crate) fn parse(value: &'a [u8]) -> Option<Self> {
+        let (essence, parameters) = match value.iter().position(|c| *c == b';') {
+            Some(semicolon) => (&value[..semicolon], &value[semicolon + 1..]),
+            None => (value, &[] as &[u8]),
+        };
+
+        let essence = essence.trim_ascii();
+        let slash = essence.iter().position(|c| *c == b'/')?;
+        let (type_, subtype) = (&essence[..slash], &essence[slash + 1..]);
+        if type_.is_empty() || subtype.is_empty() {
+            return None;
+        }
+
+        Some(HTTPMediaType {
+            type_,
+            subtype,
+            parameters,
+        })
+    }
+
+    /// Gets the top-level type (e.g. "text")
+    pub fn type_(&self) -> &'a [u8] {
+        self.type_
+    }
+
+    /// Gets the subtype (e.g. "html")
+    pub fn subtype(&self) -> &'a [u8] {
+        self.subtype
+    }
+
+    /// Does this media type have the type and subtype in `essence` (e.g. "text/html")?
+    ///
+    /// Media types are case-insensitive.
+    pub fn is(&self, essence: &[u8]) -> bool {
+        essence.len() == self.type_.len() + 1 + self.subtype.len()
+            && essence[..self.type_.len()].eq_ignore_ascii_case(self.type_)
+            && essence[self.type_.len()] == b'/'
+            && essence[self.type_.len() + 1..].eq_ignore_ascii_case(self.subtype)
+    }
+
+    /// Gets the value of the parameter called `name` (e.g. "charset")
+    ///
+    /// Quotes around the value are not removed.
+    pub fn parameter(&self, name: &[u8]) -> Option<&'a [u8]> {
+        ListElements::new(self.parameters, b';').find_map(|parameter| {
+            let equals = parameter.iter().position(|c| *c == b'=')?;
+            if parameter[..equals].trim_ascii().eq_ignore_ascii_case(name) {
+                Some(parameter[equals + 1..].trim_ascii())
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<'a> std::fmt::Debug for HTTPMediaType<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HTTPMediaType")
+            .field("type", &String::from_utf8_lossy(self.type_))
+            .field("subtype", &String::from_utf8_lossy(self.subtype))
+            .field("parameters", &String::from_utf8_lossy(self.parameters))
+            .finish()
+    }
+}
@@ -0,0 +1,156 @@
+use crate::HTTPRequestField;
+use list::ListElements;
+use std::{borrow::Cow, ops::Deref};
+
+mod list;
+mod media_type;
+mod quality;
+
+pub use media_type::HTTPMediaType;
+pub use quality::HTTPQualityValue;
+
+/// The fields of an HTTP request, looked up by case-insensitive name
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HTTPRequestFields<'a> {
+    /// The fields in the order they were sent
+    fields: Vec<HTTPRequestField<'a>>,
+}
+
+impl<'a> HTTPRequestFields<'a> {
+    /// Creates a new [`HTTPRequestFields`] from `fields`
+    pub(crate) fn new(fields: Vec<HTTPRequestField<'a>>) -> Self {
+        HTTPRequestFields { fields }
+    }
+
+    /// Adds `fields` after the current fields
+    pub(crate) fn append(&mut self, mut fields: Vec<HTTPRequestField<'a>>) {
+        self.fields.append(&mut fields);
+    }
+
+    /// Gets the first field called `name`
+    pub fn get(&self, name: &[u8]) -> Option<&HTTPRequestField<'a>> {
+        self.fields
+            .iter()
+            .find(|field| field.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Gets every field called `name` in the order they were sent
+    pub fn get_all<'b>(
+        &'b self,
+        name: &'b [u8],
+    ) -> impl Iterator<Item = &'b HTTPRequestField<'a>> + 'b {
+        self.fields
+            .iter()
+            .filter(move |field| field.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Is there a field called `name`?
+    pub fn contains(&self, name: &[u8]) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Gets the elements of the comma-separated lists in every field called `name`
+    ///
+    /// Repeating a list field is the same as sending one field with the values joined by commas.
+    pub fn get_list<'b>(&'b self, name: &'b [u8]) -> impl Iterator<Item = &'a [u8]> + 'b {
+        self.get_all(name)
+            .flat_map(|field| ListElements::new(field.value(), b','))
+    }
+
+    /// Gets the values of every field called `name` combined into one, separated by commas
+    pub fn get_combined(&self, name: &[u8]) -> Option<Cow<'a, [u8]>> {
+        let mut fields = self.get_all(name);
+        let first = fields.next()?.value();
+
+        let mut combined = match fields.next() {
+            Some(second) => {
+                let mut combined = first.to_vec();
+                combined.extend_from_slice(b", ");
+                combined.extend_from_slice(second.value());
+                combined
+            }
+            None => return Some(Cow::Borrowed(first)),
+        };
+
+        for field in fields {
+            combined.extend_from_slice(b", ");
+            combined.extend_from_slice(field.value());
+        }
+
+        Some(Cow::Owned(combined))
+    }
+
+    /// Gets the elements of every field called `name` along with their quality
+    ///
+    /// This is meant for fields like "Accept" and "Accept-Encoding".
+    pub fn get_quality_list<'b>(
+        &'b self,
+        name: &'b [u8],
+    ) -> impl Iterator<Item = HTTPQualityValue<'a>> + 'b {
+        self.get_all(name)
+            .flat_map(|field| HTTPQualityValue::parse_list(field.value()))
+    }
+
+    /// Gets the value of the "Host" field
+    pub fn host(&self) -> Option<&'a [u8]> {
+        self.get(b"Host").map(HTTPRequestField::value)
+    }
+
+    /// Gets the value of the "Content-Type" field
+    ///
+    /// Returns [`None`] if there is no "Content-Type" field or if it isn't a valid media type.
+    pub fn content_type(&self) -> Option<HTTPMediaType<'a>> {
+        self.get(b"Content-Type")
+            .and_then(|field| HTTPMediaType::parse(field.value()))
+    }
+
+    /// Gets the media ranges from the "Accept" fields with their quality
+    pub fn accept(&self) -> impl Iterator<Item = HTTPQualityValue<'a>> + '_ {
+        self.get_quality_list(b"Accept")
+    }
+
+    /// Gets the content codings from the "Accept-Encoding" fields with their quality
+    pub fn accept_encoding(&self) -> impl Iterator<Item = HTTPQualityValue<'a>> + '_ {
+        self.get_quality_list(b"Accept-Encoding")
+    }
+
+    /// Gets the cookies from the "Cookie" fields as `(name, value)` pairs
+    pub fn cookies(&self) -> impl Iterator<Item = (&'a [u8], &'a [u8])> + '_ {
+        self.get_all(b"Cookie")
+            .flat_map(|field| ListElements::new(field.value(), b';'))
+            .filter_map(|cookie| {
+                let equals = cookie.iter().position(|c| *c == b'=')?;
+                let value = cookie[equals + 1..].trim_ascii();
+                let value = match value {
+                    [b'"', value @ .., b'"'] => value,
+                    value => value,
+                };
+
+                Some((cookie[..equals].trim_ascii(), value))
+            })
+    }
+
+    /// Gets the value of the cookie called `name`
+    pub fn cookie(&self, name: &[u8]) -> Option<&'a [u8]> {
+        self.cookies()
+            .find(|(cookie, _)| *cookie == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl<'a> Deref for HTTPRequestFields<'a> {
+    type Target = [HTTPRequestField<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.fields
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b HTTPRequestFields<'a> {
+    type Item = &'b HTTPRequestField<'a>;
+    type IntoIter = std::slice::Iter<'b, HTTPRequestField<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fields.iter()
+    }
+}
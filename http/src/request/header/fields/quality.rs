@@ -0,0 +1,94 @@
+use super::list::ListElements;
+
+/// An element of a field value list with a quality, such as those in "Accept" and
+/// "Accept-Encoding" fields
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct HTTPQualityValue<'a> {
+    /// The element without its quality parameter
+    value: &'a [u8],
+
+    /// The quality of the element in thousandths
+    quality: u16,
+}
+
+/// Parses a quality value (e.g. "0.5") into thousandths
+fn parse_quality(quality: &[u8]) -> Option<u16> {
+    let (integer, fraction) = match quality.iter().position(|c| *c == b'.') {
+        Some(dot) => (&quality[..dot], &quality[dot + 1..]),
+        None => (quality, &[] as &[u8]),
+    };
+
+    if fraction.len() > 3 || !fraction.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    let mut thousandths = 0;
+    for i in 0..3 {
+        thousandths *= 10;
+        if let Some(digit) = fraction.get(i) {
+            thousandths += (*digit - b'0') as u16;
+        }
+    }
+
+    match integer {
+        b"0" => Some(thousandths),
+        b"1" if thousandths == 0 => Some(1000),
+        _ => None,
+    }
+}
+
+impl<'a> HTTPQualityValue<'a> {
+    /// Parses `element` into an [`HTTPQualityValue`]
+    ///
+    /// Elements without a quality parameter have a quality of 1, while elements with an invalid
+    /// quality have a quality of 0.
+    pub(crate) fn parse(element: &'a [u8]) -> Self {
+        let mut end = element.len();
+        let mut quality = 1000;
+        let mut offset = 0;
+        for parameter in element.split(|c| *c == b';') {
+            let start = offset;
+            offset += parameter.len() + 1;
+
+            let parameter = parameter.trim_ascii();
+            if parameter.len() < 2 || !parameter[..2].eq_ignore_ascii_case(b"q=") {
+                continue;
+            }
+
+            end = start.saturating_sub(1);
+            quality = parse_quality(&parameter[2..]).unwrap_or(0);
+            break;
+        }
+
+        HTTPQualityValue {
+            value: element[..end].trim_ascii(),
+            quality,
+        }
+    }
+
+    /// Parses all of the elements in `value`
+    pub(crate) fn parse_list(value: &'a [u8]) -> impl Iterator<Item = Self> {
+        ListElements::new(value, b',').map(HTTPQualityValue::parse)
+    }
+
+    /// Gets the element without its quality parameter
+    pub fn value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// Gets the quality of the element in thousandths, from 0 to 1000
+    ///
+    /// A quality of 0 means the element is "not acceptable".
+    pub fn quality(&self) -> u16 {
+        self.quality
+    }
+}
+
+impl<'a> std::fmt::Debug for HTTPQualityValue<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HTTPQualityValue")
+            .field("value", &String::from_utf8_lossy(self.value))
+            .field("quality", &self.quality)
+            .finish()
+    }
+}
@@ -1,9 +1,9 @@
-use super::Stream;
-use crate::HTTPParseError;
+use super::is_token_char;
+use crate::{HTTPParseError, Stream};
 
 /// A method for the request
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum HTTPMethod {
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HTTPMethod<'a> {
     /// The [`HTTPMethod::GET`] method requests transfer of a current selected representation for
     /// the target resource
     GET,
@@ -24,32 +24,93 @@ pub enum HTTPMethod {
     /// The [`HTTPMethod::DELETE`] method requests that the origin server remove the association
     /// between the target resource and its current functionality
     DELETE,
+
+    /// The [`HTTPMethod::CONNECT`] method requests that the recipient establish a tunnel to the
+    /// destination origin server identified by the request target
+    CONNECT,
+
+    /// The [`HTTPMethod::OPTIONS`] method requests information about the communication options
+    /// available for the target resource
+    OPTIONS,
+
+    /// The [`HTTPMethod::TRACE`] method requests a remote, application-level loop-back of the
+    /// request message
+    TRACE,
+
+    /// The [`HTTPMethod::PATCH`] method requests that a set of changes described in the request
+    /// content be applied to the target resource
+    PATCH,
+
+    /// Any other method, such as the WebDAV methods "PROPFIND" and "MKCOL"
+    Extension(&'a [u8]),
 }
 
-impl HTTPMethod {
+impl<'a> HTTPMethod<'a> {
     /// Attempts to parse an [`HTTPMethod`] from `stream`
-    pub(super) async fn parse<'a, 'b>(stream: &mut Stream<'a, 'b>) -> Result<Self, HTTPParseError> {
-        let method = stream.collect_until(b' ').await?;
+    pub(super) async fn parse(stream: &mut Stream<'a>) -> Result<Self, HTTPParseError> {
+        let method = stream
+            .collect_until_predicate_error(|c| match c {
+                b' ' => Ok(true),
+                c if is_token_char(c) => Ok(false),
+                _ => Err(HTTPParseError::InvalidMethod),
+            })
+            .await?;
+
+        if method.len() == 1 {
+            return Err(HTTPParseError::InvalidMethod);
+        }
+
+        Ok(HTTPMethod::from_slice(&method[..method.len() - 1]))
+    }
 
-        Ok(match &method[..method.len() - 1] {
+    /// Gets the [`HTTPMethod`] called `method`, which must be a non-empty token
+    pub(crate) fn from_slice(method: &'a [u8]) -> Self {
+        match method {
             b"GET" => HTTPMethod::GET,
             b"HEAD" => HTTPMethod::HEAD,
             b"POST" => HTTPMethod::POST,
             b"PUT" => HTTPMethod::PUT,
             b"DELETE" => HTTPMethod::DELETE,
-            _ => return Err(HTTPParseError::InvalidMethod),
-        })
+            b"CONNECT" => HTTPMethod::CONNECT,
+            b"OPTIONS" => HTTPMethod::OPTIONS,
+            b"TRACE" => HTTPMethod::TRACE,
+            b"PATCH" => HTTPMethod::PATCH,
+            method => HTTPMethod::Extension(method),
+        }
+    }
+
+    /// Gets the method as a slice of bytes
+    pub fn as_slice(&self) -> &'a [u8] {
+        match self {
+            HTTPMethod::GET => b"GET",
+            HTTPMethod::HEAD => b"HEAD",
+            HTTPMethod::POST => b"POST",
+            HTTPMethod::PUT => b"PUT",
+            HTTPMethod::DELETE => b"DELETE",
+            HTTPMethod::CONNECT => b"CONNECT",
+            HTTPMethod::OPTIONS => b"OPTIONS",
+            HTTPMethod::TRACE => b"TRACE",
+            HTTPMethod::PATCH => b"PATCH",
+            HTTPMethod::Extension(method) => method,
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for HTTPMethod<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Methods are always made up of token characters, which are ASCII
+        f.write_str(unsafe { std::str::from_utf8_unchecked(self.as_slice()) })
     }
 }
 
-impl std::fmt::Display for HTTPMethod {
+impl<'a> std::fmt::Debug for HTTPMethod<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
-            HTTPMethod::GET => "GET",
-            HTTPMethod::HEAD => "HEAD",
-            HTTPMethod::POST => "POST",
-            HTTPMethod::PUT => "PUT",
-            HTTPMethod::DELETE => "DELETE",
-        })
+        match self {
+            HTTPMethod::Extension(method) => f
+                .debug_tuple("Extension")
+                .field(&String::from_utf8_lossy(method))
+                .finish(),
+            _ => std::fmt::Display::fmt(self, f),
+        }
     }
 }
@@ -1,61 +1,88 @@
-use crate::{HTTPParseError, Stream};
+use crate::{connection::has_connection_option, HTTPParseError, Stream};
+
+// rustdoc imports
+#[allow(unused_imports)]
+use crate::HTTPResponse;
 
 mod field;
+mod fields;
 mod method;
 mod target;
+mod version;
 
 pub use field::HTTPRequestField;
+pub use fields::{HTTPMediaType, HTTPQualityValue, HTTPRequestFields};
 pub use method::HTTPMethod;
-pub use target::HTTPTarget;
+pub use target::{HTTPTarget, HTTPTargetForm};
+pub use version::HTTPVersion;
+
+/// Is `c` allowed in a token, such as a method or a field name?
+pub(crate) fn is_token_char(c: u8) -> bool {
+    match c {
+        c if c.is_ascii_alphanumeric() => true,
+        b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' | b'_'
+        | b'`' | b'|' | b'~' => true,
+        _ => false,
+    }
+}
 
 /// The header of an HTTP request
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HTTPRequestHeader<'a> {
     /// The request method
-    method: HTTPMethod,
+    method: HTTPMethod<'a>,
 
     /// The target for the request
     target: HTTPTarget<'a>,
 
+    /// The version of HTTP the request was sent with
+    version: HTTPVersion,
+
     /// The fields containing metadata about this request
-    fields: Vec<HTTPRequestField<'a>>,
+    fields: HTTPRequestFields<'a>,
 }
 
 impl<'a> HTTPRequestHeader<'a> {
     /// Attempts to parse an [`HTTPRequestHeader`] from `stream`
-    pub(super) async fn parse(stream: &mut Stream<'a, '_>) -> Result<Self, HTTPParseError> {
+    pub(super) async fn parse(stream: &mut Stream<'a>) -> Result<Self, HTTPParseError> {
         let method = HTTPMethod::parse(stream).await?;
 
         let target = HTTPTarget::parse(stream).await?;
 
-        let version = stream.collect_until_newline().await?;
-        if &version[..version.len() - 2] != b"HTTP/1.1" {
-            return Err(HTTPParseError::InvalidVersion);
-        }
+        let version = HTTPVersion::parse(stream.collect_line().await?)?;
 
-        let mut fields = Vec::new();
-        loop {
-            if stream.peek().await? == b'\r' {
-                let end = stream.collect_until_newline().await?;
-                if end != b"\r\n" {
-                    return Err(HTTPParseError::InvalidField);
-                }
+        let fields = HTTPRequestFields::new(HTTPRequestField::parse_list(stream).await?);
 
-                break;
-            }
+        HTTPRequestHeader::new(method, target, version, fields)
+    }
 
-            fields.push(HTTPRequestField::parse(stream).await?);
+    /// Creates an [`HTTPRequestHeader`], checking that `target` has a form allowed for `method`
+    pub(crate) fn new(
+        method: HTTPMethod<'a>,
+        target: HTTPTarget<'a>,
+        version: HTTPVersion,
+        fields: HTTPRequestFields<'a>,
+    ) -> Result<Self, HTTPParseError> {
+        // The authority form is only for "CONNECT" and the asterisk form only for "OPTIONS"
+        match (method, target.form()) {
+            (HTTPMethod::CONNECT, HTTPTargetForm::Authority(_))
+            | (HTTPMethod::OPTIONS, HTTPTargetForm::Asterisk) => {}
+            (HTTPMethod::CONNECT, _)
+            | (_, HTTPTargetForm::Authority(_))
+            | (_, HTTPTargetForm::Asterisk) => return Err(HTTPParseError::InvalidTarget),
+            _ => {}
         }
 
         Ok(HTTPRequestHeader {
             method,
             target,
+            version,
             fields,
         })
     }
 
     /// Gets the method of this request
-    pub fn method(&self) -> HTTPMethod {
+    pub fn method(&self) -> HTTPMethod<'a> {
         self.method
     }
 
@@ -64,19 +91,92 @@ impl<'a> HTTPRequestHeader<'a> {
         self.target
     }
 
+    /// Gets the version of HTTP this request was sent with
+    pub fn version(&self) -> HTTPVersion {
+        self.version
+    }
+
     /// Gets the fields of this request
-    pub fn fields(&self) -> &[HTTPRequestField<'a>] {
+    pub fn fields(&self) -> &HTTPRequestFields<'a> {
         &self.fields
     }
 
-    /// Gets a contained field based on its name
-    pub fn field(&self, name: &[u8]) -> Option<&HTTPRequestField<'a>> {
+    /// Does the client want the connection to stay open after this request is answered?
+    ///
+    /// This is false if any "Connection" field contains the "close" option. HTTP/1.0 connections
+    /// are only kept open if a "Connection" field contains the "keep-alive" option. HTTP/2 and HTTP/3
+    /// requests never have "Connection" fields, so this is always true for them.
+    pub fn keep_alive(&self) -> bool {
+        if self.has_connection_option(b"close") {
+            return false;
+        }
+
+        match self.version {
+            HTTPVersion::HTTP10 => self.has_connection_option(b"keep-alive"),
+            HTTPVersion::HTTP11 | HTTPVersion::HTTP2 | HTTPVersion::HTTP3 => true,
+        }
+    }
+
+    /// Is the body of this request sent with the "chunked" transfer coding?
+    ///
+    /// This is true if "chunked" is the final coding listed in the "Transfer-Encoding" fields.
+    pub fn is_chunked(&self) -> bool {
+        self.fields
+            .get_list(b"Transfer-Encoding")
+            .last()
+            .map(|coding| coding.eq_ignore_ascii_case(b"chunked"))
+            .unwrap_or(false)
+    }
+
+    /// Is the client asking to upgrade the connection to a WebSocket?
+    ///
+    /// This is true for HTTP/1.1 "GET" requests with an "Upgrade" field listing "websocket" and a
+    /// "Connection" field with the "upgrade" option. Whether the upgrade can be accepted is
+    /// decided by [`HTTPResponse::websocket`].
+    pub fn is_websocket_upgrade(&self) -> bool {
+        self.version == HTTPVersion::HTTP11
+            && self.method == HTTPMethod::GET
+            && self.has_connection_option(b"upgrade")
+            && self
+                .fields
+                .get_list(b"Upgrade")
+                .any(|protocol| protocol.eq_ignore_ascii_case(b"websocket"))
+    }
+
+    /// Is the client waiting for a "100 Continue" before sending the body?
+    ///
+    /// Returns [`HTTPParseError::UnknownExpectation`] if the "Expect" field contains anything other
+    /// than "100-continue". The expectation is ignored for HTTP/1.0 requests.
+    pub(crate) fn expects_continue(&self) -> Result<bool, HTTPParseError> {
+        let mut expects_continue = false;
         for field in &self.fields {
-            if field.name() == name {
-                return Some(field);
+            if !field.name().eq_ignore_ascii_case(b"Expect") {
+                continue;
+            }
+
+            if !field
+                .value()
+                .trim_ascii()
+                .eq_ignore_ascii_case(b"100-continue")
+            {
+                return Err(HTTPParseError::UnknownExpectation);
             }
+
+            expects_continue = true;
         }
 
-        None
+        Ok(expects_continue && self.version != HTTPVersion::HTTP10)
+    }
+
+    /// Gets the first field called `name`, ignoring case
+    pub fn field(&self, name: &[u8]) -> Option<&HTTPRequestField<'a>> {
+        self.fields.get(name)
+    }
+
+    /// Does any "Connection" field contain `option`?
+    fn has_connection_option(&self, option: &[u8]) -> bool {
+        self.fields
+            .get_all(b"Connection")
+            .any(|field| has_connection_option(field.value(), option))
     }
 }
@@ -0,0 +1,149 @@
+use crate::HTTPParseError;
+
+/// The form of the target of an HTTP request
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HTTPTargetForm<'a> {
+    /// An absolute path with an optional query (e.g. "/index.html?page=1")
+    ///
+    /// This is the form used for most requests.
+    Origin(&'a [u8]),
+
+    /// An absolute URI (e.g. "http://example.com/index.html")
+    ///
+    /// This is the form used for requests to proxies.
+    Absolute {
+        /// The scheme of the URI (e.g. "http")
+        scheme: &'a [u8],
+
+        /// The host and optional port of the URI (e.g. "example.com:8080")
+        authority: &'a [u8],
+
+        /// The path and query of the URI, which may be empty
+        path: &'a [u8],
+    },
+
+    /// A host and port (e.g. "example.com:443")
+    ///
+    /// This is the form used for "CONNECT" requests.
+    Authority(&'a [u8]),
+
+    /// A single "*"
+    ///
+    /// This is the form used for "OPTIONS" requests for the server as a whole.
+    Asterisk,
+}
+
+/// Is `c` allowed in the scheme of a URI after the first character?
+fn is_scheme_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'+' || c == b'-' || c == b'.'
+}
+
+/// Is `authority` a host followed by a port?
+fn is_host_and_port(authority: &[u8]) -> bool {
+    let colon = match authority.iter().rposition(|c| *c == b':') {
+        Some(colon) => colon,
+        None => return false,
+    };
+
+    let (host, port) = (&authority[..colon], &authority[colon + 1..]);
+    !host.is_empty() && !port.is_empty() && port.iter().all(u8::is_ascii_digit)
+}
+
+impl<'a> HTTPTargetForm<'a> {
+    /// Determines the form of `target`
+    pub(super) fn parse(target: &'a [u8]) -> Result<Self, HTTPParseError> {
+        if target == b"*" {
+            return Ok(HTTPTargetForm::Asterisk);
+        }
+
+        if target.first() == Some(&b'/') {
+            return Ok(HTTPTargetForm::Origin(target));
+        }
+
+        if let Some(form) = HTTPTargetForm::parse_absolute(target) {
+            return Ok(form);
+        }
+
+        if !target.contains(&b'/') && !target.contains(&b'?') && is_host_and_port(target) {
+            return Ok(HTTPTargetForm::Authority(target));
+        }
+
+        Err(HTTPParseError::InvalidTarget)
+    }
+
+    /// Attempts to split `target` into the parts of an absolute URI
+    fn parse_absolute(target: &'a [u8]) -> Option<Self> {
+        let scheme_length = target.iter().position(|c| *c == b':')?;
+        let scheme = &target[..scheme_length];
+        if scheme.is_empty()
+            || !scheme[0].is_ascii_alphabetic()
+            || !scheme[1..].iter().all(|c| is_scheme_char(*c))
+            || !target[scheme_length + 1..].starts_with(b"//")
+        {
+            return None;
+        }
+
+        let rest = &target[scheme_length + 3..];
+        let authority_length = rest
+            .iter()
+            .position(|c| *c == b'/' || *c == b'?')
+            .unwrap_or(rest.len());
+        if authority_length == 0 {
+            return None;
+        }
+
+        Some(HTTPTargetForm::Absolute {
+            scheme,
+            authority: &rest[..authority_length],
+            path: &rest[authority_length..],
+        })
+    }
+
+    /// Gets the path and query this target refers to
+    ///
+    /// Returns [`None`] for the authority and asterisk forms.
+    pub fn path(&self) -> Option<&'a [u8]> {
+        match self {
+            HTTPTargetForm::Origin(path) | HTTPTargetForm::Absolute { path, .. } => Some(path),
+            HTTPTargetForm::Authority(_) | HTTPTargetForm::Asterisk => None,
+        }
+    }
+
+    /// Gets the authority this target refers to
+    ///
+    /// Returns [`None`] for the origin and asterisk forms.
+    pub fn authority(&self) -> Option<&'a [u8]> {
+        match self {
+            HTTPTargetForm::Absolute { authority, .. } | HTTPTargetForm::Authority(authority) => {
+                Some(authority)
+            }
+            HTTPTargetForm::Origin(_) | HTTPTargetForm::Asterisk => None,
+        }
+    }
+}
+
+impl<'a> std::fmt::Debug for HTTPTargetForm<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HTTPTargetForm::Origin(path) => f
+                .debug_tuple("Origin")
+                .field(&String::from_utf8_lossy(path))
+                .finish(),
+            HTTPTargetForm::Absolute {
+                scheme,
+                authority,
+                path,
+            } => f
+                .debug_struct("Absolute")
+                .field("scheme", &String::from_utf8_lossy(scheme))
+                .field("authority", &String::from_utf8_lossy(authority))
+                .field("path", &String::from_utf8_lossy(path))
+                .finish(),
+            HTTPTargetForm::Authority(authority) => f
+                .debug_tuple("Authority")
+                .field(&String::from_utf8_lossy(authority))
+                .finish(),
+            HTTPTargetForm::Asterisk => f.write_str("Asterisk"),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{HTTPParseError, HTTPParseLimit, Stream};
+use std::{cell::Cell, ops::Deref};
+
+mod form;
+
+pub use form::HTTPTargetForm;
+
+/// Is `c` allowed in a request target?
+fn is_target_char(c: u8) -> bool {
+    match c {
+        c if c.is_ascii_alphanumeric() => true,
+        b'!' | b'$' | b'%' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b'-' | b'.'
+        | b'/' | b':' | b';' | b'=' | b'?' | b'@' | b'[' | b']' | b'_' | b'~' => true,
+        _ => false,
+    }
+}
+
+/// The requested target of an HTTP request
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HTTPTarget<'a> {
+    /// The target as it was sent
+    target: &'a [u8],
+
+    /// The form of the target
+    form: HTTPTargetForm<'a>,
+}
+
+impl<'a> HTTPTarget<'a> {
+    /// Attempts to parse an [`HTTPTarget`] from `stream`
+    pub(super) async fn parse(stream: &mut Stream<'a>) -> Result<Self, HTTPParseError> {
+        let max_length = stream.limits().max_target_length;
+        let length = Cell::new(0);
+
+        let uri = stream
+            .collect_until_predicate_error(|c| match c {
+                b' ' => Ok(true),
+                _ if length.replace(length.get() + 1) == max_length => {
+                    Err(HTTPParseError::LimitExceeded(HTTPParseLimit::TargetLength))
+                }
+                c if is_target_char(c) => Ok(false),
+                _ => Err(HTTPParseError::InvalidTarget),
+            })
+            .await?;
+
+        let target = &uri[..uri.len() - 1];
+        let form = HTTPTargetForm::parse(target)?;
+
+        Ok(HTTPTarget { target, form })
+    }
+
+    /// Creates an [`HTTPTarget`] from a target which wasn't sent in a request line
+    pub(crate) fn new(target: &'a [u8], max_length: usize) -> Result<Self, HTTPParseError> {
+        if target.len() > max_length {
+            return Err(HTTPParseError::LimitExceeded(HTTPParseLimit::TargetLength));
+        }
+
+        if !target.iter().all(|c| is_target_char(*c)) {
+            return Err(HTTPParseError::InvalidTarget);
+        }
+
+        Ok(HTTPTarget {
+            target,
+            form: HTTPTargetForm::parse(target)?,
+        })
+    }
+
+    /// Gets the target as a slice of bytes
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.target
+    }
+
+    /// Gets the form of this target
+    pub fn form(&self) -> HTTPTargetForm<'a> {
+        self.form
+    }
+
+    /// Gets the path and query this target refers to
+    ///
+    /// Returns [`None`] for the authority and asterisk forms.
+    pub fn path(&self) -> Option<&'a [u8]> {
+        self.form.path()
+    }
+
+    /// Gets the authority this target refers to
+    ///
+    /// Returns [`None`] for the origin and asterisk forms.
+    pub fn authority(&self) -> Option<&'a [u8]> {
+        self.form.authority()
+    }
+}
+
+impl<'a> Deref for HTTPTarget<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.target
+    }
+}
+
+impl<'a> std::fmt::Display for HTTPTarget<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", unsafe {
+            std::str::from_utf8_unchecked(self.target)
+        })
+    }
+}
+
+impl<'a> std::fmt::Debug for HTTPTarget<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::HTTPParseError;
+
+/// The version of HTTP a request was sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HTTPVersion {
+    /// HTTP/1.0
+    HTTP10,
+
+    /// HTTP/1.1
+    HTTP11,
+
+    /// HTTP/2
+    HTTP2,
+
+    /// HTTP/3
+    HTTP3,
+}
+
+impl HTTPVersion {
+    /// Parses an [`HTTPVersion`] from the version in a request line
+    ///
+    /// Later minor versions of HTTP/1 are treated as HTTP/1.1. Well-formed versions with a major
+    /// version other than 1 are rejected with [`HTTPParseError::UnsupportedVersion`].
+    pub(super) fn parse(version: &[u8]) -> Result<Self, HTTPParseError> {
+        let (major, minor) = match version {
+            [b'H', b'T', b'T', b'P', b'/', major, b'.', minor]
+                if major.is_ascii_digit() && minor.is_ascii_digit() =>
+            {
+                (*major, *minor)
+            }
+            _ => return Err(HTTPParseError::InvalidVersion),
+        };
+
+        match (major, minor) {
+            (b'1', b'0') => Ok(HTTPVersion::HTTP10),
+            (b'1', _) => Ok(HTTPVersion::HTTP11),
+            _ => Err(HTTPParseError::UnsupportedVersion),
+        }
+    }
+
+    /// Gets the version as it appears in a request or status line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HTTPVersion::HTTP10 => "HTTP/1.0",
+            HTTPVersion::HTTP11 => "HTTP/1.1",
+            HTTPVersion::HTTP2 => "HTTP/2",
+            HTTPVersion::HTTP3 => "HTTP/3",
+        }
+    }
+}
+
+impl std::fmt::Display for HTTPVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
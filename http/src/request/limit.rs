@@ -0,0 +1,60 @@
+use crate::{HTTPOptions, HTTPStatus};
+
+/// A limit on the size of a request which can be exceeded while parsing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HTTPParseLimit {
+    /// The request target is longer than [`HTTPOptions::max_target_length`]
+    TargetLength,
+
+    /// The request has more fields than [`HTTPOptions::max_header_fields`]
+    FieldCount,
+
+    /// A field is larger than [`HTTPOptions::max_field_size`]
+    FieldSize,
+}
+
+/// The limits applied while parsing a request header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HeaderLimits {
+    /// The maximum length of the request target
+    pub(crate) max_target_length: usize,
+
+    /// The maximum number of fields in the header or the trailers
+    pub(crate) max_fields: usize,
+
+    /// The maximum size of a single field, including its name
+    pub(crate) max_field_size: usize,
+}
+
+impl HTTPParseLimit {
+    /// Gets the status a request exceeding this limit should be answered with
+    pub fn status(&self) -> HTTPStatus {
+        match self {
+            HTTPParseLimit::TargetLength => HTTPStatus::URITooLong,
+            HTTPParseLimit::FieldCount | HTTPParseLimit::FieldSize => {
+                HTTPStatus::RequestHeaderFieldsTooLarge
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for HTTPParseLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HTTPParseLimit::TargetLength => "target length",
+            HTTPParseLimit::FieldCount => "field count",
+            HTTPParseLimit::FieldSize => "field size",
+        })
+    }
+}
+
+impl HeaderLimits {
+    /// Gets the limits set in `options`
+    pub(crate) fn new(options: &HTTPOptions) -> Self {
+        HeaderLimits {
+            max_target_length: options.max_target_length,
+            max_fields: options.max_header_fields,
+            max_field_size: options.max_field_size,
+        }
+    }
+}
@@ -1,47 +1,61 @@
-use crate::Stream;
-use lasync::time::Timeout;
+use crate::{coding, Stream};
+use huntsman::runtime::time::timeout;
 use std::{ops::Deref, time::Duration};
 
+// rustdoc imports
+#[allow(unused_imports)]
+use crate::HTTPOptions;
+
+mod body;
 mod display;
 mod error;
+mod framing;
 mod header;
+mod limit;
 
+pub use body::HTTPRequestBody;
 pub use display::HTTPRequestDisplay;
 pub use error::HTTPParseError;
-pub use header::{HTTPMethod, HTTPRequestField, HTTPRequestHeader, HTTPTarget};
+pub use header::{
+    HTTPMediaType, HTTPMethod, HTTPQualityValue, HTTPRequestField, HTTPRequestFields,
+    HTTPRequestHeader, HTTPTarget, HTTPTargetForm, HTTPVersion,
+};
+pub use limit::HTTPParseLimit;
+
+pub(crate) use framing::{skip_body, BodyFraming};
+pub(crate) use header::is_token_char;
+pub(crate) use limit::HeaderLimits;
 
 /// An HTTP request received from a client
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug)]
 pub struct HTTPRequest<'a> {
     /// The header for the http request
     header: HTTPRequestHeader<'a>,
 
-    /// The body of the request
+    /// The body of the request, if it was read before the request was handled
     body: Option<Box<[u8]>>,
-}
 
-/// Parse the `content_length` into a [`usize`]
-fn parse_content_length(content_length: &[u8]) -> Result<usize, HTTPParseError> {
-    let mut value = 0;
+    /// The body of the request, if it is read while the request is handled
+    body_stream: Option<HTTPRequestBody<'a>>,
 
-    for byte in content_length {
-        if !byte.is_ascii_digit() {
-            return Err(HTTPParseError::InvalidContentLength);
-        }
+    /// The maximum size for a body read with [`HTTPRequest::read_body`]
+    max_body_size: usize,
 
-        value *= 10;
-        value += (*byte - b'0') as usize;
-    }
-
-    Ok(value)
+    /// The trailer fields sent after a chunked body
+    trailers: HTTPRequestFields<'a>,
 }
 
 impl<'a> HTTPRequest<'a> {
     /// Attempts to parse an [`HTTPRequest`] from `stream`
+    ///
+    /// If `stream_body` is true or the client is waiting for a "100 Continue", the body is left to
+    /// be read by the handler instead of being read here.
     pub(crate) async fn parse(
-        mut stream: Stream<'a, '_>,
+        mut stream: Stream<'a>,
         max_body_size: usize,
         body_read_timeout: Duration,
+        write_timeout: Duration,
+        stream_body: bool,
     ) -> Result<Option<Self>, HTTPParseError> {
         let header = match HTTPRequestHeader::parse(&mut stream).await {
             Ok(header) => header,
@@ -54,37 +68,142 @@ impl<'a> HTTPRequest<'a> {
             }
         };
 
-        let body = if let Some(content_length) = header.field(b"Content-Length") {
-            let content_length = parse_content_length(content_length.value())?;
+        let expects_continue = header.expects_continue()?;
 
-            if content_length > max_body_size {
-                return Err(HTTPParseError::BodyTooLarge);
-            }
+        let framing = BodyFraming::parse(&header)?;
+        let content_length = match framing {
+            BodyFraming::Length(content_length) => Some(content_length),
+            _ => None,
+        };
 
-            let (stream, mut buffer, current_length) = stream.body(content_length);
+        let mut trailers = HTTPRequestFields::default();
+        let mut body_stream = None;
+        let body = if framing == BodyFraming::Length(0) {
+            None
+        } else if stream_body || expects_continue {
+            stream.set_body_framing(framing);
+            stream.set_continue_pending(expects_continue);
+
+            body_stream = Some(HTTPRequestBody::new(
+                stream,
+                content_length,
+                body_read_timeout,
+                write_timeout,
+            ));
+            None
+        } else if framing == BodyFraming::Chunked(0) {
+            stream.set_body_framing(framing);
 
-            if current_length < buffer.len() {
-                Timeout::new(
-                    stream.read_exact(&mut buffer[current_length..]),
+            Some(
+                timeout(
+                    framing::read_chunked(&mut stream, max_body_size, &mut trailers),
                     body_read_timeout,
-                )?
-                .await
-                .map(|result| result.map_err(Into::into))
-                .unwrap_or(Err(HTTPParseError::BodyReadTimeout))?;
+                )
+                .await?
+                .unwrap_or(Err(HTTPParseError::BodyReadTimeout))?,
+            )
+        } else {
+            let content_length = content_length.unwrap();
+            if content_length > max_body_size {
+                return Err(HTTPParseError::BodyTooLarge);
             }
 
-            Some(buffer)
-        } else {
-            None
+            Some(
+                timeout(stream.read_exact_body(content_length), body_read_timeout)
+                    .await?
+                    .unwrap_or(Err(HTTPParseError::BodyReadTimeout))?,
+            )
         };
 
-        Ok(Some(HTTPRequest { header, body }))
+        let body = match body {
+            Some(body) => Some(coding::decode(header.fields(), body, max_body_size)?),
+            None => None,
+        };
+
+        Ok(Some(HTTPRequest {
+            header,
+            body,
+            body_stream,
+            max_body_size,
+            trailers,
+        }))
+    }
+
+    /// Creates an [`HTTPRequest`] whose body was read before it was parsed
+    pub(crate) fn new(
+        header: HTTPRequestHeader<'a>,
+        body: Option<Box<[u8]>>,
+        max_body_size: usize,
+        trailers: HTTPRequestFields<'a>,
+    ) -> Self {
+        HTTPRequest {
+            header,
+            body,
+            body_stream: None,
+            max_body_size,
+            trailers,
+        }
     }
 
     /// Gets the body of this request
+    ///
+    /// Any "gzip" or "deflate" content codings are removed from the body before it is returned.
+    ///
+    /// This is [`None`] until [`HTTPRequest::read_body`] is called if the body wasn't read before
+    /// the request was handled. That is always the case when bodies are streamed or the client is
+    /// waiting for a "100 Continue".
     pub fn body(&self) -> Option<&[u8]> {
         self.body.as_ref().map(|slice| slice.as_ref())
     }
+
+    /// Gets the body of this request, reading all of it if it wasn't read before the request was
+    /// handled
+    ///
+    /// If the client is waiting for a "100 Continue", this accepts the body. To reject the body
+    /// instead, respond without calling this. The body is limited by [`HTTPOptions::max_body_size`].
+    pub async fn read_body(&mut self) -> Result<Option<&[u8]>, HTTPParseError> {
+        if let Some(body_stream) = &mut self.body_stream {
+            if self.body.is_none() {
+                let body = body_stream
+                    .read_to_end(self.max_body_size)
+                    .await?
+                    .into_boxed_slice();
+
+                self.body = Some(coding::decode(
+                    self.header.fields(),
+                    body,
+                    self.max_body_size,
+                )?);
+            }
+        }
+
+        Ok(self.body())
+    }
+
+    /// Gets the body of this request for reading while the request is handled
+    ///
+    /// This is available when [`HTTPOptions::stream_request_bodies`] is set or the client is
+    /// waiting for a "100 Continue" before sending the body. HTTP/2 request bodies are always read
+    /// before the request is handled, so this is [`None`] for them. Reading it accepts the body. The body is
+    /// read as it was sent, without removing any content codings.
+    pub fn body_stream(&mut self) -> Option<&mut HTTPRequestBody<'a>> {
+        self.body_stream.as_mut()
+    }
+
+    /// Gets the trailer fields sent after a chunked body
+    ///
+    /// When the body is streamed, these are only available once it has been completely read.
+    pub fn trailers(&self) -> &HTTPRequestFields<'a> {
+        match &self.body_stream {
+            Some(body_stream) => body_stream.trailers(),
+            None => &self.trailers,
+        }
+    }
+
+    /// Gets a trailer field based on its name
+    pub fn trailer(&self, name: &[u8]) -> Option<&HTTPRequestField<'a>> {
+        self.trailers().get(name)
+    }
 }
 
 impl<'a> Deref for HTTPRequest<'a> {
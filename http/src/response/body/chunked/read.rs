@@ -1,5 +1,5 @@
-use crate::HTTPChunkedResponseBody;
-use lasync::io::Read;
+use crate::{Error, HTTPChunkedResponseBody};
+use huntsman::runtime::io::Read;
 use std::num::NonZeroUsize;
 
 /// Transfers data from a type which implements [`Read`] in maximum sized chunks.
@@ -25,7 +25,7 @@ impl<R: Read> ReadHTTPChunkedResponseBody<R> {
 }
 
 impl<R: Read> HTTPChunkedResponseBody for ReadHTTPChunkedResponseBody<R> {
-    async fn next(&mut self) -> Result<Option<&[u8]>, lasync::Error> {
+    async fn next(&mut self) -> Result<Option<&[u8]>, Error> {
         let count = self.reader.read(&mut self.buffer).await?;
         if count == 0 {
             Ok(None)
@@ -40,6 +40,11 @@ impl<'a, B: HTTPChunkedResponseBody> HTTPResponseBody<'a, B> {
         &self.content
     }
 
+    /// Gets the contained body for modification
+    pub(super) fn content_mut(&mut self) -> &mut HTTPResponseBodyContent<'a, B> {
+        &mut self.content
+    }
+
     /// Gets the type of the body
     pub(super) fn content_type(&self) -> &[u8] {
         &self.content_type
@@ -1,10 +1,20 @@
+use crate::{
+    connection::has_connection_option,
+    websocket::{self, DynHTTPWebSocketHandler},
+    HTTPCompressionOptions, HTTPContentEncoder, HTTPContentEncoderStream, HTTPRequestHeader,
+    HTTPVersion, HTTPWebSocketHandler,
+};
 use body::HTTPResponseBody;
 use name::SERVER;
+use std::borrow::Cow;
 
 mod body;
 mod name;
 mod status;
 
+#[cfg(test)]
+mod tests;
+
 pub use body::{
     EmptyHTTPChunkedResponseBody, HTTPChunkedResponseBody, HTTPResponseBodyContent,
     ReadHTTPChunkedResponseBody,
@@ -13,7 +23,7 @@ pub use status::HTTPStatus;
 
 /// An HTTP response to be sent to the client
 pub struct HTTPResponse<'a, B: HTTPChunkedResponseBody = EmptyHTTPChunkedResponseBody> {
-    /// The header of the response
+    /// The fields of the response header
     header: Vec<u8>,
 
     /// The body of the response
@@ -21,6 +31,12 @@ pub struct HTTPResponse<'a, B: HTTPChunkedResponseBody = EmptyHTTPChunkedRespons
 
     /// The status this response was created with
     status: HTTPStatus,
+
+    /// Will the connection be closed after this response is sent?
+    close: bool,
+
+    /// The handler the connection is given to once this response accepts a WebSocket upgrade
+    websocket: Option<Box<dyn DynHTTPWebSocketHandler + 'a>>,
 }
 
 impl<'a, B: HTTPChunkedResponseBody> HTTPResponse<'a, B> {
@@ -37,16 +53,59 @@ impl<'a, B: HTTPChunkedResponseBody> HTTPResponse<'a, B> {
 
     /// Creates a new [`HTTPResponse`] without a body
     pub fn new_status(status: HTTPStatus) -> Self {
-        let mut header = status.generate();
-        header.extend_from_slice(SERVER.as_bytes());
-
         HTTPResponse {
-            header,
+            header: SERVER.as_bytes().to_vec(),
             body: None,
             status,
+            close: false,
+            websocket: None,
         }
     }
 
+    /// Creates a new [`HTTPResponse`] accepting the upgrade to a WebSocket asked for by `request`
+    ///
+    /// Once this "101 Switching Protocols" response is sent, the connection is given to `handler`
+    /// and closed when it returns. Requests which aren't valid upgrades get a "400 Bad Request"
+    /// instead, or a "426 Upgrade Required" if they ask for a version of the protocol other than
+    /// 13. Any "Sec-WebSocket-Protocol" field has to be added by the caller.
+    ///
+    /// Only HTTP/1.1 connections can be upgraded. If this response is sent in answer to any other
+    /// request, a "400 Bad Request" is sent instead.
+    pub fn websocket<H: HTTPWebSocketHandler + 'a>(
+        request: &HTTPRequestHeader,
+        handler: H,
+    ) -> Self {
+        if !request.is_websocket_upgrade() {
+            return HTTPStatus::BadRequest.into();
+        }
+
+        if request
+            .field(b"Sec-WebSocket-Version")
+            .map(|version| version.value().trim_ascii() != b"13")
+            .unwrap_or(true)
+        {
+            let mut response = Self::new_status(HTTPStatus::UpgradeRequired);
+            response.push_field(b"Upgrade", b"websocket");
+            response.push_field(b"Sec-WebSocket-Version", b"13");
+            return response;
+        }
+
+        let accept = match request
+            .field(b"Sec-WebSocket-Key")
+            .and_then(|key| websocket::accept_key(key.value()))
+        {
+            Some(accept) => accept,
+            None => return HTTPStatus::BadRequest.into(),
+        };
+
+        let mut response = Self::new_status(HTTPStatus::SwitchingProtocols);
+        response.push_field(b"Upgrade", b"websocket");
+        response.push_field(b"Connection", b"Upgrade");
+        response.push_field(b"Sec-WebSocket-Accept", &accept);
+        response.websocket = Some(Box::new(handler));
+        response
+    }
+
     /// Gets the type of this reponse's body's content
     pub fn content_type(&self) -> Option<&[u8]> {
         self.body.as_ref().map(HTTPResponseBody::content_type)
@@ -57,12 +116,109 @@ impl<'a, B: HTTPChunkedResponseBody> HTTPResponse<'a, B> {
         self.status
     }
 
+    /// Does this response have a chunked body?
+    pub(crate) fn is_chunked(&self) -> bool {
+        matches!(
+            self.body.as_ref().map(HTTPResponseBody::content),
+            Some(HTTPResponseBodyContent::Chunked(_))
+        )
+    }
+
+    /// Does this response have a field called `name`?
+    fn has_field(&self, name: &[u8]) -> bool {
+        self.header.split(|c| *c == b'\n').any(|line| {
+            line.len() > name.len()
+                && line[name.len()] == b':'
+                && line[..name.len()].eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// Prepares this response to be sent encoded with `encoder` if `compression` applies to it
+    ///
+    /// Slice bodies are encoded immediately, while chunked bodies need to be encoded with the
+    /// returned stream as they are sent. Responses which could have been compressed get a
+    /// "Vary: Accept-Encoding" field even if `encoder` is [`None`].
+    pub(crate) fn encode(
+        &mut self,
+        compression: &HTTPCompressionOptions,
+        encoder: Option<&dyn HTTPContentEncoder>,
+    ) -> Option<Box<dyn HTTPContentEncoderStream>> {
+        let body = self.body.as_ref()?;
+        if !compression.is_compressible(body.content_type()) || self.has_field(b"Content-Encoding")
+        {
+            return None;
+        }
+
+        if let HTTPResponseBodyContent::Slice(slice) = body.content() {
+            if slice.len() < compression.min_size {
+                return None;
+            }
+        }
+
+        self.push_field(b"Vary", b"Accept-Encoding");
+
+        let encoder = encoder?;
+        self.push_field(b"Content-Encoding", encoder.coding());
+
+        let mut stream = encoder.start();
+        match self.body.as_mut().unwrap().content_mut() {
+            HTTPResponseBodyContent::Slice(slice) => {
+                let mut output = Vec::new();
+                stream.write(slice, &mut output);
+                stream.finish(&mut output);
+                *slice = Cow::Owned(output);
+                None
+            }
+            HTTPResponseBodyContent::Chunked(_) => Some(stream),
+        }
+    }
+
+    /// Takes the handler the connection is given to once this response is sent, if this response
+    /// accepts a WebSocket upgrade
+    ///
+    /// `upgrade` is whether the request this response answers is an HTTP/1.1 request asking for a
+    /// WebSocket. If it isn't, the connection can't be upgraded, so a response accepting the
+    /// upgrade is replaced with a "400 Bad Request".
+    pub(crate) fn take_websocket(
+        &mut self,
+        upgrade: bool,
+    ) -> Option<Box<dyn DynHTTPWebSocketHandler + 'a>> {
+        let handler = self.websocket.take()?;
+        if upgrade {
+            return Some(handler);
+        }
+
+        *self = HTTPStatus::BadRequest.into();
+        None
+    }
+
+    /// Will the connection be closed after this response is sent?
+    pub fn closes_connection(&self) -> bool {
+        self.close
+    }
+
+    /// Closes the connection after this response is sent
+    ///
+    /// This adds a "Connection: close" field to the response if it doesn't already have one
+    pub fn close_connection(&mut self) {
+        if !self.close {
+            self.push_field(b"Connection", b"close");
+        }
+    }
+
     /// Adds a field to the end of the fields for this response
+    ///
+    /// Adding a "Connection" field with the "close" option closes the connection after this
+    /// response is sent
     pub fn push_field(&mut self, name: &[u8], content: &[u8]) {
         assert_ne!(name, b"Content-Length", "\"Content-Length\" fields cannot be inserted into a response, this is managed by huntsman-http");
         assert_ne!(name, b"Content-Type", "\"Content-Type\" fields cannot be inserted into a response, this is managed by huntsman-http");
         assert_ne!(name, b"Server", "\"Server\" fields cannot be inserted into a response, this is managed by huntsman-http");
 
+        if name.eq_ignore_ascii_case(b"Connection") && has_connection_option(content, b"close") {
+            self.close = true;
+        }
+
         self.header.extend_from_slice(name);
         self.header.extend_from_slice(b": ");
         self.header.extend_from_slice(content);
@@ -78,32 +234,49 @@ impl<'a, B: HTTPChunkedResponseBody> HTTPResponse<'a, B> {
         self.body = Some(HTTPResponseBody::new(body.into(), content_type));
     }
 
-    /// Generates the response header to write and returns the tuple `(header, body)`
-    pub(super) fn generate_header(mut self) -> (Vec<u8>, Option<HTTPResponseBodyContent<'a, B>>) {
+    /// Generates the response header to write for a client using `version` and returns the tuple
+    /// `(header, body)`
+    ///
+    /// HTTP/1.0 clients don't support chunked bodies, so they are sent without any framing and the
+    /// end of the body is marked by closing the connection. "101 Switching Protocols" responses
+    /// have no body, so they don't get a "Content-Length" field.
+    pub(super) fn generate_header(
+        self,
+        version: HTTPVersion,
+    ) -> (Vec<u8>, Option<HTTPResponseBodyContent<'a, B>>) {
+        let mut header = self.status.generate(version);
+        header.extend_from_slice(&self.header);
+
         let body = match self.body {
             Some(body) => body,
+            None if self.status == HTTPStatus::SwitchingProtocols => {
+                header.extend_from_slice(b"\r\n");
+                return (header, None);
+            }
             None => {
-                self.header.extend_from_slice(b"Content-Length: 0\r\n\r\n");
-                return (self.header, None);
+                header.extend_from_slice(b"Content-Length: 0\r\n\r\n");
+                return (header, None);
             }
         };
 
         match body.content() {
             HTTPResponseBodyContent::Chunked(_) => {
-                self.header.extend_from_slice(b"Transfer-Encoding: chunked")
+                if version != HTTPVersion::HTTP10 {
+                    header.extend_from_slice(b"Transfer-Encoding: chunked\r\n");
+                }
             }
             HTTPResponseBodyContent::Slice(slice) => {
-                self.header.extend_from_slice(b"Content-Length: ");
-                self.header
-                    .extend_from_slice(slice.len().to_string().as_bytes());
+                header.extend_from_slice(b"Content-Length: ");
+                header.extend_from_slice(slice.len().to_string().as_bytes());
+                header.extend_from_slice(b"\r\n");
             }
         }
 
-        self.header.extend_from_slice(b"\r\nContent-Type: ");
-        self.header.extend_from_slice(body.content_type());
-        self.header.extend_from_slice(b"\r\n\r\n");
+        header.extend_from_slice(b"Content-Type: ");
+        header.extend_from_slice(body.content_type());
+        header.extend_from_slice(b"\r\n\r\n");
 
-        (self.header, Some(body.unwrap()))
+        (header, Some(body.unwrap()))
     }
 }
 
@@ -1,3 +1,5 @@
+use crate::HTTPVersion;
+
 /// The status code of an HTTP response
 #[repr(usize)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -116,6 +118,9 @@ pub enum HTTPStatus {
     /// Upgrade Required
     UpgradeRequired = 426,
 
+    /// Request Header Fields Too Large
+    RequestHeaderFieldsTooLarge = 431,
+
     /// Internal Server Error
     InternalServerError = 500,
 
@@ -182,6 +187,7 @@ impl HTTPStatus {
             HTTPStatus::MisdirectedRequest => "Misdirected Request",
             HTTPStatus::UnprocessableContent => "Unprocessable Content",
             HTTPStatus::UpgradeRequired => "Upgrade Required",
+            HTTPStatus::RequestHeaderFieldsTooLarge => "Request Header Fields Too Large",
             HTTPStatus::InternalServerError => "Internal Server Error",
             HTTPStatus::NotImplemented => "Not Implemented",
             HTTPStatus::BadGateway => "Bad Gateway",
@@ -192,8 +198,8 @@ impl HTTPStatus {
     }
 
     /// Generates the start of a header
-    pub(super) fn generate(self) -> Vec<u8> {
-        format!("HTTP/1.1 {} {}\r\n", self.code(), self.message()).into_bytes()
+    pub(crate) fn generate(self, version: HTTPVersion) -> Vec<u8> {
+        format!("{} {} {}\r\n", version, self.code(), self.message()).into_bytes()
     }
 }
 
@@ -0,0 +1,47 @@
+use super::*;
+use crate::HTTPWebSocket;
+
+/// A WebSocket handler which closes the connection straight away
+struct Close;
+
+impl HTTPWebSocketHandler for Close {
+    async fn run(self, _: &mut HTTPWebSocket<'_>) {}
+}
+
+/// Creates a response accepting a WebSocket upgrade without checking a request
+fn accept_upgrade() -> HTTPResponse<'static> {
+    let mut response = HTTPResponse::new_status(HTTPStatus::SwitchingProtocols);
+    response.push_field(b"Upgrade", b"websocket");
+    response.push_field(b"Connection", b"Upgrade");
+    response.websocket = Some(Box::new(Close));
+    response
+}
+
+#[test]
+fn upgrade_requests_get_the_websocket() {
+    let mut response = accept_upgrade();
+
+    assert!(response.take_websocket(true).is_some());
+    assert_eq!(response.status(), HTTPStatus::SwitchingProtocols);
+}
+
+#[test]
+fn other_requests_get_a_bad_request() {
+    let mut response = accept_upgrade();
+
+    assert!(response.take_websocket(false).is_none());
+    assert_eq!(response.status(), HTTPStatus::BadRequest);
+
+    let (header, _) = response.generate_header(HTTPVersion::HTTP11);
+    let header = String::from_utf8(header).unwrap();
+    assert!(header.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    assert!(!header.contains("Upgrade"));
+}
+
+#[test]
+fn responses_without_websockets_are_unchanged() {
+    let mut response: HTTPResponse = HTTPStatus::OK.into();
+
+    assert!(response.take_websocket(false).is_none());
+    assert_eq!(response.status(), HTTPStatus::OK);
+}
@@ -0,0 +1,124 @@
+use super::{connect, is_closed, request, run};
+use crate::{HTTPOptions, HTTPParseError};
+use huntsman::runtime::simulation;
+
+/// Sends `head` followed by a request smuggled after it, checking the server answers the first
+/// with a "400 Bad Request" and closes the connection without handling either
+///
+/// The smuggled request is inside the body of `head` if it is framed as intended.
+fn assert_rejected(seed: u64, head: &str, error: HTTPParseError) {
+    let head = head.to_owned();
+    let record = run(seed, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            let requests = format!("{}GET /smuggled HTTP/1.1\r\nHost: a\r\n\r\n", head);
+            let response = request(&mut socket, &requests, false).await.unwrap();
+            assert_eq!(response.status, 400);
+            assert_eq!(response.field("Connection"), Some("close"));
+            assert!(is_closed(&mut socket).await);
+
+            shutdown.trigger();
+        });
+    });
+
+    assert!(record.requests.lock().unwrap().is_empty());
+    assert_eq!(*record.errors.lock().unwrap(), [error]);
+}
+
+#[test]
+fn duplicate_content_lengths_are_rejected() {
+    assert_rejected(
+        1,
+        "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\nContent-Length: 5\r\n\r\nhello",
+        HTTPParseError::DuplicateContentLength,
+    );
+    assert_rejected(
+        2,
+        "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5, 5\r\n\r\nhello",
+        HTTPParseError::DuplicateContentLength,
+    );
+}
+
+#[test]
+fn conflicting_content_lengths_are_rejected() {
+    assert_rejected(
+        3,
+        "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 0\r\nContent-Length: 42\r\n\r\n",
+        HTTPParseError::ConflictingContentLength,
+    );
+}
+
+#[test]
+fn transfer_encoding_with_content_length_is_rejected() {
+    assert_rejected(
+        4,
+        "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+        HTTPParseError::TransferEncodingWithContentLength,
+    );
+}
+
+#[test]
+fn whitespace_before_colons_is_rejected() {
+    assert_rejected(
+        5,
+        "POST / HTTP/1.1\r\nHost: a\r\nContent-Length : 5\r\n\r\nhello",
+        HTTPParseError::WhitespaceBeforeColon,
+    );
+}
+
+#[test]
+fn obsolete_line_folding_is_rejected() {
+    assert_rejected(
+        6,
+        "POST / HTTP/1.1\r\nHost: a\r\nX-Folded: a\r\n b\r\nContent-Length: 5\r\n\r\nhello",
+        HTTPParseError::ObsoleteLineFolding,
+    );
+}
+
+#[test]
+fn bare_line_endings_are_rejected() {
+    assert_rejected(
+        7,
+        "POST / HTTP/1.1\r\nHost: a\nContent-Length: 5\r\n\r\nhello",
+        HTTPParseError::BareLineFeed,
+    );
+    assert_rejected(
+        8,
+        "POST / HTTP/1.1\r\nHost: a\rContent-Length: 5\r\n\r\nhello",
+        HTTPParseError::BareCarriageReturn,
+    );
+}
+
+#[test]
+fn invalid_transfer_encodings_are_rejected() {
+    assert_rejected(
+        9,
+        "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked, chunked\r\n\r\n0\r\n\r\n",
+        HTTPParseError::InvalidTransferEncoding,
+    );
+    assert_rejected(
+        10,
+        "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nTransfer-Encoding: identity\r\n\r\n0\r\n\r\n",
+        HTTPParseError::InvalidTransferEncoding,
+    );
+    assert_rejected(
+        11,
+        "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: identity\r\n\r\n0\r\n\r\n",
+        HTTPParseError::InvalidTransferEncoding,
+    );
+}
+
+#[test]
+fn control_characters_in_field_values_are_rejected() {
+    assert_rejected(
+        12,
+        "POST / HTTP/1.1\r\nHost: a\r\nX-Value: a\x7Fb\r\nContent-Length: 5\r\n\r\nhello",
+        HTTPParseError::InvalidField,
+    );
+    assert_rejected(
+        13,
+        "POST / HTTP/1.1\r\nHost: a\r\nX-Value: a\x00b\r\nContent-Length: 5\r\n\r\nhello",
+        HTTPParseError::InvalidField,
+    );
+}
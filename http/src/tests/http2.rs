@@ -0,0 +1,590 @@
+use super::{connect, run};
+use crate::HTTPOptions;
+use huntsman::runtime::{
+    io::{Read, Write},
+    net::TCPStream,
+    simulation,
+};
+use std::time::Duration;
+
+/// The type of "DATA" frames
+const DATA: u8 = 0x0;
+
+/// The type of "HEADERS" frames
+const HEADERS: u8 = 0x1;
+
+/// The type of "RST_STREAM" frames
+const RST_STREAM: u8 = 0x3;
+
+/// The type of "SETTINGS" frames
+const SETTINGS: u8 = 0x4;
+
+/// The type of "PING" frames
+const PING: u8 = 0x6;
+
+/// The type of "GOAWAY" frames
+const GOAWAY: u8 = 0x7;
+
+/// The type of "WINDOW_UPDATE" frames
+const WINDOW_UPDATE: u8 = 0x8;
+
+/// The type of "CONTINUATION" frames
+const CONTINUATION: u8 = 0x9;
+
+/// The flag marking the last frame of a stream, or an acknowledgement
+const END_STREAM: u8 = 0x1;
+
+/// The flag marking the end of a header block
+const END_HEADERS: u8 = 0x4;
+
+/// The error code for broken protocol rules
+const PROTOCOL_ERROR: u32 = 0x1;
+
+/// The error code for broken flow control
+const FLOW_CONTROL_ERROR: u32 = 0x3;
+
+/// The error code for frames with the wrong size
+const FRAME_SIZE_ERROR: u32 = 0x6;
+
+/// The error code for streams refused before being processed
+const REFUSED_STREAM: u32 = 0x7;
+
+/// A frame read from the server
+#[derive(Debug)]
+struct Frame {
+    /// The type of the frame
+    kind: u8,
+
+    /// The flags set on the frame
+    flags: u8,
+
+    /// The stream the frame is on
+    stream: u32,
+
+    /// The payload of the frame
+    payload: Vec<u8>,
+}
+
+impl Frame {
+    /// Gets the error code of a "RST_STREAM" or "GOAWAY" frame
+    fn error_code(&self) -> u32 {
+        let start = if self.kind == GOAWAY { 4 } else { 0 };
+        u32::from_be_bytes(self.payload[start..start + 4].try_into().unwrap())
+    }
+
+    /// Gets the status of the response a "HEADERS" frame starts
+    ///
+    /// The server sends ":status" first, either from the static table or as a literal with its
+    /// name from the static table.
+    fn status(&self) -> u16 {
+        match self.payload[0] {
+            0x88 => 200,
+            0x8C => 400,
+            0x08 => std::str::from_utf8(&self.payload[2..5])
+                .unwrap()
+                .parse()
+                .unwrap(),
+            byte => panic!("unexpected start of a header block: {:#x}", byte),
+        }
+    }
+}
+
+/// Connects to the server with the connection preface and empty settings
+async fn start() -> TCPStream {
+    let mut socket = connect().await;
+    socket
+        .write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")
+        .await
+        .unwrap();
+    send(&mut socket, SETTINGS, 0, 0, &[]).await;
+    socket
+}
+
+/// Sends a frame on `socket`
+async fn send(socket: &mut TCPStream, kind: u8, flags: u8, stream: u32, payload: &[u8]) {
+    socket
+        .write_all(&frame(kind, flags, stream, payload))
+        .await
+        .unwrap();
+}
+
+/// Encodes a frame
+fn frame(kind: u8, flags: u8, stream: u32, payload: &[u8]) -> Vec<u8> {
+    let mut frame = (payload.len() as u32).to_be_bytes()[1..].to_vec();
+    frame.push(kind);
+    frame.push(flags);
+    frame.extend_from_slice(&stream.to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Encodes a header block for `fields` with literals which don't touch the dynamic table
+fn header_block(fields: &[(&str, &str)]) -> Vec<u8> {
+    let mut block = Vec::new();
+    for (name, value) in fields {
+        block.push(0);
+        for string in [name, value] {
+            block.push(string.len() as u8);
+            block.extend_from_slice(string.as_bytes());
+        }
+    }
+    block
+}
+
+/// Encodes a header block for a "GET" request for `path`
+fn get(path: &str) -> Vec<u8> {
+    header_block(&[
+        (":method", "GET"),
+        (":scheme", "http"),
+        (":authority", "a"),
+        (":path", path),
+    ])
+}
+
+/// Reads the next frame from `socket`
+///
+/// Returns [`None`] if the server closed the connection.
+async fn read_frame(socket: &mut TCPStream) -> Option<Frame> {
+    let mut header = [0; 9];
+    socket.read_exact(&mut header).await.ok()?;
+
+    let length = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+    let mut payload = vec![0; length];
+    socket.read_exact(&mut payload).await.unwrap();
+
+    Some(Frame {
+        kind: header[3],
+        flags: header[4],
+        stream: u32::from_be_bytes(header[5..].try_into().unwrap()) & 0x7FFF_FFFF,
+        payload,
+    })
+}
+
+/// Reads frames from `socket` until one of type `kind`, which is returned
+async fn read_until(socket: &mut TCPStream, kind: u8) -> Frame {
+    loop {
+        let frame = read_frame(socket)
+            .await
+            .expect("the server closed the connection");
+        if frame.kind == kind {
+            return frame;
+        }
+    }
+}
+
+/// Reads the responses to `count` requests from `socket`, returning the streams they were sent on
+/// in the order they finished and the body of each
+async fn read_responses(socket: &mut TCPStream, count: usize) -> Vec<(u32, Vec<u8>)> {
+    let mut bodies: Vec<(u32, Vec<u8>)> = Vec::new();
+    let mut finished = Vec::new();
+    while finished.len() < count {
+        let frame = read_frame(socket)
+            .await
+            .expect("the server closed the connection");
+
+        match frame.kind {
+            HEADERS => {
+                assert_eq!(frame.status(), 200);
+                bodies.push((frame.stream, Vec::new()));
+            }
+            DATA => {
+                let (_, body) = bodies
+                    .iter_mut()
+                    .find(|(stream, _)| *stream == frame.stream)
+                    .unwrap();
+                body.extend_from_slice(&frame.payload);
+            }
+            _ => continue,
+        }
+
+        if frame.flags & END_STREAM != 0 {
+            let index = bodies
+                .iter()
+                .position(|(stream, _)| *stream == frame.stream)
+                .unwrap();
+            finished.push(bodies.remove(index));
+        }
+    }
+    finished
+}
+
+/// Checks that the server closes the connection on `socket` with a "GOAWAY" carrying `code`
+async fn assert_goes_away(socket: &mut TCPStream, code: u32) {
+    let frame = read_until(socket, GOAWAY).await;
+    assert_eq!(frame.error_code(), code);
+    assert!(read_frame(socket).await.is_none());
+}
+
+#[test]
+fn requests_are_answered_concurrently() {
+    let record = run(1, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = start().await;
+            send(
+                &mut socket,
+                HEADERS,
+                END_STREAM | END_HEADERS,
+                1,
+                &get("/slow"),
+            )
+            .await;
+            send(
+                &mut socket,
+                HEADERS,
+                END_STREAM | END_HEADERS,
+                3,
+                &get("/fast"),
+            )
+            .await;
+
+            // The fast request isn't held up by the slow one sent before it
+            let start = simulation::now();
+            let responses = read_responses(&mut socket, 2).await;
+            assert_eq!(
+                responses,
+                [(3, b"GET /fast".to_vec()), (1, b"GET /slow".to_vec())]
+            );
+            assert!(simulation::now() - start < Duration::from_millis(1100));
+
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(record.requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn streams_past_the_limit_are_refused() {
+    let record = run(2, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = start().await;
+
+            // The server announces its limit in its first frame
+            let settings = read_frame(&mut socket).await.unwrap();
+            assert_eq!(settings.kind, SETTINGS);
+            assert!(settings
+                .payload
+                .chunks(6)
+                .any(|setting| setting == [0, 3, 0, 0, 0, 100]));
+
+            // Every request arrives before the server has answered any of them
+            let mut requests = Vec::new();
+            for stream in (1..=203).step_by(2) {
+                let flags = END_STREAM | END_HEADERS;
+                requests.extend(frame(HEADERS, flags, stream, &get("/slow")));
+            }
+            socket.write_all(&requests).await.unwrap();
+
+            let reset = read_until(&mut socket, RST_STREAM).await;
+            assert_eq!(reset.stream, 201);
+            assert_eq!(reset.error_code(), REFUSED_STREAM);
+
+            // Every request within the limit is handled at the same time
+            let start = simulation::now();
+            let responses = read_responses(&mut socket, 100).await;
+            assert!(responses.iter().all(|(stream, _)| *stream < 201));
+            assert!(simulation::now() - start < Duration::from_millis(1100));
+
+            // A stream can be opened again once the others are answered
+            send(
+                &mut socket,
+                HEADERS,
+                END_STREAM | END_HEADERS,
+                205,
+                &get("/a"),
+            )
+            .await;
+            assert_eq!(
+                read_responses(&mut socket, 1).await,
+                [(205, b"GET /a".to_vec())]
+            );
+
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(record.requests.lock().unwrap().len(), 101);
+}
+
+#[test]
+fn settings_are_acknowledged() {
+    run(3, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = start().await;
+
+            let settings = read_frame(&mut socket).await.unwrap();
+            assert_eq!((settings.kind, settings.flags), (SETTINGS, 0));
+
+            let ack = read_until(&mut socket, SETTINGS).await;
+            assert_eq!(ack.flags, END_STREAM);
+            assert!(ack.payload.is_empty());
+
+            // Unknown settings are ignored
+            send(&mut socket, SETTINGS, 0, 0, &[0, 0x99, 0, 0, 0, 1]).await;
+            assert_eq!(read_until(&mut socket, SETTINGS).await.flags, END_STREAM);
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn invalid_settings_close_the_connection() {
+    let cases: [(u8, u32, &[u8], u32); 5] = [
+        // An acknowledgement with settings in it
+        (END_STREAM, 0, &[0, 4, 0, 0, 0, 1], FRAME_SIZE_ERROR),
+        // Settings on a stream
+        (0, 1, &[], PROTOCOL_ERROR),
+        // A partial setting
+        (0, 0, &[0, 4, 0, 0], FRAME_SIZE_ERROR),
+        // A window larger than flow control allows
+        (0, 0, &[0, 4, 0x80, 0, 0, 0], FLOW_CONTROL_ERROR),
+        // A maximum frame size smaller than the default
+        (0, 0, &[0, 5, 0, 0, 0x3F, 0xFF], PROTOCOL_ERROR),
+    ];
+
+    run(4, HTTPOptions::default(), move |shutdown| {
+        simulation::spawn(async move {
+            for (flags, stream, payload, code) in cases {
+                let mut socket = start().await;
+                send(&mut socket, SETTINGS, flags, stream, payload).await;
+                assert_goes_away(&mut socket, code).await;
+            }
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn responses_wait_for_flow_control() {
+    run(5, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = start().await;
+
+            // Only 4 bytes of each response can be sent until the client makes room
+            send(&mut socket, SETTINGS, 0, 0, &[0, 4, 0, 0, 0, 4]).await;
+            send(
+                &mut socket,
+                HEADERS,
+                END_STREAM | END_HEADERS,
+                1,
+                &get("/window"),
+            )
+            .await;
+
+            assert_eq!(read_until(&mut socket, HEADERS).await.status(), 200);
+            let data = read_until(&mut socket, DATA).await;
+            assert_eq!((data.payload.as_slice(), data.flags), (b"GET " as &[u8], 0));
+
+            send(&mut socket, WINDOW_UPDATE, 0, 1, &100u32.to_be_bytes()).await;
+            let data = read_until(&mut socket, DATA).await;
+            assert_eq!(
+                (data.payload.as_slice(), data.flags),
+                (b"/window" as &[u8], END_STREAM)
+            );
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn window_overflows_are_errors() {
+    run(6, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            // Growing the window of a stream past the largest allowed only resets the stream
+            let mut socket = start().await;
+            send(&mut socket, HEADERS, END_HEADERS, 1, &get("/a")).await;
+            send(
+                &mut socket,
+                WINDOW_UPDATE,
+                0,
+                1,
+                &0x7FFF_FFFFu32.to_be_bytes(),
+            )
+            .await;
+
+            let reset = read_until(&mut socket, RST_STREAM).await;
+            assert_eq!((reset.stream, reset.error_code()), (1, FLOW_CONTROL_ERROR));
+
+            send(
+                &mut socket,
+                HEADERS,
+                END_STREAM | END_HEADERS,
+                3,
+                &get("/b"),
+            )
+            .await;
+            assert_eq!(
+                read_responses(&mut socket, 1).await,
+                [(3, b"GET /b".to_vec())]
+            );
+
+            // Doing the same to the connection's window closes it
+            send(
+                &mut socket,
+                WINDOW_UPDATE,
+                0,
+                0,
+                &0x7FFF_FFFFu32.to_be_bytes(),
+            )
+            .await;
+            assert_goes_away(&mut socket, FLOW_CONTROL_ERROR).await;
+
+            // As does a window update which makes no room
+            let mut socket = start().await;
+            send(&mut socket, WINDOW_UPDATE, 0, 0, &[0; 4]).await;
+            assert_goes_away(&mut socket, PROTOCOL_ERROR).await;
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn header_blocks_continue_across_frames() {
+    let record = run(7, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = start().await;
+
+            let block = get("/continued");
+            let (first, rest) = block.split_at(10);
+            let (second, third) = rest.split_at(10);
+            send(&mut socket, HEADERS, END_STREAM, 1, first).await;
+            send(&mut socket, CONTINUATION, 0, 1, second).await;
+            send(&mut socket, CONTINUATION, END_HEADERS, 1, third).await;
+
+            assert_eq!(
+                read_responses(&mut socket, 1).await,
+                [(1, b"GET /continued".to_vec())]
+            );
+
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(*record.requests.lock().unwrap(), ["GET /continued"]);
+}
+
+#[test]
+fn frames_between_continuations_are_errors() {
+    let record = run(8, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let block = get("/a");
+            let (first, rest) = block.split_at(10);
+
+            // A frame of another type
+            let mut socket = start().await;
+            send(&mut socket, HEADERS, END_STREAM, 1, first).await;
+            send(&mut socket, PING, 0, 0, &[0; 8]).await;
+            assert_goes_away(&mut socket, PROTOCOL_ERROR).await;
+
+            // A continuation of another stream
+            let mut socket = start().await;
+            send(&mut socket, HEADERS, END_STREAM, 1, first).await;
+            send(&mut socket, CONTINUATION, END_HEADERS, 3, rest).await;
+            assert_goes_away(&mut socket, PROTOCOL_ERROR).await;
+
+            // A continuation without a header block to continue
+            let mut socket = start().await;
+            send(&mut socket, CONTINUATION, END_HEADERS, 1, rest).await;
+            assert_goes_away(&mut socket, PROTOCOL_ERROR).await;
+
+            shutdown.trigger();
+        });
+    });
+
+    assert!(record.requests.lock().unwrap().is_empty());
+}
+
+#[test]
+fn frames_are_checked() {
+    run(9, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = start().await;
+
+            // Pings are echoed back, and frames of unknown types are ignored
+            send(&mut socket, 0x20, 0, 0, b"unknown").await;
+            send(&mut socket, PING, 0, 0, b"12345678").await;
+            let ping = read_until(&mut socket, PING).await;
+            assert_eq!(
+                (ping.flags, ping.payload.as_slice()),
+                (END_STREAM, b"12345678" as &[u8])
+            );
+
+            // A ping of the wrong size
+            send(&mut socket, PING, 0, 0, b"1234").await;
+            assert_goes_away(&mut socket, FRAME_SIZE_ERROR).await;
+
+            // A frame larger than the server allows
+            let mut socket = start().await;
+            send(&mut socket, 0x20, 0, 0, &[0; 16385]).await;
+            assert_goes_away(&mut socket, FRAME_SIZE_ERROR).await;
+
+            // A request on a stream the client can't open
+            let mut socket = start().await;
+            send(
+                &mut socket,
+                HEADERS,
+                END_STREAM | END_HEADERS,
+                2,
+                &get("/a"),
+            )
+            .await;
+            assert_goes_away(&mut socket, PROTOCOL_ERROR).await;
+
+            // Data on a stream which was never opened
+            let mut socket = start().await;
+            send(&mut socket, DATA, END_STREAM, 1, b"body").await;
+            assert_goes_away(&mut socket, PROTOCOL_ERROR).await;
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn read_errors_are_answered_after_earlier_requests() {
+    let record = run(10, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = start().await;
+
+            // The second request's header is over the 8 Kb limit, and arrives with the first
+            let value = "a".repeat(120);
+            let mut fields = vec![
+                (":method".to_owned(), "GET".to_owned()),
+                (":scheme".to_owned(), "http".to_owned()),
+                (":authority".to_owned(), "a".to_owned()),
+                (":path".to_owned(), "/long".to_owned()),
+            ];
+            fields.extend((0..90).map(|index| (format!("x-{index}"), value.clone())));
+            let fields: Vec<(&str, &str)> = fields
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .collect();
+
+            let flags = END_STREAM | END_HEADERS;
+            let mut requests = frame(HEADERS, flags, 1, &get("/slow"));
+            requests.extend(frame(HEADERS, flags, 3, &header_block(&fields)));
+            socket.write_all(&requests).await.unwrap();
+
+            // Both are answered on their own streams, then the connection is closed
+            let mut statuses = Vec::new();
+            while statuses.len() < 2 {
+                let frame = read_frame(&mut socket)
+                    .await
+                    .expect("the server closed the connection");
+                if frame.kind == HEADERS {
+                    statuses.push((frame.stream, frame.status()));
+                }
+            }
+            assert_eq!(statuses, [(3, 413), (1, 200)]);
+
+            while read_frame(&mut socket).await.is_some() {}
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(record.requests.lock().unwrap().as_slice(), ["GET /slow"]);
+}
@@ -0,0 +1,33 @@
+use super::{connect, request, run};
+use crate::HTTPOptions;
+use huntsman::runtime::simulation;
+
+#[test]
+fn head_responses_have_no_body() {
+    let record = run(1, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            // The GET is only answered correctly if nothing followed the HEAD response's header
+            let head = request(&mut socket, "HEAD /page HTTP/1.1\r\nHost: a\r\n\r\n", true)
+                .await
+                .unwrap();
+            assert_eq!(head.status, 200);
+            assert_eq!(head.field("Content-Length"), Some("10"));
+
+            let get = request(&mut socket, "GET /page HTTP/1.1\r\nHost: a\r\n\r\n", false)
+                .await
+                .unwrap();
+            assert_eq!(get.status, 200);
+            assert_eq!(get.body, b"GET /page");
+
+            drop(socket);
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(
+        *record.requests.lock().unwrap(),
+        ["HEAD /page", "GET /page"]
+    );
+}
@@ -0,0 +1,228 @@
+//! Tests which run a server in the simulation and talk to it over raw connections
+
+use crate::{
+    HTTPClientAddress, HTTPListenAddress, HTTPOptions, HTTPParseError, HTTPResponse, HTTPStatus,
+    HTTP,
+};
+use huntsman::{
+    runtime::{
+        io::{Read, Write},
+        net::{self, TCPStream},
+        simulation,
+        time::sleep,
+    },
+    App, Options, Protocol, Shutdown,
+};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+mod framing;
+mod http2;
+mod methods;
+mod persistence;
+
+#[cfg(feature = "tls")]
+mod tls;
+
+/// The address the test server listens on
+const ADDRESS: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8000);
+
+/// An app which answers each request with its method and target, recording what it is sent
+///
+/// Requests for "/close" are answered with "Connection: close", while requests for "/slow" are
+/// answered after a second.
+struct Recorder(Arc<Record>);
+
+/// What has been sent to a [`Recorder`]
+#[derive(Default)]
+struct Record {
+    /// The method and target of each request handled
+    requests: Mutex<Vec<String>>,
+
+    /// The errors from reading requests
+    errors: Mutex<Vec<HTTPParseError>>,
+
+    /// The parameters negotiated with each client which connected over TLS
+    #[cfg(feature = "tls")]
+    tls: Mutex<Vec<crate::HTTPTLSInfo>>,
+}
+
+/// A response read from the server
+struct Response {
+    /// The status code of the response
+    status: u16,
+
+    /// The fields of the response
+    fields: Vec<(String, String)>,
+
+    /// The body of the response
+    body: Vec<u8>,
+}
+
+impl App for Recorder {
+    type Protocol = HTTP;
+    type Client = ();
+
+    async fn handle_request<'a, 'b>(
+        self: &'a Arc<Self>,
+        _: &'a (),
+        request: &'a mut <HTTP as Protocol>::Request<'b>,
+    ) -> HTTPResponse<'a> {
+        let target = String::from_utf8_lossy(request.target().as_slice()).into_owned();
+        let line = format!("{} {}", request.method(), target);
+        self.0.requests.lock().unwrap().push(line.clone());
+
+        if target == "/slow" {
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        let mut response = HTTPResponse::new(HTTPStatus::OK, line.into_bytes(), b"text/plain");
+        if target == "/close" {
+            response.close_connection();
+        }
+        response
+    }
+
+    #[cfg_attr(not(feature = "tls"), allow(unused_variables))]
+    async fn on_client_connect(self: &Arc<Self>, address: HTTPClientAddress) -> Option<()> {
+        #[cfg(feature = "tls")]
+        if let Some(tls) = address.tls() {
+            self.0.tls.lock().unwrap().push(tls.clone());
+        }
+
+        Some(())
+    }
+
+    async fn read_error<'a>(
+        self: &'a Arc<Self>,
+        _: &'a (),
+        error: HTTPParseError,
+    ) -> Option<HTTPResponse<'a>> {
+        self.0.errors.lock().unwrap().push(error.clone());
+
+        Some(match error {
+            HTTPParseError::HeaderReadTimeout
+            | HTTPParseError::BodyReadTimeout
+            | HTTPParseError::IncompleteHeader
+            | HTTPParseError::IncompleteBody
+            | HTTPParseError::IO(_) => return None,
+            HTTPParseError::HeadersTooLong => HTTPStatus::ContentTooLarge.into(),
+            HTTPParseError::LimitExceeded(limit) => limit.status().into(),
+            HTTPParseError::UnsupportedVersion => HTTPStatus::HTTPVersionNotSupported.into(),
+            HTTPParseError::UnknownExpectation => HTTPStatus::ExpectationFailed.into(),
+            HTTPParseError::UnsupportedTransferEncoding => HTTPStatus::NotImplemented.into(),
+            HTTPParseError::UnsupportedContentEncoding => HTTPStatus::UnsupportedMediaType.into(),
+            _ => HTTPStatus::BadRequest.into(),
+        })
+    }
+}
+
+impl Response {
+    /// Gets the value of the field called `name`
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Runs a [`Recorder`] server with `options` in a new simulation seeded with `seed` until its
+/// shutdown is triggered
+///
+/// `clients` is called with the shutdown to spawn the clients into the simulation before the
+/// server starts
+fn run<F: FnOnce(Shutdown)>(seed: u64, options: HTTPOptions, clients: F) -> Arc<Record> {
+    serve(
+        seed,
+        HTTPListenAddress::http(ADDRESS).unwrap(),
+        options,
+        clients,
+    )
+}
+
+/// Runs a [`Recorder`] server listening on `address` with `options` in a new simulation seeded
+/// with `seed` until its shutdown is triggered
+///
+/// `clients` is called with the shutdown to spawn the clients into the simulation before the
+/// server starts
+fn serve<F: FnOnce(Shutdown)>(
+    seed: u64,
+    address: HTTPListenAddress,
+    options: HTTPOptions,
+    clients: F,
+) -> Arc<Record> {
+    simulation::start(seed);
+
+    let mut huntsman_options = Options::default();
+    huntsman_options.add_address(address);
+    clients(huntsman_options.shutdown().clone());
+
+    let record = Arc::new(Record::default());
+    huntsman::run(Recorder(record.clone()), huntsman_options, options).unwrap();
+    record
+}
+
+/// Connects to the server once it has had time to start listening
+async fn connect() -> TCPStream {
+    sleep(Duration::from_millis(10)).await;
+    net::connect(ADDRESS).await.unwrap()
+}
+
+/// Sends `request` on `socket` and reads the response to it
+///
+/// The body of the response isn't read if `head` is true. Returns [`None`] if the server closed
+/// the connection instead of responding.
+async fn request(socket: &mut TCPStream, request: &str, head: bool) -> Option<Response> {
+    socket.write_all(request.as_bytes()).await.ok()?;
+    read_response(socket, head).await
+}
+
+/// Reads a response from `socket`, not reading its body if `head` is true
+///
+/// Returns [`None`] if the server closed the connection before sending anything.
+async fn read_response(socket: &mut TCPStream, head: bool) -> Option<Response> {
+    let mut header = Vec::new();
+    while !header.ends_with(b"\r\n\r\n") {
+        let mut byte = [0];
+        if socket.read(&mut byte).await.ok()? == 0 {
+            assert!(header.is_empty(), "the server closed during a response");
+            return None;
+        }
+        header.push(byte[0]);
+    }
+
+    let header = String::from_utf8(header).unwrap();
+    let mut lines = header.split("\r\n").filter(|line| !line.is_empty());
+    let status = lines.next().unwrap().split(' ').nth(1).unwrap();
+    let fields = lines
+        .map(|line| {
+            let (name, value) = line.split_once(':').unwrap();
+            (name.to_owned(), value.trim().to_owned())
+        })
+        .collect();
+
+    let mut response = Response {
+        status: status.parse().unwrap(),
+        fields,
+        body: Vec::new(),
+    };
+
+    if !head {
+        if let Some(length) = response.field("Content-Length") {
+            response.body = vec![0; length.parse().unwrap()];
+            socket.read_exact(&mut response.body).await.unwrap();
+        }
+    }
+
+    Some(response)
+}
+
+/// Has the server closed `socket` without sending anything else?
+async fn is_closed(socket: &mut TCPStream) -> bool {
+    let mut byte = [0];
+    matches!(socket.read(&mut byte).await, Ok(0) | Err(_))
+}
@@ -0,0 +1,162 @@
+use super::{connect, is_closed, request, run};
+use crate::{HTTPOptions, HTTPParseError};
+use huntsman::runtime::simulation;
+use std::{num::NonZeroUsize, time::Duration};
+
+/// A request for `target` which keeps the connection open
+fn get(target: &str) -> String {
+    format!("GET {} HTTP/1.1\r\nHost: a\r\n\r\n", target)
+}
+
+#[test]
+fn connections_are_kept_open_between_requests() {
+    let record = run(1, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            for target in ["/a", "/b", "/c"] {
+                let response = request(&mut socket, &get(target), false).await.unwrap();
+                assert_eq!(response.status, 200);
+                assert_eq!(response.field("Connection"), None);
+            }
+
+            drop(socket);
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(record.requests.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn clients_can_close_the_connection() {
+    let record = run(2, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            // The request pipelined after the closing request is never answered
+            let requests = "GET /a HTTP/1.1\r\nHost: a\r\nConnection: close\r\n\r\nGET /b HTTP/1.1\r\nHost: a\r\n\r\n";
+            let response = request(&mut socket, requests, false).await.unwrap();
+            assert_eq!(response.field("Connection"), Some("close"));
+            assert!(is_closed(&mut socket).await);
+
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(*record.requests.lock().unwrap(), ["GET /a"]);
+}
+
+#[test]
+fn apps_can_close_the_connection() {
+    let record = run(3, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            let requests = format!("{}{}", get("/close"), get("/b"));
+            let response = request(&mut socket, &requests, false).await.unwrap();
+            assert_eq!(response.field("Connection"), Some("close"));
+            assert!(is_closed(&mut socket).await);
+
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(*record.requests.lock().unwrap(), ["GET /close"]);
+}
+
+#[test]
+fn errors_close_the_connection() {
+    let record = run(4, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            let requests = format!("GET /a HTTP/1.1\r\nHo@st: a\r\n\r\n{}", get("/b"));
+            let response = request(&mut socket, &requests, false).await.unwrap();
+            assert_eq!(response.status, 400);
+            assert_eq!(response.field("Connection"), Some("close"));
+            assert!(is_closed(&mut socket).await);
+
+            shutdown.trigger();
+        });
+    });
+
+    assert!(record.requests.lock().unwrap().is_empty());
+    assert_eq!(
+        *record.errors.lock().unwrap(),
+        [HTTPParseError::InvalidField]
+    );
+}
+
+#[test]
+fn idle_connections_are_closed() {
+    let options = HTTPOptions {
+        keep_alive_timeout: Duration::from_secs(5),
+        ..Default::default()
+    };
+
+    run(5, options, |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            let response = request(&mut socket, &get("/a"), false).await.unwrap();
+            assert_eq!(response.status, 200);
+
+            let answered = simulation::now();
+            assert!(is_closed(&mut socket).await);
+
+            let idle = simulation::now() - answered;
+            assert!(idle >= Duration::from_secs(5));
+            assert!(idle < Duration::from_millis(5100));
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn connections_close_after_the_maximum_requests() {
+    let options = HTTPOptions {
+        max_requests_per_connection: NonZeroUsize::new(2),
+        ..Default::default()
+    };
+
+    let record = run(6, options, |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            let response = request(&mut socket, &get("/a"), false).await.unwrap();
+            assert_eq!(response.field("Connection"), None);
+
+            let requests = format!("{}{}", get("/b"), get("/c"));
+            let response = request(&mut socket, &requests, false).await.unwrap();
+            assert_eq!(response.field("Connection"), Some("close"));
+            assert!(is_closed(&mut socket).await);
+
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(*record.requests.lock().unwrap(), ["GET /a", "GET /b"]);
+}
+
+#[test]
+fn http10_connections_close_unless_kept_alive() {
+    run(7, HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            let keep_alive = "GET /a HTTP/1.0\r\nConnection: keep-alive\r\n\r\n";
+            let response = request(&mut socket, keep_alive, false).await.unwrap();
+            assert_eq!(response.field("Connection"), Some("keep-alive"));
+
+            let response = request(&mut socket, "GET /b HTTP/1.0\r\n\r\n", false)
+                .await
+                .unwrap();
+            assert_eq!(response.status, 200);
+            assert!(is_closed(&mut socket).await);
+
+            shutdown.trigger();
+        });
+    });
+}
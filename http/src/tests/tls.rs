@@ -0,0 +1,208 @@
+use super::{connect, serve, ADDRESS};
+use crate::{HTTPListenAddress, HTTPOptions, HTTPTLSConfig, HTTPTLSVersion};
+use huntsman::runtime::{
+    io::{Read, Write},
+    net::TCPStream,
+    simulation,
+};
+use rustls::{
+    crypto::ring::default_provider,
+    pki_types::{pem::PemObject, CertificateDer, ServerName},
+    ClientConfig, ClientConnection, RootCertStore,
+};
+use std::sync::Arc;
+
+/// The certificate authority which signed [`CERTIFICATE`]
+const CA: &[u8] = include_bytes!("certificates/ca.pem");
+
+/// The certificate the server presents, for "localhost"
+const CERTIFICATE: &[u8] = include_bytes!("certificates/cert.pem");
+
+/// The private key of [`CERTIFICATE`]
+const KEY: &[u8] = include_bytes!("certificates/key.pem");
+
+/// The client's end of a connection secured with TLS
+struct Client {
+    /// The underlying stream
+    socket: TCPStream,
+
+    /// The state of the TLS connection
+    connection: ClientConnection,
+}
+
+impl Client {
+    /// Connects to the server and completes the handshake, offering `alpn` through ALPN
+    async fn connect(alpn: &[&[u8]]) -> Self {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_slice(CA).unwrap())
+            .unwrap();
+
+        let mut config = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        config.alpn_protocols = alpn.iter().map(|protocol| protocol.to_vec()).collect();
+
+        let name = ServerName::try_from("localhost").unwrap();
+        let mut client = Client {
+            socket: connect().await,
+            connection: ClientConnection::new(Arc::new(config), name).unwrap(),
+        };
+
+        while client.connection.is_handshaking() {
+            client.flush().await;
+            if client.connection.is_handshaking() {
+                assert!(
+                    client.fill().await,
+                    "the server closed during the handshake"
+                );
+            }
+        }
+
+        client
+    }
+
+    /// Encrypts and sends all of `bytes`
+    async fn write(&mut self, bytes: &[u8]) {
+        std::io::Write::write_all(&mut self.connection.writer(), bytes).unwrap();
+        self.flush().await;
+    }
+
+    /// Reads and decrypts everything the server sends until it has sent at least `length` bytes
+    /// or closes the connection
+    async fn read(&mut self, length: usize) -> Vec<u8> {
+        let mut output = Vec::new();
+        loop {
+            let mut buffer = [0; 1024];
+            match std::io::Read::read(&mut self.connection.reader(), &mut buffer) {
+                Ok(0) => return output,
+                Ok(count) => output.extend_from_slice(&buffer[..count]),
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {
+                    if output.len() >= length || !self.fill().await {
+                        return output;
+                    }
+                }
+                Err(error) => panic!("{}", error),
+            }
+        }
+    }
+
+    /// Reads encrypted data from the server and processes it
+    ///
+    /// Returns false if the server closed the connection.
+    async fn fill(&mut self) -> bool {
+        let mut buffer = [0; 16 * 1024];
+        let count = self.socket.read(&mut buffer).await.unwrap_or(0);
+
+        let mut input = &buffer[..count];
+        while !input.is_empty() {
+            self.connection.read_tls(&mut input).unwrap();
+            self.connection.process_new_packets().unwrap();
+        }
+
+        count > 0
+    }
+
+    /// Sends any encrypted data waiting to be sent to the server
+    async fn flush(&mut self) {
+        while self.connection.wants_write() {
+            let mut output = Vec::new();
+            self.connection.write_tls(&mut output).unwrap();
+            self.socket.write_all(&output).await.unwrap();
+        }
+    }
+}
+
+/// The address of a server presenting [`CERTIFICATE`]
+fn https() -> HTTPListenAddress {
+    HTTPListenAddress::https(ADDRESS, HTTPTLSConfig::from_pem(CERTIFICATE, KEY).unwrap()).unwrap()
+}
+
+/// Sends a request which closes the connection over `client`, returning the whole response
+async fn get_and_close(client: &mut Client) -> String {
+    client
+        .write(b"GET /close HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await;
+    String::from_utf8(client.read(usize::MAX).await).unwrap()
+}
+
+#[test]
+fn http1_is_negotiated_through_alpn() {
+    let record = serve(1, https(), HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut client = Client::connect(&[b"http/1.1"]).await;
+            assert_eq!(
+                client.connection.alpn_protocol(),
+                Some(b"http/1.1" as &[u8])
+            );
+
+            let response = get_and_close(&mut client).await;
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+            assert!(response.ends_with("\r\n\r\nGET /close"));
+
+            shutdown.trigger();
+        });
+    });
+
+    let tls = record.tls.lock().unwrap();
+    assert_eq!(tls.len(), 1);
+    assert_eq!(tls[0].version(), HTTPTLSVersion::TLS13);
+    assert!(tls[0].peer_certificate().is_none());
+}
+
+#[test]
+fn http2_is_negotiated_through_alpn() {
+    serve(2, https(), HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut client = Client::connect(&[b"h2", b"http/1.1"]).await;
+            assert_eq!(client.connection.alpn_protocol(), Some(b"h2" as &[u8]));
+
+            // The server answers the connection preface with its SETTINGS frame
+            client.write(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n").await;
+            client.write(&[0, 0, 0, 4, 0, 0, 0, 0, 0]).await;
+            let frame = client.read(9).await;
+            assert!(frame.len() >= 9);
+            assert_eq!(frame[3], 4);
+            assert_eq!(frame[4] & 1, 0);
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn http2_is_only_offered_when_enabled() {
+    let options = HTTPOptions {
+        http2: false,
+        ..HTTPOptions::default()
+    };
+
+    serve(3, https(), options, |shutdown| {
+        simulation::spawn(async move {
+            let client = Client::connect(&[b"h2", b"http/1.1"]).await;
+            assert_eq!(
+                client.connection.alpn_protocol(),
+                Some(b"http/1.1" as &[u8])
+            );
+
+            shutdown.trigger();
+        });
+    });
+}
+
+#[test]
+fn clients_without_alpn_get_http1() {
+    serve(4, https(), HTTPOptions::default(), |shutdown| {
+        simulation::spawn(async move {
+            let mut client = Client::connect(&[]).await;
+            assert_eq!(client.connection.alpn_protocol(), None);
+
+            let response = get_and_close(&mut client).await;
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+
+            shutdown.trigger();
+        });
+    });
+}
@@ -0,0 +1,76 @@
+use crate::HTTPTLSError;
+use rustls::{
+    crypto::ring::default_provider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    sign::CertifiedKey,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A certificate chain and private key, along with the files they were loaded from
+#[derive(Debug, Clone)]
+pub(super) struct Certificate {
+    /// The certificate chain and private key presented to clients
+    key: Arc<CertifiedKey>,
+
+    /// The files containing the certificate chain and private key, if they were loaded from files
+    files: Option<(PathBuf, PathBuf)>,
+}
+
+impl Certificate {
+    /// Loads the PEM encoded certificate chain and private key from the files at
+    /// `certificate_chain` and `private_key`
+    pub(super) fn from_pem_files(
+        certificate_chain: &Path,
+        private_key: &Path,
+    ) -> Result<Self, HTTPTLSError> {
+        let mut certificate = Certificate::from_pem(
+            &std::fs::read(certificate_chain)?,
+            &std::fs::read(private_key)?,
+        )?;
+        certificate.files = Some((certificate_chain.to_owned(), private_key.to_owned()));
+        Ok(certificate)
+    }
+
+    /// Parses a PEM encoded certificate chain and private key
+    pub(super) fn from_pem(
+        certificate_chain: &[u8],
+        private_key: &[u8],
+    ) -> Result<Self, HTTPTLSError> {
+        let certificate_chain = CertificateDer::pem_slice_iter(certificate_chain)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| HTTPTLSError::InvalidPEM)?;
+        if certificate_chain.is_empty() {
+            return Err(HTTPTLSError::NoCertificates);
+        }
+
+        let private_key =
+            PrivateKeyDer::from_pem_slice(private_key).map_err(|_| HTTPTLSError::InvalidPEM)?;
+
+        let key = CertifiedKey::from_der(certificate_chain, private_key, &default_provider())?;
+
+        Ok(Certificate {
+            key: Arc::new(key),
+            files: None,
+        })
+    }
+
+    /// Gets the certificate chain and private key presented to clients
+    pub(super) fn key(&self) -> Arc<CertifiedKey> {
+        self.key.clone()
+    }
+
+    /// Reads the certificate chain and private key from their files again
+    ///
+    /// Returns a copy of this certificate if it wasn't loaded from files.
+    pub(super) fn reload(&self) -> Result<Self, HTTPTLSError> {
+        match &self.files {
+            Some((certificate_chain, private_key)) => {
+                Certificate::from_pem_files(certificate_chain, private_key)
+            }
+            None => Ok(self.clone()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+/// How a TLS listener asks clients for certificates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HTTPTLSClientAuth {
+    /// Clients may connect without a certificate, but any certificate sent must be valid
+    Request,
+
+    /// Clients must send a valid certificate to connect
+    Require,
+}
@@ -0,0 +1,201 @@
+use super::{certificate::Certificate, resolver::CertificateResolver};
+use crate::{HTTPTLSClientAuth, HTTPTLSError};
+use rustls::{
+    crypto::ring::default_provider,
+    pki_types::{pem::PemObject, CertificateDer},
+    server::{danger::ClientCertVerifier, WebPkiClientVerifier},
+    RootCertStore, ServerConfig,
+};
+use std::{path::Path, sync::Arc};
+
+/// The certificates a TLS listener identifies itself with
+///
+/// A listener presents the certificate added for the hostname a client requests through SNI,
+/// falling back to the default certificate when none match. Clones share their certificates, so
+/// certificates added or reloaded through any clone apply to every listener using it.
+#[derive(Clone)]
+pub struct HTTPTLSConfig {
+    /// The configuration for new connections
+    config: Arc<ServerConfig>,
+
+    /// The certificates presented to clients
+    resolver: Arc<CertificateResolver>,
+}
+
+impl HTTPTLSConfig {
+    /// Loads the default PEM encoded certificate chain and private key from the files at
+    /// `certificate_chain` and `private_key`
+    pub fn from_pem_files<P1: AsRef<Path>, P2: AsRef<Path>>(
+        certificate_chain: P1,
+        private_key: P2,
+    ) -> Result<Self, HTTPTLSError> {
+        HTTPTLSConfig::new(Certificate::from_pem_files(
+            certificate_chain.as_ref(),
+            private_key.as_ref(),
+        )?)
+    }
+
+    /// Creates a new [`HTTPTLSConfig`] from a default PEM encoded certificate chain and private
+    /// key
+    ///
+    /// The certificate chain starts with the certificate for the server, followed by any
+    /// intermediate certificates.
+    pub fn from_pem(certificate_chain: &[u8], private_key: &[u8]) -> Result<Self, HTTPTLSError> {
+        HTTPTLSConfig::new(Certificate::from_pem(certificate_chain, private_key)?)
+    }
+
+    /// Loads the PEM encoded certificate chain and private key presented to clients requesting
+    /// `hostname` from the files at `certificate_chain` and `private_key`
+    ///
+    /// `hostname` may start with "*." to match any single label in its place. Exact hostnames
+    /// are preferred over wildcards.
+    pub fn add_pem_files<P1: AsRef<Path>, P2: AsRef<Path>>(
+        &self,
+        hostname: &str,
+        certificate_chain: P1,
+        private_key: P2,
+    ) -> Result<(), HTTPTLSError> {
+        let certificate =
+            Certificate::from_pem_files(certificate_chain.as_ref(), private_key.as_ref())?;
+        self.resolver.add(hostname, certificate);
+        Ok(())
+    }
+
+    /// Adds the PEM encoded certificate chain and private key presented to clients requesting
+    /// `hostname`
+    ///
+    /// `hostname` may start with "*." to match any single label in its place. Exact hostnames
+    /// are preferred over wildcards.
+    pub fn add_pem(
+        &self,
+        hostname: &str,
+        certificate_chain: &[u8],
+        private_key: &[u8],
+    ) -> Result<(), HTTPTLSError> {
+        self.resolver.add(
+            hostname,
+            Certificate::from_pem(certificate_chain, private_key)?,
+        );
+        Ok(())
+    }
+
+    /// Reads every certificate chain and private key loaded from files again
+    ///
+    /// New connections use the reloaded certificates while existing connections are unaffected.
+    /// If any certificate fails to load, all of the previous certificates are kept.
+    pub fn reload(&self) -> Result<(), HTTPTLSError> {
+        self.resolver.reload()
+    }
+
+    /// Loads the PEM encoded certificate authorities client certificates are verified against
+    /// from the file at `ca_bundle`
+    ///
+    /// Only affects this config and clones made from it afterwards.
+    pub fn set_client_auth_pem_file<P: AsRef<Path>>(
+        &mut self,
+        ca_bundle: P,
+        client_auth: HTTPTLSClientAuth,
+    ) -> Result<(), HTTPTLSError> {
+        self.set_client_auth_pem(&std::fs::read(ca_bundle)?, client_auth)
+    }
+
+    /// Asks clients for certificates, which are verified against the PEM encoded certificate
+    /// authorities in `ca_bundle`
+    ///
+    /// The verified certificate is available from [`HTTPTLSInfo::peer_certificate`]. Only
+    /// affects this config and clones made from it afterwards.
+    ///
+    /// [`HTTPTLSInfo::peer_certificate`]: crate::HTTPTLSInfo::peer_certificate
+    pub fn set_client_auth_pem(
+        &mut self,
+        ca_bundle: &[u8],
+        client_auth: HTTPTLSClientAuth,
+    ) -> Result<(), HTTPTLSError> {
+        let mut roots = RootCertStore::empty();
+        for certificate in CertificateDer::pem_slice_iter(ca_bundle) {
+            roots.add(certificate.map_err(|_| HTTPTLSError::InvalidPEM)?)?;
+        }
+
+        let verifier = WebPkiClientVerifier::builder_with_provider(
+            Arc::new(roots),
+            Arc::new(default_provider()),
+        );
+        let verifier = match client_auth {
+            HTTPTLSClientAuth::Request => verifier.allow_unauthenticated(),
+            HTTPTLSClientAuth::Require => verifier,
+        }
+        .build()?;
+
+        self.config = Arc::new(build_config(self.resolver.clone(), verifier)?);
+        Ok(())
+    }
+
+    /// Creates a new [`HTTPTLSConfig`] which presents `default` unless another certificate is
+    /// added
+    fn new(default: Certificate) -> Result<Self, HTTPTLSError> {
+        let resolver = Arc::new(CertificateResolver::new(default));
+
+        Ok(HTTPTLSConfig {
+            config: Arc::new(build_config(
+                resolver.clone(),
+                WebPkiClientVerifier::no_client_auth(),
+            )?),
+            resolver,
+        })
+    }
+
+    /// Gets the configuration for new connections
+    pub(crate) fn server_config(&self) -> Arc<ServerConfig> {
+        self.config.clone()
+    }
+
+    /// Creates the configuration for QUIC connections, which only offers HTTP/3 through ALPN
+    ///
+    /// The configuration shares its certificates with this config.
+    #[cfg(feature = "http3")]
+    pub(crate) fn http3(&self) -> Arc<ServerConfig> {
+        let mut config = (*self.config).clone();
+        config.alpn_protocols = vec![crate::http3::ALPN.to_vec()];
+        Arc::new(config)
+    }
+
+    /// Creates a copy of this config which only offers HTTP/1.1 through ALPN
+    ///
+    /// The copy shares its certificates with this config.
+    pub(crate) fn http1_only(&self) -> Self {
+        let mut config = (*self.config).clone();
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        HTTPTLSConfig {
+            config: Arc::new(config),
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+/// Creates the configuration for new connections
+fn build_config(
+    resolver: Arc<CertificateResolver>,
+    verifier: Arc<dyn ClientCertVerifier>,
+) -> Result<ServerConfig, HTTPTLSError> {
+    let mut config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_client_cert_verifier(verifier)
+        .with_cert_resolver(resolver);
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+impl std::fmt::Debug for HTTPTLSConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HTTPTLSConfig").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for HTTPTLSConfig {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.config, &other.config)
+    }
+}
+
+impl Eq for HTTPTLSConfig {}
@@ -0,0 +1,59 @@
+/// An error while loading the certificates and keys for TLS
+#[derive(Debug)]
+pub enum HTTPTLSError {
+    /// A file could not be read
+    IO(std::io::Error),
+
+    /// The certificate chain doesn't contain any certificates
+    NoCertificates,
+
+    /// A PEM file is malformed or doesn't contain a private key
+    InvalidPEM,
+
+    /// The certificate chain and private key were rejected
+    TLS(rustls::Error),
+
+    /// The certificate authorities for verifying clients were rejected
+    ClientVerifier(rustls::server::VerifierBuilderError),
+}
+
+impl std::error::Error for HTTPTLSError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HTTPTLSError::IO(error) => Some(error),
+            HTTPTLSError::TLS(error) => Some(error),
+            HTTPTLSError::ClientVerifier(error) => Some(error),
+            HTTPTLSError::NoCertificates | HTTPTLSError::InvalidPEM => None,
+        }
+    }
+}
+
+impl std::fmt::Display for HTTPTLSError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HTTPTLSError::IO(error) => error.fmt(f),
+            HTTPTLSError::NoCertificates => write!(f, "no certificates in the certificate chain"),
+            HTTPTLSError::InvalidPEM => write!(f, "invalid PEM file"),
+            HTTPTLSError::TLS(error) => error.fmt(f),
+            HTTPTLSError::ClientVerifier(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<std::io::Error> for HTTPTLSError {
+    fn from(error: std::io::Error) -> Self {
+        HTTPTLSError::IO(error)
+    }
+}
+
+impl From<rustls::Error> for HTTPTLSError {
+    fn from(error: rustls::Error) -> Self {
+        HTTPTLSError::TLS(error)
+    }
+}
+
+impl From<rustls::server::VerifierBuilderError> for HTTPTLSError {
+    fn from(error: rustls::server::VerifierBuilderError) -> Self {
+        HTTPTLSError::ClientVerifier(error)
+    }
+}
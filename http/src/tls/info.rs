@@ -0,0 +1,116 @@
+use crate::HTTPTLSPeerCertificate;
+use rustls::{ProtocolVersion, ServerConnection};
+use std::sync::Arc;
+
+#[cfg(feature = "http3")]
+use rustls::pki_types::CertificateDer;
+
+/// The version of TLS a client connected with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HTTPTLSVersion {
+    /// TLS 1.2
+    TLS12,
+
+    /// TLS 1.3
+    TLS13,
+}
+
+/// The parameters negotiated with a client which connected over TLS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HTTPTLSInfo {
+    /// The version of TLS negotiated
+    version: HTTPTLSVersion,
+
+    /// The name of the cipher suite negotiated
+    cipher_suite: &'static str,
+
+    /// The verified certificate the client authenticated itself with, if it sent one
+    peer_certificate: Option<Arc<HTTPTLSPeerCertificate>>,
+}
+
+impl HTTPTLSInfo {
+    /// Gets the parameters negotiated on `connection` once its handshake is complete
+    pub(super) fn new(connection: &ServerConnection) -> Option<Self> {
+        let version = match connection.protocol_version()? {
+            ProtocolVersion::TLSv1_2 => HTTPTLSVersion::TLS12,
+            ProtocolVersion::TLSv1_3 => HTTPTLSVersion::TLS13,
+            _ => return None,
+        };
+
+        let cipher_suite = connection
+            .negotiated_cipher_suite()?
+            .suite()
+            .as_str()
+            .unwrap_or("unknown");
+
+        let peer_certificate = match connection.peer_certificates() {
+            Some(certificates) => Some(Arc::new(HTTPTLSPeerCertificate::parse(
+                certificates.first()?,
+            )?)),
+            None => None,
+        };
+
+        Some(HTTPTLSInfo {
+            version,
+            cipher_suite,
+            peer_certificate,
+        })
+    }
+
+    /// Gets the parameters negotiated on the QUIC `connection` once its handshake is complete
+    ///
+    /// QUIC always uses TLS 1.3, but the cipher suite it negotiated isn't exposed, so it is
+    /// reported as "unknown".
+    #[cfg(feature = "http3")]
+    pub(crate) fn quic(connection: &quinn_proto::Connection) -> Option<Self> {
+        let peer_certificate = match connection.crypto_session().peer_identity() {
+            Some(identity) => {
+                let certificates = identity.downcast::<Vec<CertificateDer<'static>>>().ok()?;
+                Some(Arc::new(HTTPTLSPeerCertificate::parse(
+                    certificates.first()?,
+                )?))
+            }
+            None => None,
+        };
+
+        Some(HTTPTLSInfo {
+            version: HTTPTLSVersion::TLS13,
+            cipher_suite: "unknown",
+            peer_certificate,
+        })
+    }
+
+    /// Gets the version of TLS negotiated
+    pub fn version(&self) -> HTTPTLSVersion {
+        self.version
+    }
+
+    /// Gets the name of the cipher suite negotiated (e.g. "TLS13_AES_128_GCM_SHA256")
+    ///
+    /// This is "unknown" for HTTP/3 connections, as QUIC doesn't expose it.
+    pub fn cipher_suite(&self) -> &'static str {
+        self.cipher_suite
+    }
+
+    /// Gets the verified certificate the client authenticated itself with
+    ///
+    /// Returns [`None`] unless the listener asks for client certificates and the client sent one.
+    pub fn peer_certificate(&self) -> Option<&HTTPTLSPeerCertificate> {
+        self.peer_certificate.as_deref()
+    }
+}
+
+impl std::fmt::Display for HTTPTLSInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.version, self.cipher_suite)
+    }
+}
+
+impl std::fmt::Display for HTTPTLSVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HTTPTLSVersion::TLS12 => "TLSv1.2",
+            HTTPTLSVersion::TLS13 => "TLSv1.3",
+        })
+    }
+}
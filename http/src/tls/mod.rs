@@ -0,0 +1,16 @@
+mod certificate;
+mod client_auth;
+mod config;
+mod error;
+mod info;
+mod peer;
+mod resolver;
+mod stream;
+
+pub use client_auth::HTTPTLSClientAuth;
+pub use config::HTTPTLSConfig;
+pub use error::HTTPTLSError;
+pub use info::{HTTPTLSInfo, HTTPTLSVersion};
+pub use peer::{HTTPTLSFingerprint, HTTPTLSPeerCertificate, HTTPTLSSubjectAltName};
+
+pub(crate) use stream::TLSStream;
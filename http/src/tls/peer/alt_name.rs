@@ -0,0 +1,73 @@
+use super::der::{implicit, DER, SEQUENCE};
+use std::net::IpAddr;
+
+/// An alternative name a certificate is valid for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HTTPTLSSubjectAltName {
+    /// A domain name (e.g. "service.internal")
+    DNS(String),
+
+    /// An email address
+    Email(String),
+
+    /// A URI (e.g. a SPIFFE ID like "spiffe://example.org/service")
+    URI(String),
+
+    /// An IP address
+    IP(IpAddr),
+}
+
+/// The tag of an rfc822Name in GeneralNames
+const EMAIL: u8 = implicit(1);
+
+/// The tag of a dNSName in GeneralNames
+const DNS: u8 = implicit(2);
+
+/// The tag of a uniformResourceIdentifier in GeneralNames
+const URI: u8 = implicit(6);
+
+/// The tag of an iPAddress in GeneralNames
+const IP: u8 = implicit(7);
+
+impl HTTPTLSSubjectAltName {
+    /// Parses the DER encoded contents of a subject alternative name extension
+    ///
+    /// Kinds of names other than [`HTTPTLSSubjectAltName`] can represent are skipped.
+    pub(super) fn parse_extension(extension: &[u8]) -> Option<Vec<Self>> {
+        let mut der = DER::new(extension);
+        let mut der = DER::new(der.read_tag(SEQUENCE)?);
+
+        let mut names = Vec::new();
+        while !der.is_empty() {
+            let (tag, value) = der.read()?;
+            names.push(match tag {
+                EMAIL => HTTPTLSSubjectAltName::Email(String::from_utf8_lossy(value).into_owned()),
+                DNS => HTTPTLSSubjectAltName::DNS(String::from_utf8_lossy(value).into_owned()),
+                URI => HTTPTLSSubjectAltName::URI(String::from_utf8_lossy(value).into_owned()),
+                IP => match value.len() {
+                    4 => {
+                        HTTPTLSSubjectAltName::IP(IpAddr::from(<[u8; 4]>::try_from(value).unwrap()))
+                    }
+                    16 => HTTPTLSSubjectAltName::IP(IpAddr::from(
+                        <[u8; 16]>::try_from(value).unwrap(),
+                    )),
+                    _ => continue,
+                },
+                _ => continue,
+            });
+        }
+
+        Some(names)
+    }
+}
+
+impl std::fmt::Display for HTTPTLSSubjectAltName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HTTPTLSSubjectAltName::DNS(name) => write!(f, "DNS:{}", name),
+            HTTPTLSSubjectAltName::Email(email) => write!(f, "email:{}", email),
+            HTTPTLSSubjectAltName::URI(uri) => write!(f, "URI:{}", uri),
+            HTTPTLSSubjectAltName::IP(ip) => write!(f, "IP:{}", ip),
+        }
+    }
+}
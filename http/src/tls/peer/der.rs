@@ -0,0 +1,100 @@
+/// The tag of a SEQUENCE
+pub(super) const SEQUENCE: u8 = 0x30;
+
+/// The tag of a SET
+pub(super) const SET: u8 = 0x31;
+
+/// The tag of an OBJECT IDENTIFIER
+pub(super) const OBJECT_IDENTIFIER: u8 = 0x06;
+
+/// The tag of an OCTET STRING
+pub(super) const OCTET_STRING: u8 = 0x04;
+
+/// The tag of a BOOLEAN
+pub(super) const BOOLEAN: u8 = 0x01;
+
+/// The tag of a BMPString
+pub(super) const BMP_STRING: u8 = 0x1E;
+
+/// Gets the tag of the constructed, context-specific value numbered `number`
+pub(super) const fn explicit(number: u8) -> u8 {
+    0xA0 | number
+}
+
+/// Gets the tag of the primitive, context-specific value numbered `number`
+pub(super) const fn implicit(number: u8) -> u8 {
+    0x80 | number
+}
+
+/// Reads the values in DER encoded data one at a time
+#[derive(Clone, Copy)]
+pub(super) struct DER<'a> {
+    /// The data which hasn't been read yet
+    data: &'a [u8],
+}
+
+impl<'a> DER<'a> {
+    /// Creates a new [`DER`] reading `data`
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        DER { data }
+    }
+
+    /// Has all of the data been read?
+    pub(super) fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
+    /// Gets the tag of the next value without reading it
+    pub(super) fn peek(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Reads the next value, returning its tag and contents
+    pub(super) fn read(&mut self) -> Option<(u8, &'a [u8])> {
+        let (&tag, rest) = self.data.split_first()?;
+        let (&first, mut rest) = rest.split_first()?;
+
+        let length = if first < 0x80 {
+            first as usize
+        } else {
+            let count = (first & 0x7F) as usize;
+            if count == 0 || count > std::mem::size_of::<usize>() || rest.len() < count {
+                return None;
+            }
+
+            let mut length = 0;
+            for byte in &rest[..count] {
+                length = (length << 8) | *byte as usize;
+            }
+            rest = &rest[count..];
+            length
+        };
+
+        if rest.len() < length {
+            return None;
+        }
+
+        let (contents, rest) = rest.split_at(length);
+        self.data = rest;
+        Some((tag, contents))
+    }
+
+    /// Reads the next value, returning its contents if it has `tag`
+    pub(super) fn read_tag(&mut self, tag: u8) -> Option<&'a [u8]> {
+        match self.read()? {
+            (read_tag, contents) if read_tag == tag => Some(contents),
+            _ => None,
+        }
+    }
+
+    /// Reads the next value if it has `tag`, returning its contents
+    ///
+    /// Returns [`None`] without reading anything if the next value has a different tag.
+    pub(super) fn read_optional(&mut self, tag: u8) -> Option<&'a [u8]> {
+        if self.peek() != Some(tag) {
+            return None;
+        }
+
+        self.read_tag(tag)
+    }
+}
@@ -0,0 +1,34 @@
+use ring::digest::{digest, SHA256};
+
+/// The SHA-256 hash of a DER encoded certificate
+///
+/// Displays as colon separated uppercase hex bytes, the same as OpenSSL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HTTPTLSFingerprint([u8; 32]);
+
+impl HTTPTLSFingerprint {
+    /// Calculates the fingerprint of the DER encoded `certificate`
+    pub(super) fn new(certificate: &[u8]) -> Self {
+        let mut fingerprint = [0; 32];
+        fingerprint.copy_from_slice(digest(&SHA256, certificate).as_ref());
+        HTTPTLSFingerprint(fingerprint)
+    }
+
+    /// Gets the bytes of the hash
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for HTTPTLSFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(":")?;
+            }
+            write!(f, "{:02X}", byte)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use der::{explicit, BOOLEAN, DER, OBJECT_IDENTIFIER, OCTET_STRING, SEQUENCE};
+use name::format_name;
+
+mod alt_name;
+mod der;
+mod fingerprint;
+mod name;
+
+pub use alt_name::HTTPTLSSubjectAltName;
+pub use fingerprint::HTTPTLSFingerprint;
+
+/// The encoded object identifier of the subject alternative name extension
+const SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1D, 0x11];
+
+/// The verified certificate a client authenticated itself with
+#[derive(Clone, PartialEq, Eq)]
+pub struct HTTPTLSPeerCertificate {
+    /// The DER encoded certificate
+    der: Box<[u8]>,
+
+    /// The distinguished name of the subject
+    subject: String,
+
+    /// The alternative names the certificate is valid for
+    subject_alt_names: Vec<HTTPTLSSubjectAltName>,
+
+    /// The hash of the certificate
+    fingerprint: HTTPTLSFingerprint,
+}
+
+impl HTTPTLSPeerCertificate {
+    /// Parses the DER encoded `certificate`
+    pub(super) fn parse(certificate: &[u8]) -> Option<Self> {
+        let mut der = DER::new(certificate);
+        let mut der = DER::new(der.read_tag(SEQUENCE)?);
+        let mut tbs_certificate = DER::new(der.read_tag(SEQUENCE)?);
+
+        tbs_certificate.read_optional(explicit(0)); // version
+        tbs_certificate.read()?; // serialNumber
+        tbs_certificate.read_tag(SEQUENCE)?; // signature
+        tbs_certificate.read_tag(SEQUENCE)?; // issuer
+        tbs_certificate.read_tag(SEQUENCE)?; // validity
+        let subject = format_name(tbs_certificate.read_tag(SEQUENCE)?)?;
+        tbs_certificate.read_tag(SEQUENCE)?; // subjectPublicKeyInfo
+        tbs_certificate.read_optional(explicit(1)); // issuerUniqueID
+        tbs_certificate.read_optional(explicit(2)); // subjectUniqueID
+
+        let mut subject_alt_names = Vec::new();
+        if let Some(extensions) = tbs_certificate.read_optional(explicit(3)) {
+            let mut extensions = DER::new(DER::new(extensions).read_tag(SEQUENCE)?);
+            while !extensions.is_empty() {
+                let mut extension = DER::new(extensions.read_tag(SEQUENCE)?);
+                let id = extension.read_tag(OBJECT_IDENTIFIER)?;
+                extension.read_optional(BOOLEAN); // critical
+                let value = extension.read_tag(OCTET_STRING)?;
+
+                if id == SUBJECT_ALT_NAME {
+                    subject_alt_names = HTTPTLSSubjectAltName::parse_extension(value)?;
+                }
+            }
+        }
+
+        Some(HTTPTLSPeerCertificate {
+            der: certificate.into(),
+            subject,
+            subject_alt_names,
+            fingerprint: HTTPTLSFingerprint::new(certificate),
+        })
+    }
+
+    /// Gets the DER encoded certificate
+    pub fn der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// Gets the distinguished name of the subject as described in RFC 4514 (e.g.
+    /// "CN=client,O=Example")
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Gets the alternative names the certificate is valid for
+    pub fn subject_alt_names(&self) -> &[HTTPTLSSubjectAltName] {
+        &self.subject_alt_names
+    }
+
+    /// Gets the SHA-256 hash of the DER encoded certificate
+    pub fn fingerprint(&self) -> HTTPTLSFingerprint {
+        self.fingerprint
+    }
+}
+
+impl std::fmt::Debug for HTTPTLSPeerCertificate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HTTPTLSPeerCertificate")
+            .field("subject", &self.subject)
+            .field("subject_alt_names", &self.subject_alt_names)
+            .field("fingerprint", &format_args!("{}", self.fingerprint))
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Display for HTTPTLSPeerCertificate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.subject)
+    }
+}
@@ -0,0 +1,129 @@
+use super::der::{BMP_STRING, DER, OBJECT_IDENTIFIER, SEQUENCE, SET};
+use std::fmt::Write;
+
+/// The short names for common attribute types, by their encoded object identifiers
+const ATTRIBUTE_NAMES: &[(&[u8], &str)] = &[
+    (&[0x55, 0x04, 0x03], "CN"),
+    (&[0x55, 0x04, 0x05], "serialNumber"),
+    (&[0x55, 0x04, 0x06], "C"),
+    (&[0x55, 0x04, 0x07], "L"),
+    (&[0x55, 0x04, 0x08], "ST"),
+    (&[0x55, 0x04, 0x09], "STREET"),
+    (&[0x55, 0x04, 0x0A], "O"),
+    (&[0x55, 0x04, 0x0B], "OU"),
+    (
+        &[0x09, 0x92, 0x26, 0x89, 0x93, 0xF2, 0x2C, 0x64, 0x01, 0x01],
+        "UID",
+    ),
+    (
+        &[0x09, 0x92, 0x26, 0x89, 0x93, 0xF2, 0x2C, 0x64, 0x01, 0x19],
+        "DC",
+    ),
+    (
+        &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x01],
+        "emailAddress",
+    ),
+];
+
+/// Formats a DER encoded distinguished name as described in RFC 4514 (e.g. "CN=client,O=Example")
+pub(super) fn format_name(name: &[u8]) -> Option<String> {
+    let mut relative_names = Vec::new();
+    let mut der = DER::new(name);
+    while !der.is_empty() {
+        relative_names.push(der.read_tag(SET)?);
+    }
+
+    // RFC 4514 lists the most specific relative name first, which is the last one encoded
+    let mut output = String::new();
+    for (i, relative_name) in relative_names.into_iter().rev().enumerate() {
+        if i > 0 {
+            output.push(',');
+        }
+
+        let mut der = DER::new(relative_name);
+        let mut first = true;
+        while !der.is_empty() {
+            if !first {
+                output.push('+');
+            }
+            first = false;
+
+            let mut attribute = DER::new(der.read_tag(SEQUENCE)?);
+            let attribute_type = attribute.read_tag(OBJECT_IDENTIFIER)?;
+            let (tag, value) = attribute.read()?;
+
+            match ATTRIBUTE_NAMES
+                .iter()
+                .find(|(oid, _)| *oid == attribute_type)
+            {
+                Some((_, name)) => output.push_str(name),
+                None => format_oid(attribute_type, &mut output)?,
+            }
+            output.push('=');
+
+            let value = if tag == BMP_STRING {
+                let units = value
+                    .chunks(2)
+                    .map(|unit| u16::from_be_bytes([unit[0], *unit.get(1).unwrap_or(&0)]));
+                char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect()
+            } else {
+                String::from_utf8_lossy(value).into_owned()
+            };
+            escape_value(&value, &mut output);
+        }
+    }
+
+    Some(output)
+}
+
+/// Writes an encoded object identifier in its dotted form (e.g. "2.5.4.3")
+fn format_oid(oid: &[u8], output: &mut String) -> Option<()> {
+    let mut arcs = Vec::new();
+    let mut arc: u64 = 0;
+    for byte in oid {
+        arc = arc.checked_mul(128)? | (byte & 0x7F) as u64;
+        if byte & 0x80 == 0 {
+            arcs.push(arc);
+            arc = 0;
+        }
+    }
+
+    let (first, rest) = arcs.split_first()?;
+    let (first, second) = match *first {
+        0..40 => (0, *first),
+        40..80 => (1, *first - 40),
+        _ => (2, *first - 80),
+    };
+
+    write!(output, "{}.{}", first, second).unwrap();
+    for arc in rest {
+        write!(output, ".{}", arc).unwrap();
+    }
+
+    Some(())
+}
+
+/// Writes an attribute value, escaping the characters RFC 4514 requires
+fn escape_value(value: &str, output: &mut String) {
+    let last = value.chars().count().saturating_sub(1);
+    for (i, c) in value.chars().enumerate() {
+        let escape = match c {
+            '"' | '+' | ',' | ';' | '<' | '>' | '\\' => true,
+            ' ' => i == 0 || i == last,
+            '#' => i == 0,
+            _ => false,
+        };
+
+        if escape {
+            output.push('\\');
+        }
+
+        if c == '\0' {
+            output.push_str("\\00");
+        } else {
+            output.push(c);
+        }
+    }
+}
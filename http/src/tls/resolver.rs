@@ -0,0 +1,115 @@
+use super::certificate::Certificate;
+use crate::HTTPTLSError;
+use rustls::{
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+};
+use std::sync::{Arc, RwLock};
+
+/// Selects the certificate to present to a client based on the hostname it requested
+#[derive(Debug)]
+pub(super) struct CertificateResolver {
+    /// The certificates which can be presented
+    certificates: RwLock<Certificates>,
+}
+
+/// The certificates a [`CertificateResolver`] selects from
+#[derive(Debug, Clone)]
+struct Certificates {
+    /// The certificate presented when no other certificate matches the requested hostname
+    default: Certificate,
+
+    /// The certificates for specific hostnames, which may start with a "*." wildcard
+    hostnames: Vec<(String, Certificate)>,
+}
+
+/// Checks if `pattern` matches `hostname`, where both are lowercase
+///
+/// A pattern starting with "*." matches exactly one label in its place.
+fn matches(pattern: &str, hostname: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => match hostname.split_once('.') {
+            Some((label, rest)) => !label.is_empty() && rest == suffix,
+            None => false,
+        },
+        None => pattern == hostname,
+    }
+}
+
+/// Converts `hostname` into the form it is compared in
+fn normalize(hostname: &str) -> String {
+    hostname.trim_end_matches('.').to_ascii_lowercase()
+}
+
+impl CertificateResolver {
+    /// Creates a new [`CertificateResolver`] which presents `default` unless another certificate
+    /// is added
+    pub(super) fn new(default: Certificate) -> Self {
+        CertificateResolver {
+            certificates: RwLock::new(Certificates {
+                default,
+                hostnames: Vec::new(),
+            }),
+        }
+    }
+
+    /// Presents `certificate` to clients requesting `hostname`, replacing any certificate
+    /// previously added for it
+    pub(super) fn add(&self, hostname: &str, certificate: Certificate) {
+        let hostname = normalize(hostname);
+
+        let mut certificates = self.certificates.write().unwrap();
+        match certificates
+            .hostnames
+            .iter_mut()
+            .find(|(pattern, _)| *pattern == hostname)
+        {
+            Some((_, old)) => *old = certificate,
+            None => certificates.hostnames.push((hostname, certificate)),
+        }
+    }
+
+    /// Reads every certificate loaded from files again
+    ///
+    /// If any certificate fails to load, none of the certificates are replaced.
+    pub(super) fn reload(&self) -> Result<(), HTTPTLSError> {
+        let certificates = self.certificates.read().unwrap().clone();
+
+        let default = certificates.default.reload()?;
+        let hostnames = certificates
+            .hostnames
+            .iter()
+            .map(|(hostname, certificate)| Ok((hostname.clone(), certificate.reload()?)))
+            .collect::<Result<_, HTTPTLSError>>()?;
+
+        *self.certificates.write().unwrap() = Certificates { default, hostnames };
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        let certificates = self.certificates.read().unwrap();
+
+        let hostname = match client_hello.server_name() {
+            Some(hostname) => normalize(hostname),
+            None => return Some(certificates.default.key()),
+        };
+
+        // Exact matches take priority over wildcards
+        let certificate = certificates
+            .hostnames
+            .iter()
+            .find(|(pattern, _)| *pattern == hostname)
+            .or_else(|| {
+                certificates
+                    .hostnames
+                    .iter()
+                    .find(|(pattern, _)| matches(pattern, &hostname))
+            })
+            .map(|(_, certificate)| certificate)
+            .unwrap_or(&certificates.default);
+
+        Some(certificate.key())
+    }
+}
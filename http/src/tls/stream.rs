@@ -0,0 +1,143 @@
+use crate::{HTTPTLSConfig, HTTPTLSInfo, Result};
+use huntsman::runtime::{
+    io::{Read, Write},
+    net::TCPStream,
+    protocol_error,
+};
+use rustls::ServerConnection;
+use std::io::ErrorKind;
+
+/// The size of the buffer encrypted data is read into
+const BUFFER_SIZE: usize = 16 * 1024;
+
+/// A TCP stream secured with TLS
+pub(crate) struct TLSStream {
+    /// The underlying stream
+    stream: TCPStream,
+
+    /// The state of the TLS connection
+    connection: ServerConnection,
+
+    /// The buffer encrypted data is read into
+    input: Box<[u8]>,
+
+    /// The buffer encrypted data is written from
+    output: Vec<u8>,
+}
+
+impl TLSStream {
+    /// Creates a new [`TLSStream`] which will perform the handshake with `config` on `stream`
+    pub(crate) fn new(stream: TCPStream, config: &HTTPTLSConfig) -> Result<Self> {
+        let connection =
+            ServerConnection::new(config.server_config()).map_err(|_| protocol_error())?;
+
+        Ok(TLSStream {
+            stream,
+            connection,
+            input: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            output: Vec::new(),
+        })
+    }
+
+    /// Completes the handshake with the client, returning the negotiated parameters
+    pub(crate) async fn handshake(&mut self) -> Result<HTTPTLSInfo> {
+        while self.connection.is_handshaking() {
+            self.flush().await?;
+
+            if self.connection.wants_read() && self.fill().await? == 0 {
+                return Err(protocol_error());
+            }
+        }
+
+        self.flush().await?;
+
+        HTTPTLSInfo::new(&self.connection).ok_or_else(protocol_error)
+    }
+
+    /// Gets the protocol the client negotiated through ALPN
+    pub(crate) fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.connection.alpn_protocol()
+    }
+
+    /// Attempts to read decrypted bytes into `buffer`
+    ///
+    /// Returns 0 once the client closes the connection.
+    pub(crate) async fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        loop {
+            match std::io::Read::read(&mut self.connection.reader(), buffer) {
+                Ok(count) => return Ok(count),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => {}
+                Err(_) => return Err(protocol_error()),
+            }
+
+            if self.fill().await? == 0 {
+                return Ok(0);
+            }
+        }
+    }
+
+    /// Attempts to fill `buffer` with decrypted bytes
+    pub(crate) async fn read_exact(&mut self, mut buffer: &mut [u8]) -> Result<()> {
+        while !buffer.is_empty() {
+            let count = self.read(buffer).await?;
+            if count == 0 {
+                return Err(protocol_error());
+            }
+
+            buffer = &mut buffer[count..];
+        }
+
+        Ok(())
+    }
+
+    /// Attempts to encrypt and write all of `buffer`
+    pub(crate) async fn write(&mut self, mut buffer: &[u8]) -> Result<()> {
+        while !buffer.is_empty() {
+            let count = std::io::Write::write(&mut self.connection.writer(), buffer)
+                .map_err(|_| protocol_error())?;
+            buffer = &buffer[count..];
+
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads encrypted data from the client and processes it
+    ///
+    /// Returns the number of bytes read, which is 0 if the client closed the connection.
+    async fn fill(&mut self) -> Result<usize> {
+        let count = self.stream.read(&mut self.input).await?;
+
+        let mut input = &self.input[..count];
+        let mut failed = false;
+        while !input.is_empty() && !failed {
+            self.connection
+                .read_tls(&mut input)
+                .map_err(|_| protocol_error())?;
+
+            failed = self.connection.process_new_packets().is_err();
+        }
+
+        if failed {
+            // Let the client know why the connection is being closed
+            let _ = self.flush().await;
+            return Err(protocol_error());
+        }
+
+        Ok(count)
+    }
+
+    /// Writes any encrypted data waiting to be sent to the client
+    async fn flush(&mut self) -> Result<()> {
+        while self.connection.wants_write() {
+            self.output.clear();
+            self.connection
+                .write_tls(&mut self.output)
+                .map_err(|_| protocol_error())?;
+            self.stream.write_all(&self.output).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+/// The GUID appended to the key sent by the client before it is hashed
+const GUID: &[u8] = b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The characters used to encode six bits each in base64
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The initial state of a SHA-1 hash
+const SHA1_STATE: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+/// Creates the value of the "Sec-WebSocket-Accept" field answering the "Sec-WebSocket-Key" `key`
+///
+/// Returns [`None`] if `key` isn't 16 bytes encoded in base64.
+pub(crate) fn accept_key(key: &[u8]) -> Option<Vec<u8>> {
+    let key = key.trim_ascii();
+    if key.len() != 24 || &key[22..] != b"==" || !key[..22].iter().all(|c| BASE64.contains(c)) {
+        return None;
+    }
+
+    let mut input = key.to_vec();
+    input.extend_from_slice(GUID);
+    Some(base64(&sha1(&input)))
+}
+
+/// Hashes `input` with SHA-1
+fn sha1(input: &[u8]) -> [u8; 20] {
+    // The message is padded with a one bit, then zeros, then its length in bits
+    let mut message = input.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((input.len() as u64) * 8).to_be_bytes());
+
+    let mut state = SHA1_STATE;
+    for block in message.chunks_exact(64) {
+        let mut words = [0; 80];
+        for i in 0..16 {
+            words[i] = u32::from_be_bytes(block[i * 4..i * 4 + 4].try_into().unwrap());
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..20 => ((b & c) | (!b & d), 0x5A827999),
+                20..40 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..60 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (value, change) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(change);
+        }
+    }
+
+    let mut output = [0; 20];
+    for (i, value) in state.iter().enumerate() {
+        output[i * 4..i * 4 + 4].copy_from_slice(&value.to_be_bytes());
+    }
+    output
+}
+
+/// Encodes `input` in base64 with padding
+fn base64(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bits = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(BASE64[(bits >> (18 - i * 6)) as usize & 0x3F]);
+            } else {
+                output.push(b'=');
+            }
+        }
+    }
+    output
+}
@@ -0,0 +1,83 @@
+/// The reason a WebSocket was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HTTPWebSocketCloseCode {
+    /// The purpose of the connection was fulfilled
+    Normal,
+
+    /// The endpoint is going away, such as the server shutting down
+    GoingAway,
+
+    /// The other endpoint broke the rules of the protocol
+    ProtocolError,
+
+    /// The endpoint received a type of message it can't accept
+    UnsupportedData,
+
+    /// The client closed the connection without giving a code
+    ///
+    /// This is never sent, only reported for close frames which don't contain a code.
+    NoStatus,
+
+    /// A message contained data which wasn't consistent with its type, such as text which wasn't
+    /// valid UTF-8
+    InvalidPayload,
+
+    /// The endpoint received a message which violates its policy
+    PolicyViolation,
+
+    /// The endpoint received a message which was too large to process
+    MessageTooBig,
+
+    /// The client expected the server to negotiate an extension it didn't
+    MandatoryExtension,
+
+    /// The server encountered an unexpected condition
+    InternalError,
+
+    /// A code registered for a library, framework, or application, from 3000 to 4999
+    Other(u16),
+}
+
+impl HTTPWebSocketCloseCode {
+    /// Parses a close code sent by a client
+    ///
+    /// Returns [`None`] for codes which aren't allowed in a close frame.
+    pub(super) fn parse(code: u16) -> Option<Self> {
+        Some(match code {
+            1000 => HTTPWebSocketCloseCode::Normal,
+            1001 => HTTPWebSocketCloseCode::GoingAway,
+            1002 => HTTPWebSocketCloseCode::ProtocolError,
+            1003 => HTTPWebSocketCloseCode::UnsupportedData,
+            1007 => HTTPWebSocketCloseCode::InvalidPayload,
+            1008 => HTTPWebSocketCloseCode::PolicyViolation,
+            1009 => HTTPWebSocketCloseCode::MessageTooBig,
+            1010 => HTTPWebSocketCloseCode::MandatoryExtension,
+            1011 => HTTPWebSocketCloseCode::InternalError,
+            3000..5000 => HTTPWebSocketCloseCode::Other(code),
+            _ => return None,
+        })
+    }
+
+    /// Gets the numeric value of this code
+    pub fn code(self) -> u16 {
+        match self {
+            HTTPWebSocketCloseCode::Normal => 1000,
+            HTTPWebSocketCloseCode::GoingAway => 1001,
+            HTTPWebSocketCloseCode::ProtocolError => 1002,
+            HTTPWebSocketCloseCode::UnsupportedData => 1003,
+            HTTPWebSocketCloseCode::NoStatus => 1005,
+            HTTPWebSocketCloseCode::InvalidPayload => 1007,
+            HTTPWebSocketCloseCode::PolicyViolation => 1008,
+            HTTPWebSocketCloseCode::MessageTooBig => 1009,
+            HTTPWebSocketCloseCode::MandatoryExtension => 1010,
+            HTTPWebSocketCloseCode::InternalError => 1011,
+            HTTPWebSocketCloseCode::Other(code) => code,
+        }
+    }
+}
+
+impl std::fmt::Display for HTTPWebSocketCloseCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.code().fmt(f)
+    }
+}
@@ -0,0 +1,157 @@
+use super::HTTPWebSocketCloseCode;
+
+/// The bit in the first byte of a frame marking the last frame of a message
+const FIN: u8 = 0x80;
+
+/// The bits in the first byte of a frame reserved for extensions
+const RSV: u8 = 0x70;
+
+/// The bit in the second byte of a frame marking that its payload is masked
+const MASK: u8 = 0x80;
+
+/// The largest payload allowed in a control frame
+pub(super) const MAX_CONTROL_PAYLOAD: usize = 125;
+
+/// The type of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Opcode {
+    /// A later fragment of a message
+    Continuation = 0x0,
+
+    /// The first fragment of a text message
+    Text = 0x1,
+
+    /// The first fragment of a binary message
+    Binary = 0x2,
+
+    /// The start or answer of a close handshake
+    Close = 0x8,
+
+    /// A request for a "pong" with the same payload
+    Ping = 0x9,
+
+    /// The answer to a "ping", or a heartbeat nobody has to answer
+    Pong = 0xA,
+}
+
+/// The header of a frame received from a client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct FrameHeader {
+    /// Is this the last frame of its message?
+    pub(super) fin: bool,
+
+    /// The type of the frame
+    pub(super) opcode: Opcode,
+
+    /// The key the payload is masked with
+    pub(super) mask: [u8; 4],
+
+    /// The length of the payload
+    pub(super) length: usize,
+
+    /// The length of this header
+    pub(super) header_length: usize,
+}
+
+impl Opcode {
+    /// Parses the opcode in the low four bits of `byte`
+    fn parse(byte: u8) -> Option<Self> {
+        Some(match byte & 0x0F {
+            0x0 => Opcode::Continuation,
+            0x1 => Opcode::Text,
+            0x2 => Opcode::Binary,
+            0x8 => Opcode::Close,
+            0x9 => Opcode::Ping,
+            0xA => Opcode::Pong,
+            _ => return None,
+        })
+    }
+
+    /// Is this the type of a control frame, which can be sent between fragments of a message?
+    pub(super) fn is_control(self) -> bool {
+        self as u8 & 0x8 != 0
+    }
+}
+
+impl FrameHeader {
+    /// Parses the header of the frame at the start of `input`
+    ///
+    /// Returns [`None`] if `input` doesn't contain the whole header yet. Frames from clients have
+    /// to be masked, and no extensions are negotiated, so the reserved bits have to be clear.
+    pub(super) fn parse(input: &[u8]) -> Result<Option<Self>, HTTPWebSocketCloseCode> {
+        if input.len() < 2 {
+            return Ok(None);
+        }
+
+        if input[0] & RSV != 0 || input[1] & MASK == 0 {
+            return Err(HTTPWebSocketCloseCode::ProtocolError);
+        }
+
+        let fin = input[0] & FIN != 0;
+        let opcode = Opcode::parse(input[0]).ok_or(HTTPWebSocketCloseCode::ProtocolError)?;
+
+        let (length, length_size) = match input[1] & !MASK {
+            126 => match input.get(2..4) {
+                Some(bytes) => (u16::from_be_bytes(bytes.try_into().unwrap()) as u64, 2),
+                None => return Ok(None),
+            },
+            127 => match input.get(2..10) {
+                Some(bytes) => (u64::from_be_bytes(bytes.try_into().unwrap()), 8),
+                None => return Ok(None),
+            },
+            length => (length as u64, 0),
+        };
+
+        // The most significant bit of a 64-bit length has to be clear
+        if length >> 63 != 0 {
+            return Err(HTTPWebSocketCloseCode::ProtocolError);
+        }
+
+        let length = usize::try_from(length).map_err(|_| HTTPWebSocketCloseCode::MessageTooBig)?;
+
+        // Control frames can't be fragmented and have to be small enough to answer immediately
+        if opcode.is_control() && (!fin || length > MAX_CONTROL_PAYLOAD) {
+            return Err(HTTPWebSocketCloseCode::ProtocolError);
+        }
+
+        let header_length = 2 + length_size + 4;
+        let mask = match input.get(2 + length_size..header_length) {
+            Some(mask) => mask.try_into().unwrap(),
+            None => return Ok(None),
+        };
+
+        Ok(Some(FrameHeader {
+            fin,
+            opcode,
+            mask,
+            length,
+            header_length,
+        }))
+    }
+}
+
+/// Removes the mask `key` from `payload`
+pub(super) fn unmask(payload: &mut [u8], key: [u8; 4]) {
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= key[i % 4];
+    }
+}
+
+/// Writes an unmasked frame containing `payload` to `output`
+pub(super) fn write(output: &mut Vec<u8>, fin: bool, opcode: Opcode, payload: &[u8]) {
+    output.push(if fin { FIN } else { 0 } | opcode as u8);
+
+    match payload.len() {
+        length @ 0..126 => output.push(length as u8),
+        length @ 126..65536 => {
+            output.push(126);
+            output.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            output.push(127);
+            output.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+
+    output.extend_from_slice(payload);
+}
@@ -0,0 +1,39 @@
+use super::HTTPWebSocket;
+use std::{future::Future, pin::Pin};
+
+// rustdoc imports
+#[allow(unused_imports)]
+use crate::HTTPResponse;
+
+/// Takes over a connection once it has been upgraded to a WebSocket by
+/// [`HTTPResponse::websocket`]
+pub trait HTTPWebSocketHandler {
+    /// Exchanges messages with the client over `socket`
+    ///
+    /// The connection is closed once this returns, finishing the close handshake if it hasn't
+    /// been already.
+    fn run(self, socket: &mut HTTPWebSocket) -> impl Future<Output = ()>;
+}
+
+/// An [`HTTPWebSocketHandler`] which can be stored in a response without knowing its type
+pub(crate) trait DynHTTPWebSocketHandler {
+    /// Runs the handler over `socket`
+    fn run<'b>(
+        self: Box<Self>,
+        socket: &'b mut HTTPWebSocket<'_>,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'b>>
+    where
+        Self: 'b;
+}
+
+impl<H: HTTPWebSocketHandler> DynHTTPWebSocketHandler for H {
+    fn run<'b>(
+        self: Box<Self>,
+        socket: &'b mut HTTPWebSocket<'_>,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'b>>
+    where
+        Self: 'b,
+    {
+        Box::pin(HTTPWebSocketHandler::run(*self, socket))
+    }
+}
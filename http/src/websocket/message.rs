@@ -0,0 +1,19 @@
+/// A complete message received over a WebSocket, with its fragments joined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HTTPWebSocketMessage {
+    /// A message of UTF-8 text
+    Text(String),
+
+    /// A message of binary data
+    Binary(Vec<u8>),
+}
+
+impl HTTPWebSocketMessage {
+    /// Gets the content of this message
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            HTTPWebSocketMessage::Text(text) => text.as_bytes(),
+            HTTPWebSocketMessage::Binary(data) => data,
+        }
+    }
+}
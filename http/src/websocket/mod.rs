@@ -0,0 +1,16 @@
+//! WebSockets (RFC 6455), which HTTP/1.1 connections can be upgraded to
+
+mod accept;
+mod close;
+mod frame;
+mod handler;
+mod message;
+mod socket;
+
+pub use close::HTTPWebSocketCloseCode;
+pub use handler::HTTPWebSocketHandler;
+pub use message::HTTPWebSocketMessage;
+pub use socket::HTTPWebSocket;
+
+pub(crate) use accept::accept_key;
+pub(crate) use handler::DynHTTPWebSocketHandler;
@@ -0,0 +1,437 @@
+use super::{
+    frame::{self, FrameHeader, Opcode, MAX_CONTROL_PAYLOAD},
+    HTTPWebSocketCloseCode, HTTPWebSocketMessage,
+};
+use crate::{client::HTTPSocket, Error, Result};
+use huntsman::{
+    runtime::{
+        closed_error, protocol_error,
+        time::{timeout, timeout_error},
+    },
+    Shutdown,
+};
+use std::time::Duration;
+
+// rustdoc imports
+#[allow(unused_imports)]
+use crate::HTTPOptions;
+
+/// The number of bytes to read from the client at once
+const READ_SIZE: usize = 4096;
+
+/// A connection upgraded to a WebSocket
+///
+/// Pings from the client are answered automatically and close handshakes started by either side
+/// are finished by the session. Once the server starts shutting down, the session is closed with
+/// [`HTTPWebSocketCloseCode::GoingAway`] and sending to it fails.
+pub struct HTTPWebSocket<'a> {
+    /// The socket of the upgraded connection
+    socket: &'a mut HTTPSocket,
+
+    /// The handle the server is shut down with
+    shutdown: Shutdown,
+
+    /// The bytes received which haven't been parsed into frames yet
+    input: Vec<u8>,
+
+    /// The type of the message whose fragments are being received
+    message_type: Option<Opcode>,
+
+    /// The fragments of the message being received joined so far
+    message: Vec<u8>,
+
+    /// Is a fragmented message being sent?
+    sending: bool,
+
+    /// The maximum size of a received message
+    max_message_size: usize,
+
+    /// The maximum amount of time allowed between reads inside a message
+    read_timeout: Duration,
+
+    /// The maximum amount of time allowed between writes
+    write_timeout: Duration,
+
+    /// Has a close frame been sent?
+    close_sent: bool,
+
+    /// The code of the close frame received from the client, if one has been
+    close_received: Option<HTTPWebSocketCloseCode>,
+
+    /// Has the connection failed, leaving nothing more to send or receive?
+    failed: bool,
+}
+
+impl<'a> HTTPWebSocket<'a> {
+    /// Creates a new [`HTTPWebSocket`] over `socket` after the upgrade has been accepted
+    pub(crate) fn new(
+        socket: &'a mut HTTPSocket,
+        shutdown: Shutdown,
+        max_message_size: usize,
+        read_timeout: Duration,
+        write_timeout: Duration,
+    ) -> Self {
+        HTTPWebSocket {
+            socket,
+            shutdown,
+            input: Vec::new(),
+            message_type: None,
+            message: Vec::new(),
+            sending: false,
+            max_message_size,
+            read_timeout,
+            write_timeout,
+            close_sent: false,
+            close_received: None,
+            failed: false,
+        }
+    }
+
+    /// Has either side started closing this WebSocket, or has the connection failed?
+    pub fn is_closed(&self) -> bool {
+        self.close_sent || self.close_received.is_some() || self.failed
+    }
+
+    /// Gets the code of the close frame the client sent, if it has sent one
+    pub fn close_code(&self) -> Option<HTTPWebSocketCloseCode> {
+        self.close_received
+    }
+
+    /// Waits for the next message from the client
+    ///
+    /// Returns [`None`] once the WebSocket is closed. Messages larger than
+    /// [`HTTPOptions::max_websocket_message_size`] and frames which break the rules of the
+    /// protocol close the WebSocket with an error. The client is allowed up to
+    /// [`HTTPOptions::body_read_timeout`] between reads once a message has started.
+    pub async fn recv(&mut self) -> Result<Option<HTTPWebSocketMessage>> {
+        self.receive(None).await
+    }
+
+    /// Waits for the next message from the client like [`HTTPWebSocket::recv`], giving up if the
+    /// client doesn't start sending one within `duration`
+    ///
+    /// Giving up returns a timeout error but leaves the WebSocket open, so this can be used to send
+    /// messages periodically while waiting for the client.
+    pub async fn recv_timeout(
+        &mut self,
+        duration: Duration,
+    ) -> Result<Option<HTTPWebSocketMessage>> {
+        self.receive(Some(duration)).await
+    }
+
+    /// Sends a text message to the client
+    pub async fn send_text(&mut self, text: &str) -> Result<()> {
+        self.send_message(Opcode::Text, text.as_bytes()).await
+    }
+
+    /// Sends a binary message to the client
+    pub async fn send_binary(&mut self, data: &[u8]) -> Result<()> {
+        self.send_message(Opcode::Binary, data).await
+    }
+
+    /// Sends `data` as the next fragment of a message, which ends with the fragment where `last`
+    /// is true
+    ///
+    /// `text` decides the type of the message and is only used for its first fragment. Other
+    /// messages can't be sent until the fragmented message is finished.
+    pub async fn send_fragment(&mut self, data: &[u8], text: bool, last: bool) -> Result<()> {
+        self.check_open().await?;
+
+        let opcode = match (self.sending, text) {
+            (true, _) => Opcode::Continuation,
+            (false, true) => Opcode::Text,
+            (false, false) => Opcode::Binary,
+        };
+
+        self.sending = !last;
+        self.send_frame(last, opcode, data).await
+    }
+
+    /// Sends a ping to the client, which it should answer with a pong carrying the same `payload`
+    ///
+    /// Pongs are not reported by [`HTTPWebSocket::recv`], but receiving anything shows the client
+    /// is still there. `payload` can't be longer than 125 bytes.
+    pub async fn ping(&mut self, payload: &[u8]) -> Result<()> {
+        assert!(
+            payload.len() <= MAX_CONTROL_PAYLOAD,
+            "\"payload\" cannot be longer than 125 bytes"
+        );
+
+        self.check_open().await?;
+        self.send_frame(true, Opcode::Ping, payload).await
+    }
+
+    /// Closes the WebSocket with `code` and `reason`, waiting for the client to answer
+    ///
+    /// `reason` can't be longer than 123 bytes. Messages received while waiting are discarded.
+    pub async fn close(&mut self, code: HTTPWebSocketCloseCode, reason: &str) -> Result<()> {
+        assert!(
+            reason.len() <= MAX_CONTROL_PAYLOAD - 2,
+            "\"reason\" cannot be longer than 123 bytes"
+        );
+
+        if !self.close_sent && !self.failed {
+            self.send_close(code, reason).await?;
+        }
+
+        while self.close_received.is_none() && !self.failed {
+            self.receive(None).await?;
+        }
+        Ok(())
+    }
+
+    /// Closes the WebSocket once the handler is done with it, if it isn't already
+    pub(crate) async fn finish(mut self) -> Result<()> {
+        let code = if self.shutdown.is_triggered() {
+            HTTPWebSocketCloseCode::GoingAway
+        } else {
+            HTTPWebSocketCloseCode::Normal
+        };
+
+        self.close(code, "").await
+    }
+
+    /// Returns an error if nothing more can be sent
+    ///
+    /// A triggered shutdown starts the close handshake here, so handlers which only send stop too.
+    async fn check_open(&mut self) -> Result<()> {
+        if self.shutdown.is_triggered() && !self.is_closed() {
+            self.send_close(HTTPWebSocketCloseCode::GoingAway, "")
+                .await?;
+        }
+
+        if self.is_closed() {
+            return Err(closed_error());
+        }
+
+        Ok(())
+    }
+
+    /// Sends a complete message of type `opcode`
+    async fn send_message(&mut self, opcode: Opcode, data: &[u8]) -> Result<()> {
+        assert!(
+            !self.sending,
+            "messages cannot be sent until the fragmented message being sent is finished"
+        );
+
+        self.check_open().await?;
+
+        self.send_frame(true, opcode, data).await
+    }
+
+    /// Sends a close frame with `code` and `reason`
+    async fn send_close(&mut self, code: HTTPWebSocketCloseCode, reason: &str) -> Result<()> {
+        let mut payload = Vec::with_capacity(2 + reason.len());
+        if code != HTTPWebSocketCloseCode::NoStatus {
+            payload.extend_from_slice(&code.code().to_be_bytes());
+            payload.extend_from_slice(reason.as_bytes());
+        }
+
+        self.close_sent = true;
+        self.send_frame(true, Opcode::Close, &payload).await
+    }
+
+    /// Sends a single frame
+    async fn send_frame(&mut self, fin: bool, opcode: Opcode, payload: &[u8]) -> Result<()> {
+        let mut frame = Vec::with_capacity(payload.len() + 10);
+        frame::write(&mut frame, fin, opcode, payload);
+
+        let socket = &mut self.socket;
+        let result = timeout(
+            async move { socket.write(&frame).await },
+            self.write_timeout,
+        )
+        .await
+        .and_then(|result| result.unwrap_or(Err(timeout_error())));
+        if result.is_err() {
+            self.failed = true;
+        }
+        result
+    }
+
+    /// Closes the WebSocket because of a problem with what the client sent, returning the error
+    /// to report
+    async fn fail(&mut self, code: HTTPWebSocketCloseCode) -> Error {
+        if !self.close_sent {
+            let _ = self.send_close(code, "").await;
+        }
+
+        self.failed = true;
+        protocol_error()
+    }
+
+    /// Receives frames until a message is complete or the WebSocket is closed
+    ///
+    /// `idle_timeout` limits the wait for the first byte of a frame when no message has started.
+    async fn receive(
+        &mut self,
+        idle_timeout: Option<Duration>,
+    ) -> Result<Option<HTTPWebSocketMessage>> {
+        loop {
+            if self.failed || self.close_received.is_some() {
+                return Ok(None);
+            }
+
+            let header = match FrameHeader::parse(&self.input) {
+                Ok(header) => header,
+                Err(code) => return Err(self.fail(code).await),
+            };
+
+            if let Some(header) = header {
+                if !header.opcode.is_control()
+                    && header.length > self.max_message_size - self.message.len()
+                {
+                    return Err(self.fail(HTTPWebSocketCloseCode::MessageTooBig).await);
+                }
+
+                if self.input.len() - header.header_length >= header.length {
+                    if let Some(message) = self.process(header).await? {
+                        return Ok(Some(message));
+                    }
+                    continue;
+                }
+            }
+
+            if !self.read(idle_timeout).await? {
+                // The client left without a close handshake
+                self.failed = true;
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Reads more bytes from the client into the input
+    ///
+    /// Returns false if the client closed the connection. While no message has started, reads wait
+    /// up to `idle_timeout` and a triggered shutdown starts the close handshake.
+    async fn read(&mut self, idle_timeout: Option<Duration>) -> Result<bool> {
+        let idle = self.input.is_empty() && self.message_type.is_none() && !self.close_sent;
+
+        let mut buffer = [0; READ_SIZE];
+        let socket = &mut self.socket;
+        let count = if idle {
+            let shutdown = &self.shutdown;
+            let read = async { shutdown.run_until(socket.read(&mut buffer)).await };
+            let count = match idle_timeout {
+                Some(idle_timeout) => timeout(read, idle_timeout).await?.ok_or(timeout_error())?,
+                None => read.await,
+            };
+
+            match count {
+                Some(count) => count,
+                None => {
+                    self.send_close(HTTPWebSocketCloseCode::GoingAway, "")
+                        .await?;
+                    return Ok(true);
+                }
+            }
+        } else {
+            match timeout(socket.read(&mut buffer), self.read_timeout).await? {
+                Some(count) => count,
+                None => {
+                    self.failed = true;
+                    return Err(timeout_error());
+                }
+            }
+        };
+
+        let count = match count {
+            Ok(count) => count,
+            Err(error) => {
+                self.failed = true;
+                return Err(error);
+            }
+        };
+
+        self.input.extend_from_slice(&buffer[..count]);
+        Ok(count > 0)
+    }
+
+    /// Handles the complete frame described by `header` at the start of the input
+    ///
+    /// Returns the message this frame finishes, if it finishes one.
+    async fn process(&mut self, header: FrameHeader) -> Result<Option<HTTPWebSocketMessage>> {
+        let end = header.header_length + header.length;
+        let mut payload = self.input[header.header_length..end].to_vec();
+        self.input.drain(..end);
+        frame::unmask(&mut payload, header.mask);
+
+        match header.opcode {
+            Opcode::Ping => {
+                if !self.close_sent {
+                    self.send_frame(true, Opcode::Pong, &payload).await?;
+                }
+                Ok(None)
+            }
+            Opcode::Pong => Ok(None),
+            Opcode::Close => self.process_close(&payload).await.map(|_| None),
+            Opcode::Text | Opcode::Binary => {
+                if self.message_type.is_some() {
+                    return Err(self.fail(HTTPWebSocketCloseCode::ProtocolError).await);
+                }
+
+                self.message_type = Some(header.opcode);
+                self.process_data(header.fin, payload).await
+            }
+            Opcode::Continuation => {
+                if self.message_type.is_none() {
+                    return Err(self.fail(HTTPWebSocketCloseCode::ProtocolError).await);
+                }
+
+                self.process_data(header.fin, payload).await
+            }
+        }
+    }
+
+    /// Adds `payload` to the message being received, returning the message if `fin` finishes it
+    ///
+    /// Messages which arrive after a close frame has been sent are discarded.
+    async fn process_data(
+        &mut self,
+        fin: bool,
+        payload: Vec<u8>,
+    ) -> Result<Option<HTTPWebSocketMessage>> {
+        self.message.extend_from_slice(&payload);
+        if !fin {
+            return Ok(None);
+        }
+
+        let message = std::mem::take(&mut self.message);
+        let message_type = self.message_type.take();
+        if self.close_sent {
+            return Ok(None);
+        }
+
+        match message_type {
+            Some(Opcode::Text) => match String::from_utf8(message) {
+                Ok(text) => Ok(Some(HTTPWebSocketMessage::Text(text))),
+                Err(_) => Err(self.fail(HTTPWebSocketCloseCode::InvalidPayload).await),
+            },
+            _ => Ok(Some(HTTPWebSocketMessage::Binary(message))),
+        }
+    }
+
+    /// Handles a close frame containing `payload`, answering it if the server hasn't sent one
+    async fn process_close(&mut self, payload: &[u8]) -> Result<()> {
+        let code = match payload.len() {
+            0 => HTTPWebSocketCloseCode::NoStatus,
+            1 => return Err(self.fail(HTTPWebSocketCloseCode::ProtocolError).await),
+            _ => {
+                match HTTPWebSocketCloseCode::parse(u16::from_be_bytes([payload[0], payload[1]])) {
+                    Some(code) => code,
+                    None => return Err(self.fail(HTTPWebSocketCloseCode::ProtocolError).await),
+                }
+            }
+        };
+
+        if std::str::from_utf8(&payload[payload.len().min(2)..]).is_err() {
+            return Err(self.fail(HTTPWebSocketCloseCode::InvalidPayload).await);
+        }
+
+        self.close_received = Some(code);
+        if !self.close_sent {
+            self.send_close(code, "").await?;
+        }
+        Ok(())
+    }
+}
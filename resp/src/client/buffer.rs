@@ -0,0 +1,99 @@
+use crate::{request::ParsedRequest, RESPParseError};
+use huntsman::runtime::{io::Read, net::TCPStream, time::timeout};
+use std::time::Duration;
+
+// rustdoc imports
+#[allow(unused_imports)]
+use super::RESPClient;
+
+/// A buffer which commands from a [`RESPClient`] are read into
+///
+/// Pipelined commands stay in the buffer until they are read
+pub struct RESPRequestBuffer {
+    /// The buffer itself
+    buffer: Box<[u8]>,
+
+    /// The number of bytes read into the buffer
+    length: usize,
+
+    /// The length of the command at the start of the buffer which was last returned
+    consumed: usize,
+
+    /// The maximum number of arguments in a command
+    max_arguments: usize,
+
+    /// The maximum time between reads
+    read_timeout: Duration,
+}
+
+impl RESPRequestBuffer {
+    /// Creates a new [`RESPRequestBuffer`] with `capacity` bytes of space
+    pub(super) fn new(capacity: usize, max_arguments: usize, read_timeout: Duration) -> Self {
+        RESPRequestBuffer {
+            buffer: vec![0; capacity].into_boxed_slice(),
+            length: 0,
+            consumed: 0,
+            max_arguments,
+            read_timeout,
+        }
+    }
+
+    /// Gets the bytes between `start` and `end`
+    pub(super) fn get(&self, start: usize, end: usize) -> &[u8] {
+        &self.buffer[start..end]
+    }
+
+    /// Reads from `socket` until the buffer contains a complete command
+    ///
+    /// Returns [`None`] if the client disconnected between commands
+    pub(super) async fn read_request(
+        &mut self,
+        socket: &mut TCPStream,
+    ) -> Result<Option<ParsedRequest>, RESPParseError> {
+        self.discard();
+
+        loop {
+            if let Some(request) =
+                crate::request::parse(&self.buffer[..self.length], self.max_arguments)?
+            {
+                self.consumed = request.length;
+                return Ok(Some(request));
+            }
+
+            if self.length == self.buffer.len() {
+                return Err(RESPParseError::RequestTooLarge);
+            }
+
+            let count = timeout(
+                socket.read(&mut self.buffer[self.length..]),
+                self.read_timeout,
+            )
+            .await?
+            .map(|result| result.map_err(Into::into))
+            .unwrap_or(Err(RESPParseError::ReadTimeout))?;
+
+            if count == 0 {
+                return match self.buffer[..self.length]
+                    .iter()
+                    .all(|byte| byte.is_ascii_whitespace())
+                {
+                    true => Ok(None),
+                    false => Err(RESPParseError::IncompleteRequest),
+                };
+            }
+
+            self.length += count;
+        }
+    }
+
+    /// Removes the last returned command from the start of the buffer
+    fn discard(&mut self) {
+        if self.consumed == 0 {
+            return;
+        }
+
+        self.buffer.copy_within(self.consumed..self.length, 0);
+        self.length -= self.consumed;
+        self.consumed = 0;
+    }
+}
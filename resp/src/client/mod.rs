@@ -0,0 +1,131 @@
+use crate::{Error, RESPOptions, RESPParseError, RESPReply, RESPRequest, RESPVersion};
+use huntsman::{
+    runtime::{
+        io::Write,
+        net::TCPStream,
+        time::{timeout, timeout_error},
+    },
+    ProtocolClient,
+};
+use std::{
+    cell::{Cell, RefCell},
+    time::Duration,
+};
+
+mod buffer;
+
+pub use buffer::RESPRequestBuffer;
+
+/// A client connected to the server
+pub struct RESPClient {
+    /// The socket representing the underlying connection
+    ///
+    /// Commands are answered one at a time, so the socket is never borrowed twice
+    socket: RefCell<TCPStream>,
+
+    /// The version of the protocol the client is using
+    version: Cell<RESPVersion>,
+
+    /// The version requested by the last `HELLO` command, which is switched to once the command
+    /// is answered without an error
+    requested_version: Cell<Option<RESPVersion>>,
+
+    /// The maximum size of a command
+    max_request_size: usize,
+
+    /// The maximum number of arguments in a command
+    max_arguments: usize,
+
+    /// The maximum amount of time allowed between reads
+    read_timeout: Duration,
+
+    /// The maximum amount of time allowed between writes
+    write_timeout: Duration,
+}
+
+impl RESPClient {
+    /// Creates a new [`RESPClient`] on `socket`
+    pub(crate) fn new(socket: TCPStream, options: &RESPOptions) -> Self {
+        RESPClient {
+            socket: RefCell::new(socket),
+            version: Cell::new(RESPVersion::RESP2),
+            requested_version: Cell::new(None),
+            max_request_size: options.max_request_size,
+            max_arguments: options.max_arguments,
+            read_timeout: options.read_timeout,
+            write_timeout: options.write_timeout,
+        }
+    }
+}
+
+impl ProtocolClient for RESPClient {
+    type ReadError = RESPParseError;
+
+    type SendError = Error;
+
+    type Request<'a> = RESPRequest<'a>;
+
+    type Response<'a> = RESPReply<'a>;
+
+    type Buffer = RESPRequestBuffer;
+
+    fn new_buffer(&self) -> Self::Buffer {
+        RESPRequestBuffer::new(self.max_request_size, self.max_arguments, self.read_timeout)
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn read<'a>(
+        &self,
+        _: usize,
+        buffer: &'a mut Self::Buffer,
+    ) -> Result<Option<Self::Request<'a>>, Self::ReadError> {
+        let parsed = match buffer.read_request(&mut self.socket.borrow_mut()).await? {
+            Some(parsed) => parsed,
+            None => return Ok(None),
+        };
+
+        let buffer: &'a RESPRequestBuffer = buffer;
+        let request = RESPRequest::new(
+            parsed
+                .arguments
+                .into_iter()
+                .map(|(start, end)| buffer.get(start, end))
+                .collect(),
+        );
+
+        self.requested_version
+            .set(match request.is_command("HELLO") {
+                true => request.argument(0).and_then(RESPVersion::parse),
+                false => None,
+            });
+
+        Ok(Some(request))
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn send<'a>(
+        &self,
+        _: usize,
+        response: Self::Response<'a>,
+    ) -> Result<(), Self::SendError> {
+        // The reply to "HELLO" is sent using the version it switches to
+        if let Some(version) = self.requested_version.take() {
+            if !response.is_error() {
+                self.version.set(version);
+            }
+        }
+
+        let mut output = Vec::new();
+        response.serialize(self.version.get(), &mut output);
+
+        timeout(
+            self.socket.borrow_mut().write_all(&output),
+            self.write_timeout,
+        )
+        .await?
+        .unwrap_or(Err(timeout_error()))
+    }
+}
+
+unsafe impl Send for RESPClient {}
+unsafe impl Sync for RESPClient {}
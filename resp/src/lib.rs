@@ -0,0 +1,91 @@
+//! RESP (Redis serialization protocol) implementation for huntsman
+//!
+//! Both RESP2 and RESP3 are supported. Clients start on RESP2 and switch to RESP3 when they send a
+//! `HELLO 3` command which isn't answered with an error. Replies using types only found in RESP3
+//! are converted to their closest RESP2 equivalent when sent to a RESP2 client.
+//!
+//! Requests can be parsed into a [`RESPCommand`] for the commands commonly used by caches, while
+//! other commands are handled using the arguments of the [`RESPRequest`].
+
+#![deny(missing_docs)]
+#![deny(rustdoc::private_intra_doc_links)]
+#![deny(rustdoc::unescaped_backticks)]
+#![deny(rustdoc::redundant_explicit_links)]
+#![warn(rustdoc::broken_intra_doc_links)]
+
+use huntsman::Protocol;
+use std::net::SocketAddr;
+
+mod client;
+mod listener;
+mod options;
+mod reply;
+mod request;
+
+pub use client::{RESPClient, RESPRequestBuffer};
+pub use huntsman::runtime::{Error, Result};
+pub use listener::RESPListener;
+pub use options::RESPOptions;
+pub use reply::{RESPReply, RESPVersion};
+pub use request::{
+    RESPCommand, RESPCommandError, RESPExpiry, RESPParseError, RESPRequest, RESPSetCondition,
+    RESPSetOptions,
+};
+
+/// The RESP protocol
+pub struct RESP {
+    /// The sockets for accepting clients
+    listeners: Vec<RESPListener>,
+
+    /// The addresses the server is listening on
+    listen_addresses: Vec<SocketAddr>,
+
+    /// The options to define how this server should run
+    options: RESPOptions,
+}
+
+impl Protocol for RESP {
+    type Options = RESPOptions;
+
+    type ClientAddress = SocketAddr;
+    type Request<'a> = RESPRequest<'a>;
+    type Response<'a> = RESPReply<'a>;
+    type ReadError = RESPParseError;
+    type SendError = Error;
+    type Client = RESPClient;
+
+    type ListenAddress = SocketAddr;
+    type ListenError = Error;
+    type Listener = RESPListener;
+
+    async fn start(addresses: &[Self::ListenAddress], options: Self::Options) -> Result<Self> {
+        let mut listeners = Vec::with_capacity(addresses.len());
+        let mut listen_addresses = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let (listener, listen_address) = RESPListener::new(*address)?;
+            listeners.push(listener);
+            listen_addresses.push(listen_address);
+        }
+
+        Ok(RESP {
+            listeners,
+            listen_addresses,
+            options,
+        })
+    }
+
+    fn addresses(&self) -> &[Self::ListenAddress] {
+        &self.listen_addresses
+    }
+
+    fn listeners(&self) -> &[Self::Listener] {
+        &self.listeners
+    }
+
+    fn options(&self) -> &Self::Options {
+        &self.options
+    }
+}
+
+unsafe impl Send for RESP {}
+unsafe impl Sync for RESP {}
@@ -0,0 +1,33 @@
+use crate::{RESPClient, RESPOptions, Result};
+use huntsman::{runtime::net::TCPListener, ProtocolListener};
+use std::net::SocketAddr;
+
+/// A socket to listen for RESP connections on
+pub struct RESPListener(TCPListener);
+
+impl RESPListener {
+    /// Creates a new [`RESPListener`] bound to `address`
+    pub(crate) fn new(address: SocketAddr) -> Result<(Self, SocketAddr)> {
+        let socket = TCPListener::bind(address)?;
+        let listen_address = socket.local_addr()?;
+        Ok((RESPListener(socket), listen_address))
+    }
+}
+
+impl ProtocolListener for RESPListener {
+    type Address = SocketAddr;
+    type Client = RESPClient;
+    type ClientAddress = SocketAddr;
+    type Error = crate::Error;
+    type Options = RESPOptions;
+
+    async fn accept(
+        &self,
+        options: &Self::Options,
+    ) -> std::result::Result<(Self::Client, Self::ClientAddress), Self::Error> {
+        let (mut socket, address) = self.0.accept().await?;
+        socket.set_nodelay(true)?;
+
+        Ok((RESPClient::new(socket, options), address))
+    }
+}
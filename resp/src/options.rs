@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// The options to determine how RESP will operate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RESPOptions {
+    /// The maximum size of a single command in bytes
+    pub max_request_size: usize,
+
+    /// The maximum number of arguments in a single command, including the command name
+    pub max_arguments: usize,
+
+    /// The maximum amount of time allowed between reads
+    pub read_timeout: Duration,
+
+    /// The maximum amount of time allowed between writes
+    pub write_timeout: Duration,
+}
+
+impl Default for RESPOptions {
+    fn default() -> Self {
+        RESPOptions {
+            max_request_size: 1024 * 1024, // 1 Mb
+            max_arguments: 1024,
+            read_timeout: Duration::from_secs(300),
+            write_timeout: Duration::from_secs(60),
+        }
+    }
+}
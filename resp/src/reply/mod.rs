@@ -0,0 +1,217 @@
+use std::borrow::Cow;
+
+mod version;
+
+pub use version::RESPVersion;
+
+/// A reply sent to a client
+#[derive(Debug, Clone, PartialEq)]
+pub enum RESPReply<'a> {
+    /// A short string which can't contain '\r' or '\n'
+    SimpleString(Cow<'a, str>),
+
+    /// An error message which can't contain '\r' or '\n'
+    ///
+    /// By convention, the message starts with an uppercase error code such as "ERR"
+    Error(Cow<'a, str>),
+
+    /// A signed integer
+    Integer(i64),
+
+    /// A binary safe string
+    BulkString(Cow<'a, [u8]>),
+
+    /// A null value
+    ///
+    /// Sent as a null bulk string to RESP2 clients
+    Null,
+
+    /// An array of replies
+    Array(Vec<RESPReply<'a>>),
+
+    /// A boolean
+    ///
+    /// Sent as the integers 1 or 0 to RESP2 clients
+    Boolean(bool),
+
+    /// A floating point number
+    ///
+    /// Sent as a bulk string to RESP2 clients
+    Double(f64),
+
+    /// An integer too large for [`RESPReply::Integer`], in decimal
+    ///
+    /// Sent as a bulk string to RESP2 clients
+    BigNumber(Cow<'a, str>),
+
+    /// An ordered map of keys to values
+    ///
+    /// Sent as an array of alternating keys and values to RESP2 clients
+    Map(Vec<(RESPReply<'a>, RESPReply<'a>)>),
+
+    /// An unordered collection of unique replies
+    ///
+    /// Sent as an array to RESP2 clients
+    Set(Vec<RESPReply<'a>>),
+
+    /// Out of band data which is not a reply to a command
+    ///
+    /// Sent as an array to RESP2 clients
+    Push(Vec<RESPReply<'a>>),
+}
+
+impl<'a> RESPReply<'a> {
+    /// Creates the "OK" simple string reply
+    pub fn ok() -> Self {
+        RESPReply::SimpleString(Cow::Borrowed("OK"))
+    }
+
+    /// Creates an error reply with the generic "ERR" code and `message`
+    pub fn error<S: std::fmt::Display>(message: S) -> Self {
+        RESPReply::Error(Cow::Owned(format!("ERR {}", message)))
+    }
+
+    /// Is this reply an error?
+    pub fn is_error(&self) -> bool {
+        matches!(self, RESPReply::Error(_))
+    }
+
+    /// Serializes this reply for a client using `version` into `output`
+    pub fn serialize(&self, version: RESPVersion, output: &mut Vec<u8>) {
+        match self {
+            RESPReply::SimpleString(string) => write_line(output, b'+', string),
+            RESPReply::Error(message) => write_line(output, b'-', message),
+            RESPReply::Integer(integer) => write_header(output, b':', *integer),
+            RESPReply::BulkString(string) => write_bulk_string(output, string),
+
+            RESPReply::Null => match version {
+                RESPVersion::RESP2 => output.extend_from_slice(b"$-1\r\n"),
+                RESPVersion::RESP3 => output.extend_from_slice(b"_\r\n"),
+            },
+
+            RESPReply::Array(replies) => write_aggregate(output, b'*', replies, version),
+
+            RESPReply::Boolean(boolean) => match version {
+                RESPVersion::RESP2 => write_header(output, b':', *boolean as i64),
+                RESPVersion::RESP3 => {
+                    output.extend_from_slice(if *boolean { b"#t\r\n" } else { b"#f\r\n" })
+                }
+            },
+
+            RESPReply::Double(double) => {
+                let double = format_double(*double);
+                match version {
+                    RESPVersion::RESP2 => write_bulk_string(output, double.as_bytes()),
+                    RESPVersion::RESP3 => write_line(output, b',', &double),
+                }
+            }
+
+            RESPReply::BigNumber(number) => match version {
+                RESPVersion::RESP2 => write_bulk_string(output, number.as_bytes()),
+                RESPVersion::RESP3 => write_line(output, b'(', number),
+            },
+
+            RESPReply::Map(entries) => {
+                match version {
+                    RESPVersion::RESP2 => write_header(output, b'*', entries.len() as i64 * 2),
+                    RESPVersion::RESP3 => write_header(output, b'%', entries.len() as i64),
+                }
+
+                for (key, value) in entries {
+                    key.serialize(version, output);
+                    value.serialize(version, output);
+                }
+            }
+
+            RESPReply::Set(replies) => match version {
+                RESPVersion::RESP2 => write_aggregate(output, b'*', replies, version),
+                RESPVersion::RESP3 => write_aggregate(output, b'~', replies, version),
+            },
+
+            RESPReply::Push(replies) => match version {
+                RESPVersion::RESP2 => write_aggregate(output, b'*', replies, version),
+                RESPVersion::RESP3 => write_aggregate(output, b'>', replies, version),
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a str> for RESPReply<'a> {
+    fn from(string: &'a str) -> Self {
+        RESPReply::BulkString(Cow::Borrowed(string.as_bytes()))
+    }
+}
+
+impl<'a> From<&'a [u8]> for RESPReply<'a> {
+    fn from(string: &'a [u8]) -> Self {
+        RESPReply::BulkString(Cow::Borrowed(string))
+    }
+}
+
+impl<'a> From<Vec<u8>> for RESPReply<'a> {
+    fn from(string: Vec<u8>) -> Self {
+        RESPReply::BulkString(Cow::Owned(string))
+    }
+}
+
+impl<'a> From<String> for RESPReply<'a> {
+    fn from(string: String) -> Self {
+        RESPReply::BulkString(Cow::Owned(string.into_bytes()))
+    }
+}
+
+impl<'a> From<i64> for RESPReply<'a> {
+    fn from(integer: i64) -> Self {
+        RESPReply::Integer(integer)
+    }
+}
+
+impl<'a, T: Into<RESPReply<'a>>> From<Option<T>> for RESPReply<'a> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => RESPReply::Null,
+        }
+    }
+}
+
+/// Writes a line starting with `prefix`, replacing any line breaks in `line` with spaces
+fn write_line(output: &mut Vec<u8>, prefix: u8, line: &str) {
+    output.push(prefix);
+    output.extend(line.bytes().map(|byte| match byte {
+        b'\r' | b'\n' => b' ',
+        byte => byte,
+    }));
+    output.extend_from_slice(b"\r\n");
+}
+
+/// Writes a line starting with `prefix` containing `value`
+fn write_header(output: &mut Vec<u8>, prefix: u8, value: i64) {
+    write_line(output, prefix, &value.to_string());
+}
+
+/// Writes `string` as a bulk string
+fn write_bulk_string(output: &mut Vec<u8>, string: &[u8]) {
+    write_header(output, b'$', string.len() as i64);
+    output.extend_from_slice(string);
+    output.extend_from_slice(b"\r\n");
+}
+
+/// Writes an aggregate type starting with `prefix` containing `replies`
+fn write_aggregate(output: &mut Vec<u8>, prefix: u8, replies: &[RESPReply], version: RESPVersion) {
+    write_header(output, prefix, replies.len() as i64);
+    for reply in replies {
+        reply.serialize(version, output);
+    }
+}
+
+/// Formats `double` the way RESP3 expects
+fn format_double(double: f64) -> String {
+    if double.is_nan() {
+        "nan".to_owned()
+    } else if double.is_infinite() {
+        if double > 0.0 { "inf" } else { "-inf" }.to_owned()
+    } else {
+        double.to_string()
+    }
+}
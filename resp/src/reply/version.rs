@@ -0,0 +1,29 @@
+/// A version of the RESP protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RESPVersion {
+    /// RESP2, which all clients start with
+    RESP2,
+
+    /// RESP3, which clients switch to using `HELLO 3`
+    RESP3,
+}
+
+impl RESPVersion {
+    /// Parses the protocol version argument of a `HELLO` command
+    pub(crate) fn parse(version: &[u8]) -> Option<Self> {
+        match version {
+            b"2" => Some(RESPVersion::RESP2),
+            b"3" => Some(RESPVersion::RESP3),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RESPVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RESPVersion::RESP2 => write!(f, "RESP2"),
+            RESPVersion::RESP3 => write!(f, "RESP3"),
+        }
+    }
+}
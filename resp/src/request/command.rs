@@ -0,0 +1,341 @@
+use crate::{RESPReply, RESPRequest, RESPVersion};
+use std::borrow::Cow;
+
+#[cfg(test)]
+mod tests;
+
+/// A command commonly used by caches, parsed from a [`RESPRequest`]
+///
+/// Keys and values borrow from the request they were parsed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RESPCommand<'a> {
+    /// `PING [message]`
+    Ping(Option<&'a [u8]>),
+
+    /// `ECHO message`
+    Echo(&'a [u8]),
+
+    /// `HELLO [protover [AUTH username password] [SETNAME clientname]]`
+    Hello {
+        /// The version of the protocol to switch to
+        version: Option<RESPVersion>,
+
+        /// The username and password to authenticate with
+        auth: Option<(&'a [u8], &'a [u8])>,
+
+        /// The name to give the connection
+        name: Option<&'a [u8]>,
+    },
+
+    /// `QUIT`
+    Quit,
+
+    /// `GET key`
+    Get(&'a [u8]),
+
+    /// `SET key value [NX | XX] [GET] [EX seconds | PX milliseconds | EXAT unix-time-seconds |
+    /// PXAT unix-time-milliseconds | KEEPTTL]`
+    Set {
+        /// The key to set
+        key: &'a [u8],
+
+        /// The value to set the key to
+        value: &'a [u8],
+
+        /// The options changing how the key is set
+        options: RESPSetOptions,
+    },
+
+    /// `DEL key [key ...]`
+    Del(Vec<&'a [u8]>),
+
+    /// `EXISTS key [key ...]`
+    Exists(Vec<&'a [u8]>),
+
+    /// `EXPIRE key seconds`
+    Expire {
+        /// The key to expire
+        key: &'a [u8],
+
+        /// The number of seconds until the key expires, which deletes it if it isn't positive
+        seconds: i64,
+    },
+
+    /// `TTL key`
+    TTL(&'a [u8]),
+
+    /// `INCR key`, `DECR key`, `INCRBY key increment`, or `DECRBY key decrement`
+    IncrBy {
+        /// The key holding the integer to change
+        key: &'a [u8],
+
+        /// The amount to add to the integer, which is negative for `DECR` and `DECRBY`
+        increment: i64,
+    },
+
+    /// `MGET key [key ...]`
+    MGet(Vec<&'a [u8]>),
+
+    /// `MSET key value [key value ...]`
+    MSet(Vec<(&'a [u8], &'a [u8])>),
+}
+
+/// The options of a `SET` command
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RESPSetOptions {
+    /// Only set the key if it does or doesn't already exist
+    pub condition: Option<RESPSetCondition>,
+
+    /// Reply with the old value of the key instead of "OK"
+    pub get: bool,
+
+    /// When the key expires, or [`None`] if it never expires
+    pub expiry: Option<RESPExpiry>,
+}
+
+/// The condition for a `SET` command to set its key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RESPSetCondition {
+    /// `NX`, only set the key if it doesn't exist
+    NotExists,
+
+    /// `XX`, only set the key if it already exists
+    Exists,
+}
+
+/// When a key set by a `SET` command expires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RESPExpiry {
+    /// `EX`, a number of seconds from now
+    Seconds(u64),
+
+    /// `PX`, a number of milliseconds from now
+    Milliseconds(u64),
+
+    /// `EXAT`, a Unix time in seconds
+    UnixSeconds(u64),
+
+    /// `PXAT`, a Unix time in milliseconds
+    UnixMilliseconds(u64),
+
+    /// `KEEPTTL`, keep the time the key already expires at
+    KeepTTL,
+}
+
+/// An error while parsing a [`RESPCommand`] from a [`RESPRequest`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RESPCommandError {
+    /// The command is not one of the commands [`RESPCommand`] supports
+    UnknownCommand,
+
+    /// The command has the wrong number of arguments
+    WrongArgumentCount,
+
+    /// An argument which should be an integer isn't, or is out of range
+    NotAnInteger,
+
+    /// An expiry time is not positive
+    InvalidExpireTime,
+
+    /// The arguments don't follow the syntax of the command
+    SyntaxError,
+
+    /// `HELLO` requested a protocol version which isn't supported
+    UnsupportedVersion,
+}
+
+impl<'a> RESPCommand<'a> {
+    /// Parses `request` into a command
+    ///
+    /// Command names and options are matched ignoring case. Requests for other commands return
+    /// [`RESPCommandError::UnknownCommand`], and can still be handled using the arguments of the
+    /// request.
+    pub fn parse(request: &RESPRequest<'a>) -> Result<Self, RESPCommandError> {
+        let name = request.command().to_ascii_uppercase();
+        let arguments = request.arguments();
+
+        Ok(match (name.as_slice(), arguments) {
+            (b"PING", []) => RESPCommand::Ping(None),
+            (b"PING", [message]) => RESPCommand::Ping(Some(message)),
+            (b"ECHO", [message]) => RESPCommand::Echo(message),
+            (b"HELLO", _) => parse_hello(arguments)?,
+            (b"QUIT", []) => RESPCommand::Quit,
+            (b"GET", [key]) => RESPCommand::Get(key),
+            (b"SET", [key, value, options @ ..]) => RESPCommand::Set {
+                key,
+                value,
+                options: RESPSetOptions::parse(options)?,
+            },
+            (b"DEL", [_, ..]) => RESPCommand::Del(arguments.to_vec()),
+            (b"EXISTS", [_, ..]) => RESPCommand::Exists(arguments.to_vec()),
+            (b"EXPIRE", [key, seconds]) => RESPCommand::Expire {
+                key,
+                seconds: parse_integer(seconds)?,
+            },
+            (b"TTL", [key]) => RESPCommand::TTL(key),
+            (b"INCR", [key]) => RESPCommand::IncrBy { key, increment: 1 },
+            (b"DECR", [key]) => RESPCommand::IncrBy { key, increment: -1 },
+            (b"INCRBY", [key, increment]) => RESPCommand::IncrBy {
+                key,
+                increment: parse_integer(increment)?,
+            },
+            (b"DECRBY", [key, decrement]) => RESPCommand::IncrBy {
+                key,
+                increment: parse_integer(decrement)?
+                    .checked_neg()
+                    .ok_or(RESPCommandError::NotAnInteger)?,
+            },
+            (b"MGET", [_, ..]) => RESPCommand::MGet(arguments.to_vec()),
+            (b"MSET", [_, _, ..]) if arguments.len().is_multiple_of(2) => {
+                RESPCommand::MSet(arguments.chunks(2).map(|pair| (pair[0], pair[1])).collect())
+            }
+
+            (
+                b"PING" | b"ECHO" | b"QUIT" | b"GET" | b"SET" | b"DEL" | b"EXISTS" | b"EXPIRE"
+                | b"TTL" | b"INCR" | b"DECR" | b"INCRBY" | b"DECRBY" | b"MGET" | b"MSET",
+                _,
+            ) => return Err(RESPCommandError::WrongArgumentCount),
+            _ => return Err(RESPCommandError::UnknownCommand),
+        })
+    }
+}
+
+impl RESPSetOptions {
+    /// Parses the options following the key and value of a `SET` command
+    fn parse(arguments: &[&[u8]]) -> Result<Self, RESPCommandError> {
+        let mut options = RESPSetOptions::default();
+
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            let option = argument.to_ascii_uppercase();
+            match option.as_slice() {
+                b"NX" if options.condition.is_none() => {
+                    options.condition = Some(RESPSetCondition::NotExists)
+                }
+                b"XX" if options.condition.is_none() => {
+                    options.condition = Some(RESPSetCondition::Exists)
+                }
+                b"GET" if !options.get => options.get = true,
+                b"KEEPTTL" if options.expiry.is_none() => {
+                    options.expiry = Some(RESPExpiry::KeepTTL)
+                }
+                b"EX" | b"PX" | b"EXAT" | b"PXAT" if options.expiry.is_none() => {
+                    let time = arguments.next().ok_or(RESPCommandError::SyntaxError)?;
+                    let time = parse_integer(time)?;
+                    if time <= 0 {
+                        return Err(RESPCommandError::InvalidExpireTime);
+                    }
+
+                    let time = time as u64;
+                    options.expiry = Some(match option.as_slice() {
+                        b"EX" => RESPExpiry::Seconds(time),
+                        b"PX" => RESPExpiry::Milliseconds(time),
+                        b"EXAT" => RESPExpiry::UnixSeconds(time),
+                        _ => RESPExpiry::UnixMilliseconds(time),
+                    });
+                }
+                _ => return Err(RESPCommandError::SyntaxError),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl RESPCommandError {
+    /// Creates the reply Redis sends for this error in answer to `request`
+    pub fn reply(&self, request: &RESPRequest) -> RESPReply<'static> {
+        let name = String::from_utf8_lossy(request.command()).to_lowercase();
+
+        match self {
+            RESPCommandError::UnknownCommand => {
+                let mut message = format!(
+                    "unknown command '{}', with args beginning with: ",
+                    String::from_utf8_lossy(request.command())
+                );
+                for argument in request.arguments() {
+                    message.push_str(&format!("'{}' ", String::from_utf8_lossy(argument)));
+                }
+                RESPReply::error(message)
+            }
+            RESPCommandError::WrongArgumentCount => {
+                RESPReply::error(format!("wrong number of arguments for '{}' command", name))
+            }
+            RESPCommandError::NotAnInteger => {
+                RESPReply::error("value is not an integer or out of range")
+            }
+            RESPCommandError::InvalidExpireTime => {
+                RESPReply::error(format!("invalid expire time in '{}' command", name))
+            }
+            RESPCommandError::SyntaxError => RESPReply::error("syntax error"),
+            RESPCommandError::UnsupportedVersion => {
+                RESPReply::Error(Cow::Borrowed("NOPROTO unsupported protocol version"))
+            }
+        }
+    }
+}
+
+impl std::error::Error for RESPCommandError {}
+
+impl std::fmt::Display for RESPCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RESPCommandError::UnknownCommand => write!(f, "unknown command"),
+            RESPCommandError::WrongArgumentCount => write!(f, "wrong number of arguments"),
+            RESPCommandError::NotAnInteger => write!(f, "value is not an integer or out of range"),
+            RESPCommandError::InvalidExpireTime => write!(f, "invalid expire time"),
+            RESPCommandError::SyntaxError => write!(f, "syntax error"),
+            RESPCommandError::UnsupportedVersion => write!(f, "unsupported protocol version"),
+        }
+    }
+}
+
+/// Parses the arguments of a `HELLO` command
+fn parse_hello<'a>(arguments: &[&'a [u8]]) -> Result<RESPCommand<'a>, RESPCommandError> {
+    let (version, options) = match arguments.split_first() {
+        Some((version, options)) => {
+            parse_integer(version)?;
+            let version =
+                RESPVersion::parse(version).ok_or(RESPCommandError::UnsupportedVersion)?;
+            (Some(version), options)
+        }
+        None => (None, arguments),
+    };
+
+    let mut auth = None;
+    let mut name = None;
+
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"AUTH" => match (options.next(), options.next()) {
+                (Some(username), Some(password)) => auth = Some((*username, *password)),
+                _ => return Err(RESPCommandError::SyntaxError),
+            },
+            b"SETNAME" => match options.next() {
+                Some(client_name) => name = Some(*client_name),
+                None => return Err(RESPCommandError::SyntaxError),
+            },
+            _ => return Err(RESPCommandError::SyntaxError),
+        }
+    }
+
+    Ok(RESPCommand::Hello {
+        version,
+        auth,
+        name,
+    })
+}
+
+/// Parses an integer argument
+fn parse_integer(argument: &[u8]) -> Result<i64, RESPCommandError> {
+    // Redis doesn't accept a leading '+'
+    if argument.first() == Some(&b'+') {
+        return Err(RESPCommandError::NotAnInteger);
+    }
+
+    std::str::from_utf8(argument)
+        .ok()
+        .and_then(|argument| argument.parse().ok())
+        .ok_or(RESPCommandError::NotAnInteger)
+}
@@ -0,0 +1,107 @@
+use super::*;
+
+/// Parses a command from its arguments
+fn parse(arguments: &[&'static [u8]]) -> Result<RESPCommand<'static>, RESPCommandError> {
+    RESPCommand::parse(&RESPRequest::new(arguments.to_vec()))
+}
+
+#[test]
+fn names_ignore_case() {
+    assert_eq!(parse(&[b"get", b"key"]), Ok(RESPCommand::Get(b"key")));
+    assert_eq!(parse(&[b"PiNg"]), Ok(RESPCommand::Ping(None)));
+}
+
+#[test]
+fn set_options() {
+    assert_eq!(
+        parse(&[b"SET", b"key", b"value", b"nx", b"GET", b"PX", b"100"]),
+        Ok(RESPCommand::Set {
+            key: b"key",
+            value: b"value",
+            options: RESPSetOptions {
+                condition: Some(RESPSetCondition::NotExists),
+                get: true,
+                expiry: Some(RESPExpiry::Milliseconds(100)),
+            },
+        })
+    );
+
+    assert_eq!(
+        parse(&[b"SET", b"key", b"value", b"NX", b"XX"]),
+        Err(RESPCommandError::SyntaxError)
+    );
+    assert_eq!(
+        parse(&[b"SET", b"key", b"value", b"EX", b"1", b"KEEPTTL"]),
+        Err(RESPCommandError::SyntaxError)
+    );
+    assert_eq!(
+        parse(&[b"SET", b"key", b"value", b"EX"]),
+        Err(RESPCommandError::SyntaxError)
+    );
+    assert_eq!(
+        parse(&[b"SET", b"key", b"value", b"EX", b"0"]),
+        Err(RESPCommandError::InvalidExpireTime)
+    );
+}
+
+#[test]
+fn integers() {
+    assert_eq!(
+        parse(&[b"DECRBY", b"key", b"-5"]),
+        Ok(RESPCommand::IncrBy {
+            key: b"key",
+            increment: 5
+        })
+    );
+    assert_eq!(
+        parse(&[b"INCRBY", b"key", b"+5"]),
+        Err(RESPCommandError::NotAnInteger)
+    );
+    assert_eq!(
+        parse(&[b"DECRBY", b"key", b"-9223372036854775808"]),
+        Err(RESPCommandError::NotAnInteger)
+    );
+}
+
+#[test]
+fn argument_counts() {
+    assert_eq!(parse(&[b"GET"]), Err(RESPCommandError::WrongArgumentCount));
+    assert_eq!(
+        parse(&[b"MSET", b"a", b"1", b"b"]),
+        Err(RESPCommandError::WrongArgumentCount)
+    );
+    assert_eq!(
+        parse(&[b"MSET", b"a", b"1", b"b", b"2"]),
+        Ok(RESPCommand::MSet(vec![(b"a", b"1"), (b"b", b"2")]))
+    );
+    assert_eq!(parse(&[b"FLUSHALL"]), Err(RESPCommandError::UnknownCommand));
+}
+
+#[test]
+fn hello() {
+    assert_eq!(
+        parse(&[b"HELLO", b"3", b"AUTH", b"user", b"pass", b"SETNAME", b"name"]),
+        Ok(RESPCommand::Hello {
+            version: Some(RESPVersion::RESP3),
+            auth: Some((b"user", b"pass")),
+            name: Some(b"name"),
+        })
+    );
+    assert_eq!(
+        parse(&[b"HELLO", b"4"]),
+        Err(RESPCommandError::UnsupportedVersion)
+    );
+    assert_eq!(
+        parse(&[b"HELLO", b"three"]),
+        Err(RESPCommandError::NotAnInteger)
+    );
+}
+
+#[test]
+fn error_replies() {
+    let request = RESPRequest::new(vec![b"GET"]);
+    assert_eq!(
+        RESPCommandError::WrongArgumentCount.reply(&request),
+        RESPReply::error("wrong number of arguments for 'get' command")
+    );
+}
@@ -0,0 +1,74 @@
+/// An error while parsing a RESP command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RESPParseError {
+    /// The number of arguments in a command is not a valid number
+    InvalidMultibulkLength,
+
+    /// The length of an argument is not a valid number
+    InvalidBulkLength,
+
+    /// An argument does not start with '$'
+    ExpectedBulkString,
+
+    /// An argument is not followed by "\r\n"
+    ExpectedLineEnd,
+
+    /// The command has more arguments than allowed
+    TooManyArguments,
+
+    /// The command is too large to fit into the buffer
+    RequestTooLarge,
+
+    /// The client sent an incomplete command before disconnecting
+    IncompleteRequest,
+
+    /// The client took too long to send a command
+    ReadTimeout,
+
+    /// An I/O error occurred while parsing a command
+    IO(crate::Error),
+}
+
+impl std::error::Error for RESPParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RESPParseError::IO(error) => Some(error),
+
+            RESPParseError::InvalidMultibulkLength
+            | RESPParseError::InvalidBulkLength
+            | RESPParseError::ExpectedBulkString
+            | RESPParseError::ExpectedLineEnd
+            | RESPParseError::TooManyArguments
+            | RESPParseError::RequestTooLarge
+            | RESPParseError::IncompleteRequest
+            | RESPParseError::ReadTimeout => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RESPParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RESPParseError::InvalidMultibulkLength => write!(f, "invalid multibulk length"),
+            RESPParseError::InvalidBulkLength => write!(f, "invalid bulk length"),
+            RESPParseError::ExpectedBulkString => write!(f, "expected '$'"),
+            RESPParseError::ExpectedLineEnd => write!(f, "expected \"\\r\\n\""),
+            RESPParseError::TooManyArguments => write!(f, "too many arguments"),
+            RESPParseError::RequestTooLarge => write!(f, "request too large"),
+            RESPParseError::IncompleteRequest => write!(f, "request is incomplete"),
+            RESPParseError::ReadTimeout => write!(f, "reading request timed out"),
+
+            RESPParseError::IO(error) => write!(
+                f,
+                "an I/O error occurred while parsing a request - {}",
+                error
+            ),
+        }
+    }
+}
+
+impl From<crate::Error> for RESPParseError {
+    fn from(error: crate::Error) -> Self {
+        RESPParseError::IO(error)
+    }
+}
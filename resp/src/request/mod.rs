@@ -0,0 +1,64 @@
+mod command;
+mod error;
+mod parse;
+
+pub use command::{RESPCommand, RESPCommandError, RESPExpiry, RESPSetCondition, RESPSetOptions};
+pub use error::RESPParseError;
+
+pub(crate) use parse::{parse, ParsedRequest};
+
+/// A command sent by a client
+///
+/// Commands always contain at least one argument, the name of the command
+pub struct RESPRequest<'a> {
+    /// The arguments of the command, starting with its name
+    arguments: Vec<&'a [u8]>,
+}
+
+impl<'a> RESPRequest<'a> {
+    /// Creates a new [`RESPRequest`] from its `arguments`
+    pub(crate) fn new(arguments: Vec<&'a [u8]>) -> Self {
+        assert!(!arguments.is_empty());
+
+        RESPRequest { arguments }
+    }
+
+    /// Gets the name of the command
+    pub fn command(&self) -> &'a [u8] {
+        self.arguments[0]
+    }
+
+    /// Is this the command named `name`, ignoring case?
+    pub fn is_command(&self, name: &str) -> bool {
+        self.command().eq_ignore_ascii_case(name.as_bytes())
+    }
+
+    /// Gets the arguments of the command, not including its name
+    pub fn arguments(&self) -> &[&'a [u8]] {
+        &self.arguments[1..]
+    }
+
+    /// Gets the argument at `index`, not including the name of the command
+    pub fn argument(&self, index: usize) -> Option<&'a [u8]> {
+        self.arguments().get(index).copied()
+    }
+
+    /// Parses this request into a typed [`RESPCommand`]
+    pub fn parse_command(&self) -> Result<RESPCommand<'a>, RESPCommandError> {
+        RESPCommand::parse(self)
+    }
+}
+
+impl<'a> std::fmt::Display for RESPRequest<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, argument) in self.arguments.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", String::from_utf8_lossy(argument))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use crate::RESPParseError;
+
+/// The location of a command in a buffer
+pub(crate) struct ParsedRequest {
+    /// The start and end of each argument
+    pub(crate) arguments: Vec<(usize, usize)>,
+
+    /// The number of bytes the command takes up, including any empty commands before it
+    pub(crate) length: usize,
+}
+
+/// Parses the next command from `buffer`
+///
+/// Both multibulk commands and inline commands are accepted. Empty commands are skipped. Returns
+/// [`None`] if `buffer` doesn't contain a complete command yet.
+pub(crate) fn parse(
+    buffer: &[u8],
+    max_arguments: usize,
+) -> Result<Option<ParsedRequest>, RESPParseError> {
+    let mut index = 0;
+
+    loop {
+        let ParsedRequest { arguments, length } = match buffer.get(index) {
+            Some(b'*') => match parse_multibulk(&buffer[index..], max_arguments)? {
+                Some(command) => command,
+                None => return Ok(None),
+            },
+            Some(_) => match parse_inline(&buffer[index..], max_arguments)? {
+                Some(command) => command,
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+
+        let start = index;
+        index += length;
+
+        if !arguments.is_empty() {
+            return Ok(Some(ParsedRequest {
+                arguments: arguments
+                    .into_iter()
+                    .map(|(argument_start, argument_end)| {
+                        (start + argument_start, start + argument_end)
+                    })
+                    .collect(),
+                length: index,
+            }));
+        }
+    }
+}
+
+/// Parses a multibulk command, which is an array of bulk strings
+fn parse_multibulk(
+    buffer: &[u8],
+    max_arguments: usize,
+) -> Result<Option<ParsedRequest>, RESPParseError> {
+    let (count, mut index) = match parse_integer(buffer, 1) {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+    let count = count.ok_or(RESPParseError::InvalidMultibulkLength)?;
+
+    // Redis treats non-positive counts as empty commands
+    if count <= 0 {
+        return Ok(Some(ParsedRequest {
+            arguments: Vec::new(),
+            length: index,
+        }));
+    }
+    if count as u64 > max_arguments as u64 {
+        return Err(RESPParseError::TooManyArguments);
+    }
+
+    let mut arguments = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        match buffer.get(index) {
+            Some(b'$') => {}
+            Some(_) => return Err(RESPParseError::ExpectedBulkString),
+            None => return Ok(None),
+        }
+
+        let (length, start) = match parse_integer(buffer, index + 1) {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        let length = match length {
+            Some(length) if length >= 0 => length as usize,
+            _ => return Err(RESPParseError::InvalidBulkLength),
+        };
+
+        let end = match start.checked_add(length) {
+            Some(end) => end,
+            None => return Err(RESPParseError::RequestTooLarge),
+        };
+        if buffer.len() < end + 2 {
+            return Ok(None);
+        }
+        if &buffer[end..end + 2] != b"\r\n" {
+            return Err(RESPParseError::ExpectedLineEnd);
+        }
+
+        arguments.push((start, end));
+        index = end + 2;
+    }
+
+    Ok(Some(ParsedRequest {
+        arguments,
+        length: index,
+    }))
+}
+
+/// Parses an inline command, which is a line of arguments separated by whitespace
+fn parse_inline(
+    buffer: &[u8],
+    max_arguments: usize,
+) -> Result<Option<ParsedRequest>, RESPParseError> {
+    let line_end = match buffer.iter().position(|byte| *byte == b'\n') {
+        Some(line_end) => line_end,
+        None => return Ok(None),
+    };
+
+    let mut arguments = Vec::new();
+    let mut start = None;
+    for (i, byte) in buffer[..line_end].iter().enumerate() {
+        match (byte.is_ascii_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(argument_start)) => {
+                arguments.push((argument_start, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(argument_start) = start {
+        arguments.push((argument_start, line_end));
+    }
+
+    if arguments.len() > max_arguments {
+        return Err(RESPParseError::TooManyArguments);
+    }
+
+    Ok(Some(ParsedRequest {
+        arguments,
+        length: line_end + 1,
+    }))
+}
+
+/// Parses an integer starting at `index` which is terminated by "\r\n"
+///
+/// Returns the integer, or [`None`] if it is invalid, and the index after the line. Returns
+/// [`None`] if the line isn't complete yet.
+fn parse_integer(buffer: &[u8], index: usize) -> Option<(Option<i64>, usize)> {
+    let line_end = index
+        + buffer[index..]
+            .windows(2)
+            .position(|window| window == b"\r\n")?;
+
+    let value = std::str::from_utf8(&buffer[index..line_end])
+        .ok()
+        .and_then(|value| value.parse().ok());
+
+    Some((value, line_end + 2))
+}
@@ -10,10 +10,13 @@ pub trait App: 'static + Send + Sync {
     type Client;
 
     /// Handle a request from a client
+    ///
+    /// The returned response can borrow from the `request` it answers. Protocols which allow it
+    /// can have more than one request from the same client handled at the same time.
     fn handle_request<'a, 'b>(
         self: &'a Arc<Self>,
-        client: &'a mut Self::Client,
-        request: <Self::Protocol as Protocol>::Request<'b>,
+        client: &'a Self::Client,
+        request: &'a mut <Self::Protocol as Protocol>::Request<'b>,
     ) -> impl Future<Output = <Self::Protocol as Protocol>::Response<'a>>;
 
     /// Called when the server starts
@@ -25,6 +28,43 @@ pub trait App: 'static + Send + Sync {
         async {}
     }
 
+    /// Called when the server has stopped, after every worker has drained its connections
+    fn on_server_stop(self: &Arc<Self>) -> impl Future<Output = ()> {
+        async {}
+    }
+
+    /// Called on each worker thread before it starts accepting clients
+    ///
+    /// `worker` is the index of the worker, with the thread [`crate::run`] was called on being 0
+    #[allow(unused_variables)]
+    fn on_worker_start(self: &Arc<Self>, worker: usize) -> impl Future<Output = ()> {
+        async {}
+    }
+
+    /// Called on each worker thread once it has stopped accepting clients and all of its
+    /// connections have closed
+    #[allow(unused_variables)]
+    fn on_worker_stop(self: &Arc<Self>, worker: usize) -> impl Future<Output = ()> {
+        async {}
+    }
+
+    /// Called when a reload is requested through the control channel
+    ///
+    /// This is called on the thread of worker 0, between handling its clients. Returns a message
+    /// describing the error if the reload failed
+    fn on_reload(self: &Arc<Self>) -> impl Future<Output = Result<(), String>> {
+        async { Err("this app does not support reloading".to_owned()) }
+    }
+
+    /// Called when a new log level is requested through the control channel
+    ///
+    /// This is called on the thread of worker 0, between handling its clients. Returns a message
+    /// describing the error if the log level couldn't be changed
+    #[allow(unused_variables)]
+    fn on_log_level(self: &Arc<Self>, level: &str) -> impl Future<Output = Result<(), String>> {
+        async { Err("this app does not support changing the log level".to_owned()) }
+    }
+
     /// Called when a client connects to the server
     ///
     /// Returns [`None`] if the client should be rejected
@@ -56,7 +96,7 @@ pub trait App: 'static + Send + Sync {
     #[allow(unused_variables)]
     fn read_error<'a>(
         self: &'a Arc<Self>,
-        client: &'a mut Self::Client,
+        client: &'a Self::Client,
         error: <Self::Protocol as Protocol>::ReadError,
     ) -> impl Future<Output = Option<<Self::Protocol as Protocol>::Response<'a>>> {
         async { None }
@@ -66,7 +106,7 @@ pub trait App: 'static + Send + Sync {
     #[allow(unused_variables)]
     fn send_error(
         self: &Arc<Self>,
-        client: &mut Self::Client,
+        client: &Self::Client,
         error: <Self::Protocol as Protocol>::SendError,
     ) -> impl Future<Output = ()> {
         async {}
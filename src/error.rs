@@ -1,13 +1,16 @@
 /// An error during the runtime of the server
 pub enum StartError<Protocol: crate::Protocol> {
     /// The error occurred while starting the asynchronous runtime
-    Async(lasync::Error),
+    Async(crate::runtime::Error),
 
     /// The error occurred while the protocol was starting the listen sockets
     Protocol(Protocol::ListenError),
 
     /// The error occurred while a worker was being spawned
     Worker(std::io::Error),
+
+    /// The error occurred while the control channel was being started
+    Control(std::io::Error),
 }
 
 impl<Protocol: crate::Protocol> std::error::Error for StartError<Protocol> {
@@ -16,6 +19,7 @@ impl<Protocol: crate::Protocol> std::error::Error for StartError<Protocol> {
             StartError::Async(error) => Some(error),
             StartError::Protocol(error) => Some(error),
             StartError::Worker(error) => Some(error),
+            StartError::Control(error) => Some(error),
         }
     }
 }
@@ -26,6 +30,9 @@ impl<Protocol: crate::Protocol> std::fmt::Display for StartError<Protocol> {
             StartError::Async(error) => write!(f, "unable to start the runtime - {}", error),
             StartError::Protocol(error) => write!(f, "unable to start the server - {}", error),
             StartError::Worker(error) => write!(f, "unable to spawn a worker - {}", error),
+            StartError::Control(error) => {
+                write!(f, "unable to start the control channel - {}", error)
+            }
         }
     }
 }
@@ -36,8 +43,8 @@ impl<Protocol: crate::Protocol> std::fmt::Debug for StartError<Protocol> {
     }
 }
 
-impl<Protocol: crate::Protocol> From<lasync::Error> for StartError<Protocol> {
-    fn from(error: lasync::Error) -> Self {
+impl<Protocol: crate::Protocol> From<crate::runtime::Error> for StartError<Protocol> {
+    fn from(error: crate::runtime::Error) -> Self {
         StartError::Async(error)
     }
 }
@@ -13,7 +13,9 @@ mod error;
 mod protocol;
 mod runner;
 
+pub mod runtime;
+
 pub use app::App;
 pub use error::StartError;
 pub use protocol::{Protocol, ProtocolClient, ProtocolListener};
-pub use runner::{run, Options};
+pub use runner::{run, Options, Shutdown};
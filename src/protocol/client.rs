@@ -1,3 +1,4 @@
+use crate::Shutdown;
 use std::{error::Error, future::Future};
 
 /// A client connection
@@ -14,14 +15,47 @@ pub trait ProtocolClient: 'static + Sized + Send {
     /// A response sent to a client
     type Response<'a>;
 
-    /// Attempt to read and parse the next request from the client
+    /// The buffer requests are read into
+    ///
+    /// Requests borrow from this buffer instead of the client so that responses can borrow from
+    /// the request they answer while being sent on the client
+    type Buffer;
+
+    /// Creates a buffer for reading requests from this client into
+    ///
+    /// A buffer is created for each request which can be handled at the same time
+    fn new_buffer(&self) -> Self::Buffer;
+
+    /// The most requests from this client which can be handled at the same time
+    ///
+    /// Each request is read and answered in a slot, numbered from 0 up to this limit. Slots run
+    /// concurrently, so clients allowing more than one have to support reading a request in one
+    /// slot while another slot is sending its response. The next request is only read once the
+    /// last one has been read, so reads never overlap.
+    fn max_concurrent_requests(&self) -> usize {
+        1
+    }
+
+    /// Attempt to read and parse the next request from the client into `buffer`
+    ///
+    /// The request is answered by a call to [`ProtocolClient::send`] with the same `slot`
     fn read<'a>(
-        &'a mut self,
+        &self,
+        slot: usize,
+        buffer: &'a mut Self::Buffer,
     ) -> impl Future<Output = Result<Option<Self::Request<'a>>, Self::ReadError>>;
 
-    /// Send this response on `transport`
+    /// Gives this client the handle the server is shut down with before its first request is read
+    ///
+    /// Clients which can keep their connection busy outside of reading requests, such as after
+    /// upgrading it to another protocol, should use this to finish once the shutdown is triggered.
+    #[allow(unused_variables)]
+    fn set_shutdown(&mut self, shutdown: Shutdown) {}
+
+    /// Send this response to the request read in `slot`
     fn send<'a>(
-        &mut self,
+        &self,
+        slot: usize,
         response: Self::Response<'a>,
     ) -> impl Future<Output = Result<(), Self::SendError>>;
 }
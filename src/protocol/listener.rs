@@ -23,4 +23,18 @@ pub trait ProtocolListener {
         &self,
         options: &Self::Options,
     ) -> impl Future<Output = Result<(Self::Client, Self::ClientAddress), Self::Error>>;
+
+    /// Finishes connecting a client accepted on this socket, such as by completing a handshake
+    ///
+    /// This is run separately from [`ProtocolListener::accept`] so slow clients can't stop other
+    /// clients from being accepted. The client is dropped if this returns an error.
+    #[allow(unused_variables)]
+    fn connect(
+        &self,
+        client: &mut Self::Client,
+        address: &mut Self::ClientAddress,
+        options: &Self::Options,
+    ) -> impl Future<Output = Result<(), Self::Error>> {
+        async { Ok(()) }
+    }
 }
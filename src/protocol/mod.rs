@@ -12,7 +12,7 @@ pub trait Protocol: 'static + Sized + Send + Sync {
     type Options;
 
     /// The address of a connecting client
-    type ClientAddress: Send;
+    type ClientAddress: Send + std::fmt::Display;
 
     /// Parser for requests from a client
     type Request<'a>;
@@ -0,0 +1,60 @@
+/// A command sent over the control channel
+pub(super) enum Command<'a> {
+    /// List the available commands
+    Help,
+
+    /// List the open connections
+    Connections,
+
+    /// Report statistics about the server
+    Stats,
+
+    /// Stop accepting clients and shut down once every connection has closed
+    Drain,
+
+    /// Ask the app to reload
+    Reload,
+
+    /// Ask the app to change its log level
+    LogLevel(&'a str),
+}
+
+/// The text sent in response to [`Command::Help`]
+pub(super) const HELP: &str = "\
+help               List the available commands
+connections        List the open connections with their ids, workers, ages, and peers
+stats              Report statistics about the server
+drain              Stop accepting clients and shut down once every connection has closed
+reload             Ask the app to reload
+log-level LEVEL    Ask the app to change its log level to LEVEL";
+
+impl<'a> Command<'a> {
+    /// Parses a command from a `line` sent over the control channel
+    ///
+    /// Returns an error message if the line is not a valid command
+    pub(super) fn parse(line: &'a str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = match words.next() {
+            Some(name) => name,
+            None => return Err("missing command".to_owned()),
+        };
+
+        let command = match name {
+            "help" => Command::Help,
+            "connections" => Command::Connections,
+            "stats" => Command::Stats,
+            "drain" => Command::Drain,
+            "reload" => Command::Reload,
+            "log-level" => match words.next() {
+                Some(level) => Command::LogLevel(level),
+                None => return Err("missing LEVEL for log-level".to_owned()),
+            },
+            _ => return Err(format!("unknown command \"{}\", try \"help\"", name)),
+        };
+
+        match words.next() {
+            Some(_) => Err(format!("too many arguments for \"{}\"", name)),
+            None => Ok(command),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    sync::{
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    task::{Poll, Waker},
+};
+
+/// An app hook requested through the control channel
+pub(super) enum Hook {
+    /// Call [`crate::App::on_reload`]
+    Reload,
+
+    /// Call [`crate::App::on_log_level`] with the level
+    LogLevel(String),
+}
+
+/// The hooks requested by control connections, waiting to be run on a worker
+///
+/// Control connections are handled on their own threads, but app hooks are asynchronous and have
+/// to run on a runtime. Instead of starting a runtime for each command, the hooks are sent to the
+/// first worker, which runs them between handling its clients.
+pub(super) struct HookQueue(Mutex<HookQueueState>);
+
+/// The state of a [`HookQueue`]
+struct HookQueueState {
+    /// The hooks waiting to be run and where to send their results
+    hooks: VecDeque<(Hook, Sender<Result<(), String>>)>,
+
+    /// The waker of the worker task waiting for hooks
+    waker: Option<Waker>,
+
+    /// Has the control server stopped?
+    closed: bool,
+}
+
+impl HookQueue {
+    /// Creates a new empty [`HookQueue`]
+    pub(super) fn new() -> Arc<Self> {
+        Arc::new(HookQueue(Mutex::new(HookQueueState {
+            hooks: VecDeque::new(),
+            waker: None,
+            closed: false,
+        })))
+    }
+
+    /// Runs `hook` on the worker, blocking the current thread until it finishes
+    ///
+    /// Returns an error message if the hook failed or the server stopped before running it
+    pub(super) fn run(&self, hook: Hook) -> Result<(), String> {
+        let (sender, receiver) = mpsc::channel();
+
+        {
+            let mut state = self.0.lock().unwrap();
+            if state.closed {
+                return Err("the server is stopping".to_owned());
+            }
+
+            state.hooks.push_back((hook, sender));
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+
+        receiver
+            .recv()
+            .unwrap_or_else(|_| Err("the server stopped before the command finished".to_owned()))
+    }
+
+    /// Runs the hooks on `app` as they are requested until the queue is closed
+    pub(super) async fn run_hooks<App: crate::App>(&self, app: Arc<App>) {
+        while let Some((hook, sender)) = self.next().await {
+            let result = match hook {
+                Hook::Reload => app.on_reload().await,
+                Hook::LogLevel(level) => app.on_log_level(&level).await,
+            };
+
+            let _ = sender.send(result);
+        }
+    }
+
+    /// Stops accepting hooks, failing any which haven't been run yet
+    pub(super) fn close(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.closed = true;
+        state.hooks.clear();
+
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Waits for the next hook to run
+    ///
+    /// Returns [`None`] once the queue is closed
+    async fn next(&self) -> Option<(Hook, Sender<Result<(), String>>)> {
+        poll_fn(|cx| {
+            let mut state = self.0.lock().unwrap();
+            if let Some(hook) = state.hooks.pop_front() {
+                return Poll::Ready(Some(hook));
+            }
+
+            if state.closed {
+                return Poll::Ready(None);
+            }
+
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
@@ -0,0 +1,228 @@
+use super::Stats;
+use crate::Shutdown;
+use command::{Command, HELP};
+use hooks::{Hook, HookQueue};
+use std::{
+    fs::Permissions,
+    future::Future,
+    io::{BufRead, BufReader, ErrorKind, Write},
+    os::unix::{
+        fs::PermissionsExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+mod command;
+mod hooks;
+
+#[cfg(test)]
+mod tests;
+
+/// The permissions of the control socket, which only let the user running the server connect
+const SOCKET_MODE: u32 = 0o600;
+
+/// The most control connections which can be handled at once, each on its own thread
+const MAX_CONNECTIONS: usize = 16;
+
+/// A local Unix socket operators can use to inspect and control a running server
+///
+/// Each line sent to the socket is a command, which is answered by its output followed by a line
+/// of either "ok" or "error: " and a message.
+pub(super) struct ControlServer {
+    /// The path of the socket
+    path: PathBuf,
+
+    /// Has the control server been told to stop?
+    stopped: Arc<AtomicBool>,
+
+    /// The thread accepting connections
+    thread: JoinHandle<()>,
+
+    /// The app hooks requested by control connections
+    hooks: Arc<HookQueue>,
+}
+
+/// The values the commands of a control connection act on
+struct Context {
+    /// The statistics of the server
+    stats: Arc<Stats>,
+
+    /// The handle used to drain the server
+    shutdown: Shutdown,
+
+    /// The queue app hooks are sent to a worker through
+    hooks: Arc<HookQueue>,
+
+    /// The number of control connections currently being handled
+    connections: AtomicUsize,
+}
+
+impl ControlServer {
+    /// Starts listening for control connections on `path`
+    ///
+    /// A stale socket left at `path` by a previous server is replaced, but a socket which is
+    /// still being listened on is not. Only the user running the server can connect to the socket.
+    pub(super) fn start(
+        path: &Path,
+        stats: Arc<Stats>,
+        shutdown: Shutdown,
+    ) -> std::io::Result<Self> {
+        let listener = match UnixListener::bind(path) {
+            Ok(listener) => listener,
+            Err(error) if error.kind() == ErrorKind::AddrInUse => {
+                if UnixStream::connect(path).is_ok() {
+                    return Err(error);
+                }
+
+                std::fs::remove_file(path)?;
+                UnixListener::bind(path)?
+            }
+            Err(error) => return Err(error),
+        };
+        std::fs::set_permissions(path, Permissions::from_mode(SOCKET_MODE))?;
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let hooks = HookQueue::new();
+        let context = Arc::new(Context {
+            stats,
+            shutdown,
+            hooks: hooks.clone(),
+            connections: AtomicUsize::new(0),
+        });
+
+        let child_stopped = stopped.clone();
+        let thread = std::thread::Builder::new()
+            .name("control".to_owned())
+            .spawn(move || accept_connections(listener, context, child_stopped))?;
+
+        Ok(ControlServer {
+            path: path.to_owned(),
+            stopped,
+            thread,
+            hooks,
+        })
+    }
+
+    /// Creates the task which runs the app hooks requested by control connections
+    ///
+    /// The task has to be run on a worker for commands which call the app, like "reload", to
+    /// finish. It completes once the control server is stopped.
+    pub(super) fn hook_runner<App: crate::App>(&self, app: Arc<App>) -> impl Future<Output = ()> {
+        let hooks = self.hooks.clone();
+        async move { hooks.run_hooks(app).await }
+    }
+
+    /// Stops accepting control connections and removes the socket
+    pub(super) fn stop(self) {
+        self.hooks.close();
+        self.stopped.store(true, Ordering::Release);
+
+        // Wake the control thread so it sees it has been stopped
+        drop(UnixStream::connect(&self.path));
+        let _ = self.thread.join();
+
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Accepts control connections until the control server is stopped
+fn accept_connections(listener: UnixListener, context: Arc<Context>, stopped: Arc<AtomicBool>) {
+    for stream in listener.incoming() {
+        if stopped.load(Ordering::Acquire) {
+            return;
+        }
+
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+
+        if context.connections.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+            context.connections.fetch_sub(1, Ordering::AcqRel);
+            let _ = writeln!(stream, "error: too many control connections");
+            continue;
+        }
+
+        let child_context = context.clone();
+        let spawn_result = std::thread::Builder::new()
+            .name("control connection".to_owned())
+            .spawn(move || {
+                let _ = handle_connection(stream, &child_context);
+                child_context.connections.fetch_sub(1, Ordering::AcqRel);
+            });
+
+        if spawn_result.is_err() {
+            context.connections.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// Answers the commands sent on a control connection until it closes
+fn handle_connection(stream: UnixStream, context: &Context) -> std::io::Result<()> {
+    let mut output = stream.try_clone()?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match Command::parse(&line).and_then(|command| execute(command, context, &mut output)) {
+            Ok(()) => writeln!(output, "ok")?,
+            Err(message) => writeln!(output, "error: {}", message)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Executes `command`, writing its output to `output`
+///
+/// Returns an error message if the command failed
+fn execute<W: Write>(command: Command, context: &Context, output: &mut W) -> Result<(), String> {
+    let stats = &context.stats;
+
+    match command {
+        Command::Help => writeln!(output, "{}", HELP),
+        Command::Connections => {
+            let mut result = Ok(());
+            stats.for_each_connection(|connection| {
+                if result.is_ok() {
+                    result = writeln!(
+                        output,
+                        "{}\tworker {}\t{:.1}s\t{}",
+                        connection.id,
+                        connection.worker,
+                        connection.connected.elapsed().as_secs_f64(),
+                        connection.peer
+                    );
+                }
+            });
+            result
+        }
+        Command::Stats => writeln!(
+            output,
+            "uptime: {:.1}s\nworkers: {}\nactive connections: {}\naccepted connections: {}\nrejected connections: {}\nrequests: {}\ndraining: {}",
+            stats.uptime().as_secs_f64(),
+            stats.workers(),
+            stats.active(),
+            stats.accepted(),
+            stats.rejected(),
+            stats.requests(),
+            context.shutdown.is_triggered()
+        ),
+        Command::Drain => {
+            context.shutdown.trigger();
+            Ok(())
+        }
+        Command::Reload => return context.hooks.run(Hook::Reload),
+        Command::LogLevel(level) => return context.hooks.run(Hook::LogLevel(level.to_owned())),
+    }
+    .map_err(|error| error.to_string())
+}
@@ -0,0 +1,216 @@
+use super::{
+    execute, handle_connection, Command, Context, ControlServer, HookQueue, MAX_CONNECTIONS,
+};
+use crate::{runner::Stats, Shutdown};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    os::unix::{fs::PermissionsExt, net::UnixStream},
+    path::PathBuf,
+    sync::{atomic::AtomicUsize, Arc},
+};
+
+/// Creates the context of a control connection for a server with two workers
+fn context() -> Context {
+    Context {
+        stats: Arc::new(Stats::new(2)),
+        shutdown: Shutdown::new(),
+        hooks: HookQueue::new(),
+        connections: AtomicUsize::new(0),
+    }
+}
+
+/// Executes the command on `line`, returning its output or error message
+fn run(line: &str, context: &Context) -> Result<String, String> {
+    let mut output = Vec::new();
+    Command::parse(line).and_then(|command| execute(command, context, &mut output))?;
+    Ok(String::from_utf8(output).unwrap())
+}
+
+/// Gets a path for a control socket which no other test uses
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("huntsman-{}-{}.sock", std::process::id(), name))
+}
+
+#[test]
+fn commands_are_parsed() {
+    assert!(matches!(Command::parse("help"), Ok(Command::Help)));
+    assert!(matches!(
+        Command::parse("connections"),
+        Ok(Command::Connections)
+    ));
+    assert!(matches!(Command::parse(" stats "), Ok(Command::Stats)));
+    assert!(matches!(Command::parse("drain"), Ok(Command::Drain)));
+    assert!(matches!(Command::parse("reload"), Ok(Command::Reload)));
+    assert!(matches!(
+        Command::parse("log-level  debug"),
+        Ok(Command::LogLevel("debug"))
+    ));
+}
+
+#[test]
+fn invalid_commands_are_rejected() {
+    let error = |line| Command::parse(line).err().unwrap();
+
+    assert_eq!(error(""), "missing command");
+    assert_eq!(
+        error("restart"),
+        "unknown command \"restart\", try \"help\""
+    );
+    assert_eq!(error("HELP"), "unknown command \"HELP\", try \"help\"");
+    assert_eq!(error("log-level"), "missing LEVEL for log-level");
+    assert_eq!(error("stats now"), "too many arguments for \"stats\"");
+    assert_eq!(
+        error("log-level debug trace"),
+        "too many arguments for \"log-level\""
+    );
+}
+
+#[test]
+fn help_lists_every_command() {
+    let output = run("help", &context()).unwrap();
+
+    for command in [
+        "help",
+        "connections",
+        "stats",
+        "drain",
+        "reload",
+        "log-level",
+    ] {
+        assert!(output.lines().any(|line| line.starts_with(command)));
+    }
+}
+
+#[test]
+fn connections_are_listed_oldest_first() {
+    let context = context();
+    let first = context.stats.connect(1, "127.0.0.1:5000".to_owned());
+    let second = context.stats.connect(0, "[::1]:6000".to_owned());
+    let third = context.stats.connect(1, "127.0.0.1:7000".to_owned());
+    context.stats.disconnect(second);
+
+    let output = run("connections", &context).unwrap();
+    let lines: Vec<Vec<&str>> = output
+        .lines()
+        .map(|line| line.split('\t').collect())
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0][0], first.to_string());
+    assert_eq!(lines[0][1], "worker 1");
+    assert_eq!(lines[0][3], "127.0.0.1:5000");
+    assert_eq!(lines[1][0], third.to_string());
+    assert_eq!(lines[1][3], "127.0.0.1:7000");
+}
+
+#[test]
+fn stats_are_reported() {
+    let context = context();
+    let id = context.stats.connect(0, "127.0.0.1:5000".to_owned());
+    context.stats.connect(1, "127.0.0.1:6000".to_owned());
+    context.stats.disconnect(id);
+    context.stats.reject();
+    context.stats.request();
+    context.stats.request();
+
+    let output = run("stats", &context).unwrap();
+    let lines: Vec<&str> = output.lines().skip(1).collect();
+
+    assert!(output.starts_with("uptime: "));
+    assert_eq!(
+        lines,
+        [
+            "workers: 2",
+            "active connections: 1",
+            "accepted connections: 2",
+            "rejected connections: 1",
+            "requests: 2",
+            "draining: false",
+        ]
+    );
+}
+
+#[test]
+fn drain_shuts_down_the_server() {
+    let context = context();
+
+    assert_eq!(run("drain", &context).unwrap(), "");
+    assert!(context.shutdown.is_triggered());
+    assert!(run("stats", &context)
+        .unwrap()
+        .ends_with("draining: true\n"));
+}
+
+#[test]
+fn app_commands_fail_once_the_server_stops() {
+    let context = context();
+    context.hooks.close();
+
+    assert_eq!(
+        run("reload", &context).unwrap_err(),
+        "the server is stopping"
+    );
+    assert_eq!(
+        run("log-level debug", &context).unwrap_err(),
+        "the server is stopping"
+    );
+}
+
+#[test]
+fn each_line_is_answered() {
+    let context = context();
+    let (server, mut client) = UnixStream::pair().unwrap();
+
+    client.write_all(b"drain\n\nbogus\nstats now\n").unwrap();
+    client.shutdown(std::net::Shutdown::Write).unwrap();
+    handle_connection(server, &context).unwrap();
+
+    let mut output = String::new();
+    client.read_to_string(&mut output).unwrap();
+    assert_eq!(
+        output,
+        "ok\nerror: unknown command \"bogus\", try \"help\"\nerror: too many arguments for \"stats\"\n"
+    );
+}
+
+#[test]
+fn only_the_owner_can_use_the_socket() {
+    let path = socket_path("mode");
+    let control = ControlServer::start(&path, Arc::new(Stats::new(1)), Shutdown::new()).unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    control.stop();
+
+    assert_eq!(mode & 0o777, 0o600);
+    assert!(!path.exists());
+}
+
+#[test]
+fn connections_past_the_limit_are_refused() {
+    let path = socket_path("limit");
+    let control = ControlServer::start(&path, Arc::new(Stats::new(1)), Shutdown::new()).unwrap();
+
+    // Each connection is answered once it is being handled
+    let mut connections = Vec::new();
+    for _ in 0..MAX_CONNECTIONS {
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream.write_all(b"drain\n").unwrap();
+
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "ok\n");
+
+        connections.push(reader);
+    }
+
+    let mut refused = String::new();
+    UnixStream::connect(&path)
+        .unwrap()
+        .read_to_string(&mut refused)
+        .unwrap();
+    assert_eq!(refused, "error: too many control connections\n");
+
+    drop(connections);
+    control.stop();
+}
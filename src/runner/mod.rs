@@ -1,15 +1,27 @@
-use crate::StartError;
-use lasync::FutureQueue;
-use std::{num::NonZeroUsize, sync::Arc};
+use crate::{
+    runtime::{self, FutureQueue},
+    StartError,
+};
+use control::ControlServer;
+use stats::Stats;
+use std::{panic::AssertUnwindSafe, sync::Arc};
 
+mod control;
 mod options;
+mod shutdown;
+mod stats;
 mod worker;
 
-pub use options::Options;
+#[cfg(all(test, feature = "simulation"))]
+mod tests;
 
-const NUM_EVENTS: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(8192) };
+pub use options::Options;
+pub use shutdown::Shutdown;
 
 /// Run a huntsman server on the current thread
+///
+/// Returns once the server has been shut down using the [`Shutdown`] from `huntsman_options`. If
+/// a worker panics, the panic is resumed on this thread once the other workers have stopped.
 pub fn run<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>>(
     app: App,
     huntsman_options: Options<Protocol>,
@@ -23,7 +35,7 @@ pub fn run<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>>(
         result = async_run(app, huntsman_options, protocol_options, child_future_queue).await;
     });
 
-    lasync::run_queue(NUM_EVENTS, future_queue)?;
+    runtime::run_queue(future_queue)?;
 
     result
 }
@@ -34,6 +46,14 @@ pub async fn async_run<'a, Protocol: crate::Protocol, App: crate::App<Protocol =
     protocol_options: Protocol::Options,
     future_queue: FutureQueue<'a>,
 ) -> Result<(), StartError<Protocol>> {
+    // A simulation is local to the thread it was started on
+    if cfg!(feature = "simulation") && huntsman_options.workers().get() > 1 {
+        return Err(StartError::Worker(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the simulation runtime only supports one worker",
+        )));
+    }
+
     // Create the listener
     let listener = Protocol::start(huntsman_options.addresses(), protocol_options)
         .await
@@ -43,20 +63,111 @@ pub async fn async_run<'a, Protocol: crate::Protocol, App: crate::App<Protocol =
     let app = Arc::new(app);
     let listener = Arc::new(listener);
     let connections_per_worker = huntsman_options.connections_per_worker();
+    let shutdown = huntsman_options.shutdown().clone();
+
+    // Start the control channel, which is the only user of the statistics
+    let (control, stats) = match huntsman_options.control_path() {
+        Some(path) => {
+            let stats = Arc::new(Stats::new(huntsman_options.workers().get()));
+            let control = ControlServer::start(path, stats.clone(), shutdown.clone())
+                .map_err(StartError::Control)?;
+            (Some(control), Some(stats))
+        }
+        None => (None, None),
+    };
 
     // Signal the server start
     app.on_server_start(listener.addresses()).await;
 
     // Create workers
-    for i in 0..huntsman_options.workers().get() - 1 {
+    let mut workers = Vec::with_capacity(huntsman_options.workers().get() - 1);
+    for i in 1..huntsman_options.workers().get() {
         let child_listener = listener.clone();
         let child_app = app.clone();
+        let child_shutdown = shutdown.clone();
+        let child_stats = stats.clone();
+
+        // Triggered once the worker has stopped, even if it panicked, so it can be waited on
+        // without blocking this thread
+        let stopped = Shutdown::new();
+        let child_stopped = stopped.clone();
+
+        let spawn_result = std::thread::Builder::new()
+            .name(format!("worker {}", i))
+            .spawn(move || {
+                let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    worker::run(
+                        child_app,
+                        child_listener,
+                        connections_per_worker,
+                        child_shutdown,
+                        child_stats,
+                        i,
+                    )
+                }));
+                child_stopped.trigger();
+                result
+            });
+
+        let handle = match spawn_result {
+            Ok(handle) => handle,
+            Err(error) => {
+                // The workers which did start are stopped before the error is returned
+                shutdown.trigger();
+                join_workers(workers).await;
+                if let Some(control) = control {
+                    control.stop();
+                }
+                return Err(StartError::Worker(error));
+            }
+        };
+
+        workers.push((handle, stopped));
+    }
 
-        std::thread::Builder::new()
-            .name(format!("worker {}", i + 1))
-            .spawn(move || worker::run(child_app, child_listener, connections_per_worker))?;
+    // App hooks requested through the control channel run on this worker
+    if let Some(control) = &control {
+        future_queue.push(control.hook_runner(app.clone()));
     }
 
-    worker::accept_clients(app, listener, connections_per_worker, &future_queue);
+    worker::run_worker(
+        app.clone(),
+        listener,
+        connections_per_worker,
+        shutdown,
+        stats,
+        0,
+        future_queue,
+    )
+    .await;
+
+    // The other workers finish once they have drained their connections
+    let panic = join_workers(workers).await;
+
+    if let Some(control) = control {
+        control.stop();
+    }
+
+    // A worker crashing isn't a clean shutdown, so its panic is passed on instead
+    if let Some(payload) = panic {
+        std::panic::resume_unwind(payload);
+    }
+
+    // Signal the server stop
+    app.on_server_stop().await;
     Ok(())
 }
+
+/// Waits for each worker in `workers` to stop, returning the first panic any of them raised
+async fn join_workers(
+    workers: Vec<(std::thread::JoinHandle<std::thread::Result<()>>, Shutdown)>,
+) -> Option<Box<dyn std::any::Any + Send>> {
+    let mut panic = None;
+    for (handle, stopped) in workers {
+        stopped.wait().await;
+        if let Err(payload) = handle.join().and_then(|result| result) {
+            panic.get_or_insert(payload);
+        }
+    }
+    panic
+}
@@ -1,4 +1,8 @@
-use std::num::NonZeroUsize;
+use crate::Shutdown;
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
 
 /// The settings for the huntsman server
 #[derive(Debug, PartialEq, Eq)]
@@ -11,12 +15,25 @@ pub struct Options<Protocol: crate::Protocol> {
 
     /// The address to listen for connections on
     addresses: Vec<Protocol::ListenAddress>,
+
+    /// The handle used to shut the server down
+    shutdown: Shutdown,
+
+    /// The path of the Unix socket to listen for control commands on
+    control_path: Option<PathBuf>,
 }
 
 impl<Protocol: crate::Protocol> Options<Protocol> {
     /// Gets the number of workers to handle connections
+    ///
+    /// Defaults to the available parallelism, or to 1 on the "simulation" runtime, which only
+    /// supports a single worker
     pub fn workers(&self) -> NonZeroUsize {
         self.workers.unwrap_or_else(|| {
+            if cfg!(feature = "simulation") {
+                return NonZeroUsize::new(1).unwrap();
+            }
+
             std::thread::available_parallelism().unwrap_or(NonZeroUsize::new(1).unwrap())
         })
     }
@@ -31,6 +48,16 @@ impl<Protocol: crate::Protocol> Options<Protocol> {
         &self.addresses
     }
 
+    /// Gets the handle used to shut the server down
+    pub fn shutdown(&self) -> &Shutdown {
+        &self.shutdown
+    }
+
+    /// Gets the path of the Unix socket to listen for control commands on
+    pub fn control_path(&self) -> Option<&Path> {
+        self.control_path.as_deref()
+    }
+
     /// Sets the number of workers to handle connections
     pub fn set_workers(&mut self, workers: NonZeroUsize) {
         self.workers = Some(workers);
@@ -46,6 +73,18 @@ impl<Protocol: crate::Protocol> Options<Protocol> {
         self.addresses.push(address);
     }
 
+    /// Sets the handle used to shut the server down
+    pub fn set_shutdown(&mut self, shutdown: Shutdown) {
+        self.shutdown = shutdown;
+    }
+
+    /// Sets the path of the Unix socket to listen for control commands on
+    ///
+    /// The control channel is disabled if this is [`None`], which is the default
+    pub fn set_control_path(&mut self, control_path: Option<PathBuf>) {
+        self.control_path = control_path;
+    }
+
     /// Gets the address to listen for connections on mutably
     pub fn addresses_mut(&mut self) -> &mut Vec<Protocol::ListenAddress> {
         &mut self.addresses
@@ -58,6 +97,8 @@ impl<Protocol: crate::Protocol> Default for Options<Protocol> {
             workers: None,
             connections_per_worker: NonZeroUsize::new(64).unwrap(),
             addresses: Vec::new(),
+            shutdown: Shutdown::new(),
+            control_path: None,
         }
     }
 }
@@ -72,6 +113,8 @@ impl<
             workers: self.workers.clone(),
             connections_per_worker: self.connections_per_worker.clone(),
             addresses: self.addresses.clone(),
+            shutdown: self.shutdown.clone(),
+            control_path: self.control_path.clone(),
         }
     }
 }
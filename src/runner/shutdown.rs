@@ -0,0 +1,156 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::{pin, Pin},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+/// A handle used to gracefully shut down a running server
+///
+/// Once triggered, workers stop accepting clients, close connections once their current request
+/// has been answered, and stop after all of their connections have closed.
+#[derive(Clone)]
+pub struct Shutdown(Arc<ShutdownState>);
+
+/// The state shared between all handles of a [`Shutdown`]
+struct ShutdownState {
+    /// Has the shutdown been triggered?
+    triggered: AtomicBool,
+
+    /// The wakers of the futures waiting for the shutdown
+    waiters: Mutex<Waiters>,
+}
+
+/// The futures waiting for a shutdown to be triggered
+#[derive(Default)]
+struct Waiters {
+    /// The wakers of the waiting futures, keyed by the id of the future
+    wakers: HashMap<usize, Waker>,
+
+    /// The id to give the next waiting future
+    next_id: usize,
+}
+
+/// A future which completes once a [`Shutdown`] is triggered
+struct ShutdownWait {
+    /// The state of the shutdown being waited on
+    state: Arc<ShutdownState>,
+
+    /// The id this future has been registered with, if it has been polled
+    id: Option<usize>,
+}
+
+impl Shutdown {
+    /// Creates a new [`Shutdown`] which has not been triggered
+    pub fn new() -> Self {
+        Shutdown(Arc::new(ShutdownState {
+            triggered: AtomicBool::new(false),
+            waiters: Mutex::new(Waiters::default()),
+        }))
+    }
+
+    /// Signals the server to start shutting down
+    pub fn trigger(&self) {
+        self.0.triggered.store(true, Ordering::Release);
+
+        let wakers = std::mem::take(&mut self.0.waiters.lock().unwrap().wakers);
+        for (_, waker) in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Has the shutdown been triggered?
+    pub fn is_triggered(&self) -> bool {
+        self.0.triggered.load(Ordering::Acquire)
+    }
+
+    /// Waits until the shutdown is triggered
+    pub async fn wait(&self) {
+        ShutdownWait {
+            state: self.0.clone(),
+            id: None,
+        }
+        .await
+    }
+
+    /// Runs `future` until it completes or the shutdown is triggered
+    ///
+    /// Returns [`None`] if the shutdown was triggered first
+    pub async fn run_until<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut future = pin!(future);
+        let mut wait = pin!(self.wait());
+
+        std::future::poll_fn(|cx| {
+            if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                return Poll::Ready(Some(output));
+            }
+
+            match wait.as_mut().poll(cx) {
+                Poll::Ready(()) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown::new()
+    }
+}
+
+impl PartialEq for Shutdown {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Shutdown {}
+
+impl std::fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Shutdown")
+            .field("triggered", &self.is_triggered())
+            .finish()
+    }
+}
+
+impl Future for ShutdownWait {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let wait = self.get_mut();
+        if wait.state.triggered.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        let mut waiters = wait.state.waiters.lock().unwrap();
+
+        // The shutdown may have been triggered while acquiring the lock
+        if wait.state.triggered.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        let id = *wait.id.get_or_insert_with(|| {
+            let id = waiters.next_id;
+            waiters.next_id += 1;
+            id
+        });
+        waiters.wakers.insert(id, cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+impl Drop for ShutdownWait {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.state.waiters.lock().unwrap().wakers.remove(&id);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Statistics about a running server, shared between its workers and the control channel
+pub(super) struct Stats {
+    /// The time the server started
+    started: Instant,
+
+    /// The number of workers the server is running
+    workers: usize,
+
+    /// The currently open connections, keyed by their id
+    connections: Mutex<HashMap<u64, Connection>>,
+
+    /// The id to give the next connection
+    next_id: AtomicU64,
+
+    /// The number of clients which have been accepted
+    accepted: AtomicU64,
+
+    /// The number of clients which have been rejected by the app
+    rejected: AtomicU64,
+
+    /// The number of requests which have been answered
+    requests: AtomicU64,
+}
+
+/// An open connection
+pub(super) struct Connection {
+    /// The id of the connection
+    pub id: u64,
+
+    /// The index of the worker handling the connection
+    pub worker: usize,
+
+    /// The address of the client
+    pub peer: String,
+
+    /// The time the client connected
+    pub connected: Instant,
+}
+
+impl Stats {
+    /// Creates a new [`Stats`] for a server running `workers` workers
+    pub(super) fn new(workers: usize) -> Self {
+        Stats {
+            started: Instant::now(),
+            workers,
+            connections: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            accepted: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            requests: AtomicU64::new(0),
+        }
+    }
+
+    /// Records a client from `peer` being accepted on `worker`, returning the id of its connection
+    pub(super) fn connect(&self, worker: usize, peer: String) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+
+        self.connections.lock().unwrap().insert(
+            id,
+            Connection {
+                id,
+                worker,
+                peer,
+                connected: Instant::now(),
+            },
+        );
+
+        id
+    }
+
+    /// Records a client being rejected by the app
+    pub(super) fn reject(&self) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a request being answered
+    pub(super) fn request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the connection with `id` closing
+    pub(super) fn disconnect(&self, id: u64) {
+        self.connections.lock().unwrap().remove(&id);
+    }
+
+    /// Calls `f` with each open connection, from oldest to newest
+    pub(super) fn for_each_connection<F: FnMut(&Connection)>(&self, f: F) {
+        let connections = self.connections.lock().unwrap();
+
+        let mut connections: Vec<_> = connections.values().collect();
+        connections.sort_by_key(|connection| connection.id);
+        connections.into_iter().for_each(f);
+    }
+
+    /// Gets how long the server has been running
+    pub(super) fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Gets the number of workers the server is running
+    pub(super) fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Gets the number of open connections
+    pub(super) fn active(&self) -> usize {
+        self.connections.lock().unwrap().len()
+    }
+
+    /// Gets the number of clients which have been accepted
+    pub(super) fn accepted(&self) -> u64 {
+        self.accepted.load(Ordering::Relaxed)
+    }
+
+    /// Gets the number of clients which have been rejected by the app
+    pub(super) fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Gets the number of requests which have been answered
+    pub(super) fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,459 @@
+use crate::{
+    runtime::{
+        io::{Read, Write},
+        net::{self, TCPListener, TCPStream},
+        simulation,
+        time::{sleep, timeout, timeout_error},
+        Error, Result,
+    },
+    App, Options, Protocol, ProtocolClient, ProtocolListener, Shutdown,
+};
+use std::{
+    cell::RefCell,
+    net::{Ipv4Addr, SocketAddr},
+    num::NonZeroUsize,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// The address the test server listens on
+const ADDRESS: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::LOCALHOST), 7000);
+
+/// A protocol of newline terminated requests, each answered by a single line
+struct Lines {
+    /// The addresses the server is listening on
+    addresses: Vec<SocketAddr>,
+
+    /// The sockets listening for clients
+    listeners: Vec<LinesListener>,
+
+    /// The options the server was started with
+    options: LinesOptions,
+}
+
+/// The options for [`Lines`]
+struct LinesOptions {
+    /// The longest a client can take to send a line
+    read_timeout: Duration,
+}
+
+/// A socket listening for [`Lines`] clients
+struct LinesListener(TCPListener);
+
+/// A client connected to a [`Lines`] server
+struct LinesClient {
+    /// The connection to the client
+    ///
+    /// Only one request is handled at a time, so the socket is never borrowed twice
+    socket: RefCell<TCPStream>,
+
+    /// The longest the client can take to send a line
+    read_timeout: Duration,
+}
+
+/// An app which echoes every line back, recording what happens to its clients
+struct Echo(Arc<Record>);
+
+/// What has happened to the clients of an [`Echo`] app
+struct Record {
+    /// The events which have happened and the simulated times they happened at
+    events: Mutex<Vec<(Duration, String)>>,
+
+    /// The number of clients currently connected
+    active: Mutex<usize>,
+
+    /// The most clients which have been connected at once
+    max_active: Mutex<usize>,
+}
+
+impl Protocol for Lines {
+    type Options = LinesOptions;
+    type ClientAddress = SocketAddr;
+    type Request<'a> = &'a [u8];
+    type Response<'a> = Vec<u8>;
+    type ReadError = Error;
+    type SendError = Error;
+    type Client = LinesClient;
+    type ListenAddress = SocketAddr;
+    type ListenError = Error;
+    type Listener = LinesListener;
+
+    async fn start(addresses: &[SocketAddr], options: LinesOptions) -> Result<Self> {
+        let mut listeners = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            listeners.push(LinesListener(TCPListener::bind(*address)?));
+        }
+
+        Ok(Lines {
+            addresses: addresses.to_vec(),
+            listeners,
+            options,
+        })
+    }
+
+    fn addresses(&self) -> &[SocketAddr] {
+        &self.addresses
+    }
+
+    fn listeners(&self) -> &[LinesListener] {
+        &self.listeners
+    }
+
+    fn options(&self) -> &LinesOptions {
+        &self.options
+    }
+}
+
+// A simulation only runs on a single thread
+unsafe impl Send for Lines {}
+unsafe impl Sync for Lines {}
+
+impl ProtocolListener for LinesListener {
+    type Address = SocketAddr;
+    type Client = LinesClient;
+    type ClientAddress = SocketAddr;
+    type Error = Error;
+    type Options = LinesOptions;
+
+    async fn accept(&self, options: &LinesOptions) -> Result<(LinesClient, SocketAddr)> {
+        let (socket, address) = self.0.accept().await?;
+        Ok((
+            LinesClient {
+                socket: RefCell::new(socket),
+                read_timeout: options.read_timeout,
+            },
+            address,
+        ))
+    }
+}
+
+impl ProtocolClient for LinesClient {
+    type ReadError = Error;
+    type SendError = Error;
+    type Request<'a> = &'a [u8];
+    type Response<'a> = Vec<u8>;
+    type Buffer = Vec<u8>;
+
+    fn new_buffer(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn read<'a>(&self, _: usize, buffer: &'a mut Vec<u8>) -> Result<Option<&'a [u8]>> {
+        buffer.clear();
+
+        let socket = &mut *self.socket.borrow_mut();
+        let line = timeout(read_line(socket, buffer), self.read_timeout)
+            .await?
+            .unwrap_or(Err(timeout_error()))?;
+
+        Ok(line.then_some(&buffer[..]))
+    }
+
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn send<'a>(&self, _: usize, response: Vec<u8>) -> Result<()> {
+        self.socket.borrow_mut().write_all(&response).await
+    }
+}
+
+// A simulation only runs on a single thread
+unsafe impl Send for LinesClient {}
+
+impl Record {
+    /// Creates a new [`Record`] with no events
+    fn new() -> Arc<Self> {
+        Arc::new(Record {
+            events: Mutex::new(Vec::new()),
+            active: Mutex::new(0),
+            max_active: Mutex::new(0),
+        })
+    }
+
+    /// Records `event` happening now
+    fn push(&self, event: String) {
+        self.events.lock().unwrap().push((simulation::now(), event));
+    }
+
+    /// Gets the times of the events ending with `suffix`
+    fn times_of(&self, suffix: &str) -> Vec<Duration> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, event)| event.ends_with(suffix))
+            .map(|(time, _)| *time)
+            .collect()
+    }
+}
+
+impl App for Echo {
+    type Protocol = Lines;
+    type Client = SocketAddr;
+
+    async fn handle_request<'a, 'b>(
+        self: &'a Arc<Self>,
+        client: &'a SocketAddr,
+        request: &'a mut <Lines as Protocol>::Request<'b>,
+    ) -> Vec<u8> {
+        let line = String::from_utf8_lossy(request).into_owned();
+        self.0.push(format!("{} request {}", client.port(), line));
+
+        let mut response = request.to_vec();
+        response.push(b'\n');
+        response
+    }
+
+    async fn on_client_connect(self: &Arc<Self>, source: SocketAddr) -> Option<SocketAddr> {
+        let mut active = self.0.active.lock().unwrap();
+        *active += 1;
+
+        let mut max_active = self.0.max_active.lock().unwrap();
+        *max_active = (*max_active).max(*active);
+
+        self.0.push(format!("{} connect", source.port()));
+        Some(source)
+    }
+
+    async fn on_client_disconnect(self: &Arc<Self>, client: &mut SocketAddr) {
+        *self.0.active.lock().unwrap() -= 1;
+        self.0.push(format!("{} disconnect", client.port()));
+    }
+
+    async fn read_error(self: &Arc<Self>, client: &SocketAddr, error: Error) -> Option<Vec<u8>> {
+        self.0
+            .push(format!("{} read error {}", client.port(), error.kind()));
+        None
+    }
+}
+
+/// Reads bytes into `buffer` until a newline, which is not included
+///
+/// Returns false if the socket closed before any bytes were read
+async fn read_line(socket: &mut TCPStream, buffer: &mut Vec<u8>) -> Result<bool> {
+    let mut byte = [0];
+    loop {
+        if socket.read(&mut byte).await? == 0 {
+            return match buffer.len() {
+                0 => Ok(false),
+                _ => Err(std::io::ErrorKind::UnexpectedEof.into()),
+            };
+        }
+
+        if byte[0] == b'\n' {
+            return Ok(true);
+        }
+
+        buffer.push(byte[0]);
+    }
+}
+
+/// Connects to the server once it has had time to start listening
+async fn connect() -> TCPStream {
+    sleep(Duration::from_millis(10)).await;
+    net::connect(ADDRESS).await.unwrap()
+}
+
+/// Sends `line` on `socket` and reads the line answering it
+///
+/// Returns [`None`] if the server closed the connection instead
+async fn request(socket: &mut TCPStream, line: &str) -> Option<String> {
+    socket
+        .write_all(format!("{}\n", line).as_bytes())
+        .await
+        .ok()?;
+
+    let mut response = Vec::new();
+    match read_line(socket, &mut response).await {
+        Ok(true) => Some(String::from_utf8(response).unwrap()),
+        _ => None,
+    }
+}
+
+/// Runs an [`Echo`] server in a new simulation seeded with `seed` until its shutdown is triggered
+///
+/// `clients` is called with the shutdown to spawn the clients into the simulation before the
+/// server starts
+fn run_echo<F: FnOnce(Shutdown)>(
+    seed: u64,
+    connections_per_worker: usize,
+    read_timeout: Duration,
+    clients: F,
+) -> Arc<Record> {
+    simulation::start(seed);
+
+    let mut options = Options::default();
+    options.add_address(ADDRESS);
+    options.set_connections_per_worker(NonZeroUsize::new(connections_per_worker).unwrap());
+    clients(options.shutdown().clone());
+
+    let record = Record::new();
+    crate::run(Echo(record.clone()), options, LinesOptions { read_timeout }).unwrap();
+    record
+}
+
+#[test]
+fn idle_clients_time_out() {
+    let closed = Rc::new(RefCell::new(None));
+
+    let child_closed = closed.clone();
+    let app = run_echo(1, 4, Duration::from_secs(1), move |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            let mut byte = [0];
+            assert_eq!(socket.read(&mut byte).await.unwrap(), 0);
+            *child_closed.borrow_mut() = Some(simulation::now());
+
+            shutdown.trigger();
+        });
+    });
+
+    // The server gives up on the client after the read timeout, plus some latency
+    let closed = closed.borrow().unwrap();
+    assert!(closed >= Duration::from_secs(1));
+    assert!(closed < Duration::from_millis(1100));
+
+    assert_eq!(app.times_of(" read error timed out").len(), 1);
+    assert_eq!(app.times_of(" disconnect").len(), 1);
+}
+
+#[test]
+fn slow_clients_do_not_block_others() {
+    let answered = Rc::new(RefCell::new(Vec::new()));
+
+    let slow_answered = answered.clone();
+    let fast_answered = answered.clone();
+    let app = run_echo(2, 4, Duration::from_secs(10), move |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+
+            // Send the request slowly, but within the read timeout
+            socket.write_all(b"sl").await.unwrap();
+            sleep(Duration::from_secs(3)).await;
+            assert_eq!(request(&mut socket, "ow").await.as_deref(), Some("slow"));
+            slow_answered.borrow_mut().push("slow");
+        });
+
+        simulation::spawn(async move {
+            sleep(Duration::from_secs(1)).await;
+
+            let mut socket = connect().await;
+            assert_eq!(request(&mut socket, "fast").await.as_deref(), Some("fast"));
+            fast_answered.borrow_mut().push("fast");
+        });
+
+        simulation::spawn(async move {
+            sleep(Duration::from_secs(5)).await;
+            shutdown.trigger();
+        });
+    });
+
+    assert_eq!(*answered.borrow(), ["fast", "slow"]);
+    assert_eq!(*app.max_active.lock().unwrap(), 2);
+}
+
+#[test]
+fn connection_limit_defers_clients() {
+    let app = run_echo(3, 1, Duration::from_secs(10), move |shutdown| {
+        let remaining = Rc::new(RefCell::new(2));
+
+        for _ in 0..2 {
+            let remaining = remaining.clone();
+            let shutdown = shutdown.clone();
+
+            simulation::spawn(async move {
+                let mut socket = connect().await;
+                assert_eq!(
+                    request(&mut socket, "hello").await.as_deref(),
+                    Some("hello")
+                );
+
+                // Hold the connection open to keep the other client waiting
+                sleep(Duration::from_secs(2)).await;
+                drop(socket);
+
+                *remaining.borrow_mut() -= 1;
+                if *remaining.borrow() == 0 {
+                    shutdown.trigger();
+                }
+            });
+        }
+    });
+
+    assert_eq!(*app.max_active.lock().unwrap(), 1);
+
+    // The second client is only accepted once the first disconnects
+    let connects = app.times_of(" connect");
+    let disconnects = app.times_of(" disconnect");
+    assert_eq!(connects.len(), 2);
+    assert!(connects[0] < Duration::from_secs(1));
+    assert!(connects[1] >= disconnects[0]);
+    assert!(connects[1] >= Duration::from_secs(2));
+}
+
+#[test]
+fn shutdown_closes_idle_clients() {
+    let app = run_echo(4, 4, Duration::from_secs(60), move |shutdown| {
+        simulation::spawn(async move {
+            let mut socket = connect().await;
+            assert_eq!(
+                request(&mut socket, "first").await.as_deref(),
+                Some("first")
+            );
+
+            shutdown.trigger();
+
+            // The server closes the connection instead of waiting for the read timeout
+            let mut byte = [0];
+            assert_eq!(socket.read(&mut byte).await.unwrap(), 0);
+            assert!(simulation::now() < Duration::from_secs(1));
+        });
+    });
+
+    assert_eq!(app.times_of(" disconnect").len(), 1);
+}
+
+#[test]
+fn seeds_reproduce_runs() {
+    let run = |seed| {
+        let app = run_echo(seed, 2, Duration::from_secs(1), move |shutdown| {
+            for i in 0..3 {
+                simulation::spawn(async move {
+                    let mut socket = connect().await;
+                    request(&mut socket, &format!("client {}", i)).await;
+                });
+            }
+
+            simulation::spawn(async move {
+                sleep(Duration::from_secs(5)).await;
+                shutdown.trigger();
+            });
+        });
+
+        let events = app.events.lock().unwrap().clone();
+        events
+    };
+
+    let events = run(5);
+    assert!(!events.is_empty());
+    assert_eq!(events, run(5));
+}
+
+#[test]
+fn simulations_reject_multiple_workers() {
+    simulation::start(6);
+
+    let mut options = Options::default();
+    options.add_address(ADDRESS);
+    options.set_workers(NonZeroUsize::new(2).unwrap());
+
+    let result = crate::run(
+        Echo(Record::new()),
+        options,
+        LinesOptions {
+            read_timeout: Duration::from_secs(1),
+        },
+    );
+    assert!(matches!(result, Err(crate::StartError::Worker(_))));
+}
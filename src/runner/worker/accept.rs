@@ -1,71 +1,115 @@
-use super::Connections;
-use crate::ProtocolListener;
-use lasync::FutureQueue;
-use std::{num::NonZeroUsize, rc::Rc, sync::Arc};
+use super::WorkerContext;
+use crate::{runtime::FutureQueue, ProtocolListener};
+use std::rc::Rc;
 
-/// Spawns the tasks to accept clients from the `protocol`'s listener
-pub(in crate::runner) fn accept_clients<
+/// Spawns the tasks to accept clients from the protocol's listeners
+pub(super) fn accept_clients<
     'a,
     Protocol: crate::Protocol,
     App: crate::App<Protocol = Protocol>,
 >(
-    app: Arc<App>,
-    protocol: Arc<Protocol>,
-    max_connections: NonZeroUsize,
+    context: Rc<WorkerContext<Protocol, App>>,
     future_queue: &FutureQueue<'a>,
 ) {
-    let connections = Connections::new(max_connections);
-    for i in 0..protocol.listeners().len() {
-        let child_app = app.clone();
-        let child_protocol = protocol.clone();
+    for i in 0..context.protocol.listeners().len() {
+        let child_context = context.clone();
         let child_future_queue = future_queue.clone();
-        let child_connections = connections.clone();
 
         future_queue.push(async move {
-            accept_client(
-                child_app,
-                child_protocol,
-                i,
-                child_connections,
-                child_future_queue,
-            )
-            .await;
+            accept_client(child_context, i, child_future_queue).await;
         });
     }
 }
 
-/// Asynchronously accepts clients, waiting if the max connections are reached
+/// Asynchronously accepts clients, waiting if the max connections are reached, until the server
+/// shuts down
 async fn accept_client<'a, Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>>(
-    app: Arc<App>,
-    protocol: Arc<Protocol>,
+    context: Rc<WorkerContext<Protocol, App>>,
     listener: usize,
-    connections: Rc<Connections>,
     future_queue: FutureQueue<'a>,
-) -> ! {
-    let listener = &protocol.listeners()[listener];
+) {
+    let listener_index = listener;
+    let listener = &context.protocol.listeners()[listener];
 
     loop {
-        connections.wait_until_available().await;
+        if context
+            .shutdown
+            .run_until(context.connections.wait_until_available())
+            .await
+            .is_none()
+        {
+            return;
+        }
 
-        let (client_socket, address) = match listener.accept(protocol.options()).await {
-            Ok(client) => client,
-            Err(error) => {
-                app.accept_error(error).await;
+        let (client_socket, address) = match context
+            .shutdown
+            .run_until(listener.accept(context.protocol.options()))
+            .await
+        {
+            Some(Ok(client)) => client,
+            Some(Err(error)) => {
+                context.app.accept_error(error).await;
                 continue;
             }
+            None => return,
         };
 
-        let client = match app.on_client_connect(address).await {
-            Some(client) => client,
-            None => continue,
-        };
-
-        connections.new_connection();
+        // The connection is counted before the app sees it so workers can't finish draining
+        // while the app is deciding to accept it
+        context.connections.new_connection();
 
-        let child_app = app.clone();
-        let child_connections = connections.clone();
+        let child_context = context.clone();
         future_queue.push(async move {
-            super::handle_client(child_app, child_connections, client, client_socket).await;
+            connect_client(child_context, listener_index, client_socket, address).await;
         });
     }
 }
+
+/// Finishes connecting an accepted client and lets the app decide whether to accept it before
+/// handling it
+async fn connect_client<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>>(
+    context: Rc<WorkerContext<Protocol, App>>,
+    listener: usize,
+    mut client_socket: Protocol::Client,
+    mut address: Protocol::ClientAddress,
+) {
+    match context
+        .shutdown
+        .run_until(context.protocol.listeners()[listener].connect(
+            &mut client_socket,
+            &mut address,
+            context.protocol.options(),
+        ))
+        .await
+    {
+        Some(Ok(())) => {}
+        Some(Err(error)) => {
+            context.connections.end_connection();
+            context.app.accept_error(error).await;
+            return;
+        }
+        None => {
+            context.connections.end_connection();
+            return;
+        }
+    }
+
+    let peer = context.stats.as_ref().map(|_| address.to_string());
+    let client = match context.app.on_client_connect(address).await {
+        Some(client) => client,
+        None => {
+            context.connections.end_connection();
+            if let Some(stats) = &context.stats {
+                stats.reject();
+            }
+            return;
+        }
+    };
+    let id = context
+        .stats
+        .as_ref()
+        .zip(peer)
+        .map(|(stats, peer)| stats.connect(context.worker, peer));
+
+    super::handle_client(context, id, client, client_socket).await;
+}
@@ -1,49 +1,192 @@
-use super::Connections;
-use crate::ProtocolClient;
-use std::{rc::Rc, sync::Arc};
+use super::WorkerContext;
+use crate::{runtime::sync::LocalNotify, ProtocolClient};
+use std::{
+    cell::Cell,
+    future::{poll_fn, Future},
+    rc::Rc,
+    task::Poll,
+};
 
-/// A function which handles a client until an error occurs or a client disconnects
+/// A client whose requests are being handled
+struct Connection<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>> {
+    /// The state shared by the tasks of the worker
+    context: Rc<WorkerContext<Protocol, App>>,
+
+    /// The app's state for the client
+    client: App::Client,
+
+    /// The connection requests are read from and responses are sent on
+    socket: Protocol::Client,
+
+    /// The most slots which can handle requests at the same time
+    max_slots: usize,
+
+    /// The number of slots which have been started
+    slots: Cell<usize>,
+
+    /// Is a slot currently reading a request?
+    reading: Cell<bool>,
+
+    /// The number of slots waiting to read the next request
+    waiting: Cell<usize>,
+
+    /// Has the last request been read?
+    finished: Cell<bool>,
+
+    /// Notified when a slot stops reading
+    notify: LocalNotify,
+}
+
+/// A function which handles a client until an error occurs, a client disconnects, or the server
+/// shuts down
+///
+/// Requests are handled in slots, as described by [`ProtocolClient::max_concurrent_requests`].
+/// Only one slot is started at first, with another started whenever a request is read while no
+/// other slot is free to read the next one.
 pub(super) async fn handle_client<
     Protocol: crate::Protocol,
     App: crate::App<Protocol = Protocol>,
 >(
-    app: Arc<App>,
-    connections: Rc<Connections>,
-    mut client: App::Client,
+    context: Rc<WorkerContext<Protocol, App>>,
+    id: Option<u64>,
+    client: App::Client,
     mut client_socket: Protocol::Client,
 ) {
-    let mut response = None;
-
-    loop {
-        let request = match client_socket.read().await {
-            Ok(request) => match request {
-                Some(request) => request,
-                None => break,
-            },
-            Err(error) => {
-                response = app.read_error(&mut client, error).await;
-                break;
+    client_socket.set_shutdown(context.shutdown.clone());
+
+    let connection = Connection {
+        context,
+        client,
+        max_slots: client_socket.max_concurrent_requests().max(1),
+        socket: client_socket,
+        slots: Cell::new(1),
+        reading: Cell::new(false),
+        waiting: Cell::new(0),
+        finished: Cell::new(false),
+        notify: LocalNotify::new(),
+    };
+
+    {
+        let mut slots = Vec::new();
+        let mut started = 0;
+        poll_fn(|cx| {
+            // Slots started while polling the others are polled straight away
+            loop {
+                while started < connection.slots.get() {
+                    slots.push(Box::pin(run_slot(&connection, started)));
+                    started += 1;
+                }
+
+                slots.retain_mut(|slot| slot.as_mut().poll(cx).is_pending());
+                if started == connection.slots.get() {
+                    break;
+                }
+            }
+
+            if slots.is_empty() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+
+    let Connection {
+        context,
+        mut client,
+        ..
+    } = connection;
+
+    context.connections.end_connection();
+    if let (Some(stats), Some(id)) = (&context.stats, id) {
+        stats.disconnect(id);
+    }
+    context.app.on_client_disconnect(&mut client).await;
+}
+
+/// Reads and answers requests in `slot` until the client has no more requests to handle
+async fn run_slot<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>>(
+    connection: &Connection<Protocol, App>,
+    slot: usize,
+) {
+    let context = &connection.context;
+    let mut buffer = connection.socket.new_buffer();
+
+    while connection.start_reading().await {
+        let mut request = match context
+            .shutdown
+            .run_until(connection.socket.read(slot, &mut buffer))
+            .await
+        {
+            Some(Ok(Some(request))) => request,
+            Some(Ok(None)) | None => return connection.finish(),
+            Some(Err(error)) => {
+                connection.finish();
+
+                let response = context.app.read_error(&connection.client, error).await;
+                if let Some(response) = response {
+                    if let Err(error) = connection.socket.send(slot, response).await {
+                        context.app.send_error(&connection.client, error).await;
+                    }
+                }
+                return;
             }
         };
+        connection.stop_reading();
 
-        let response = app.handle_request(&mut client, request).await;
+        let response = context
+            .app
+            .handle_request(&connection.client, &mut request)
+            .await;
+
+        if let Err(error) = connection.socket.send(slot, response).await {
+            connection.finish();
+            context.app.send_error(&connection.client, error).await;
+            return;
+        }
+        if let Some(stats) = &context.stats {
+            stats.request();
+        }
 
-        if let Err(error) = client_socket.send(response).await {
-            app.send_error(&mut client, error).await;
-            break;
+        if context.shutdown.is_triggered() {
+            return connection.finish();
         }
     }
+}
 
-    let send_result = match response.take() {
-        Some(response) => client_socket.send(response).await,
-        None => Ok(()),
-    };
-    drop(response);
+impl<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>> Connection<Protocol, App> {
+    /// Waits until no other slot is reading a request
+    ///
+    /// Returns false if no more requests will be read
+    async fn start_reading(&self) -> bool {
+        self.waiting.set(self.waiting.get() + 1);
+        loop {
+            if self.finished.get() || !self.reading.get() {
+                self.waiting.set(self.waiting.get() - 1);
+                self.reading.set(!self.finished.get());
+                return !self.finished.get();
+            }
 
-    if let Err(error) = send_result {
-        app.send_error(&mut client, error).await;
+            self.notify.notified().await;
+        }
     }
 
-    connections.end_connection();
-    app.on_client_disconnect(&mut client).await;
+    /// Lets the next request be read while the one just read is handled
+    ///
+    /// Starts another slot if every started slot is busy
+    fn stop_reading(&self) {
+        self.reading.set(false);
+        if self.waiting.get() == 0 && self.slots.get() < self.max_slots {
+            self.slots.set(self.slots.get() + 1);
+        }
+        self.notify.notify_all();
+    }
+
+    /// Stops any more requests from being read, letting the other slots finish their requests
+    fn finish(&self) {
+        self.reading.set(false);
+        self.finished.set(true);
+        self.notify.notify_all();
+    }
 }
@@ -1,4 +1,4 @@
-use lasync::sync::LocalNotify;
+use crate::runtime::sync::LocalNotify;
 use std::{cell::RefCell, num::NonZeroUsize, rc::Rc};
 
 /// Records the current connections on a worker
@@ -30,6 +30,13 @@ impl Connections {
         }
     }
 
+    /// Waits until every connection has ended
+    pub(super) async fn wait_until_empty(&self) {
+        while *self.count.borrow() > 0 {
+            self.notify.notified().await;
+        }
+    }
+
     /// Signals a new connection has started
     pub(super) fn new_connection(&self) {
         let mut count = self.count.borrow_mut();
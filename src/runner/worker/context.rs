@@ -0,0 +1,24 @@
+use super::{Connections, Stats};
+use crate::Shutdown;
+use std::{rc::Rc, sync::Arc};
+
+/// The state shared by the tasks of a worker
+pub(super) struct WorkerContext<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>> {
+    /// The app handling the clients
+    pub(super) app: Arc<App>,
+
+    /// The protocol whose listeners clients are accepted from
+    pub(super) protocol: Arc<Protocol>,
+
+    /// The connections currently being handled
+    pub(super) connections: Rc<Connections>,
+
+    /// The handle the server is shut down with
+    pub(super) shutdown: Shutdown,
+
+    /// The statistics of the server, which are only kept when the control channel is enabled
+    pub(super) stats: Option<Arc<Stats>>,
+
+    /// The index of the worker
+    pub(super) worker: usize,
+}
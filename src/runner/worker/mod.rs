@@ -1,22 +1,74 @@
+use super::Stats;
+use crate::{
+    runtime::{self, FutureQueue},
+    Shutdown,
+};
+use accept::accept_clients;
 use client::handle_client;
 use connections::Connections;
-use lasync::FutureQueue;
-use std::{num::NonZeroUsize, sync::Arc};
+use context::WorkerContext;
+use std::{num::NonZeroUsize, rc::Rc, sync::Arc};
 
 mod accept;
 mod client;
 mod connections;
+mod context;
 
-pub(super) use accept::accept_clients;
-
+/// Runs the worker with the index `worker` on the current thread
 pub(super) fn run<Protocol: crate::Protocol, App: crate::App<Protocol = Protocol>>(
     app: Arc<App>,
     listener: Arc<Protocol>,
     max_connections: NonZeroUsize,
-) -> ! {
+    shutdown: Shutdown,
+    stats: Option<Arc<Stats>>,
+    worker: usize,
+) {
     let future_queue = FutureQueue::new();
-    accept_clients(app, listener, max_connections, &future_queue);
+    let child_future_queue = future_queue.clone();
+    future_queue.push(async move {
+        run_worker(
+            app,
+            listener,
+            max_connections,
+            shutdown,
+            stats,
+            worker,
+            child_future_queue,
+        )
+        .await;
+    });
+
+    runtime::run_queue(future_queue).unwrap();
+}
+
+/// Accepts and handles clients until the server shuts down and every connection has closed
+pub(super) async fn run_worker<
+    'a,
+    Protocol: crate::Protocol,
+    App: crate::App<Protocol = Protocol>,
+>(
+    app: Arc<App>,
+    listener: Arc<Protocol>,
+    max_connections: NonZeroUsize,
+    shutdown: Shutdown,
+    stats: Option<Arc<Stats>>,
+    worker: usize,
+    future_queue: FutureQueue<'a>,
+) {
+    app.on_worker_start(worker).await;
+
+    let context = Rc::new(WorkerContext {
+        app: app.clone(),
+        protocol: listener,
+        connections: Connections::new(max_connections),
+        shutdown: shutdown.clone(),
+        stats,
+        worker,
+    });
+    accept_clients(context.clone(), &future_queue);
+
+    shutdown.wait().await;
+    context.connections.wait_until_empty().await;
 
-    lasync::run_queue(super::NUM_EVENTS, future_queue).unwrap();
-    panic!("Executor returned in huntsman thread!");
+    app.on_worker_stop(worker).await;
 }
@@ -0,0 +1,62 @@
+use std::io::ErrorKind;
+
+/// An error from the runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Error {
+    /// The kind of error that occurred
+    kind: ErrorKind,
+
+    /// The OS error code, if the error came from the OS
+    code: Option<i32>,
+}
+
+/// A result with a runtime [`Error`]
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Gets the kind of error that occurred
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Gets the OS error code, if the error came from the OS
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.code
+    }
+}
+
+/// Creates the error returned when a peer breaks the rules of a protocol
+pub fn protocol_error() -> Error {
+    ErrorKind::InvalidData.into()
+}
+
+/// Creates the error returned when using a connection which has already been closed
+pub fn closed_error() -> Error {
+    ErrorKind::NotConnected.into()
+}
+
+impl std::error::Error for Error {}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.code {
+            Some(code) => std::io::Error::from_raw_os_error(code).fmt(f),
+            None => self.kind.fmt(f),
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error { kind, code: None }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error {
+            kind: error.kind(),
+            code: error.raw_os_error(),
+        }
+    }
+}
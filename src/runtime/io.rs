@@ -0,0 +1,21 @@
+//! Asynchronous reading and writing
+
+use super::Result;
+use std::future::Future;
+
+/// A source of bytes which can be read asynchronously
+pub trait Read {
+    /// Reads bytes into `buffer`, returning the number of bytes read
+    ///
+    /// Returns 0 once the end of the source is reached
+    fn read(&mut self, buffer: &mut [u8]) -> impl Future<Output = Result<usize>>;
+
+    /// Reads bytes until `buffer` is full
+    fn read_exact(&mut self, buffer: &mut [u8]) -> impl Future<Output = Result<()>>;
+}
+
+/// A destination for bytes which can be written asynchronously
+pub trait Write {
+    /// Writes every byte in `buffer`
+    fn write_all(&mut self, buffer: &[u8]) -> impl Future<Output = Result<()>>;
+}
@@ -0,0 +1,23 @@
+use std::num::NonZeroUsize;
+
+pub mod time;
+
+pub use ::lasync::{fs, io, net, sync, Error, FutureQueue, Result};
+
+/// The maximum number of events the executor waits on at once
+const NUM_EVENTS: NonZeroUsize = NonZeroUsize::new(8192).unwrap();
+
+/// Creates the error returned when a peer breaks the rules of a protocol
+pub fn protocol_error() -> Error {
+    Error::EPROTO
+}
+
+/// Creates the error returned when using a connection which has already been closed
+pub fn closed_error() -> Error {
+    Error::ENOTCONN
+}
+
+/// Runs the futures in `future_queue` on the current thread until all of them complete
+pub fn run_queue(future_queue: FutureQueue) -> Result<()> {
+    ::lasync::run_queue(NUM_EVENTS, future_queue)
+}
@@ -0,0 +1,17 @@
+//! Utilities for timing futures
+
+use super::{Error, Result};
+use ::lasync::time::Timeout;
+use std::{future::Future, time::Duration};
+
+/// Runs `future` until it completes or `duration` passes
+///
+/// Returns [`None`] if `duration` passed before `future` completed
+pub async fn timeout<F: Future>(future: F, duration: Duration) -> Result<Option<F::Output>> {
+    Ok(Timeout::new(future, duration)?.await)
+}
+
+/// Creates the error returned when an operation times out
+pub fn timeout_error() -> Error {
+    Error::ETIMEDOUT
+}
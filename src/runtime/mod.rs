@@ -0,0 +1,49 @@
+//! The asynchronous runtime huntsman runs on
+//!
+//! The runtime is selected at compile time with either the "lasync" feature (the default), the
+//! "tokio" feature, or the "simulation" feature. The features don't exclude each other, so that
+//! feature unification and `--all-features` still build. If more than one is enabled,
+//! "simulation" is picked over "tokio", and "tokio" over "lasync". Protocols should use the items
+//! in this module instead of depending on a runtime directly so they can run on any of them. The
+//! one exception is `net::UDPSocket`, which only the "tokio" runtime provides.
+//!
+//! The "simulation" runtime does not touch the network or the clock. Instead, time, accepts and
+//! socket events are driven by a seeded simulator so tests can reproduce timeouts, slow clients
+//! and connection limits deterministically. See [`simulation`] for details.
+
+#[cfg(not(any(feature = "lasync", feature = "tokio", feature = "simulation")))]
+compile_error!("one of the \"lasync\", \"tokio\", or \"simulation\" runtimes must be enabled");
+
+#[cfg(any(feature = "tokio", feature = "simulation"))]
+mod error;
+#[cfg(any(feature = "tokio", feature = "simulation"))]
+mod queue;
+
+#[cfg(any(feature = "tokio", feature = "simulation"))]
+pub mod io;
+
+#[cfg(not(any(feature = "tokio", feature = "simulation")))]
+mod lasync;
+
+#[cfg(all(feature = "tokio", not(feature = "simulation")))]
+mod tokio;
+
+#[cfg(feature = "simulation")]
+pub mod simulation;
+
+#[cfg(not(any(feature = "tokio", feature = "simulation")))]
+pub use self::lasync::{
+    closed_error, fs, io, net, protocol_error, run_queue, sync, time, Error, FutureQueue, Result,
+};
+
+#[cfg(any(feature = "tokio", feature = "simulation"))]
+pub use self::{
+    error::{closed_error, protocol_error, Error, Result},
+    queue::FutureQueue,
+};
+
+#[cfg(all(feature = "tokio", not(feature = "simulation")))]
+pub use self::tokio::{fs, net, run_queue, sync, time};
+
+#[cfg(feature = "simulation")]
+pub use self::simulation::{fs, net, run_queue, sync, time};
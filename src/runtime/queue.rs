@@ -0,0 +1,192 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Wake, Waker},
+};
+
+/// A future in a [`FutureQueue`]
+type Task<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// A queue of futures which run on the current thread
+///
+/// Unlike tokio's local tasks, the futures in the queue are not required to be `'static`.
+pub struct FutureQueue<'a> {
+    /// The futures which have been pushed but not yet polled
+    new_tasks: Rc<RefCell<Vec<Task<'a>>>>,
+}
+
+/// Drives the futures of a [`FutureQueue`] until all of them complete
+pub(super) struct QueueRunner<'a> {
+    /// The queue new futures are pushed onto
+    queue: FutureQueue<'a>,
+
+    /// The futures currently being run and their wakers
+    tasks: Vec<(Task<'a>, Arc<TaskWaker>)>,
+
+    /// The waker for the [`QueueRunner`] itself
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+/// Wakes a single future in a [`QueueRunner`]
+struct TaskWaker {
+    /// Has this future been woken since it was last polled?
+    ready: AtomicBool,
+
+    /// The waker for the [`QueueRunner`] the future is in
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<'a> FutureQueue<'a> {
+    /// Creates a new empty [`FutureQueue`]
+    pub fn new() -> Self {
+        FutureQueue {
+            new_tasks: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Pushes `future` onto the queue to be run
+    pub fn push<F: Future<Output = ()> + 'a>(&self, future: F) {
+        self.new_tasks.borrow_mut().push(Box::pin(future));
+    }
+}
+
+impl<'a> Clone for FutureQueue<'a> {
+    fn clone(&self) -> Self {
+        FutureQueue {
+            new_tasks: self.new_tasks.clone(),
+        }
+    }
+}
+
+impl<'a> Default for FutureQueue<'a> {
+    fn default() -> Self {
+        FutureQueue::new()
+    }
+}
+
+impl<'a> QueueRunner<'a> {
+    /// Creates a new [`QueueRunner`] for the futures pushed onto `queue`
+    pub(super) fn new(queue: FutureQueue<'a>) -> Self {
+        QueueRunner {
+            queue,
+            tasks: Vec::new(),
+            waker: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Are there no futures left to run?
+    pub(super) fn is_empty(&self) -> bool {
+        self.tasks.is_empty() && self.queue.new_tasks.borrow().is_empty()
+    }
+
+    /// Are any futures waiting to be polled?
+    pub(super) fn has_ready(&self) -> bool {
+        !self.queue.new_tasks.borrow().is_empty()
+            || self
+                .tasks
+                .iter()
+                .any(|(_, waker)| waker.ready.load(Ordering::Acquire))
+    }
+
+    /// Moves any newly pushed futures into the running futures
+    ///
+    /// Returns true if any futures were moved
+    pub(super) fn take_new_tasks(&mut self) -> bool {
+        let new_tasks = std::mem::take(&mut *self.queue.new_tasks.borrow_mut());
+        if new_tasks.is_empty() {
+            return false;
+        }
+
+        for task in new_tasks {
+            let waker = Arc::new(TaskWaker {
+                ready: AtomicBool::new(true),
+                waker: self.waker.clone(),
+            });
+
+            self.tasks.push((task, waker));
+        }
+
+        true
+    }
+
+    /// Shuffles the order futures will be polled in, using `random` to pick a number below the
+    /// value passed to it
+    #[cfg(feature = "simulation")]
+    pub(super) fn shuffle<F: FnMut(usize) -> usize>(&mut self, mut random: F) {
+        for i in (1..self.tasks.len()).rev() {
+            self.tasks.swap(i, random(i + 1));
+        }
+    }
+
+    /// Polls every future which has been woken once
+    pub(super) fn poll_ready(&mut self) {
+        let mut i = 0;
+        while i < self.tasks.len() {
+            let (task, task_waker) = &mut self.tasks[i];
+            if !task_waker.ready.swap(false, Ordering::AcqRel) {
+                i += 1;
+                continue;
+            }
+
+            let waker = Waker::from(task_waker.clone());
+            if task
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_ready()
+            {
+                drop(self.tasks.swap_remove(i));
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+impl<'a> Future for QueueRunner<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let runner = self.get_mut();
+        *runner.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        runner.take_new_tasks();
+        loop {
+            runner.poll_ready();
+
+            if !runner.take_new_tasks() {
+                break;
+            }
+        }
+
+        if runner.is_empty() {
+            return Poll::Ready(());
+        }
+
+        // Give the runtime a chance to process events before polling futures which woke themselves
+        if runner.has_ready() {
+            cx.waker().wake_by_ref();
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.ready.store(true, Ordering::Release);
+
+        if let Some(waker) = self.waker.lock().unwrap().as_ref() {
+            waker.wake_by_ref();
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use super::{net::TCPStream, state};
+use crate::runtime::Result;
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// The connections waiting to be accepted by a simulated listener
+pub(super) struct Backlog {
+    /// The connections, their peer addresses, and the times they arrive at
+    connections: VecDeque<(Duration, TCPStream, SocketAddr)>,
+
+    /// The waker of the listener waiting for a connection
+    waker: Option<Waker>,
+}
+
+impl Backlog {
+    /// Creates a new empty [`Backlog`]
+    pub(super) fn new() -> Self {
+        Backlog {
+            connections: VecDeque::new(),
+            waker: None,
+        }
+    }
+
+    /// Adds a connection from `address` arriving at `time`
+    pub(super) fn push(&mut self, time: Duration, stream: TCPStream, address: SocketAddr) {
+        self.connections.push_back((time, stream, address));
+
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Takes the next connection which has arrived
+    pub(super) fn poll_accept(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(TCPStream, SocketAddr)>> {
+        let now = state::with(|simulation| simulation.now());
+        if let Some((time, _, _)) = self.connections.front() {
+            if *time <= now {
+                let (_, stream, address) = self.connections.pop_front().unwrap();
+                return Poll::Ready(Ok((stream, address)));
+            }
+
+            let time = *time;
+            state::with(|simulation| simulation.wake_at(time, cx.waker().clone()));
+        }
+
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
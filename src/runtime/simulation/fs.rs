@@ -0,0 +1,33 @@
+//! Filesystem access
+//!
+//! Files are read directly from the real filesystem without any simulated time passing
+
+use crate::runtime::{io::Read, Result};
+use std::path::Path;
+
+pub use std::fs::Metadata;
+
+/// A file opened for reading
+pub struct File(std::fs::File);
+
+impl File {
+    /// Opens the file at `path` for reading
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(File(std::fs::File::open(path)?))
+    }
+
+    /// Gets the metadata of this file
+    pub async fn metadata(&self) -> Result<Metadata> {
+        Ok(self.0.metadata()?)
+    }
+}
+
+impl Read for File {
+    async fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        Ok(std::io::Read::read(&mut self.0, buffer)?)
+    }
+
+    async fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+        Ok(std::io::Read::read_exact(&mut self.0, buffer)?)
+    }
+}
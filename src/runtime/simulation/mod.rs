@@ -0,0 +1,88 @@
+//! A seeded, single-threaded simulation of the network and the clock
+//!
+//! When the "simulation" feature is enabled, huntsman runs on this runtime instead of a real one.
+//! No real sockets are opened and no real time passes: listeners and connections are in-memory,
+//! every network event is delivered after a random latency, and the clock only advances when no
+//! future can make progress. The order futures are polled in is also shuffled at each step. All
+//! of the randomness comes from the seed passed to [`start`], so a run can be reproduced exactly
+//! by reusing its seed.
+//!
+//! A simulation is local to the thread it was started on, so the server must be run with a single
+//! worker. This is the default on this runtime, and starting a server with more workers fails
+//! with [`StartError::Worker`](crate::StartError::Worker). Clients are spawned into the
+//! simulation with [`spawn`] before the server is run and connect to it using [`net::connect`].
+
+use super::{queue::QueueRunner, FutureQueue, Result};
+use std::{future::Future, task::Waker, time::Duration};
+
+mod backlog;
+mod pipe;
+mod state;
+
+#[cfg(test)]
+mod tests;
+
+pub mod fs;
+pub mod net;
+pub mod sync;
+pub mod time;
+
+/// Starts a new simulation on the current thread using `seed` for all of its randomness
+///
+/// Any previous simulation on this thread is discarded
+pub fn start(seed: u64) {
+    state::start(seed);
+}
+
+/// Sets the range the latency of each simulated network event is picked from
+///
+/// The default range is 1 to 5 milliseconds
+pub fn set_latency(min: Duration, max: Duration) {
+    assert!(min <= max);
+    state::with(|simulation| simulation.set_latency(min, max));
+}
+
+/// Spawns `future` into the simulation
+///
+/// The future starts running with the next call to [`run_queue`] on this thread
+pub fn spawn<F: Future<Output = ()> + 'static>(future: F) {
+    state::with(|simulation| simulation.spawn(Box::pin(future)));
+}
+
+/// Gets the amount of simulated time which has passed since the simulation started
+pub fn now() -> Duration {
+    state::with(|simulation| simulation.now())
+}
+
+/// Runs the futures in `future_queue` and any spawned futures until all of them complete or none
+/// of them can make progress
+///
+/// # Panics
+/// This function panics if no simulation has been started on the current thread
+pub fn run_queue(future_queue: FutureQueue) -> Result<()> {
+    let queue = future_queue.clone();
+    let mut runner = QueueRunner::new(future_queue);
+
+    loop {
+        for future in state::with(|simulation| simulation.take_spawned()) {
+            queue.push(future);
+        }
+        runner.take_new_tasks();
+
+        if runner.is_empty() {
+            return Ok(());
+        }
+
+        if runner.has_ready() {
+            runner.shuffle(|max| state::with(|simulation| simulation.random_below(max)));
+            runner.poll_ready();
+            continue;
+        }
+
+        // Nothing can progress until the next timer fires
+        match state::with(|simulation| simulation.advance()) {
+            Some(wakers) => wakers.into_iter().for_each(Waker::wake),
+            None => return Ok(()),
+        }
+    }
+}
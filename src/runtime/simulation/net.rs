@@ -0,0 +1,149 @@
+//! Simulated networking primitives
+
+use super::{backlog::Backlog, pipe::Pipe, state, time::sleep_until};
+use crate::runtime::{
+    io::{Read, Write},
+    Result,
+};
+use std::{
+    cell::RefCell,
+    future::poll_fn,
+    io::ErrorKind,
+    net::{Ipv4Addr, SocketAddr},
+    rc::Rc,
+};
+
+/// A simulated socket which listens for TCP connections
+pub struct TCPListener {
+    /// The address this listener is bound to
+    address: SocketAddr,
+
+    /// The connections waiting to be accepted
+    backlog: Rc<RefCell<Backlog>>,
+}
+
+/// A simulated connected TCP socket
+pub struct TCPStream {
+    /// The data sent by the peer
+    incoming: Rc<RefCell<Pipe>>,
+
+    /// The data sent to the peer
+    outgoing: Rc<RefCell<Pipe>>,
+}
+
+/// Connects to the simulated listener bound to `address`
+///
+/// Fails with [`ErrorKind::ConnectionRefused`] if no listener is bound to `address`
+pub async fn connect(address: SocketAddr) -> Result<TCPStream> {
+    let (backlog, local_address, time) = state::with(|simulation| {
+        simulation.listener(&address).map(|backlog| {
+            let local_address = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), simulation.next_port());
+            (backlog, local_address, simulation.delivery_time())
+        })
+    })
+    .ok_or(ErrorKind::ConnectionRefused)?;
+
+    let (client, server) = TCPStream::pair();
+    backlog.borrow_mut().push(time, server, local_address);
+
+    sleep_until(time).await;
+    Ok(client)
+}
+
+impl TCPListener {
+    /// Creates a new [`TCPListener`] bound to `address`
+    ///
+    /// If the port of `address` is 0, a port is assigned
+    pub fn bind(mut address: SocketAddr) -> Result<Self> {
+        let backlog = Rc::new(RefCell::new(Backlog::new()));
+
+        let bound = state::with(|simulation| {
+            if address.port() == 0 {
+                address.set_port(simulation.next_port());
+            }
+
+            simulation.bind(address, backlog.clone())
+        });
+        if !bound {
+            return Err(ErrorKind::AddrInUse.into());
+        }
+
+        Ok(TCPListener { address, backlog })
+    }
+
+    /// Gets the address this listener is bound to
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.address)
+    }
+
+    /// Accepts a new connection
+    pub async fn accept(&self) -> Result<(TCPStream, SocketAddr)> {
+        poll_fn(|cx| self.backlog.borrow_mut().poll_accept(cx)).await
+    }
+}
+
+impl Drop for TCPListener {
+    fn drop(&mut self) {
+        // The backlog is dropped outside of the simulation as it may contain sockets
+        drop(state::try_with(|simulation| {
+            simulation.unbind(&self.address)
+        }));
+    }
+}
+
+impl TCPStream {
+    /// Creates two [`TCPStream`]s connected to each other
+    fn pair() -> (Self, Self) {
+        let a = Rc::new(RefCell::new(Pipe::new()));
+        let b = Rc::new(RefCell::new(Pipe::new()));
+
+        (
+            TCPStream {
+                incoming: a.clone(),
+                outgoing: b.clone(),
+            },
+            TCPStream {
+                incoming: b,
+                outgoing: a,
+            },
+        )
+    }
+
+    /// Sets if Nagle's algorithm should be disabled on this socket
+    ///
+    /// This has no effect on a simulated socket
+    #[allow(unused_variables)]
+    pub fn set_nodelay(&mut self, nodelay: bool) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Read for TCPStream {
+    async fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        poll_fn(|cx| self.incoming.borrow_mut().poll_read(buffer, cx)).await
+    }
+
+    async fn read_exact(&mut self, mut buffer: &mut [u8]) -> Result<()> {
+        while !buffer.is_empty() {
+            match self.read(buffer).await? {
+                0 => return Err(ErrorKind::UnexpectedEof.into()),
+                length => buffer = &mut buffer[length..],
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for TCPStream {
+    async fn write_all(&mut self, buffer: &[u8]) -> Result<()> {
+        self.outgoing.borrow_mut().write(buffer)
+    }
+}
+
+impl Drop for TCPStream {
+    fn drop(&mut self) {
+        self.outgoing.borrow_mut().close();
+        self.incoming.borrow_mut().close_reader();
+    }
+}
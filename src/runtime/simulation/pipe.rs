@@ -0,0 +1,126 @@
+use super::state;
+use crate::runtime::Result;
+use std::{
+    collections::VecDeque,
+    io::ErrorKind,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// One direction of a simulated connection
+pub(super) struct Pipe {
+    /// The data written to the pipe and the times it is delivered at
+    segments: VecDeque<(Duration, Vec<u8>)>,
+
+    /// The number of bytes already read from the first segment
+    offset: usize,
+
+    /// The time the writing end closing is delivered at
+    closed: Option<Duration>,
+
+    /// Has the reading end been dropped?
+    reader_closed: bool,
+
+    /// The waker of the reader waiting for data
+    waker: Option<Waker>,
+}
+
+impl Pipe {
+    /// Creates a new empty [`Pipe`]
+    pub(super) fn new() -> Self {
+        Pipe {
+            segments: VecDeque::new(),
+            offset: 0,
+            closed: None,
+            reader_closed: false,
+            waker: None,
+        }
+    }
+
+    /// Reads delivered data into `buffer`, returning 0 once the writing end has closed
+    pub(super) fn poll_read(
+        &mut self,
+        buffer: &mut [u8],
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<usize>> {
+        if buffer.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let now = state::with(|simulation| simulation.now());
+        let next = match self.segments.front() {
+            Some((time, data)) if *time <= now => {
+                let length = buffer.len().min(data.len() - self.offset);
+                buffer[..length].copy_from_slice(&data[self.offset..self.offset + length]);
+
+                self.offset += length;
+                if self.offset == data.len() {
+                    self.segments.pop_front();
+                    self.offset = 0;
+                }
+
+                return Poll::Ready(Ok(length));
+            }
+            Some((time, _)) => Some(*time),
+            None => match self.closed {
+                Some(time) if time <= now => return Poll::Ready(Ok(0)),
+                closed => closed,
+            },
+        };
+
+        if let Some(time) = next {
+            state::with(|simulation| simulation.wake_at(time, cx.waker().clone()));
+        }
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    /// Writes `data` into the pipe to be delivered after a random latency
+    pub(super) fn write(&mut self, data: &[u8]) -> Result<()> {
+        if self.reader_closed {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let time = self.delivery_time();
+        self.segments.push_back((time, data.to_vec()));
+        self.wake();
+        Ok(())
+    }
+
+    /// Closes the writing end of the pipe
+    pub(super) fn close(&mut self) {
+        let time = state::try_with(|simulation| simulation.delivery_time()).unwrap_or_default();
+        self.closed = Some(match self.segments.back() {
+            Some((last, _)) => time.max(*last),
+            None => time,
+        });
+        self.wake();
+    }
+
+    /// Closes the reading end of the pipe, discarding any undelivered data
+    pub(super) fn close_reader(&mut self) {
+        self.reader_closed = true;
+        self.segments.clear();
+        self.offset = 0;
+    }
+
+    /// Picks when the next event is delivered, keeping events in the order they were sent
+    fn delivery_time(&self) -> Duration {
+        let time = state::with(|simulation| simulation.delivery_time());
+        match self.segments.back() {
+            Some((last, _)) => time.max(*last),
+            None => time,
+        }
+    }
+
+    /// Wakes the reader so it can wait for the next delivery
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
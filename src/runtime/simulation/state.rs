@@ -0,0 +1,213 @@
+use super::backlog::Backlog;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    rc::Rc,
+    task::Waker,
+    time::Duration,
+};
+
+/// The state of the simulation running on a thread
+pub(super) struct Simulation {
+    /// The amount of simulated time which has passed
+    now: Duration,
+
+    /// The state of the random number generator
+    random: u64,
+
+    /// The minimum latency of a network event
+    min_latency: Duration,
+
+    /// The maximum latency of a network event
+    max_latency: Duration,
+
+    /// The wakers to wake at given times, keyed by the time and a sequence number
+    timers: BTreeMap<(Duration, u64), Waker>,
+
+    /// The sequence number for the next timer
+    next_timer: u64,
+
+    /// The next port to be assigned
+    next_port: u16,
+
+    /// The listeners currently bound, by their address
+    listeners: HashMap<SocketAddr, Rc<RefCell<Backlog>>>,
+
+    /// Futures which have been spawned but not yet given to a runner
+    spawned: Vec<Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+thread_local! {
+    /// The simulation running on this thread
+    static SIMULATION: RefCell<Option<Simulation>> = const { RefCell::new(None) };
+}
+
+/// The first port assigned to sockets which don't specify one
+const FIRST_PORT: u16 = 49152;
+
+/// Starts a new simulation on the current thread
+pub(super) fn start(seed: u64) {
+    let simulation = Simulation {
+        now: Duration::ZERO,
+        random: (seed ^ 0x9E37_79B9_7F4A_7C15).max(1),
+        min_latency: Duration::from_millis(1),
+        max_latency: Duration::from_millis(5),
+        timers: BTreeMap::new(),
+        next_timer: 0,
+        next_port: FIRST_PORT,
+        listeners: HashMap::new(),
+        spawned: Vec::new(),
+    };
+
+    // The old simulation is dropped after the borrow ends as its sockets may access the new one
+    drop(SIMULATION.replace(Some(simulation)));
+}
+
+/// Calls `f` with the simulation running on the current thread
+///
+/// # Panics
+/// This function panics if no simulation has been started on the current thread
+pub(super) fn with<T, F: FnOnce(&mut Simulation) -> T>(f: F) -> T {
+    SIMULATION.with_borrow_mut(|simulation| {
+        f(simulation
+            .as_mut()
+            .expect("no simulation has been started on this thread, note that simulations only support one worker"))
+    })
+}
+
+/// Calls `f` with the simulation running on the current thread, if it is available
+///
+/// Used when dropping sockets, which may happen after the simulation has been destroyed
+pub(super) fn try_with<T, F: FnOnce(&mut Simulation) -> T>(f: F) -> Option<T> {
+    SIMULATION
+        .try_with(|simulation| {
+            let mut simulation = simulation.try_borrow_mut().ok()?;
+            simulation.as_mut().map(f)
+        })
+        .ok()
+        .flatten()
+}
+
+impl Simulation {
+    /// Gets the amount of simulated time which has passed
+    pub(super) fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Sets the range of latencies for network events
+    pub(super) fn set_latency(&mut self, min: Duration, max: Duration) {
+        self.min_latency = min;
+        self.max_latency = max;
+    }
+
+    /// Generates a random number less than `max`
+    pub(super) fn random_below(&mut self, max: usize) -> usize {
+        (self.next_random() % max as u64) as usize
+    }
+
+    /// Picks the time a network event happening now will be delivered at
+    pub(super) fn delivery_time(&mut self) -> Duration {
+        let range = (self.max_latency - self.min_latency).as_nanos() as u64;
+        let latency = match range {
+            0 => self.min_latency,
+            _ => self.min_latency + Duration::from_nanos(self.next_random() % (range + 1)),
+        };
+
+        self.now + latency
+    }
+
+    /// Wakes `waker` once the simulation reaches `time`, returning the key of the new timer
+    pub(super) fn wake_at(&mut self, time: Duration, waker: Waker) -> (Duration, u64) {
+        let key = (time, self.next_timer);
+        self.timers.insert(key, waker);
+        self.next_timer += 1;
+        key
+    }
+
+    /// Replaces the waker of the timer with `key`
+    ///
+    /// Returns false if the timer has already fired or been cancelled
+    pub(super) fn rewake(&mut self, key: (Duration, u64), waker: &Waker) -> bool {
+        match self.timers.get_mut(&key) {
+            Some(old) => {
+                old.clone_from(waker);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the timer with `key` if it hasn't fired yet
+    pub(super) fn cancel(&mut self, key: (Duration, u64)) {
+        self.timers.remove(&key);
+    }
+
+    /// Advances time to the next timer, returning the wakers of every timer which fired
+    ///
+    /// Returns [`None`] if there are no timers left
+    pub(super) fn advance(&mut self) -> Option<Vec<Waker>> {
+        let ((time, _), waker) = self.timers.pop_first()?;
+        self.now = self.now.max(time);
+
+        let mut wakers = vec![waker];
+        while let Some(entry) = self.timers.first_entry() {
+            if entry.key().0 > self.now {
+                break;
+            }
+
+            wakers.push(entry.remove());
+        }
+
+        Some(wakers)
+    }
+
+    /// Assigns a new port
+    pub(super) fn next_port(&mut self) -> u16 {
+        let port = self.next_port;
+        self.next_port = self.next_port.checked_add(1).unwrap_or(FIRST_PORT);
+        port
+    }
+
+    /// Gets the backlog of the listener bound to `address`
+    pub(super) fn listener(&self, address: &SocketAddr) -> Option<Rc<RefCell<Backlog>>> {
+        self.listeners.get(address).cloned()
+    }
+
+    /// Registers a listener bound to `address`
+    ///
+    /// Returns false if a listener is already bound to `address`
+    pub(super) fn bind(&mut self, address: SocketAddr, backlog: Rc<RefCell<Backlog>>) -> bool {
+        if self.listeners.contains_key(&address) {
+            return false;
+        }
+
+        self.listeners.insert(address, backlog);
+        true
+    }
+
+    /// Removes the listener bound to `address`
+    pub(super) fn unbind(&mut self, address: &SocketAddr) -> Option<Rc<RefCell<Backlog>>> {
+        self.listeners.remove(address)
+    }
+
+    /// Adds a future to be run
+    pub(super) fn spawn(&mut self, future: Pin<Box<dyn Future<Output = ()>>>) {
+        self.spawned.push(future);
+    }
+
+    /// Takes the futures which have been spawned since the last call
+    pub(super) fn take_spawned(&mut self) -> Vec<Pin<Box<dyn Future<Output = ()>>>> {
+        std::mem::take(&mut self.spawned)
+    }
+
+    /// Generates the next random number using xorshift64*
+    fn next_random(&mut self) -> u64 {
+        self.random ^= self.random >> 12;
+        self.random ^= self.random << 25;
+        self.random ^= self.random >> 27;
+        self.random.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
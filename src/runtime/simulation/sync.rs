@@ -0,0 +1,61 @@
+//! Synchronization primitives
+
+use std::{
+    cell::{Cell, RefCell},
+    future::poll_fn,
+    task::{Poll, Waker},
+};
+
+/// Notifies tasks on the current thread
+pub struct LocalNotify {
+    /// The number of notifications which have been sent
+    generation: Cell<u64>,
+
+    /// The wakers of the tasks waiting for the next notification
+    wakers: RefCell<Vec<Waker>>,
+}
+
+impl LocalNotify {
+    /// Creates a new [`LocalNotify`]
+    pub fn new() -> Self {
+        LocalNotify {
+            generation: Cell::new(0),
+            wakers: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Waits until the next notification
+    pub async fn notified(&self) {
+        let generation = self.generation.get();
+
+        poll_fn(|cx| {
+            if self.generation.get() != generation {
+                return Poll::Ready(());
+            }
+
+            let mut wakers = self.wakers.borrow_mut();
+            if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Wakes every task currently waiting for a notification
+    pub fn notify_all(&self) {
+        self.generation.set(self.generation.get().wrapping_add(1));
+
+        let wakers = std::mem::take(&mut *self.wakers.borrow_mut());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for LocalNotify {
+    fn default() -> Self {
+        LocalNotify::new()
+    }
+}
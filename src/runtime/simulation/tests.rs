@@ -0,0 +1,23 @@
+use super::{now, run_queue, start, time};
+use crate::runtime::FutureQueue;
+use std::time::Duration;
+
+#[test]
+fn timeouts_remove_their_timers() {
+    start(0);
+
+    let queue = FutureQueue::new();
+    queue.push(async {
+        for _ in 0..100 {
+            let sleep = time::sleep(Duration::from_secs(60));
+            let result = time::timeout(sleep, Duration::from_secs(1)).await;
+            assert_eq!(result.unwrap(), None);
+        }
+
+        // Once nothing can progress, the clock only moves if timers were left behind
+        std::future::pending::<()>().await;
+    });
+    run_queue(queue).unwrap();
+
+    assert_eq!(now(), Duration::from_secs(100));
+}
@@ -0,0 +1,87 @@
+//! Utilities for timing futures using simulated time
+
+use super::state;
+use crate::runtime::{Error, Result};
+use std::{
+    future::{poll_fn, Future},
+    io::ErrorKind,
+    pin::{pin, Pin},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A future which completes once a point in simulated time is reached
+pub struct Sleep {
+    /// The simulated time this future completes at
+    deadline: Duration,
+
+    /// The key of the timer waking this future, if it has been registered
+    timer: Option<(Duration, u64)>,
+}
+
+/// Waits until `duration` of simulated time has passed
+pub fn sleep(duration: Duration) -> Sleep {
+    sleep_until(state::with(|simulation| simulation.now()) + duration)
+}
+
+/// Waits until the simulation reaches `deadline`
+pub(super) fn sleep_until(deadline: Duration) -> Sleep {
+    Sleep {
+        deadline,
+        timer: None,
+    }
+}
+
+/// Runs `future` until it completes or `duration` of simulated time passes
+///
+/// Returns [`None`] if `duration` passed before `future` completed
+pub async fn timeout<F: Future>(future: F, duration: Duration) -> Result<Option<F::Output>> {
+    let mut future = pin!(future);
+    let mut sleep = sleep(duration);
+
+    Ok(poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        Pin::new(&mut sleep).poll(cx).map(|_| None)
+    })
+    .await)
+}
+
+/// Creates the error returned when an operation times out
+pub fn timeout_error() -> Error {
+    ErrorKind::TimedOut.into()
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let sleep = self.get_mut();
+
+        state::with(|simulation| {
+            if simulation.now() >= sleep.deadline {
+                sleep.timer = None;
+                return Poll::Ready(());
+            }
+
+            // Polling again only replaces the waker so each sleep has at most one timer
+            match sleep.timer {
+                Some(key) if simulation.rewake(key, cx.waker()) => {}
+                _ => sleep.timer = Some(simulation.wake_at(sleep.deadline, cx.waker().clone())),
+            }
+
+            Poll::Pending
+        })
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        // Cancelled sleeps would otherwise leave their timer behind, moving the clock forward
+        if let Some(key) = self.timer {
+            state::try_with(|simulation| simulation.cancel(key));
+        }
+    }
+}
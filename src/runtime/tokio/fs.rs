@@ -0,0 +1,31 @@
+//! Filesystem access
+
+use crate::runtime::Result;
+use std::path::Path;
+
+pub use std::fs::Metadata;
+
+/// A file opened for reading
+pub struct File(tokio::fs::File);
+
+impl File {
+    /// Opens the file at `path` for reading
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(File(tokio::fs::File::open(path).await?))
+    }
+
+    /// Gets the metadata of this file
+    pub async fn metadata(&self) -> Result<Metadata> {
+        Ok(self.0.metadata().await?)
+    }
+}
+
+impl crate::runtime::io::Read for File {
+    async fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        crate::runtime::io::Read::read(&mut self.0, buffer).await
+    }
+
+    async fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+        crate::runtime::io::Read::read_exact(&mut self.0, buffer).await
+    }
+}
@@ -0,0 +1,22 @@
+use crate::runtime::{
+    io::{Read, Write},
+    Result,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+impl<T: AsyncRead + Unpin> Read for T {
+    async fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        Ok(AsyncReadExt::read(self, buffer).await?)
+    }
+
+    async fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+        AsyncReadExt::read_exact(self, buffer).await?;
+        Ok(())
+    }
+}
+
+impl<T: AsyncWrite + Unpin> Write for T {
+    async fn write_all(&mut self, buffer: &[u8]) -> Result<()> {
+        Ok(AsyncWriteExt::write_all(self, buffer).await?)
+    }
+}
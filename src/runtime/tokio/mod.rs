@@ -0,0 +1,35 @@
+use super::{queue::QueueRunner, FutureQueue, Result};
+use ::tokio::runtime::{Handle, RuntimeFlavor};
+
+#[cfg(test)]
+mod tests;
+
+mod io;
+
+pub mod fs;
+pub mod net;
+pub mod sync;
+pub mod time;
+
+/// Runs the futures in `future_queue` on the current thread until all of them complete
+///
+/// If the current thread is already inside a multi-threaded tokio runtime, the futures run on it
+/// instead of a new runtime. A current-thread runtime can't be blocked on, so calling this from
+/// inside one returns an error instead.
+pub fn run_queue(future_queue: FutureQueue) -> Result<()> {
+    if let Ok(handle) = Handle::try_current() {
+        if handle.runtime_flavor() != RuntimeFlavor::MultiThread {
+            return Err(std::io::ErrorKind::Unsupported.into());
+        }
+
+        ::tokio::task::block_in_place(|| handle.block_on(QueueRunner::new(future_queue)));
+        return Ok(());
+    }
+
+    let runtime = ::tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(QueueRunner::new(future_queue));
+    Ok(())
+}
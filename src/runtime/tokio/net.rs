@@ -0,0 +1,92 @@
+//! Networking primitives
+
+use crate::runtime::{
+    io::{Read, Write},
+    Result,
+};
+use std::net::SocketAddr;
+
+/// A socket which listens for TCP connections
+pub struct TCPListener(tokio::net::TcpListener);
+
+/// A connected TCP socket
+pub struct TCPStream(tokio::net::TcpStream);
+
+/// A UDP socket which sends and receives datagrams
+///
+/// Datagrams can be sent and received from multiple tasks, and from multiple threads, at once.
+pub struct UDPSocket(tokio::net::UdpSocket);
+
+impl TCPListener {
+    /// Creates a new [`TCPListener`] bound to `address`
+    ///
+    /// This must be called from inside the runtime
+    pub fn bind(address: SocketAddr) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(TCPListener(tokio::net::TcpListener::from_std(listener)?))
+    }
+
+    /// Gets the address this listener is bound to
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.0.local_addr()?)
+    }
+
+    /// Accepts a new connection
+    pub async fn accept(&self) -> Result<(TCPStream, SocketAddr)> {
+        let (stream, address) = self.0.accept().await?;
+        Ok((TCPStream(stream), address))
+    }
+}
+
+impl TCPStream {
+    /// Sets if Nagle's algorithm should be disabled on this socket
+    pub fn set_nodelay(&mut self, nodelay: bool) -> Result<()> {
+        Ok(self.0.set_nodelay(nodelay)?)
+    }
+}
+
+impl UDPSocket {
+    /// Creates a new [`UDPSocket`] bound to `address`
+    ///
+    /// This must be called from inside the runtime
+    pub fn bind(address: SocketAddr) -> Result<Self> {
+        let socket = std::net::UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        Ok(UDPSocket(tokio::net::UdpSocket::from_std(socket)?))
+    }
+
+    /// Gets the address this socket is bound to
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.0.local_addr()?)
+    }
+
+    /// Receives the next datagram into `buffer`, returning its length and where it came from
+    ///
+    /// The end of a datagram longer than `buffer` is discarded.
+    pub async fn recv_from(&self, buffer: &mut [u8]) -> Result<(usize, SocketAddr)> {
+        Ok(self.0.recv_from(buffer).await?)
+    }
+
+    /// Sends `buffer` as a datagram to `address`
+    pub async fn send_to(&self, buffer: &[u8], address: SocketAddr) -> Result<()> {
+        self.0.send_to(buffer, address).await?;
+        Ok(())
+    }
+}
+
+impl Read for TCPStream {
+    async fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        self.0.read(buffer).await
+    }
+
+    async fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+        self.0.read_exact(buffer).await
+    }
+}
+
+impl Write for TCPStream {
+    async fn write_all(&mut self, buffer: &[u8]) -> Result<()> {
+        self.0.write_all(buffer).await
+    }
+}
@@ -0,0 +1,29 @@
+//! Synchronization primitives
+
+use tokio::sync::Notify;
+
+/// Notifies tasks on the current thread
+pub struct LocalNotify(Notify);
+
+impl LocalNotify {
+    /// Creates a new [`LocalNotify`]
+    pub fn new() -> Self {
+        LocalNotify(Notify::new())
+    }
+
+    /// Waits until the next notification
+    pub async fn notified(&self) {
+        self.0.notified().await
+    }
+
+    /// Wakes every task currently waiting for a notification
+    pub fn notify_all(&self) {
+        self.0.notify_waiters()
+    }
+}
+
+impl Default for LocalNotify {
+    fn default() -> Self {
+        LocalNotify::new()
+    }
+}
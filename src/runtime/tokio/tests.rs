@@ -0,0 +1,44 @@
+use super::{run_queue, time};
+use crate::runtime::FutureQueue;
+use std::{cell::Cell, io::ErrorKind, time::Duration};
+
+/// Runs a queue which waits for a timer once, returning if the timer finished
+fn run_sleeping_queue() -> crate::runtime::Result<bool> {
+    let done = Cell::new(false);
+
+    let queue = FutureQueue::new();
+    queue.push(async {
+        let pending = std::future::pending::<()>();
+        let result = time::timeout(pending, Duration::from_millis(1)).await;
+        done.set(result.unwrap().is_none());
+    });
+    run_queue(queue)?;
+
+    Ok(done.get())
+}
+
+#[test]
+fn queues_run_on_their_own_runtime() {
+    assert!(run_sleeping_queue().unwrap());
+}
+
+#[test]
+fn queues_run_inside_a_multi_threaded_runtime() {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    assert!(runtime.block_on(async { run_sleeping_queue() }).unwrap());
+}
+
+#[test]
+fn queues_fail_inside_a_current_thread_runtime() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let result = runtime.block_on(async { run_sleeping_queue() });
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::Unsupported);
+}
@@ -0,0 +1,16 @@
+//! Utilities for timing futures
+
+use crate::runtime::{Error, Result};
+use std::{future::Future, io::ErrorKind, time::Duration};
+
+/// Runs `future` until it completes or `duration` passes
+///
+/// Returns [`None`] if `duration` passed before `future` completed
+pub async fn timeout<F: Future>(future: F, duration: Duration) -> Result<Option<F::Output>> {
+    Ok(tokio::time::timeout(duration, future).await.ok())
+}
+
+/// Creates the error returned when an operation times out
+pub fn timeout_error() -> Error {
+    ErrorKind::TimedOut.into()
+}
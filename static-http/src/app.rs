@@ -1,12 +1,18 @@
 use crate::{
-    error::HandleError, path::parse_extension, response_display::ResponseDisplay, HTTPResponse,
+    error::HandleError,
+    log_level::{RuntimeLogLevel, Severity},
+    path::parse_extension,
+    response_display::ResponseDisplay,
+    HTTPResponse,
+};
+use huntsman::{
+    runtime::fs::{File, Metadata},
+    App, Protocol,
 };
-use huntsman::{App, Protocol};
 use huntsman_http::{
-    HTTPClientAddress, HTTPListenAddress, HTTPParseError, HTTPRequestDisplay, HTTPStatus,
-    HTTPTarget, ReadHTTPChunkedResponseBody, HTTP,
+    HTTPClientAddress, HTTPListenAddress, HTTPMethod, HTTPParseError, HTTPRequestDisplay,
+    HTTPStatus, HTTPTLSConfig, HTTPTarget, ReadHTTPChunkedResponseBody, HTTP,
 };
-use lasync::fs::{File, Metadata};
 use oak::{error, info, LogController, LogLevel, Logger};
 use std::{
     borrow::Cow,
@@ -40,6 +46,9 @@ pub struct StaticHuntsman {
     /// Log for errors
     error_logger: Logger,
 
+    /// The minimum severity of logged messages, which can be changed through the control channel
+    log_level: RuntimeLogLevel,
+
     /// Should request headers be logged in the access logger?
     log_headers: bool,
 
@@ -51,6 +60,9 @@ pub struct StaticHuntsman {
 
     /// The maximum size for chunks in response bodies
     max_chunk_size: NonZeroUsize,
+
+    /// The certificates for HTTPS listeners, which are reloaded when requested
+    tls_config: Option<HTTPTLSConfig>,
 }
 
 /// Attempts to read the file at `path`, or one of the `indexes` if the `path` is a directory.
@@ -139,6 +151,7 @@ impl StaticHuntsman {
         log_bodies: bool,
         log_responses: bool,
         max_chunk_size: NonZeroUsize,
+        tls_config: Option<HTTPTLSConfig>,
     ) -> Self {
         let connections_logger = log_controller.create_logger("connections");
         let access_logger = log_controller.create_logger("access");
@@ -152,10 +165,12 @@ impl StaticHuntsman {
             connections_logger,
             access_logger,
             error_logger,
+            log_level: RuntimeLogLevel::new(),
             log_headers,
             log_bodies,
             log_responses,
             max_chunk_size,
+            tls_config,
         }
     }
 
@@ -194,15 +209,30 @@ impl StaticHuntsman {
     /// Handles a request from a client
     async fn do_handle_request<'a, 'b>(
         self: &'a Arc<Self>,
-        client: HTTPClientAddress,
+        client: &HTTPClientAddress,
         request: &<HTTP as Protocol>::Request<'b>,
     ) -> Result<(HTTPResponse<'a>, Option<PathBuf>), HandleError<'a>> {
+        match request.method() {
+            HTTPMethod::GET | HTTPMethod::HEAD => {}
+            HTTPMethod::Extension(_) => {
+                return Err(HandleError::bad_method(
+                    HTTPStatus::NotImplemented.into(),
+                    client.clone(),
+                ))
+            }
+            _ => {
+                let mut response: HTTPResponse = HTTPStatus::MethodNotAllowed.into();
+                response.push_field(b"Allow", b"GET, HEAD");
+                return Err(HandleError::bad_method(response, client.clone()));
+            }
+        }
+
         let path = self
             .parse_path(request.target())
-            .map_err(|response| HandleError::bad_path(response, client))?;
+            .map_err(|response| HandleError::bad_path(response, client.clone()))?;
 
         let (body, path) = self.read_file(path).await.map_err(|(response, path)| {
-            HandleError::not_found_or_unreadable(response, path, client)
+            HandleError::not_found_or_unreadable(response, path, client.clone())
         })?;
 
         Ok((
@@ -218,18 +248,60 @@ impl App for StaticHuntsman {
     type Client = HTTPClientAddress;
 
     async fn on_server_start(self: &Arc<Self>, addresses: &[HTTPListenAddress]) {
-        info!(
-            self.connections_logger,
-            "Sever listening on: {:?}", addresses
-        );
+        if self.log_level.allows(Severity::Info) {
+            info!(
+                self.connections_logger,
+                "Sever listening on: {:?}", addresses
+            );
+        }
+    }
+
+    async fn on_reload(self: &Arc<Self>) -> Result<(), String> {
+        let tls_config = match &self.tls_config {
+            Some(tls_config) => tls_config,
+            None => return Ok(()),
+        };
+
+        match tls_config.reload() {
+            Ok(()) => {
+                if self.log_level.allows(Severity::Info) {
+                    info!(self.connections_logger, "Reloaded TLS certificates");
+                }
+                Ok(())
+            }
+            Err(error) => {
+                if self.log_level.allows(Severity::Err) {
+                    error!(
+                        self.error_logger,
+                        "Unable to reload TLS certificates - {}", error
+                    );
+                }
+                Err(format!("unable to reload TLS certificates - {}", error))
+            }
+        }
+    }
+
+    async fn on_log_level(self: &Arc<Self>, level: &str) -> Result<(), String> {
+        let severity = Severity::parse(level).ok_or_else(|| {
+            format!(
+                "unknown log level \"{}\", LEVEL can be \"trace\", \"debug\", \"info\", \"warn\", \"err\", or \"fatal\"",
+                level
+            )
+        })?;
+        self.log_level.set(severity);
+
+        if self.log_level.allows(Severity::Info) {
+            info!(self.connections_logger, "Log level changed to {}", level);
+        }
+        Ok(())
     }
 
     async fn handle_request<'a, 'b>(
         self: &'a Arc<Self>,
-        client: &'a mut Self::Client,
-        request: <Self::Protocol as Protocol>::Request<'b>,
+        client: &'a Self::Client,
+        request: &'a mut <Self::Protocol as Protocol>::Request<'b>,
     ) -> HTTPResponse<'a> {
-        let result = self.do_handle_request(*client, &request).await;
+        let result = self.do_handle_request(client, request).await;
 
         let response_display = if self.log_responses {
             Some(match &result {
@@ -243,21 +315,25 @@ impl App for StaticHuntsman {
             None
         };
 
-        info!(
-            self.access_logger,
-            &HTTPRequestDisplay::new(
-                &request,
-                *client,
-                response_display,
-                self.log_headers,
-                self.log_bodies,
-            )
-        );
+        if self.log_level.allows(Severity::Info) {
+            info!(
+                self.access_logger,
+                &HTTPRequestDisplay::new(
+                    request,
+                    client.clone(),
+                    response_display,
+                    self.log_headers,
+                    self.log_bodies,
+                )
+            );
+        }
 
         match result {
             Ok((response, _)) => response,
             Err(error) => {
-                self.error_logger.log(LogLevel::Error, &error);
+                if self.log_level.allows(Severity::Err) {
+                    self.error_logger.log(LogLevel::Error, &error);
+                }
                 error.unwrap_response()
             }
         }
@@ -267,33 +343,59 @@ impl App for StaticHuntsman {
         self: &Arc<Self>,
         source: HTTPClientAddress,
     ) -> Option<HTTPClientAddress> {
-        info!(self.connections_logger, "Client connected from {}", source);
+        if self.log_level.allows(Severity::Info) {
+            match source.tls().and_then(|tls| tls.peer_certificate()) {
+                Some(certificate) => info!(
+                    self.connections_logger,
+                    "Client connected from {} as \"{}\" ({})",
+                    source,
+                    certificate,
+                    certificate.fingerprint()
+                ),
+                None => info!(self.connections_logger, "Client connected from {}", source),
+            }
+        }
         Some(source)
     }
 
     async fn on_client_disconnect(self: &Arc<Self>, client: &mut HTTPClientAddress) {
-        info!(self.connections_logger, "{} disconnected", client);
+        if self.log_level.allows(Severity::Info) {
+            info!(self.connections_logger, "{} disconnected", client);
+        }
     }
 
     async fn accept_error(self: &Arc<Self>, error: huntsman_http::Error) {
-        error!(
-            self.error_logger,
-            "An error occurred while accepting a client - {}", error
-        );
+        if self.log_level.allows(Severity::Err) {
+            error!(
+                self.error_logger,
+                "An error occurred while accepting a client - {}", error
+            );
+        }
     }
 
     async fn read_error<'a>(
         self: &'a Arc<Self>,
-        client: &'a mut Self::Client,
+        client: &'a Self::Client,
         error: HTTPParseError,
     ) -> Option<HTTPResponse<'a>> {
-        error!(
-            self.error_logger,
-            "An error occurred while parsing a request from {} - {}", client, error
-        );
+        if self.log_level.allows(Severity::Err) {
+            error!(
+                self.error_logger,
+                "An error occurred while parsing a request from {} - {}", client, error
+            );
+        }
 
         Some(match error {
             HTTPParseError::HeadersTooLong => HTTPStatus::ContentTooLarge.into(),
+            HTTPParseError::LimitExceeded(limit) => limit.status().into(),
+            HTTPParseError::UnsupportedVersion => HTTPStatus::HTTPVersionNotSupported.into(),
+            HTTPParseError::UnknownExpectation => HTTPStatus::ExpectationFailed.into(),
+            HTTPParseError::UnsupportedTransferEncoding => HTTPStatus::NotImplemented.into(),
+            HTTPParseError::UnsupportedContentEncoding => {
+                let mut response: HTTPResponse = HTTPStatus::UnsupportedMediaType.into();
+                response.push_field(b"Accept-Encoding", b"gzip, deflate");
+                response
+            }
             _ => (
                 HTTPStatus::BadRequest,
                 self.bad_request.0.as_ref(),
@@ -303,10 +405,12 @@ impl App for StaticHuntsman {
         })
     }
 
-    async fn send_error(self: &Arc<Self>, client: &mut Self::Client, error: huntsman_http::Error) {
-        error!(
-            self.error_logger,
-            "An error occurred while sending a response to {} - {}", client, error
-        );
+    async fn send_error(self: &Arc<Self>, client: &Self::Client, error: huntsman_http::Error) {
+        if self.log_level.allows(Severity::Err) {
+            error!(
+                self.error_logger,
+                "An error occurred while sending a response to {} - {}", client, error
+            );
+        }
     }
 }
@@ -1,6 +1,6 @@
 use argparse::{config_flag, help_flag, parser, parsing_flag, simple_flag, version_flag};
+use huntsman::runtime::fs::File;
 use huntsman_http::{HTTPListenAddress, HTTPOptions, ReadHTTPChunkedResponseBody, HTTP};
-use lasync::fs::File;
 use oak::{FilterListType, LogLevel, StdLogOutput};
 use std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, time::Duration};
 
@@ -23,6 +23,28 @@ pub struct StaticHuntsmanOptions {
     /// The huntsman options
     pub huntsman_options: huntsman::Options<HTTP<ReadHTTPChunkedResponseBody<File>>>,
 
+    /// The addresses to listen for HTTPS connections on
+    pub https_addresses: Vec<SocketAddr>,
+
+    /// The addresses to listen for HTTP/3 connections on
+    pub http3_addresses: Vec<SocketAddr>,
+
+    /* TLS Flags */
+    /// The file containing the certificate chain for HTTPS
+    pub certificate: Option<PathBuf>,
+
+    /// The file containing the private key for HTTPS
+    pub private_key: Option<PathBuf>,
+
+    /// The certificates for specific hostnames as "HOSTNAME,CERTIFICATE,PRIVATE-KEY"
+    pub sni_certificates: Vec<String>,
+
+    /// The file containing the certificate authorities client certificates are verified against
+    pub client_ca: Option<PathBuf>,
+
+    /// Must clients send a certificate to connect?
+    pub require_client_certificate: bool,
+
     /* HTTP Flags */
     /// The HTTP options
     pub http_options: HTTPOptions,
@@ -95,6 +117,50 @@ parser! {
                       "Specify an address to listen for insecure HTTP/1.1 connections on"
                       |options: StaticHuntsmanOptions, address: SocketAddr| { options.huntsman_options.add_address(HTTPListenAddress::HTTP(address)); }
         ).group("HUNTSMAN FLAGS"),
+        parsing_flag!(, "https" "ADDRESS:PORT" "missing ADDRESS for https"
+                      ["Specify an address to listen for HTTPS/1.1 connections on",
+                       "Requires \"certificate\" and \"private-key\" to be set"]
+                      |options: StaticHuntsmanOptions, address: SocketAddr| { options.https_addresses.push(address); }
+        ).group("HUNTSMAN FLAGS"),
+        parsing_flag!(, "http3" "ADDRESS:PORT" "missing ADDRESS for http3"
+                      ["Specify a UDP address to listen for HTTP/3 connections on",
+                       "Requires \"certificate\" and \"private-key\" to be set",
+                       "Only available when built with the \"http3\" feature, which uses the tokio runtime"]
+                      |options: StaticHuntsmanOptions, address: SocketAddr| { options.http3_addresses.push(address); }
+        ).group("HUNTSMAN FLAGS"),
+        parsing_flag!(, "control-socket" "PATH" "missing PATH for control-socket"
+                      ["Specify a Unix socket to listen for control commands on",
+                       "Send \"help\" to the socket for a list of commands"]
+                      |options: StaticHuntsmanOptions, path: PathBuf| { options.huntsman_options.set_control_path(Some(path)); }
+        ).group("HUNTSMAN FLAGS"),
+
+        // TLS Flags
+        parsing_flag!(, "certificate" "PATH" "missing PATH for certificate"
+                      ["Specify the PEM file containing the certificate chain for HTTPS",
+                       "The server's certificate comes first, followed by any intermediate certificates",
+                       "All certificates are read again when \"reload\" is sent to the control socket"]
+                      |options: StaticHuntsmanOptions, path: PathBuf| { options.certificate = Some(path); }
+        ).group("TLS FLAGS"),
+        parsing_flag!(, "private-key" "PATH" "missing PATH for private-key"
+                      "Specify the PEM file containing the private key for HTTPS"
+                      |options: StaticHuntsmanOptions, path: PathBuf| { options.private_key = Some(path); }
+        ).group("TLS FLAGS"),
+        parsing_flag!(, "sni-certificate" "HOSTNAME,CERTIFICATE,PRIVATE-KEY" "missing HOSTNAME,CERTIFICATE,PRIVATE-KEY for sni-certificate"
+                      ["Specify PEM files containing a certificate chain and private key for clients requesting HOSTNAME",
+                       "HOSTNAME may start with \"*.\" to match any subdomain",
+                       "Clients requesting other hostnames are sent \"certificate\""]
+                      |options: StaticHuntsmanOptions, certificate: String| { options.sni_certificates.push(certificate); }
+        ).group("TLS FLAGS").repeatable(true),
+        parsing_flag!(, "client-ca" "PATH" "missing PATH for client-ca"
+                      ["Specify the PEM file containing the certificate authorities to verify client certificates against",
+                       "Clients are asked for a certificate, but may connect without one unless \"require-client-certificate\" is set"]
+                      |options: StaticHuntsmanOptions, path: PathBuf| { options.client_ca = Some(path); }
+        ).group("TLS FLAGS"),
+        simple_flag!(, "require-client-certificate"
+                     ["Reject clients which don't send a valid certificate",
+                      "Requires \"client-ca\" to be set"]
+                     |options: StaticHuntsmanOptions, _| { options.require_client_certificate = true; }
+        ).group("TLS FLAGS"),
 
         // HTTP Flags
         parsing_flag!(, "max-header-size" "SIZE" "missing size for max-header-size"
@@ -107,6 +173,21 @@ parser! {
                        "Defaults to 1,048,576 bytes (1 Mb)"]
                       |options: StaticHuntsmanOptions, size: usize| { options.http_options.max_body_size = size; }
         ).group("HTTP FLAGS"),
+        parsing_flag!(, "max-target-length" "LENGTH" "missing LENGTH for max-target-length"
+                      ["Specify the maximum length of request targets in bytes to accept",
+                       "Defaults to 4,096 bytes (4 Kb)"]
+                      |options: StaticHuntsmanOptions, length: usize| { options.http_options.max_target_length = length; }
+        ).group("HTTP FLAGS"),
+        parsing_flag!(, "max-header-fields" "COUNT" "missing COUNT for max-header-fields"
+                      ["Specify the maximum number of fields in request headers to accept",
+                       "Defaults to 100 fields"]
+                      |options: StaticHuntsmanOptions, count: usize| { options.http_options.max_header_fields = count; }
+        ).group("HTTP FLAGS"),
+        parsing_flag!(, "max-field-size" "SIZE" "missing SIZE for max-field-size"
+                      ["Specify the maximum size of a single request field in bytes to accept",
+                       "Defaults to 4,096 bytes (4 Kb)"]
+                      |options: StaticHuntsmanOptions, size: usize| { options.http_options.max_field_size = size; }
+        ).group("HTTP FLAGS"),
         parsing_flag!(, "timeout" "TIMEOUT" "missing TIMEOUT for timeout"
                       ["Specify all timeouts to wait TIMEOUT milliseconds",
                        "Defaults to 60,000 milliseconds (1 minute)",
@@ -116,6 +197,7 @@ parser! {
                           options.http_options.header_read_timeout = timeout;
                           options.http_options.body_read_timeout = timeout;
                           options.http_options.write_timeout = timeout;
+                          options.http_options.keep_alive_timeout = timeout;
                       }
         ).group("HTTP FLAGS"),
         parsing_flag!(, "read-timeout" "TIMEOUT" "missing TIMEOUT for read-timeout"
@@ -155,11 +237,38 @@ parser! {
                           options.http_options.write_timeout = timeout;
                       }
         ).group("HTTP FLAGS"),
+        parsing_flag!(, "keep-alive-timeout" "TIMEOUT" "missing TIMEOUT for keep-alive-timeout"
+                      ["Specify how long to wait TIMEOUT milliseconds for the next request on an open connection",
+                       "Defaults to 15,000 milliseconds (15 seconds)",
+                       "If any conflicting flags are specified, the latest one specified will take precedence"]
+                      |options: StaticHuntsmanOptions, timeout: u64| {
+                          let timeout = Duration::from_millis(timeout);
+                          options.http_options.keep_alive_timeout = timeout;
+                      }
+        ).group("HTTP FLAGS"),
+        parsing_flag!(, "max-requests" "COUNT" "missing COUNT for max-requests"
+                      ["Specify the maximum number of requests to answer on a connection before closing it",
+                       "Defaults to 1,000, use 0 for no limit"]
+                      |options: StaticHuntsmanOptions, count: usize| { options.http_options.max_requests_per_connection = NonZeroUsize::new(count); }
+        ).group("HTTP FLAGS"),
         parsing_flag!(, "max-chunk-size" "SIZE" "missing SIZE for max-chunk-size"
                       ["Specify the maximum size chunks when sending response bodies",
                        "Defaults to 32,768 bytes (32 Kb)"]
                       |options: StaticHuntsmanOptions, size: NonZeroUsize| { options.max_chunk_size = size; }
         ).group("HTTP FLAGS"),
+        simple_flag!(, "compress"
+                     "Enable compressing responses with gzip or deflate for clients which accept it"
+                     |options: StaticHuntsmanOptions, _| { options.http_options.compression.get_or_insert_with(Default::default); }
+        ).group("HTTP FLAGS"),
+        parsing_flag!(, "compress-min-size" "SIZE" "missing SIZE for compress-min-size"
+                      ["Specify the smallest response body in bytes to compress and enable compression",
+                       "Defaults to 1,024 bytes (1 Kb)"]
+                      |options: StaticHuntsmanOptions, size: usize| { options.http_options.compression.get_or_insert_with(Default::default).min_size = size; }
+        ).group("HTTP FLAGS"),
+        simple_flag!(, "no-http2"
+                     "Disable HTTP/2, so every client uses HTTP/1.1"
+                     |options: StaticHuntsmanOptions, _| { options.http_options.http2 = false; }
+        ).group("HTTP FLAGS"),
 
         // Logging Flags
         simple_flag!(, "log-headers"
@@ -177,7 +286,8 @@ parser! {
         parsing_flag!(, "min-log-level" "LEVEL" "missing LEVEL for min-log-level"
                       ["Sets the minimum severity of messages to log",
                        "LEVEL can be \"trace\", \"debug\", \"info\", \"warn\", \"err\", or \"fatal\"",
-                       "Defaults to \"info\""]
+                       "Defaults to \"info\"",
+                       "Can be raised while running by sending \"log-level LEVEL\" to the control socket"]
                       |options: StaticHuntsmanOptions, level: LogLevel| { options.min_log_level = level; }
         ).group("LOGGING FLAGS"),
         parsing_flag!(, "max-log-level" "LEVEL" "missing LEVEL for max-log-level"
@@ -224,6 +334,13 @@ impl Default for StaticHuntsmanOptions {
             bad_request: None,
             not_found: None,
             huntsman_options: huntsman::Options::default(),
+            https_addresses: Vec::new(),
+            http3_addresses: Vec::new(),
+            certificate: None,
+            private_key: None,
+            sni_certificates: Vec::new(),
+            client_ca: None,
+            require_client_certificate: false,
             http_options: HTTPOptions::default(),
             max_chunk_size: NonZeroUsize::new(32768).unwrap(),
             log_headers: false,
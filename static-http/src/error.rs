@@ -5,6 +5,9 @@ use std::{ffi::OsString, fmt::Debug};
 /// The kind of error that occurred
 #[derive(Debug)]
 enum HandleErrorKind {
+    /// The method of the request is not one which can be served
+    BadMethod,
+
     /// The path in the request was not valid
     BadPath,
 
@@ -25,6 +28,15 @@ pub struct HandleError<'a> {
 }
 
 impl<'a> HandleError<'a> {
+    /// Creates a [`HandleError`] for when the request method is not supported
+    pub fn bad_method(response: HTTPResponse<'a>, client: HTTPClientAddress) -> Self {
+        HandleError {
+            response,
+            client,
+            kind: HandleErrorKind::BadMethod,
+        }
+    }
+
     /// Creates a [`HandleError`] for when path parsing fails
     pub fn bad_path(response: HTTPResponse<'a>, client: HTTPClientAddress) -> Self {
         HandleError {
@@ -63,6 +75,7 @@ impl<'a> std::error::Error for HandleError<'a> {}
 impl<'a> std::fmt::Display for HandleError<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.kind {
+            HandleErrorKind::BadMethod => write!(f, "Bad method received from {}", self.client),
             HandleErrorKind::BadPath => write!(f, "Bad path received from {}", self.client),
             HandleErrorKind::NotFoundOrUnreadable(path) => write!(
                 f,
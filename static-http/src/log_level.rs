@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The severity of a message logged by the app
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Err,
+    Fatal,
+}
+
+/// The minimum severity of the messages the app logs, which can be changed while the server runs
+///
+/// Messages are filtered by this before they reach the log controller, so messages below the
+/// "min-log-level" the server was started with are still not logged.
+pub struct RuntimeLogLevel(AtomicU8);
+
+impl Severity {
+    /// Parses a severity using the same names as the "min-log-level" flag
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "trace" => Severity::Trace,
+            "debug" => Severity::Debug,
+            "info" => Severity::Info,
+            "warn" => Severity::Warn,
+            "err" => Severity::Err,
+            "fatal" => Severity::Fatal,
+            _ => return None,
+        })
+    }
+}
+
+impl RuntimeLogLevel {
+    /// Creates a new [`RuntimeLogLevel`] which allows every message
+    pub fn new() -> Self {
+        RuntimeLogLevel(AtomicU8::new(Severity::Trace as u8))
+    }
+
+    /// Sets the minimum severity of logged messages
+    pub fn set(&self, severity: Severity) {
+        self.0.store(severity as u8, Ordering::Relaxed);
+    }
+
+    /// Should a message with `severity` be logged?
+    pub fn allows(&self, severity: Severity) -> bool {
+        severity as u8 >= self.0.load(Ordering::Relaxed)
+    }
+}
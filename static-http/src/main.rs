@@ -1,6 +1,8 @@
 use app::StaticHuntsman;
-use huntsman_http::ReadHTTPChunkedResponseBody;
-use lasync::fs::File;
+use huntsman::runtime::fs::File;
+use huntsman_http::{
+    HTTPListenAddress, HTTPTLSClientAuth, HTTPTLSConfig, ReadHTTPChunkedResponseBody,
+};
 use oak::LogController;
 use path::parse_extension;
 use std::{borrow::Cow, path::PathBuf};
@@ -8,6 +10,7 @@ use std::{borrow::Cow, path::PathBuf};
 mod app;
 mod args;
 mod error;
+mod log_level;
 mod path;
 mod response_display;
 
@@ -31,8 +34,93 @@ fn read_file(path: Option<PathBuf>, default: &'static [u8]) -> (Cow<'static, [u8
     (Cow::Owned(content), parse_extension(&path))
 }
 
+/// Loads the default certificate chain and private key for HTTPS, along with the certificates for
+/// specific hostnames and the certificate authorities for verifying clients
+fn load_tls_config(
+    certificate: Option<PathBuf>,
+    private_key: Option<PathBuf>,
+    sni_certificates: Vec<String>,
+    client_ca: Option<PathBuf>,
+    require_client_certificate: bool,
+) -> HTTPTLSConfig {
+    let (certificate, private_key) = match (certificate, private_key) {
+        (Some(certificate), Some(private_key)) => (certificate, private_key),
+        _ => {
+            eprintln!(
+                "Error: \"certificate\" and \"private-key\" must be set to listen for HTTPS or HTTP/3"
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let mut config = match HTTPTLSConfig::from_pem_files(&certificate, &private_key) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!(
+                "Error: Unable to load \"{}\" and \"{}\" - {}",
+                certificate.display(),
+                private_key.display(),
+                error
+            );
+            std::process::exit(1);
+        }
+    };
+
+    for sni_certificate in sni_certificates {
+        let mut parts = sni_certificate.splitn(3, ',');
+        let (hostname, certificate, private_key) = match (parts.next(), parts.next(), parts.next())
+        {
+            (Some(hostname), Some(certificate), Some(private_key)) => {
+                (hostname, certificate, private_key)
+            }
+            _ => {
+                eprintln!(
+                        "Error: Invalid sni-certificate \"{}\" - expected HOSTNAME,CERTIFICATE,PRIVATE-KEY",
+                        sni_certificate
+                    );
+                std::process::exit(1);
+            }
+        };
+
+        if let Err(error) = config.add_pem_files(hostname, certificate, private_key) {
+            eprintln!(
+                "Error: Unable to load \"{}\" and \"{}\" for \"{}\" - {}",
+                certificate, private_key, hostname, error
+            );
+            std::process::exit(1);
+        }
+    }
+
+    match client_ca {
+        Some(client_ca) => {
+            let client_auth = if require_client_certificate {
+                HTTPTLSClientAuth::Require
+            } else {
+                HTTPTLSClientAuth::Request
+            };
+
+            if let Err(error) = config.set_client_auth_pem_file(&client_ca, client_auth) {
+                eprintln!(
+                    "Error: Unable to load \"{}\" - {}",
+                    client_ca.display(),
+                    error
+                );
+                std::process::exit(1);
+            }
+        }
+        None => {
+            if require_client_certificate {
+                eprintln!("Error: \"client-ca\" must be set to require client certificates");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    config
+}
+
 fn main() {
-    let args = match args::parse() {
+    let mut args = match args::parse() {
         Ok(args) => match args {
             Some(args) => args,
             None => return,
@@ -43,6 +131,34 @@ fn main() {
         }
     };
 
+    #[cfg(not(feature = "http3"))]
+    if !args.http3_addresses.is_empty() {
+        eprintln!("Error: Listening for HTTP/3 requires building with the \"http3\" feature");
+        std::process::exit(1);
+    }
+
+    let tls_config = if !args.https_addresses.is_empty() || !args.http3_addresses.is_empty() {
+        let config = load_tls_config(
+            args.certificate,
+            args.private_key,
+            args.sni_certificates,
+            args.client_ca,
+            args.require_client_certificate,
+        );
+        for address in args.https_addresses {
+            args.huntsman_options
+                .add_address(HTTPListenAddress::HTTPS(address, config.clone()));
+        }
+        #[cfg(feature = "http3")]
+        for address in args.http3_addresses {
+            args.huntsman_options
+                .add_address(HTTPListenAddress::HTTP3(address, config.clone()));
+        }
+        Some(config)
+    } else {
+        None
+    };
+
     let bad_request = read_file(args.bad_request, include_bytes!("400.html"));
     let not_found = read_file(args.not_found, include_bytes!("404.html"));
 
@@ -84,6 +200,7 @@ fn main() {
         args.log_bodies,
         args.log_responses,
         args.max_chunk_size,
+        tls_config,
     );
 
     if let Err(error) = huntsman::run(app, args.huntsman_options, args.http_options) {